@@ -30,7 +30,7 @@ fn main() -> Result<(), wpilog_parser::Error> {
     println!();
 
     println!("🏗️  Struct Schemas:");
-    for schema in &formatter.struct_schemas {
+    for schema in formatter.struct_schemas.values() {
         println!("  {}: {} fields", schema.name, schema.columns.len());
         for col in &schema.columns {
             println!("    - {} ({})", col.name, col.type_name);
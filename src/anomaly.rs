@@ -0,0 +1,309 @@
+//! Flagging suspicious samples of numeric entries.
+//!
+//! [`AnomalyDetector`] makes one pass over a log's numeric scalar entries and
+//! returns [`Anomaly`] intervals for two kinds of trouble:
+//!
+//! - **Spikes**: samples more than a z-score threshold away from the mean of
+//!   the entry's preceding samples, such as encoder glitches or CAN dropouts
+//!   reading as zero
+//! - **Flatlines**: an entry that otherwise varies repeating the exact same
+//!   value for a long time, as a stuck or disconnected sensor does
+//!
+//! Consecutive flagged samples are merged into one interval, so a report can
+//! annotate the time ranges directly.
+
+use crate::datalog::{DataLogRecord, RecordVisitor, StartRecordData};
+use crate::entry_type::{DecodedValue, EntryType};
+use crate::error::Result;
+use crate::profile::EntrySelector;
+use crate::reader::WpilogReader;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Kind of an [`Anomaly`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Samples far from the recent mean
+    Spike,
+    /// The same value repeated for at least the flatline duration
+    Flatline,
+}
+
+/// An interval of anomalous samples of one entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Anomaly {
+    pub entry: String,
+    pub kind: AnomalyKind,
+    /// Timestamp of the first anomalous sample (seconds)
+    pub start_secs: f64,
+    /// Timestamp of the last anomalous sample (seconds)
+    pub end_secs: f64,
+    /// Number of samples in the interval
+    pub samples: usize,
+    /// The most extreme value of a spike, or the repeated value of a flatline
+    pub value: f64,
+    /// Largest absolute z-score of a spike (0 for flatlines)
+    pub z_score: f64,
+}
+
+/// Finds spikes and flatlines in numeric entries.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{AnomalyDetector, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("match.wpilog")?;
+/// let anomalies = AnomalyDetector::new()
+///     .z_threshold(5.0)
+///     .flatline_secs(Some(2.0))
+///     .entries(&["/Drive/*"], &[])?
+///     .run(&reader)?;
+/// for anomaly in &anomalies {
+///     println!("{:?} in {} at {:.2}s", anomaly.kind, anomaly.entry, anomaly.start_secs);
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct AnomalyDetector {
+    z_threshold: f64,
+    window: usize,
+    flatline_secs: Option<f64>,
+    selector: Option<EntrySelector>,
+}
+
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        Self {
+            z_threshold: 4.0,
+            window: 50,
+            flatline_secs: Some(1.0),
+            selector: None,
+        }
+    }
+}
+
+impl AnomalyDetector {
+    /// Create a detector with the default sensitivity: z-score 4 over the
+    /// last 50 samples, flatlines of 1 second.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flag samples whose z-score against the recent samples exceeds
+    /// `threshold` (default: 4). Lower values are more sensitive.
+    pub fn z_threshold(mut self, threshold: f64) -> Self {
+        self.z_threshold = threshold;
+        self
+    }
+
+    /// Number of preceding samples the mean and standard deviation are taken
+    /// over (default: 50). Spikes are only flagged once the window is full.
+    pub fn window(mut self, samples: usize) -> Self {
+        self.window = samples.max(2);
+        self
+    }
+
+    /// Flag repeated values lasting at least this many seconds, or `None` to
+    /// skip flatline detection (default: 1 second).
+    pub fn flatline_secs(mut self, secs: Option<f64>) -> Self {
+        self.flatline_secs = secs;
+        self
+    }
+
+    /// Only check entries matching the include globs and none of the exclude
+    /// globs (default: all numeric entries).
+    pub fn entries<S: AsRef<str>>(mut self, include: &[S], exclude: &[S]) -> Result<Self> {
+        self.selector = Some(EntrySelector::new(include, exclude)?);
+        Ok(self)
+    }
+
+    /// Check every matching `double`, `float` and `int64` entry of the log.
+    ///
+    /// Returns the anomalies sorted by start time, then entry name.
+    pub fn run(&self, reader: &WpilogReader) -> Result<Vec<Anomaly>> {
+        let mut visitor = DetectorVisitor {
+            detector: self,
+            selected: HashMap::new(),
+            states: HashMap::new(),
+        };
+        reader.low_level_reader().visit(&mut visitor)?;
+
+        let mut anomalies: Vec<Anomaly> = visitor
+            .states
+            .into_iter()
+            .flat_map(|(entry, state)| state.finish(&entry, self.flatline_secs))
+            .collect();
+        anomalies.sort_by(|a, b| {
+            a.start_secs
+                .total_cmp(&b.start_secs)
+                .then_with(|| a.entry.cmp(&b.entry))
+        });
+        Ok(anomalies)
+    }
+}
+
+struct DetectorVisitor<'d> {
+    detector: &'d AnomalyDetector,
+    /// Entry ID -> whether it is a numeric entry to check
+    selected: HashMap<u32, bool>,
+    /// Per entry name, so a name reused with new IDs keeps its history
+    states: HashMap<String, EntryState>,
+}
+
+impl RecordVisitor for DetectorVisitor<'_> {
+    fn on_start(&mut self, start: &StartRecordData, _timestamp: u64) -> Result<()> {
+        let numeric = matches!(start.entry_type, EntryType::Double | EntryType::Float | EntryType::Int64);
+        let selected = numeric
+            && self
+                .detector
+                .selector
+                .as_ref()
+                .is_none_or(|selector| selector.matches(&start.name));
+        self.selected.insert(start.entry, selected);
+        Ok(())
+    }
+
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        if !self.selected.get(&entry.entry).copied().unwrap_or(false) {
+            return Ok(());
+        }
+
+        let record = DataLogRecord {
+            entry: entry.entry,
+            timestamp,
+            data: payload.to_vec(),
+        };
+        let value = match record.decode_as(&entry.entry_type) {
+            Ok(DecodedValue::Double(v)) => v,
+            Ok(DecodedValue::Float(v)) => v as f64,
+            Ok(DecodedValue::Int64(v)) => v as f64,
+            _ => return Ok(()),
+        };
+        if !value.is_finite() {
+            return Ok(());
+        }
+
+        let state = self.states.entry(entry.name.clone()).or_default();
+        state.observe(self.detector, &entry.name, timestamp as f64 / 1_000_000.0, value);
+        Ok(())
+    }
+}
+
+/// Detection state of one entry.
+#[derive(Default)]
+struct EntryState {
+    /// Recent non-spike samples
+    window: VecDeque<f64>,
+    /// Spike interval being extended
+    spike: Option<Anomaly>,
+    /// Current run of equal values
+    run: Option<Run>,
+    /// Whether the entry has taken more than one value
+    varied: bool,
+    spikes: Vec<Anomaly>,
+    flatlines: Vec<Anomaly>,
+}
+
+impl EntryState {
+    fn observe(&mut self, detector: &AnomalyDetector, entry: &str, t: f64, value: f64) {
+        // Flatlines: runs of identical values
+        match &mut self.run {
+            Some(run) if run.value == value => {
+                run.end = t;
+                run.samples += 1;
+            }
+            _ => {
+                let run = Run {
+                    value,
+                    start: t,
+                    end: t,
+                    samples: 1,
+                };
+                if let Some(previous) = self.run.replace(run) {
+                    self.varied = true;
+                    self.flatlines.extend(previous.flatline(entry, detector.flatline_secs));
+                }
+            }
+        }
+
+        // Spikes: z-score against the window of preceding samples
+        match self.z_score(detector.window, value).filter(|z| z.abs() > detector.z_threshold) {
+            Some(z) => {
+                let spike = self.spike.get_or_insert_with(|| Anomaly {
+                    entry: entry.to_string(),
+                    kind: AnomalyKind::Spike,
+                    start_secs: t,
+                    end_secs: t,
+                    samples: 0,
+                    value,
+                    z_score: 0.0,
+                });
+                spike.end_secs = t;
+                spike.samples += 1;
+                if z.abs() > spike.z_score {
+                    spike.z_score = z.abs();
+                    spike.value = value;
+                }
+            }
+            None => {
+                // Spikes stay out of the window so they don't mask the next one
+                self.spikes.extend(self.spike.take());
+                self.window.push_back(value);
+                if self.window.len() > detector.window {
+                    self.window.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Z-score of `value` against a full window, if it has any spread.
+    fn z_score(&self, window: usize, value: f64) -> Option<f64> {
+        if self.window.len() < window {
+            return None;
+        }
+        let n = self.window.len() as f64;
+        let mean = self.window.iter().sum::<f64>() / n;
+        let variance = self.window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        let std = variance.sqrt();
+        (std > 0.0).then(|| (value - mean) / std)
+    }
+
+    fn finish(mut self, entry: &str, flatline_secs: Option<f64>) -> Vec<Anomaly> {
+        self.spikes.extend(self.spike.take());
+        if let Some(run) = self.run.take() {
+            self.flatlines.extend(run.flatline(entry, flatline_secs));
+        }
+        // A constant entry is not a stuck sensor
+        if self.varied {
+            self.spikes.append(&mut self.flatlines);
+        }
+        self.spikes
+    }
+}
+
+/// A run of samples with the same value.
+struct Run {
+    value: f64,
+    start: f64,
+    end: f64,
+    samples: usize,
+}
+
+impl Run {
+    /// The run as a flatline anomaly, if it lasted at least `flatline_secs`.
+    fn flatline(&self, entry: &str, flatline_secs: Option<f64>) -> Option<Anomaly> {
+        flatline_secs
+            .is_some_and(|secs| self.samples > 1 && self.end - self.start >= secs)
+            .then(|| Anomaly {
+                entry: entry.to_string(),
+                kind: AnomalyKind::Flatline,
+                start_secs: self.start,
+                end_secs: self.end,
+                samples: self.samples,
+                value: self.value,
+                z_score: 0.0,
+            })
+    }
+}
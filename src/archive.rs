@@ -0,0 +1,223 @@
+//! Logs packed in `.zip` and `.tar`/`.tar.gz` archives.
+//!
+//! Sync tools often hand over a match's logs as one archive. [`LogSet`] reads
+//! the `.wpilog` files out of it into memory, so they can be converted without
+//! extracting them first:
+//!
+//! ```no_run
+//! use wpilog_parser::{LogSet, ParquetWriter};
+//!
+//! for log in &LogSet::from_archive("event.zip")? {
+//!     let records = log.reader()?.read_all()?;
+//!     ParquetWriter::new(format!("out/filename={}", log.stem())).write(&records)?;
+//! }
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+
+use crate::error::{Error, Result};
+use crate::reader::{WpilogReader, WpilogReaderBuilder};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Offset of the `ustar` magic in a tar header.
+const TAR_MAGIC_OFFSET: usize = 257;
+
+/// A log read out of an archive.
+#[derive(Debug, Clone)]
+pub struct ArchivedLog {
+    name: String,
+    data: Arc<[u8]>,
+}
+
+impl ArchivedLog {
+    /// Path of the log inside the archive, e.g. `logs/FRC_20240302_1.wpilog`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// File name of the log without its directory.
+    pub fn file_name(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+
+    /// File name of the log without its extension, as used for
+    /// `filename=<stem>` output directories.
+    pub fn stem(&self) -> &str {
+        let file_name = self.file_name();
+        file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem)
+    }
+
+    /// Bytes of the log.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Open the log with default options.
+    pub fn reader(&self) -> Result<WpilogReader> {
+        WpilogReader::from_shared_bytes(Arc::clone(&self.data))
+    }
+
+    /// Open the log with the options of `builder`.
+    pub fn reader_with(&self, builder: WpilogReaderBuilder) -> Result<WpilogReader> {
+        builder.from_shared_bytes(Arc::clone(&self.data))
+    }
+}
+
+/// The `.wpilog` files of an archive, in name order.
+///
+/// Other files, directories and macOS resource forks (`._*`) are skipped.
+/// Every log is held in memory.
+#[derive(Debug, Clone, Default)]
+pub struct LogSet {
+    logs: Vec<ArchivedLog>,
+}
+
+impl LogSet {
+    /// Read the logs of the `.zip`, `.tar` or `.tar.gz` archive at `path`.
+    ///
+    /// The format is detected from the archive's contents, not its extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFormat`] if the file is not one of these
+    /// archives, or an error if it cannot be read.
+    pub fn from_archive<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = BufReader::new(File::open(path)?);
+        let mut prefix = Vec::new();
+        (&mut file).take(TAR_MAGIC_OFFSET as u64 + 5).read_to_end(&mut prefix)?;
+        file.seek(SeekFrom::Start(0))?;
+        match ArchiveFormat::detect(&prefix) {
+            Some(ArchiveFormat::Zip) => Self::from_zip(file),
+            Some(ArchiveFormat::TarGz) => Self::from_tar(GzDecoder::new(file)),
+            Some(ArchiveFormat::Tar) => Self::from_tar(file),
+            None => Err(Error::InvalidFormat(format!(
+                "{} is not a zip or tar archive",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Read the logs of a zip archive.
+    pub fn from_zip<R: Read + Seek>(reader: R) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(reader).map_err(zip_error)?;
+        let mut logs = Vec::new();
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index).map_err(zip_error)?;
+            if !file.is_file() || !is_log_name(file.name()) {
+                continue;
+            }
+            let name = file.name().to_string();
+            let mut data = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut data)?;
+            logs.push(ArchivedLog { name, data: data.into() });
+        }
+        Ok(Self::from_logs(logs))
+    }
+
+    /// Read the logs of an uncompressed tar archive; wrap the reader in a
+    /// [`GzDecoder`] for `.tar.gz`.
+    pub fn from_tar<R: Read>(reader: R) -> Result<Self> {
+        let mut archive = tar::Archive::new(reader);
+        let mut logs = Vec::new();
+        for file in archive.entries()? {
+            let mut file = file?;
+            if !file.header().entry_type().is_file() {
+                continue;
+            }
+            let name = file.path()?.to_string_lossy().replace('\\', "/");
+            if !is_log_name(&name) {
+                continue;
+            }
+            let mut data = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut data)?;
+            logs.push(ArchivedLog { name, data: data.into() });
+        }
+        Ok(Self::from_logs(logs))
+    }
+
+    fn from_logs(mut logs: Vec<ArchivedLog>) -> Self {
+        logs.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { logs }
+    }
+
+    /// Number of logs.
+    pub fn len(&self) -> usize {
+        self.logs.len()
+    }
+
+    /// Whether the archive held no logs.
+    pub fn is_empty(&self) -> bool {
+        self.logs.is_empty()
+    }
+
+    /// Iterate over the logs in name order.
+    pub fn iter(&self) -> std::slice::Iter<'_, ArchivedLog> {
+        self.logs.iter()
+    }
+
+    /// The log at `name` inside the archive.
+    pub fn get(&self, name: &str) -> Option<&ArchivedLog> {
+        self.logs.iter().find(|log| log.name == name)
+    }
+}
+
+impl<'a> IntoIterator for &'a LogSet {
+    type Item = &'a ArchivedLog;
+    type IntoIter = std::slice::Iter<'a, ArchivedLog>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for LogSet {
+    type Item = ArchivedLog;
+    type IntoIter = std::vec::IntoIter<ArchivedLog>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.logs.into_iter()
+    }
+}
+
+/// Archive formats recognized by [`LogSet::from_archive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Recognize an archive from its first bytes.
+    fn detect(prefix: &[u8]) -> Option<Self> {
+        if prefix.starts_with(b"PK\x03\x04") || prefix.starts_with(b"PK\x05\x06") {
+            Some(ArchiveFormat::Zip)
+        } else if prefix.starts_with(&[0x1f, 0x8b]) {
+            Some(ArchiveFormat::TarGz)
+        } else if prefix.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + 5) == Some(b"ustar") {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a file in an archive is a log, leaving out macOS resource forks.
+fn is_log_name(name: &str) -> bool {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+    !file_name.starts_with("._")
+        && Path::new(file_name)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("wpilog"))
+}
+
+fn zip_error(e: zip::result::ZipError) -> Error {
+    match e {
+        zip::result::ZipError::Io(e) => Error::Io(e),
+        e => Error::InvalidFormat(format!("Invalid zip archive: {}", e)),
+    }
+}
@@ -0,0 +1,104 @@
+//! In-memory Arrow record batches of decoded rows.
+//!
+//! [`ArrowConverter`] builds the same wide-format batches that
+//! [`ParquetWriter`](crate::ParquetWriter) writes to files, so rows can be
+//! handed to DataFusion, Polars or another Arrow consumer without a round
+//! trip through Parquet.
+
+use crate::error::{Error, Result};
+use crate::formats::parquet::{ColumnOrder, ColumnType, ParquetFormatter};
+use crate::models::WideRow;
+use arrow::array::RecordBatch;
+use std::collections::HashMap;
+
+/// Converts wide rows to Arrow [`RecordBatch`]es.
+///
+/// Batches have the schema of the files written by
+/// [`ParquetWriter`](crate::ParquetWriter): the `timestamp`, `entry`, `type`
+/// and `loop_count` columns followed by one typed column per value column,
+/// inferred once over all rows converted together.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{ArrowConverter, WpilogReader};
+///
+/// let records = WpilogReader::from_file("data.wpilog")?.read_all()?;
+/// let batches = ArrowConverter::new().to_record_batches(&records, 50_000)?;
+/// for batch in &batches {
+///     println!("{} rows, {} columns", batch.num_rows(), batch.num_columns());
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub struct ArrowConverter {
+    formatter: ParquetFormatter,
+}
+
+impl ArrowConverter {
+    /// Create a converter with inferred column types, sorted by name.
+    pub fn new() -> Self {
+        Self {
+            formatter: ParquetFormatter::new(String::new(), 1),
+        }
+    }
+
+    /// Override the inferred types of value columns; see
+    /// [`ParquetWriter::column_types`](crate::ParquetWriter::column_types).
+    pub fn column_types(mut self, column_types: HashMap<String, ColumnType>) -> Self {
+        self.formatter = self.formatter.with_column_types(column_types);
+        self
+    }
+
+    /// Set the order of value columns.
+    pub fn column_order(mut self, order: ColumnOrder) -> Self {
+        self.formatter = self.formatter.with_column_order(order);
+        self
+    }
+
+    /// Add a column holding `value` in every row.
+    pub fn constant_column(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.formatter = self.formatter.with_constant_column(name, value);
+        self
+    }
+
+    /// Convert all of `rows` into one batch.
+    pub fn to_record_batch(&self, rows: &[WideRow]) -> Result<RecordBatch> {
+        self.formatter.record_batch(rows)
+    }
+
+    /// Convert `rows` into batches of at most `chunk_size` rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `chunk_size` is zero.
+    pub fn to_record_batches(&self, rows: &[WideRow], chunk_size: usize) -> Result<Vec<RecordBatch>> {
+        self.record_batches(rows, chunk_size)?.collect()
+    }
+
+    /// Lazily convert `rows` into batches of at most `chunk_size` rows, so
+    /// only one batch is built at a time. Every batch has the same schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `chunk_size` is zero.
+    pub fn record_batches<'a>(
+        &'a self,
+        rows: &'a [WideRow],
+        chunk_size: usize,
+    ) -> Result<impl Iterator<Item = Result<RecordBatch>> + 'a> {
+        if chunk_size == 0 {
+            return Err(Error::Config("chunk_size must be positive".to_string()));
+        }
+        let chunks: Vec<&[WideRow]> = rows.chunks(chunk_size).collect();
+        let schema = self.formatter.unified_schema(&chunks)?;
+        Ok(chunks
+            .into_iter()
+            .map(move |chunk| self.formatter.record_batch_with_schema(chunk, &schema)))
+    }
+}
+
+impl Default for ArrowConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,166 @@
+//! Export of entries whose payloads are not decoded.
+//!
+//! `proto:`, `raw` and unrecognized vendor types are written as null columns
+//! by the wide and long formats. [`WpilogReader::read_blobs`] collects their
+//! payloads instead, and a [`BlobStore`] writes them next to the main table,
+//! keyed by entry and timestamp, so nothing in the log is lost:
+//!
+//! ```no_run
+//! use wpilog_parser::{BlobStore, ParquetWriter, WpilogReaderBuilder};
+//!
+//! let reader = WpilogReaderBuilder::new()
+//!     .exclude_undecoded(true)
+//!     .from_file("data.wpilog")?;
+//! BlobStore::parquet("output/blobs.parquet").write(&reader.read_blobs()?)?;
+//! ParquetWriter::new("output").write(&reader.read_all()?)?;
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+//!
+//! [`WpilogReader::read_blobs`]: crate::WpilogReader::read_blobs
+
+use crate::datalog::{RecordVisitor, StartRecordData};
+use crate::error::Result;
+use arrow::array::{ArrayRef, BinaryArray, Float64Array, RecordBatch, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Payload of one data record of an undecoded entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blob {
+    /// Record timestamp in microseconds
+    pub timestamp: u64,
+    /// Entry ID
+    pub entry: u32,
+    /// Entry name
+    pub name: String,
+    /// Declared type, e.g. `proto:Pose2d`
+    pub type_name: String,
+    /// Undecoded payload
+    pub data: Vec<u8>,
+}
+
+/// Where [`Blob`]s are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlobStore {
+    /// One Parquet file with `timestamp` (seconds, as in the main table),
+    /// `entry`, `name`, `type` and `data` (Binary) columns
+    Parquet(PathBuf),
+    /// A directory with one `<entry name>/<timestamp>.bin` file per record;
+    /// records of an entry sharing a timestamp get `-1`, `-2`, ... suffixes
+    Directory(PathBuf),
+}
+
+impl BlobStore {
+    /// Store blobs in a single Parquet file.
+    pub fn parquet<P: AsRef<Path>>(path: P) -> Self {
+        BlobStore::Parquet(path.as_ref().to_path_buf())
+    }
+
+    /// Store blobs as files in a directory.
+    pub fn directory<P: AsRef<Path>>(path: P) -> Self {
+        BlobStore::Directory(path.as_ref().to_path_buf())
+    }
+
+    /// Write `blobs`, returning how many were written. Nothing is created
+    /// when there are none.
+    pub fn write(&self, blobs: &[Blob]) -> Result<usize> {
+        if blobs.is_empty() {
+            return Ok(0);
+        }
+        match self {
+            BlobStore::Parquet(path) => write_parquet(path, blobs)?,
+            BlobStore::Directory(dir) => write_directory(dir, blobs)?,
+        }
+        Ok(blobs.len())
+    }
+}
+
+fn write_parquet(path: &Path, blobs: &[Blob]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("timestamp", DataType::Float64, false),
+        Field::new("entry", DataType::UInt32, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("type", DataType::Utf8, false),
+        Field::new("data", DataType::Binary, false),
+    ]));
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from_iter_values(
+            blobs.iter().map(|blob| blob.timestamp as f64 / 1_000_000.0),
+        )),
+        Arc::new(UInt32Array::from_iter_values(blobs.iter().map(|blob| blob.entry))),
+        Arc::new(StringArray::from_iter_values(blobs.iter().map(|blob| &blob.name))),
+        Arc::new(StringArray::from_iter_values(blobs.iter().map(|blob| &blob.type_name))),
+        Arc::new(BinaryArray::from_iter_values(blobs.iter().map(|blob| &blob.data))),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+fn write_directory(dir: &Path, blobs: &[Blob]) -> Result<()> {
+    let mut seen: HashMap<(PathBuf, u64), usize> = HashMap::new();
+    for blob in blobs {
+        let entry_dir = dir.join(entry_path(&blob.name));
+        fs::create_dir_all(&entry_dir)?;
+        let repeats = seen.entry((entry_dir.clone(), blob.timestamp)).or_insert(0);
+        let file = match *repeats {
+            0 => format!("{}.bin", blob.timestamp),
+            n => format!("{}-{}.bin", blob.timestamp, n),
+        };
+        *repeats += 1;
+        fs::write(entry_dir.join(file), &blob.data)?;
+    }
+    Ok(())
+}
+
+/// Relative directory for an entry name: one component per `/`-separated
+/// segment, with characters unsafe in file names replaced by `_`.
+fn entry_path(name: &str) -> PathBuf {
+    let path: PathBuf = name
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match segment {
+            "." | ".." => "_".to_string(),
+            _ => segment
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+                .collect(),
+        })
+        .collect();
+    if path.as_os_str().is_empty() {
+        PathBuf::from("_")
+    } else {
+        path
+    }
+}
+
+/// Collects the data records of undecoded entries.
+#[derive(Default)]
+pub(crate) struct BlobCollector {
+    pub(crate) blobs: Vec<Blob>,
+}
+
+impl RecordVisitor for BlobCollector {
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        if entry.entry_type.unsupported_hint().is_some() {
+            self.blobs.push(Blob {
+                timestamp,
+                entry: entry.entry,
+                name: entry.name.clone(),
+                type_name: entry.type_name.clone(),
+                data: payload.to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+//! Advice for fitting a conversion into a size budget.
+//!
+//! A handful of high-rate or array-valued entries usually make up most of a
+//! log. [`SizeBudget`] looks at the per-entry payload sizes of a
+//! [`QuickScan`] and suggests the least lossy way to bring the log under a
+//! target size: first a maximum sample rate, then excluding the largest
+//! entries if downsampling alone is not enough. The suggestion can be saved
+//! as a [`FilterProfile`]:
+//!
+//! ```no_run
+//! use wpilog_parser::{ProfileSet, SizeBudget, WpilogReader};
+//!
+//! let scan = WpilogReader::from_file("data.wpilog")?.quick_scan()?;
+//! let advice = SizeBudget::new(50_000_000).advise(&scan)?;
+//! for entry in &advice.entries {
+//!     println!("{}: {:?}", entry.name, entry.action);
+//! }
+//!
+//! let mut profiles = ProfileSet::default();
+//! profiles.insert("budget", advice.profile());
+//! profiles.save("wpilog-profiles.json")?;
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+
+use crate::error::{Error, Result};
+use crate::index::QuickScan;
+use crate::profile::FilterProfile;
+use serde::{Deserialize, Serialize};
+
+/// Sample rates tried for downsampling, highest first.
+const CANDIDATE_RATES_HZ: [f64; 12] = [
+    1000.0, 500.0, 250.0, 200.0, 100.0, 50.0, 25.0, 20.0, 10.0, 5.0, 2.0, 1.0,
+];
+
+/// Suggests how to reduce a log to a target payload size.
+///
+/// Sizes are the payload bytes counted by [`QuickScan::entry_sizes`], which
+/// track the size of the converted output closely enough to compare entries
+/// and budgets. Downsampled sizes are estimated assuming each entry's records
+/// are spread evenly over the log.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeBudget {
+    target_bytes: u64,
+    min_rate_hz: f64,
+}
+
+impl SizeBudget {
+    /// Aim for at most `target_bytes` of payload, downsampling to no less
+    /// than 10 Hz.
+    pub fn new(target_bytes: u64) -> Self {
+        Self {
+            target_bytes,
+            min_rate_hz: 10.0,
+        }
+    }
+
+    /// Lowest sample rate to suggest before excluding entries instead.
+    pub fn min_rate_hz(mut self, hz: f64) -> Self {
+        self.min_rate_hz = hz;
+        self
+    }
+
+    /// Work out the advice for the log `scan` describes.
+    ///
+    /// The highest rate from a fixed list of round rates (1000 Hz down to
+    /// 1 Hz) that meets the budget is suggested. If even `min_rate_hz` is not
+    /// enough, entries are excluded, largest first, until the rest fit;
+    /// `.schema/` entries are never excluded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the target is zero or the minimum rate is
+    /// not positive.
+    pub fn advise(&self, scan: &QuickScan) -> Result<SizeAdvice> {
+        if self.target_bytes == 0 {
+            return Err(Error::Config("size budget must be positive".to_string()));
+        }
+        if self.min_rate_hz.is_nan() || self.min_rate_hz <= 0.0 {
+            return Err(Error::Config("min_rate_hz must be positive".to_string()));
+        }
+
+        let duration = scan.duration_secs();
+        let sizes = scan.entry_sizes();
+        let current_bytes = scan.data_bytes();
+        let mut advice = SizeAdvice {
+            target_bytes: self.target_bytes,
+            current_bytes,
+            estimated_bytes: current_bytes,
+            max_rate_hz: None,
+            entries: Vec::new(),
+        };
+        if current_bytes <= self.target_bytes {
+            return Ok(advice);
+        }
+
+        // Rates in records per second; without a duration rates are unknown
+        // and downsampling cannot be estimated
+        let rate = |records: u64| (duration > 0.0).then(|| records as f64 / duration);
+        let downsampled = |bytes: u64, records: u64, hz: f64| match rate(records) {
+            Some(rate) if rate > hz => {
+                let kept = (hz * duration).ceil() + 1.0;
+                ((bytes as f64 * kept / records as f64) as u64).min(bytes)
+            }
+            _ => bytes,
+        };
+        let total_at = |hz: f64| -> u64 {
+            sizes
+                .iter()
+                .map(|size| downsampled(size.bytes, size.records, hz))
+                .sum()
+        };
+
+        let fastest = sizes.iter().filter_map(|size| rate(size.records)).fold(0.0, f64::max);
+        let mut rates: Vec<f64> = CANDIDATE_RATES_HZ
+            .iter()
+            .copied()
+            .filter(|&hz| hz >= self.min_rate_hz && hz < fastest)
+            .collect();
+        if self.min_rate_hz < fastest && !rates.contains(&self.min_rate_hz) {
+            rates.push(self.min_rate_hz);
+        }
+        advice.max_rate_hz = rates
+            .iter()
+            .copied()
+            .find(|&hz| total_at(hz) <= self.target_bytes)
+            .or_else(|| rates.last().copied());
+
+        let mut entries: Vec<EntryAdvice> = sizes
+            .iter()
+            .map(|size| {
+                let estimated_bytes = match advice.max_rate_hz {
+                    Some(hz) => downsampled(size.bytes, size.records, hz),
+                    None => size.bytes,
+                };
+                EntryAdvice {
+                    name: size.name.clone(),
+                    action: EntryAction::Downsample {
+                        rate_hz: rate(size.records).unwrap_or(0.0),
+                    },
+                    bytes: size.bytes,
+                    estimated_bytes,
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.estimated_bytes
+                .cmp(&a.estimated_bytes)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut estimated_bytes: u64 = entries.iter().map(|entry| entry.estimated_bytes).sum();
+        // Struct schemas are needed to decode the struct entries kept
+        for entry in entries.iter_mut().filter(|entry| !entry.name.starts_with(".schema/")) {
+            if estimated_bytes <= self.target_bytes {
+                break;
+            }
+            estimated_bytes -= entry.estimated_bytes;
+            entry.action = EntryAction::Exclude;
+            entry.estimated_bytes = 0;
+        }
+
+        // Keep only the entries the advice changes, biggest savings first
+        entries.retain(|entry| entry.estimated_bytes < entry.bytes);
+        entries.sort_by(|a, b| {
+            (b.bytes - b.estimated_bytes)
+                .cmp(&(a.bytes - a.estimated_bytes))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        advice.estimated_bytes = estimated_bytes;
+        advice.entries = entries;
+        Ok(advice)
+    }
+}
+
+/// The result of [`SizeBudget::advise`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizeAdvice {
+    /// The payload size aimed for
+    pub target_bytes: u64,
+    /// Payload bytes of the log as it is
+    pub current_bytes: u64,
+    /// Estimated payload bytes after following the advice
+    pub estimated_bytes: u64,
+    /// Suggested maximum sample rate of every entry, if downsampling helps
+    pub max_rate_hz: Option<f64>,
+    /// Entries the advice shrinks or drops, biggest savings first
+    pub entries: Vec<EntryAdvice>,
+}
+
+impl SizeAdvice {
+    /// Whether the log fits the budget without changes.
+    pub fn fits(&self) -> bool {
+        self.current_bytes <= self.target_bytes
+    }
+
+    /// Names of the entries to exclude.
+    pub fn excluded(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.action == EntryAction::Exclude)
+            .map(|entry| entry.name.as_str())
+    }
+
+    /// A filter profile applying the advice: the suggested maximum rate and
+    /// an exclude selector per excluded entry.
+    pub fn profile(&self) -> FilterProfile {
+        FilterProfile {
+            // Selectors are globs; `?` stands in for the rare `*` in a name
+            exclude: self.excluded().map(|name| name.replace('*', "?")).collect(),
+            max_rate_hz: self.max_rate_hz,
+            ..Default::default()
+        }
+    }
+}
+
+/// What [`SizeAdvice`] suggests for one entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryAdvice {
+    pub name: String,
+    pub action: EntryAction,
+    /// Payload bytes of the entry as it is
+    pub bytes: u64,
+    /// Estimated payload bytes after following the advice
+    pub estimated_bytes: u64,
+}
+
+/// How an entry is reduced.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum EntryAction {
+    /// Limited to [`SizeAdvice::max_rate_hz`] from its average rate of
+    /// `rate_hz` records per second
+    Downsample { rate_hz: f64 },
+    /// Left out of the conversion
+    Exclude,
+}
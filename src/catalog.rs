@@ -0,0 +1,163 @@
+//! Entry catalog describing the lifetime of every entry in a WPILog file.
+//!
+//! The catalog is built from control records only, so it is cheap to compute
+//! and useful for auditing logging hygiene: entries that were started several
+//! times, entries that were never finished, and how many data records each
+//! entry produced while it was active.
+
+use crate::datalog::DataLogReader;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Extract the `alias` key from an entry's JSON metadata, if present.
+///
+/// Some teams log display names via metadata such as
+/// `{"alias":"Left Drive Current"}`. Non-JSON metadata has no alias.
+pub fn metadata_alias(metadata: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(metadata).ok()?;
+    value
+        .get("alias")?
+        .as_str()
+        .filter(|alias| !alias.is_empty())
+        .map(str::to_string)
+}
+
+/// One Start..Finish lifetime of an entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryLifetime {
+    /// Entry ID assigned by the Start record
+    pub entry: u32,
+    /// Entry name
+    pub name: String,
+    /// Declared type string
+    pub type_name: String,
+    /// Latest metadata (updated by Set Metadata records)
+    pub metadata: String,
+    /// 1-based count of Start records seen so far for this entry name
+    pub generation: u32,
+    /// Timestamp of the Start record (microseconds)
+    pub start_timestamp: u64,
+    /// Timestamp of the Finish record (microseconds), if one was seen
+    pub finish_timestamp: Option<u64>,
+    /// Number of data records logged during this lifetime
+    pub record_count: u64,
+}
+
+impl EntryLifetime {
+    /// Display alias from the metadata's `alias` key, if any.
+    pub fn alias(&self) -> Option<String> {
+        metadata_alias(&self.metadata)
+    }
+
+    /// Whether a Finish record closed this lifetime.
+    pub fn is_finished(&self) -> bool {
+        self.finish_timestamp.is_some()
+    }
+}
+
+/// Catalog of all entry lifetimes in a log, in Start-record order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryCatalog {
+    lifetimes: Vec<EntryLifetime>,
+}
+
+impl EntryCatalog {
+    /// Build a catalog by scanning the records of a log.
+    pub fn from_reader(reader: &DataLogReader<'_>) -> Result<Self> {
+        let mut lifetimes: Vec<EntryLifetime> = Vec::new();
+        let mut active: HashMap<u32, usize> = HashMap::new();
+        let mut generations: HashMap<String, u32> = HashMap::new();
+
+        for record_result in reader.records()?.allow_truncated() {
+            let record = record_result?;
+
+            if record.is_start() {
+                let data = record.get_start_data()?;
+                let generation = generations.entry(data.name.clone()).or_insert(0);
+                *generation += 1;
+
+                active.insert(data.entry, lifetimes.len());
+                lifetimes.push(EntryLifetime {
+                    entry: data.entry,
+                    name: data.name,
+                    type_name: data.type_name,
+                    metadata: data.metadata,
+                    generation: *generation,
+                    start_timestamp: record.timestamp,
+                    finish_timestamp: None,
+                    record_count: 0,
+                });
+            } else if record.is_finish() {
+                let entry = record.get_finish_entry()?;
+                if let Some(idx) = active.remove(&entry) {
+                    lifetimes[idx].finish_timestamp = Some(record.timestamp);
+                }
+            } else if record.is_set_metadata() {
+                let data = record.get_set_metadata_data()?;
+                if let Some(&idx) = active.get(&data.entry) {
+                    lifetimes[idx].metadata = data.metadata;
+                }
+            } else if !record.is_control() {
+                if let Some(&idx) = active.get(&record.entry) {
+                    lifetimes[idx].record_count += 1;
+                }
+            }
+        }
+
+        Ok(Self { lifetimes })
+    }
+
+    /// All lifetimes in the order their Start records appeared.
+    pub fn lifetimes(&self) -> &[EntryLifetime] {
+        &self.lifetimes
+    }
+
+    /// Number of lifetimes in the catalog.
+    pub fn len(&self) -> usize {
+        self.lifetimes.len()
+    }
+
+    /// Whether the catalog has no lifetimes.
+    pub fn is_empty(&self) -> bool {
+        self.lifetimes.is_empty()
+    }
+
+    /// All lifetimes of the entry with the given name.
+    pub fn generations<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a EntryLifetime> + 'a {
+        self.lifetimes.iter().filter(move |l| l.name == name)
+    }
+
+    /// Names of entries that were started more than once, sorted.
+    pub fn restarted_entries(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .lifetimes
+            .iter()
+            .filter(|l| l.generation > 1)
+            .map(|l| l.name.as_str())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Mapping of entry name to metadata alias for all aliased entries.
+    ///
+    /// If an entry was started several times, the latest alias wins.
+    pub fn aliases(&self) -> BTreeMap<String, String> {
+        self.lifetimes
+            .iter()
+            .filter_map(|l| l.alias().map(|alias| (l.name.clone(), alias)))
+            .collect()
+    }
+
+    /// Lifetimes that were never closed by a Finish record.
+    pub fn unfinished(&self) -> impl Iterator<Item = &EntryLifetime> {
+        self.lifetimes.iter().filter(|l| !l.is_finished())
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -0,0 +1,52 @@
+//! `align`: convert several logs onto a shared relative-time axis.
+
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+use wpilog_parser::{LogAligner, ParquetWriter, TimeOrigin};
+
+/// Options for `align`.
+#[derive(Args, Debug)]
+pub struct AlignArgs {
+    /// The .wpilog files to align
+    #[arg(value_name = "FILES", required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Output directory for the aligned Parquet files
+    #[arg(short, long, value_name = "OUT_DIR")]
+    pub output: PathBuf,
+
+    /// Time zero of each log: first-record, first-enabled, auto-start, or a
+    /// log time in seconds
+    #[arg(long, value_name = "ORIGIN", default_value = "auto-start")]
+    pub anchor: TimeOrigin,
+
+    /// Name of the column identifying each row's log (its file stem)
+    #[arg(long, value_name = "NAME", default_value = "log")]
+    pub log_column: String,
+
+    /// Number of rows per Parquet file chunk
+    #[arg(long, default_value = "50000")]
+    pub chunk_size: usize,
+}
+
+pub fn align(args: &AlignArgs) -> Result<()> {
+    let aligner = args
+        .files
+        .iter()
+        .fold(LogAligner::new(args.anchor).log_column(&args.log_column), |aligner, file| {
+            aligner.add_file(file)
+        });
+    let rows = aligner.read_all()?;
+
+    let stats = ParquetWriter::new(&args.output)
+        .chunk_size(args.chunk_size)
+        .write_with_stats(&rows)?;
+    log::info!(
+        "Aligned {} logs into {}: {}",
+        args.files.len(),
+        args.output.display(),
+        stats.summary()
+    );
+    Ok(())
+}
@@ -0,0 +1,186 @@
+//! `convert`: convert a single log to CSV, JSON Lines or Parquet, for shell
+//! pipelines.
+//!
+//! The log may be read from stdin (`-`) and the output defaults to stdout, so
+//! `ssh robot cat log.wpilog | wpilog-parser convert - --format csv` works
+//! without temporary files.
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use wpilog_parser::formats::parquet::ParquetFormatter;
+use wpilog_parser::{
+    JsonlWriter, Quirks, Subsample, TimeOrigin, WideRow, WpilogReader, WpilogReaderBuilder,
+};
+
+use super::csv_field;
+
+/// Output formats for `convert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConvertFormat {
+    /// Wide-format CSV with a header line
+    Csv,
+    /// One JSON object per row (NDJSON), for jq and other JSON tools
+    Jsonl,
+    /// A single wide-format Parquet file
+    Parquet,
+}
+
+/// Options for `convert`.
+#[derive(Args, Debug)]
+pub struct ConvertFileArgs {
+    /// The .wpilog file to convert, or `-` to read it from stdin
+    #[arg(value_name = "FILE")]
+    pub file: String,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "parquet")]
+    pub format: ConvertFormat,
+
+    /// Output file (defaults to stdout)
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Gzip-compress JSON Lines output
+    #[arg(long)]
+    pub gzip: bool,
+
+    /// Rows per Parquet row group
+    #[arg(long, default_value = "50000")]
+    pub chunk_size: usize,
+
+    /// Tolerate off-spec records from third-party loggers
+    #[arg(long)]
+    pub quirks: bool,
+
+    /// Convert only a sample of each entry's records, as a percentage (`1%`)
+    /// or every Nth record (`100`)
+    #[arg(long, value_name = "RATE")]
+    pub sample: Option<Subsample>,
+
+    /// Convert only the first SECONDS of the log
+    #[arg(long, value_name = "SECONDS", conflicts_with = "tail_duration")]
+    pub head_duration: Option<f64>,
+
+    /// Convert only the last SECONDS of the log
+    #[arg(long, value_name = "SECONDS")]
+    pub tail_duration: Option<f64>,
+
+    /// Add a record_id column tracing each row to its record in the log
+    #[arg(long)]
+    pub record_ids: bool,
+
+    /// Re-zero timestamps to first-record, first-enabled, auto-start, or a log
+    /// time in seconds
+    #[arg(long, value_name = "ORIGIN")]
+    pub time_origin: Option<TimeOrigin>,
+}
+
+pub fn convert(args: &ConvertFileArgs) -> Result<()> {
+    if args.gzip && args.format != ConvertFormat::Jsonl {
+        anyhow::bail!("--gzip only applies to --format jsonl");
+    }
+    let mut builder = WpilogReaderBuilder::new();
+    if args.quirks {
+        builder = builder.quirks(Quirks::all());
+    }
+    if let Some(sample) = args.sample {
+        builder = builder.subsample(sample);
+    }
+    if let Some(seconds) = args.head_duration {
+        builder = builder.head_duration(Duration::try_from_secs_f64(seconds)?);
+    }
+    if let Some(seconds) = args.tail_duration {
+        builder = builder.tail_duration(Duration::try_from_secs_f64(seconds)?);
+    }
+    if let Some(origin) = args.time_origin {
+        builder = builder.time_origin(origin);
+    }
+    if args.record_ids {
+        builder = builder.record_ids(true);
+    }
+    let reader = if args.file == "-" {
+        let mut data = Vec::new();
+        io::stdin().lock().read_to_end(&mut data)?;
+        builder.from_bytes(data)?
+    } else {
+        builder.from_file(&args.file)?
+    };
+
+    let out: Box<dyn Write + Send> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    match args.format {
+        ConvertFormat::Csv => write_csv(&reader, out),
+        ConvertFormat::Jsonl if args.gzip => write_jsonl(&reader, JsonlWriter::gzip(out)),
+        ConvertFormat::Jsonl => write_jsonl(&reader, JsonlWriter::new(out)),
+        ConvertFormat::Parquet => write_parquet(reader, out, args.chunk_size),
+    }
+}
+
+/// Stream rows as CSV. A first pass over the log collects the column names
+/// for the header, so rows are written as they are decoded instead of being
+/// held in memory.
+fn write_csv(reader: &WpilogReader, mut out: Box<dyn Write + Send>) -> Result<()> {
+    let mut columns = BTreeSet::new();
+    reader.stream(|row| {
+        for column in row.data.keys() {
+            if !columns.contains(column) {
+                columns.insert(column.clone());
+            }
+        }
+        Ok(())
+    })?;
+
+    write!(out, "timestamp,entry,type,loop_count")?;
+    for column in &columns {
+        write!(out, ",{}", csv_field(&serde_json::Value::String(column.clone())))?;
+    }
+    writeln!(out)?;
+
+    reader.stream(|row| Ok(write_csv_row(&mut out, &row, &columns)?))?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_csv_row(out: &mut impl Write, row: &WideRow, columns: &BTreeSet<String>) -> io::Result<()> {
+    write!(out, "{},{},{},{}", row.timestamp, row.entry, row.type_name, row.loop_count)?;
+    for column in columns {
+        match row.data.get(column) {
+            Some(value) => write!(out, ",{}", csv_field(value))?,
+            None => write!(out, ",")?,
+        }
+    }
+    writeln!(out)
+}
+
+/// Stream rows as JSON Lines.
+fn write_jsonl(reader: &WpilogReader, mut writer: JsonlWriter<Box<dyn Write + Send>>) -> Result<()> {
+    reader.stream(|row| writer.write_row(&row))?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Write every row to one Parquet file. The schema has to cover all rows
+/// before the first row group is written, so rows are decoded up front; row
+/// groups of `chunk_size` rows are written out as they fill.
+fn write_parquet(reader: WpilogReader, out: Box<dyn Write + Send>, chunk_size: usize) -> Result<()> {
+    let rows = reader.read_all()?;
+    let batch = ParquetFormatter::new(String::new(), chunk_size).record_batch(&rows)?;
+    drop(rows);
+
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(chunk_size.max(1))
+        .build();
+    let mut writer = ArrowWriter::try_new(out, batch.schema(), Some(properties))?;
+    writer.write(&batch)?;
+    writer.into_inner()?.flush()?;
+    Ok(())
+}
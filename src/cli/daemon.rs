@@ -0,0 +1,56 @@
+//! `daemon`: watch a directory and convert new logs as they appear.
+
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+use std::time::Duration;
+use wpilog_parser::IngestDaemon;
+
+/// Options for `daemon`.
+#[derive(Args, Debug)]
+pub struct DaemonArgs {
+    /// Directory to watch for .wpilog files
+    #[arg(value_name = "IN_DIR")]
+    pub in_dir: PathBuf,
+
+    /// Root output directory; conversions are recorded in manifest.jsonl here
+    #[arg(short, long, value_name = "OUT_ROOT")]
+    pub out_root: PathBuf,
+
+    /// Seconds between directory scans
+    #[arg(long, default_value = "10")]
+    pub interval: u64,
+
+    /// Seconds a log must go unmodified before it is converted
+    #[arg(long, default_value = "5")]
+    pub min_age: u64,
+
+    /// Number of rows per Parquet file chunk
+    #[arg(long, default_value = "50000")]
+    pub chunk_size: usize,
+
+    /// Delete raw logs after their conversion has been verified
+    #[arg(long, conflicts_with = "incremental")]
+    pub prune: bool,
+
+    /// Convert logs while they are written, appending chunk files for the
+    /// records added since each scan
+    #[arg(long)]
+    pub incremental: bool,
+}
+
+pub fn daemon(args: &DaemonArgs) -> Result<()> {
+    log::info!(
+        "Watching {} for new logs (output: {})",
+        args.in_dir.display(),
+        args.out_root.display()
+    );
+    IngestDaemon::new(&args.in_dir, &args.out_root)
+        .poll_interval(Duration::from_secs(args.interval))
+        .min_age(Duration::from_secs(args.min_age))
+        .chunk_size(args.chunk_size)
+        .prune_raw(args.prune)
+        .incremental(args.incremental)
+        .run()?;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+//! `dump`: print records as logged, for troubleshooting logs that do not
+//! parse as expected.
+
+use anyhow::Result;
+use clap::Args;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use wpilog_parser::low_level::{hex_dump, DataLogReader, DataLogRecord, StartRecordData};
+use wpilog_parser::Quirks;
+
+/// Options for `dump`.
+#[derive(Args, Debug)]
+pub struct DumpArgs {
+    /// The .wpilog file to dump
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Number of records to print (default: all)
+    #[arg(short = 'n', long, value_name = "COUNT")]
+    pub count: Option<usize>,
+
+    /// Only print data records of this entry
+    #[arg(short, long, value_name = "NAME")]
+    pub entry: Option<String>,
+
+    /// Also print each record's header bytes, payload hex and candidate
+    /// interpretations of the payload
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Tolerate off-spec records from third-party loggers
+    #[arg(long)]
+    pub quirks: bool,
+}
+
+pub fn dump(args: &DumpArgs) -> Result<()> {
+    let data = fs::read(&args.file)?;
+    let mut reader = DataLogReader::new(&data);
+    if args.quirks {
+        reader = reader.with_quirks(Quirks::all());
+    }
+    let mut records = reader.records()?;
+    let mut entries: HashMap<u32, StartRecordData> = HashMap::new();
+    let mut printed = 0;
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    while args.count.is_none_or(|count| printed < count) {
+        let offset = records.position();
+        let Some(record) = records.next() else {
+            break;
+        };
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                writeln!(out, "{:>10}  error: {}", offset, e)?;
+                if args.raw {
+                    let end = data.len().min(offset + 16);
+                    write!(out, "{}", hex_dump(&data[offset..end], "            "))?;
+                }
+                break;
+            }
+        };
+
+        let description = describe(&record, &mut entries);
+        if let Some(name) = &args.entry {
+            let entry_name = entries.get(&record.entry).map(|start| start.name.as_str());
+            if record.is_control() || entry_name != Some(name) {
+                continue;
+            }
+        }
+
+        writeln!(
+            out,
+            "{:>10}  {:>14.6}  {:>5}  {}",
+            offset,
+            record.timestamp as f64 / 1_000_000.0,
+            record.entry,
+            description
+        )?;
+        if args.raw {
+            let payload_start = records.position() - record.data.len();
+            writeln!(out, "            header:")?;
+            write!(out, "{}", hex_dump(&data[offset..payload_start], "              "))?;
+            writeln!(out, "            payload:")?;
+            for line in record.debug_dump().lines().skip(1) {
+                writeln!(out, "            {}", line)?;
+            }
+        }
+        printed += 1;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// One-line description of a record, tracking Start records in `entries` so
+/// data payloads can be decoded by their declared type.
+fn describe(record: &DataLogRecord, entries: &mut HashMap<u32, StartRecordData>) -> String {
+    if record.is_start() {
+        return match record.get_start_data() {
+            Ok(start) => {
+                let description = format!("Start {} ({}) {:?}", start.name, start.type_name, start.metadata);
+                entries.insert(start.entry, start);
+                description
+            }
+            Err(e) => format!("Start <invalid: {}>", e),
+        };
+    }
+    if record.is_finish() {
+        return match record.get_finish_entry() {
+            Ok(entry) => {
+                let name = entries.remove(&entry).map(|start| start.name).unwrap_or_default();
+                format!("Finish {} {}", entry, name)
+            }
+            Err(e) => format!("Finish <invalid: {}>", e),
+        };
+    }
+    if record.is_set_metadata() {
+        return match record.get_set_metadata_data() {
+            Ok(metadata) => format!("SetMetadata {} {:?}", metadata.entry, metadata.metadata),
+            Err(e) => format!("SetMetadata <invalid: {}>", e),
+        };
+    }
+    if record.is_control() {
+        return "<unknown control record>".to_string();
+    }
+
+    match entries.get(&record.entry) {
+        Some(start) => match record.decode_as(&start.entry_type) {
+            Ok(value) => format!("{} = {:?}", start.name, value),
+            Err(e) => format!("{} <undecodable {}: {}>", start.name, start.type_name, e),
+        },
+        None => format!("<no active entry> {} bytes", record.data.len()),
+    }
+}
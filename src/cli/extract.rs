@@ -0,0 +1,71 @@
+//! `extract`: write one entry as a two-column (timestamp, value) file.
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use wpilog_parser::WpilogReader;
+
+use super::csv_field;
+
+/// Output formats for `extract`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExtractFormat {
+    /// `timestamp,value` with a header line
+    Csv,
+    /// One `{"timestamp": ..., "value": ...}` object per line
+    Jsonl,
+}
+
+/// Options for `extract`.
+#[derive(Args, Debug)]
+pub struct ExtractArgs {
+    /// The .wpilog file to read
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Entry to extract
+    #[arg(short, long, value_name = "NAME")]
+    pub entry: String,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "csv")]
+    pub format: ExtractFormat,
+
+    /// Output file (defaults to stdout)
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+pub fn extract(args: &ExtractArgs) -> Result<()> {
+    let reader = WpilogReader::from_file(&args.file)?;
+    let rows = reader.read_entry(&args.entry)?;
+    if rows.is_empty() {
+        anyhow::bail!("No records found for entry '{}'", args.entry);
+    }
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    if args.format == ExtractFormat::Csv {
+        writeln!(out, "timestamp,value")?;
+    }
+    for row in &rows {
+        let value = row.data.get(&args.entry).unwrap_or(&Value::Null);
+        match args.format {
+            ExtractFormat::Csv => writeln!(out, "{},{}", row.timestamp, csv_field(value))?,
+            ExtractFormat::Jsonl => writeln!(
+                out,
+                "{}",
+                serde_json::json!({ "timestamp": row.timestamp, "value": value })
+            )?,
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
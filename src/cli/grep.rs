@@ -0,0 +1,93 @@
+//! `grep`: search string and JSON entries for a regular expression.
+
+use anyhow::Result;
+use clap::Args;
+use regex::{Regex, RegexBuilder};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use wpilog_parser::low_level::{RecordVisitor, StartRecordData};
+use wpilog_parser::{EntryType, WpilogReader};
+
+/// Options for `grep`.
+#[derive(Args, Debug)]
+pub struct GrepArgs {
+    /// Regular expression to search for
+    #[arg(value_name = "PATTERN")]
+    pub pattern: String,
+
+    /// The .wpilog files to search
+    #[arg(value_name = "FILE", required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Match case-insensitively
+    #[arg(short, long)]
+    pub ignore_case: bool,
+
+    /// Only search this entry
+    #[arg(short, long, value_name = "NAME")]
+    pub entry: Option<String>,
+}
+
+pub fn grep(args: &GrepArgs) -> Result<()> {
+    let regex = RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.ignore_case)
+        .build()?;
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    for file in &args.files {
+        let reader = WpilogReader::from_file(file)?;
+        let mut matcher = Matcher {
+            out: &mut out,
+            regex: &regex,
+            entry: args.entry.as_deref(),
+            prefix: if args.files.len() > 1 {
+                format!("{}: ", file.display())
+            } else {
+                String::new()
+            },
+        };
+        reader.low_level_reader().visit(&mut matcher)?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Prints `timestamp  entry  value` for every matching string payload.
+struct Matcher<'a> {
+    out: &'a mut dyn Write,
+    regex: &'a Regex,
+    entry: Option<&'a str>,
+    prefix: String,
+}
+
+impl RecordVisitor for Matcher<'_> {
+    fn on_data(
+        &mut self,
+        entry: &StartRecordData,
+        timestamp: u64,
+        payload: &[u8],
+    ) -> wpilog_parser::Result<()> {
+        if !matches!(entry.entry_type, EntryType::String | EntryType::Json) {
+            return Ok(());
+        }
+        if self.entry.is_some_and(|name| name != entry.name) {
+            return Ok(());
+        }
+        // Payloads are decoded lazily; invalid UTF-8 cannot match
+        let Ok(value) = std::str::from_utf8(payload) else {
+            return Ok(());
+        };
+        if self.regex.is_match(value) {
+            writeln!(
+                self.out,
+                "{}{:>14.6}  {:<40} {}",
+                self.prefix,
+                timestamp as f64 / 1_000_000.0,
+                entry.name,
+                value
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//! `histogram`: print the distribution of one entry's values as text bars.
+
+use anyhow::Result;
+use clap::Args;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use wpilog_parser::stats::{self, Histogram};
+use wpilog_parser::WpilogReader;
+
+/// Width of the longest bar, in characters.
+const BAR_WIDTH: usize = 40;
+
+/// Options for `histogram`.
+#[derive(Args, Debug)]
+pub struct HistogramArgs {
+    /// The .wpilog file to read
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Entry to summarize
+    #[arg(short, long, value_name = "NAME")]
+    pub entry: String,
+
+    /// Number of bins for numeric entries
+    #[arg(short, long, default_value = "20")]
+    pub bins: usize,
+
+    /// Print the histogram as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn histogram(args: &HistogramArgs) -> Result<()> {
+    let reader = WpilogReader::from_file(&args.file)?;
+    let histogram = stats::histogram(&reader, &args.entry, args.bins)?;
+    let mut out = io::stdout().lock();
+
+    if args.json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&histogram)?)?;
+        return Ok(());
+    }
+
+    match &histogram {
+        Histogram::Numeric(numeric) => {
+            let largest = numeric.bins.iter().map(|bin| bin.count).max().unwrap_or(0);
+            for bin in &numeric.bins {
+                writeln!(
+                    out,
+                    "{:>12.4} .. {:<12.4} {:>8}  {}",
+                    bin.lower,
+                    bin.upper,
+                    bin.count,
+                    bar(bin.count as f64, largest as f64)
+                )?;
+            }
+            writeln!(out, "{} samples", numeric.samples)?;
+            if numeric.non_finite > 0 {
+                writeln!(out, "{} NaN or infinite samples not binned", numeric.non_finite)?;
+            }
+        }
+        Histogram::States(states) => {
+            let longest = states.states.first().map_or(0.0, |state| state.duration_secs);
+            let width = states.states.iter().map(|state| state.state.len()).max().unwrap_or(0);
+            for state in &states.states {
+                let share = if states.total_secs > 0.0 {
+                    100.0 * state.duration_secs / states.total_secs
+                } else {
+                    0.0
+                };
+                writeln!(
+                    out,
+                    "{:<width$}  {:>10.3} s {:>5.1}%  {}",
+                    state.state,
+                    state.duration_secs,
+                    share,
+                    bar(state.duration_secs, longest),
+                    width = width
+                )?;
+            }
+            writeln!(out, "{:.3} s total", states.total_secs)?;
+        }
+    }
+    Ok(())
+}
+
+/// A bar of `value` relative to `largest`, at least one character for any
+/// non-zero value.
+fn bar(value: f64, largest: f64) -> String {
+    if value <= 0.0 || largest <= 0.0 {
+        return String::new();
+    }
+    let len = ((value / largest) * BAR_WIDTH as f64).round().max(1.0) as usize;
+    "█".repeat(len)
+}
@@ -0,0 +1,143 @@
+//! `head`, `tail` and `sample`: print decoded records without converting.
+
+use anyhow::Result;
+use clap::Args;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use wpilog_parser::{WideRow, WpilogReader};
+
+use super::print_row;
+
+/// Options shared by the inspection subcommands.
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// The .wpilog file to inspect
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Number of records to print
+    #[arg(short = 'n', long, default_value = "10")]
+    pub count: usize,
+
+    /// Only print records of this entry
+    #[arg(short, long, value_name = "NAME")]
+    pub entry: Option<String>,
+}
+
+/// Options for `sample`.
+#[derive(Args, Debug)]
+pub struct SampleArgs {
+    #[command(flatten)]
+    pub inspect: InspectArgs,
+
+    /// Seed for reproducible samples
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+pub fn head(args: &InspectArgs) -> Result<()> {
+    // Rows are decoded lazily, so the rest of the log is never decoded
+    let reader = WpilogReader::from_file(&args.file)?;
+    let mut rows = Vec::with_capacity(args.count);
+    for row in reader.rows()? {
+        if rows.len() == args.count {
+            break;
+        }
+        let row = row?;
+        if matches_entry(args, &row) {
+            rows.push(row);
+        }
+    }
+    print_rows(rows.iter())
+}
+
+pub fn tail(args: &InspectArgs) -> Result<()> {
+    let mut rows = VecDeque::with_capacity(args.count + 1);
+    for_each_matching(args, |row| {
+        rows.push_back(row);
+        if rows.len() > args.count {
+            rows.pop_front();
+        }
+    })?;
+    print_rows(rows.iter())
+}
+
+pub fn sample(args: &SampleArgs) -> Result<()> {
+    let count = args.inspect.count;
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64)
+    });
+    let mut rng = XorShift::new(seed);
+
+    // Reservoir sampling, keeping each row's position to restore time order
+    let mut reservoir: Vec<(usize, WideRow)> = Vec::with_capacity(count);
+    let mut seen = 0;
+    for_each_matching(&args.inspect, |row| {
+        if reservoir.len() < count {
+            reservoir.push((seen, row));
+        } else {
+            let slot = rng.below(seen as u64 + 1) as usize;
+            if slot < count {
+                reservoir[slot] = (seen, row);
+            }
+        }
+        seen += 1;
+    })?;
+
+    reservoir.sort_by_key(|(index, _)| *index);
+    print_rows(reservoir.iter().map(|(_, row)| row))
+}
+
+fn print_rows<'a>(rows: impl Iterator<Item = &'a WideRow>) -> Result<()> {
+    let mut out = io::stdout().lock();
+    for row in rows {
+        print_row(&mut out, row)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Stream the file's rows, passing those matching `--entry` to `f`.
+fn for_each_matching<F: FnMut(WideRow)>(args: &InspectArgs, mut f: F) -> Result<()> {
+    let reader = WpilogReader::from_file(&args.file)?;
+    reader.stream(|row| {
+        if matches_entry(args, &row) {
+            f(row);
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn matches_entry(args: &InspectArgs, row: &WideRow) -> bool {
+    match &args.entry {
+        Some(entry) => row.data.contains_key(entry),
+        None => true,
+    }
+}
+
+/// Small xorshift64* generator; sampling does not need a cryptographic RNG.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // Zero is a fixed point of xorshift
+        Self(seed | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform value in `0..bound`.
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
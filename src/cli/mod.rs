@@ -0,0 +1,55 @@
+//! Subcommands of the `wpilog-parser` binary.
+
+pub mod align;
+pub mod convert;
+pub mod daemon;
+pub mod dump;
+pub mod extract;
+pub mod grep;
+pub mod histogram;
+pub mod inspect;
+pub mod pid;
+pub mod presence;
+pub mod replay;
+pub mod sizes;
+pub mod timeline;
+pub mod usage;
+pub mod validate;
+
+use std::io::{self, Write};
+use serde_json::Value;
+use wpilog_parser::WideRow;
+
+/// Write a row as `timestamp  column  value`, one line per column.
+pub fn print_row(out: &mut impl Write, row: &WideRow) -> io::Result<()> {
+    let mut columns: Vec<_> = row.data.iter().collect();
+    columns.sort_by(|a, b| a.0.cmp(b.0));
+    for (column, value) in columns {
+        writeln!(out, "{:>14.6}  {:<40} {}", row.timestamp, column, value)?;
+    }
+    Ok(())
+}
+
+/// Format a value as a CSV field; arrays and objects become quoted JSON.
+pub fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// Whether `err` is a closed stdout (e.g., output piped into `head`), which
+/// subcommands treat as a normal way to stop.
+pub fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    let io_error = match err.downcast_ref::<wpilog_parser::Error>() {
+        Some(wpilog_parser::Error::Io(e)) => Some(e),
+        _ => err.downcast_ref::<io::Error>(),
+    };
+    io_error.is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
@@ -0,0 +1,61 @@
+//! `pid`: report how well measurements tracked their setpoints.
+
+use anyhow::{bail, Result};
+use clap::Args;
+use std::path::PathBuf;
+use wpilog_parser::{TrackingAnalysis, TrackingPair, WpilogReader};
+
+/// Options for `pid`.
+#[derive(Args, Debug)]
+pub struct PidArgs {
+    /// The .wpilog file to analyze
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Setpoint and measurement entries as SETPOINT=MEASUREMENT (repeatable)
+    #[arg(short, long = "pair", value_name = "SETPOINT=MEASUREMENT")]
+    pub pairs: Vec<TrackingPair>,
+
+    /// JSON file with an array of {"setpoint": ..., "measurement": ...} pairs
+    #[arg(long, value_name = "PATH")]
+    pub pairs_file: Option<PathBuf>,
+
+    /// Ignore setpoint changes no larger than this
+    #[arg(long, default_value = "0")]
+    pub min_step: f64,
+
+    /// Settling band as a fraction of the step size
+    #[arg(long, default_value = "0.02")]
+    pub settling_band: f64,
+
+    /// Print the reports as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn pid(args: &PidArgs) -> Result<()> {
+    let mut pairs = args.pairs.clone();
+    if let Some(path) = &args.pairs_file {
+        let file_pairs: Vec<TrackingPair> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        pairs.extend(file_pairs);
+    }
+    if pairs.is_empty() {
+        bail!("No setpoint/measurement pairs given; use --pair or --pairs-file");
+    }
+
+    let reader = WpilogReader::from_file(&args.file)?;
+    let reports = TrackingAnalysis::new()
+        .pairs(pairs)
+        .min_step(args.min_step)
+        .settling_band(args.settling_band)
+        .run(&reader)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        for report in &reports {
+            print!("{}", report);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! `presence`: which entries each log of a directory contains.
+
+use anyhow::Result;
+use clap::Args;
+use std::io;
+use std::path::PathBuf;
+use wpilog_parser::PresenceMatrix;
+
+/// Options for `presence`.
+#[derive(Args, Debug)]
+pub struct PresenceArgs {
+    /// Directory of .wpilog files, compared in file name order
+    #[arg(value_name = "DIR")]
+    pub dir: PathBuf,
+
+    /// Print the full entry x log matrix of record counts as CSV
+    #[arg(long, conflicts_with = "json")]
+    pub csv: bool,
+
+    /// Print the matrix as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn presence(args: &PresenceArgs) -> Result<()> {
+    let matrix = PresenceMatrix::from_directory(&args.dir)?;
+
+    if args.csv {
+        matrix.write_csv(io::stdout().lock())?;
+        return Ok(());
+    }
+    if args.json {
+        println!("{}", matrix.to_json()?);
+        return Ok(());
+    }
+
+    println!(
+        "{} entries across {} logs, {} in every log",
+        matrix.entries.len(),
+        matrix.logs.len(),
+        matrix.common_entries().len()
+    );
+    let mut log: Option<String> = None;
+    for change in matrix.changes() {
+        if log.as_deref() != Some(change.log.as_str()) {
+            println!("{}", change.log);
+            log = Some(change.log.clone());
+        }
+        println!("  {} {}", if change.appeared { "+" } else { "-" }, change.entry);
+    }
+    Ok(())
+}
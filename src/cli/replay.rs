@@ -0,0 +1,51 @@
+//! `replay`: play a log back at the pace it was recorded.
+
+use anyhow::Result;
+use clap::Args;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use wpilog_parser::{Replayer, WpilogReader};
+
+use super::print_row;
+
+/// Options for `replay`.
+#[derive(Args, Debug)]
+pub struct ReplayArgs {
+    /// The .wpilog file to replay
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Playback speed multiplier
+    #[arg(long, default_value = "1.0")]
+    pub speed: f64,
+
+    /// Republish entries to the NT4 server on this host instead of printing them
+    #[cfg(feature = "nt4")]
+    #[arg(long, value_name = "HOST")]
+    pub nt4: Option<String>,
+
+    /// Port of the NT4 server given with --nt4
+    #[cfg(feature = "nt4")]
+    #[arg(long, value_name = "PORT", default_value_t = wpilog_parser::nt4::NT4_PORT, requires = "nt4")]
+    pub nt4_port: u16,
+}
+
+pub fn replay(args: &ReplayArgs) -> Result<()> {
+    let reader = WpilogReader::from_file(&args.file)?;
+    let replayer = Replayer::new(&reader).speed(args.speed);
+
+    #[cfg(feature = "nt4")]
+    if let Some(host) = &args.nt4 {
+        let mut publisher = wpilog_parser::nt4::Nt4Publisher::connect_to_port(host, args.nt4_port, "wpilog-parser-replay")?;
+        replayer.visit(&mut publisher)?;
+        return Ok(());
+    }
+
+    let mut out = io::stdout().lock();
+    replayer.run(|row| {
+        print_row(&mut out, &row)?;
+        out.flush()?;
+        Ok(())
+    })?;
+    Ok(())
+}
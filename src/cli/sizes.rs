@@ -0,0 +1,125 @@
+//! `sizes`: which entries take up the most space in a log, and how to fit
+//! it in a size budget.
+
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+use wpilog_parser::profile::DEFAULT_PROFILE_FILE;
+use wpilog_parser::{EntryAction, ProfileSet, QuickScan, SizeBudget, WpilogReader};
+
+/// Options for `sizes`.
+#[derive(Args, Debug)]
+pub struct SizesArgs {
+    /// The .wpilog file to inspect
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Number of entries to list, largest first (0 = all)
+    #[arg(short = 'n', long, default_value = "20")]
+    pub top: usize,
+
+    /// Print every entry's size as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Suggest downsampling or excluding entries to fit this many payload bytes
+    #[arg(long, value_name = "BYTES")]
+    pub budget: Option<u64>,
+
+    /// Lowest sample rate to suggest before excluding entries
+    #[arg(long, value_name = "HZ", default_value = "10", requires = "budget")]
+    pub min_rate: f64,
+
+    /// Save the suggestion as a filter profile with this name
+    #[arg(long, value_name = "NAME", requires = "budget")]
+    pub save_profile: Option<String>,
+
+    /// JSON file to save the profile to
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_PROFILE_FILE, requires = "save_profile")]
+    pub profile_file: PathBuf,
+}
+
+pub fn sizes(args: &SizesArgs) -> Result<()> {
+    let scan = WpilogReader::from_file(&args.file)?.quick_scan()?;
+    if let Some(target) = args.budget {
+        return budget(args, &scan, target);
+    }
+    let sizes = scan.entry_sizes();
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&sizes)?);
+        return Ok(());
+    }
+
+    let shown = if args.top == 0 { sizes.len() } else { args.top.min(sizes.len()) };
+    println!("{:<50} {:>10} {:>12} {:>7}", "entry", "records", "bytes", "share");
+    for size in &sizes[..shown] {
+        println!(
+            "{:<50} {:>10} {:>12} {:>6.1}%",
+            size.name,
+            size.records,
+            size.bytes,
+            size.share * 100.0
+        );
+    }
+    if shown < sizes.len() {
+        let rest = &sizes[shown..];
+        println!(
+            "{:<50} {:>10} {:>12} {:>6.1}%",
+            format!("({} more entries)", rest.len()),
+            rest.iter().map(|size| size.records).sum::<u64>(),
+            rest.iter().map(|size| size.bytes).sum::<u64>(),
+            rest.iter().map(|size| size.share).sum::<f64>() * 100.0
+        );
+    }
+    println!(
+        "{} bytes of payload in {} data records",
+        scan.data_bytes(),
+        scan.data_record_count
+    );
+    Ok(())
+}
+
+fn budget(args: &SizesArgs, scan: &QuickScan, target: u64) -> Result<()> {
+    let advice = SizeBudget::new(target).min_rate_hz(args.min_rate).advise(scan)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&advice)?);
+    } else if advice.fits() {
+        println!(
+            "{} bytes of payload already fit the budget of {} bytes",
+            advice.current_bytes, advice.target_bytes
+        );
+    } else {
+        if let Some(hz) = advice.max_rate_hz {
+            println!("Downsample every entry to at most {} Hz", hz);
+        }
+        println!("{:<50} {:>12} {:>12}  action", "entry", "bytes", "estimated");
+        for entry in &advice.entries {
+            let action = match entry.action {
+                EntryAction::Downsample { rate_hz } => format!("downsample from {:.1} Hz", rate_hz),
+                EntryAction::Exclude => "exclude".to_string(),
+            };
+            println!(
+                "{:<50} {:>12} {:>12}  {}",
+                entry.name, entry.bytes, entry.estimated_bytes, action
+            );
+        }
+        println!(
+            "{} bytes of payload, estimated {} after changes (budget {})",
+            advice.current_bytes, advice.estimated_bytes, advice.target_bytes
+        );
+    }
+
+    if let Some(name) = &args.save_profile {
+        let mut profiles = if args.profile_file.exists() {
+            ProfileSet::from_file(&args.profile_file)?
+        } else {
+            ProfileSet::default()
+        };
+        profiles.insert(name.as_str(), advice.profile());
+        profiles.save(&args.profile_file)?;
+        eprintln!("Saved profile '{}' to {}", name, args.profile_file.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! `timeline`: print which commands ran when, as a text Gantt chart.
+
+use anyhow::Result;
+use clap::Args;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use wpilog_parser::WpilogReader;
+
+/// Options for `timeline`.
+#[derive(Args, Debug)]
+pub struct TimelineArgs {
+    /// The .wpilog file to read
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Width of the chart, in characters
+    #[arg(short, long, default_value = "50")]
+    pub width: usize,
+
+    /// Print the timeline as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn timeline(args: &TimelineArgs) -> Result<()> {
+    let reader = WpilogReader::from_file(&args.file)?;
+    let timeline = reader.command_timeline()?;
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    if args.json {
+        writeln!(out, "{}", timeline.to_json()?)?;
+        out.flush()?;
+        return Ok(());
+    }
+    if timeline.spans.is_empty() {
+        writeln!(out, "No command scheduler entries found")?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    let labels: Vec<String> = timeline
+        .spans
+        .iter()
+        .map(|span| match &span.subsystem {
+            Some(subsystem) => format!("{} ({})", span.command, subsystem),
+            None => span.command.clone(),
+        })
+        .collect();
+    let label_width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+    let start = timeline.log_start_secs;
+    let length = (timeline.log_end_secs - start).max(f64::EPSILON);
+    let column = |secs: f64| (((secs - start) / length) * args.width as f64) as usize;
+
+    for (span, label) in timeline.spans.iter().zip(&labels) {
+        let end = span.end_secs.unwrap_or(timeline.log_end_secs);
+        let first = column(span.start_secs).min(args.width.saturating_sub(1));
+        let last = column(end).clamp(first + 1, args.width.max(first + 1));
+        let end_label = match span.end_secs {
+            Some(end) => format!("{:.3}", end),
+            None => "...".to_string(),
+        };
+        writeln!(
+            out,
+            "{:<label_width$}  {:>9.3} {:>9}  |{}{}{}|",
+            label,
+            span.start_secs,
+            end_label,
+            " ".repeat(first),
+            "█".repeat(last - first),
+            " ".repeat(args.width.saturating_sub(last)),
+            label_width = label_width
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! `usage`: enabled time, enable cycles and brownouts for wear tracking.
+
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+use wpilog_parser::{UsageReport, UsageSummary, WpilogReader};
+
+/// Options for `usage`.
+#[derive(Args, Debug)]
+pub struct UsageArgs {
+    /// A .wpilog file, or a directory to total all of its logs
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn usage(args: &UsageArgs) -> Result<()> {
+    let report = if args.path.is_dir() {
+        UsageReport::from_directory(&args.path)?
+    } else {
+        let mut report = UsageReport::default();
+        report.add(
+            args.path.to_string_lossy(),
+            WpilogReader::from_file(&args.path)?.usage()?,
+        );
+        report
+    };
+
+    if args.json {
+        println!("{}", report.to_json()?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<40} {:>10} {:>10} {:>10} {:>10} {:>7} {:>9}",
+        "log", "enabled", "auto", "teleop", "test", "cycles", "brownouts"
+    );
+    for (file, summary) in &report.logs {
+        print_line(file, summary);
+    }
+    if report.logs.len() > 1 {
+        print_line(&format!("total ({} logs)", report.total.logs), &report.total);
+    }
+    Ok(())
+}
+
+fn print_line(label: &str, summary: &UsageSummary) {
+    println!(
+        "{:<40} {:>10} {:>10} {:>10} {:>10} {:>7} {:>9}",
+        label,
+        hms(summary.enabled_secs),
+        hms(summary.auto_secs),
+        hms(summary.teleop_secs),
+        hms(summary.test_secs),
+        summary.enable_cycles,
+        summary.brownouts
+    );
+}
+
+/// Format seconds as `H:MM:SS`.
+fn hms(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
@@ -0,0 +1,91 @@
+//! `validate`: check the checksum records of rewritten logs, or check logs
+//! against an expected-entries contract.
+
+use anyhow::{bail, Result};
+use clap::Args;
+use std::path::PathBuf;
+use wpilog_parser::{verify_checksums, SchemaContract, WpilogReader};
+
+/// Options for `validate`.
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// The .wpilog files to check
+    #[arg(value_name = "FILE", required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Check the logs against this expected-entries manifest (JSON) instead
+    /// of their checksums: missing, mistyped and under-sampled entries fail
+    #[arg(long, value_name = "MANIFEST")]
+    pub contract: Option<PathBuf>,
+
+    /// Print the full report of each file as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn validate(args: &ValidateArgs) -> Result<()> {
+    if let Some(manifest) = &args.contract {
+        return validate_contract(args, &SchemaContract::from_file(manifest)?);
+    }
+
+    let mut failed = 0;
+    for file in &args.files {
+        let reader = WpilogReader::from_file(file)?;
+        let report = verify_checksums(&reader.low_level_reader())?;
+        if !report.is_ok() {
+            failed += 1;
+        }
+
+        if args.json {
+            println!("{}", report.to_json()?);
+        } else if !report.declared {
+            println!("{}: no checksums", file.display());
+        } else if report.is_ok() {
+            println!("{}: ok ({} blocks)", file.display(), report.verified_blocks);
+        } else {
+            println!(
+                "{}: {} bad blocks, {} unverified records",
+                file.display(),
+                report.mismatches.len(),
+                report.unverified_records
+            );
+            for offset in &report.mismatches {
+                println!("  checksum mismatch at offset {}", offset);
+            }
+            if let Some(error) = &report.error {
+                println!("  stopped early: {}", error);
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} logs failed validation", failed, args.files.len());
+    }
+    Ok(())
+}
+
+fn validate_contract(args: &ValidateArgs, contract: &SchemaContract) -> Result<()> {
+    let mut failed = 0;
+    for file in &args.files {
+        let report = contract.check(&WpilogReader::from_file(file)?)?;
+        if !report.is_ok() {
+            failed += 1;
+        }
+
+        if args.json {
+            println!("{}", report.to_json()?);
+        } else if report.is_ok() {
+            println!("{}: ok ({} entries)", file.display(), report.checked);
+        } else {
+            println!("{}: {} of {} entries failed", file.display(), report.violations.len(), report.checked);
+            for violation in &report.violations {
+                println!("  {}", violation);
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} logs failed the contract", failed, args.files.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,293 @@
+//! Expected-entries manifests for gating robot code on logging completeness.
+//!
+//! A [`SchemaContract`] lists the entries a log must contain, optionally with
+//! their type and a minimum sample rate, in a JSON file checked in next to
+//! the robot code:
+//!
+//! ```json
+//! {
+//!   "entries": [
+//!     { "name": "/Drive/LeftVelocity", "type": "double", "min_rate_hz": 45 },
+//!     { "name": "/Vision/Pose", "type": "struct:Pose2d" },
+//!     { "name": "DS:enabled" }
+//!   ]
+//! }
+//! ```
+//!
+//! [`SchemaContract::check`] reports every entry that is missing, declared
+//! with another type, or sampled below its minimum rate.
+
+use crate::datalog::{DataLogReader, RecordVisitor, StartRecordData};
+use crate::entry_type::EntryType;
+use crate::error::{Error, Result};
+use crate::reader::WpilogReader;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// One entry a log must contain.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntryContract {
+    /// Entry name as logged
+    pub name: String,
+    /// Declared type, e.g. `double` or `struct:Pose2d` (any type when absent)
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub entry_type: Option<String>,
+    /// Minimum average sample rate over the whole log
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_rate_hz: Option<f64>,
+}
+
+/// Entries a log is expected to contain, as stored in a JSON manifest.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{SchemaContract, WpilogReader};
+///
+/// let contract = SchemaContract::from_file("logging-contract.json")?;
+/// let report = contract.check(&WpilogReader::from_file("match.wpilog")?)?;
+/// if !report.is_ok() {
+///     eprintln!("{}", report);
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemaContract {
+    pub entries: Vec<EntryContract>,
+}
+
+impl SchemaContract {
+    /// Load a contract from a JSON manifest.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a contract from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let contract: Self = serde_json::from_str(json)?;
+        for entry in &contract.entries {
+            if entry.min_rate_hz.is_some_and(|hz| hz.is_nan() || hz < 0.0) {
+                return Err(Error::Other(format!(
+                    "Contract entry '{}': min_rate_hz must not be negative",
+                    entry.name
+                )));
+            }
+        }
+        Ok(contract)
+    }
+
+    /// Add an entry to the contract.
+    pub fn entry(mut self, entry: EntryContract) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Check the log read by `reader` against the contract.
+    pub fn check(&self, reader: &WpilogReader) -> Result<ContractReport> {
+        self.check_reader(&reader.low_level_reader())
+    }
+
+    /// Check a low-level reader against the contract.
+    pub fn check_reader(&self, reader: &DataLogReader<'_>) -> Result<ContractReport> {
+        let mut visitor = ContractVisitor {
+            wanted: self.entries.iter().map(|entry| entry.name.as_str()).collect(),
+            ..Default::default()
+        };
+        reader.visit(&mut visitor)?;
+        let duration_secs = match visitor.first_timestamp {
+            Some(first) => visitor.last_timestamp.saturating_sub(first) as f64 / 1_000_000.0,
+            None => 0.0,
+        };
+
+        let mut report = ContractReport {
+            checked: self.entries.len(),
+            duration_secs,
+            violations: Vec::new(),
+        };
+        for contract in &self.entries {
+            let Some(seen) = visitor.seen.get(contract.name.as_str()) else {
+                report.violations.push(ContractViolation::Missing {
+                    entry: contract.name.clone(),
+                });
+                continue;
+            };
+            if let Some(expected) = &contract.entry_type {
+                let mismatched: Vec<String> = seen
+                    .types
+                    .iter()
+                    .filter(|actual| **actual != EntryType::parse(expected))
+                    .map(|actual| actual.to_string())
+                    .collect();
+                if !mismatched.is_empty() {
+                    report.violations.push(ContractViolation::Mistyped {
+                        entry: contract.name.clone(),
+                        expected: expected.clone(),
+                        actual: mismatched.join(", "),
+                    });
+                    continue;
+                }
+            }
+            if seen.samples == 0 {
+                report.violations.push(ContractViolation::Missing {
+                    entry: contract.name.clone(),
+                });
+                continue;
+            }
+            if let Some(min_rate_hz) = contract.min_rate_hz {
+                let rate_hz = if duration_secs > 0.0 {
+                    seen.samples as f64 / duration_secs
+                } else {
+                    0.0
+                };
+                if rate_hz < min_rate_hz {
+                    report.violations.push(ContractViolation::UnderSampled {
+                        entry: contract.name.clone(),
+                        min_rate_hz,
+                        rate_hz,
+                        samples: seen.samples,
+                    });
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// An entry of a [`SchemaContract`] that a log does not satisfy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContractViolation {
+    /// Never started, or started but never written
+    Missing { entry: String },
+    /// Started with another type (all conflicting types, comma-separated)
+    Mistyped {
+        entry: String,
+        expected: String,
+        actual: String,
+    },
+    /// Average rate over the log below the minimum
+    UnderSampled {
+        entry: String,
+        min_rate_hz: f64,
+        rate_hz: f64,
+        samples: u64,
+    },
+}
+
+impl ContractViolation {
+    /// Name of the offending entry.
+    pub fn entry(&self) -> &str {
+        match self {
+            ContractViolation::Missing { entry }
+            | ContractViolation::Mistyped { entry, .. }
+            | ContractViolation::UnderSampled { entry, .. } => entry,
+        }
+    }
+}
+
+impl fmt::Display for ContractViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContractViolation::Missing { entry } => write!(f, "'{}': missing", entry),
+            ContractViolation::Mistyped {
+                entry,
+                expected,
+                actual,
+            } => write!(f, "'{}': expected type {}, logged as {}", entry, expected, actual),
+            ContractViolation::UnderSampled {
+                entry,
+                min_rate_hz,
+                rate_hz,
+                samples,
+            } => write!(
+                f,
+                "'{}': {:.1} Hz ({} samples), expected at least {} Hz",
+                entry, rate_hz, samples, min_rate_hz
+            ),
+        }
+    }
+}
+
+/// Result of checking one log against a [`SchemaContract`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContractReport {
+    /// Number of entries in the contract
+    pub checked: usize,
+    /// Time between the first and last record, which rates are taken over
+    pub duration_secs: f64,
+    /// Violations in contract order
+    pub violations: Vec<ContractViolation>,
+}
+
+impl ContractReport {
+    /// Whether the log satisfied every entry of the contract.
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl fmt::Display for ContractReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for violation in &self.violations {
+            writeln!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// What was logged of one contract entry.
+#[derive(Default)]
+struct SeenEntry {
+    /// Distinct types the entry was started with
+    types: Vec<EntryType>,
+    samples: u64,
+}
+
+#[derive(Default)]
+struct ContractVisitor<'c> {
+    wanted: HashSet<&'c str>,
+    seen: HashMap<String, SeenEntry>,
+    first_timestamp: Option<u64>,
+    last_timestamp: u64,
+}
+
+impl ContractVisitor<'_> {
+    fn timestamp(&mut self, timestamp: u64) {
+        self.first_timestamp.get_or_insert(timestamp);
+        self.last_timestamp = self.last_timestamp.max(timestamp);
+    }
+}
+
+impl RecordVisitor for ContractVisitor<'_> {
+    fn on_start(&mut self, start: &StartRecordData, timestamp: u64) -> Result<()> {
+        self.timestamp(timestamp);
+        if self.wanted.contains(start.name.as_str()) {
+            let seen = self.seen.entry(start.name.clone()).or_default();
+            if !seen.types.contains(&start.entry_type) {
+                seen.types.push(start.entry_type.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn on_finish(&mut self, _entry: u32, timestamp: u64) -> Result<()> {
+        self.timestamp(timestamp);
+        Ok(())
+    }
+
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, _payload: &[u8]) -> Result<()> {
+        self.timestamp(timestamp);
+        if let Some(seen) = self.seen.get_mut(&entry.name) {
+            seen.samples += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Time-indexed random access for scrubbing through a log.
+//!
+//! [`LogCursor`] is built on a [`RecordIndex`], so creating one only walks
+//! record headers. Values are decoded on demand when the cursor is asked for
+//! an entry's sample around the current playhead.
+
+use crate::datalog::DataLogReader;
+use crate::entry_type::DecodedValue;
+use crate::error::Result;
+use crate::index::{RecordIndex, RecordLocation};
+use std::collections::HashMap;
+
+/// A decoded sample returned by [`LogCursor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// Record timestamp (microseconds)
+    pub timestamp: u64,
+    /// Value decoded according to the entry's declared type
+    pub value: DecodedValue,
+}
+
+/// Playhead over a log that can seek to any timestamp.
+///
+/// All lifetimes of an entry name are merged into one timeline sorted by
+/// timestamp.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::WpilogReader;
+///
+/// let reader = WpilogReader::from_file("data.wpilog")?;
+/// let mut cursor = reader.cursor()?;
+///
+/// cursor.seek_to(15_000_000);
+/// if let Some(sample) = cursor.next_before("/Drive/LeftVelocity")? {
+///     println!("value at 15s: {:?}", sample.value);
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub struct LogCursor<'a> {
+    reader: DataLogReader<'a>,
+    index: RecordIndex,
+    /// Entry name -> (record, lifetime index) sorted by timestamp
+    timelines: HashMap<String, Vec<(RecordLocation, usize)>>,
+    position: u64,
+}
+
+impl<'a> LogCursor<'a> {
+    /// Index the log read by `reader` and place the playhead at time 0.
+    pub fn new(reader: DataLogReader<'a>) -> Result<Self> {
+        let index = RecordIndex::build(&reader)?;
+        let mut timelines: HashMap<String, Vec<(RecordLocation, usize)>> = HashMap::new();
+        for (lifetime, entry) in index.entries().iter().enumerate() {
+            timelines
+                .entry(entry.start.name.clone())
+                .or_default()
+                .extend(entry.records.iter().map(|&location| (location, lifetime)));
+        }
+        for timeline in timelines.values_mut() {
+            // Stable, so records with equal timestamps keep log order
+            timeline.sort_by_key(|(location, _)| location.timestamp);
+        }
+
+        Ok(Self {
+            reader,
+            index,
+            timelines,
+            position: 0,
+        })
+    }
+
+    /// Move the playhead to `timestamp` (microseconds).
+    pub fn seek_to(&mut self, timestamp: u64) {
+        self.position = timestamp;
+    }
+
+    /// Current playhead timestamp (microseconds).
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Names of all entries that have data records, sorted.
+    pub fn entry_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.timelines.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Latest sample of `name` at or before the playhead, i.e. the entry's value
+    /// at the current time. Returns `None` for unknown entries or if the entry
+    /// has no data yet.
+    pub fn next_before(&self, name: &str) -> Result<Option<Sample>> {
+        let Some(timeline) = self.timelines.get(name) else {
+            return Ok(None);
+        };
+        let idx = timeline.partition_point(|(location, _)| location.timestamp <= self.position);
+        match idx.checked_sub(1) {
+            Some(idx) => self.decode(timeline[idx]).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// First sample of `name` strictly after the playhead. Returns `None` for
+    /// unknown entries or if the entry has no later data.
+    pub fn next_after(&self, name: &str) -> Result<Option<Sample>> {
+        let Some(timeline) = self.timelines.get(name) else {
+            return Ok(None);
+        };
+        let idx = timeline.partition_point(|(location, _)| location.timestamp <= self.position);
+        match timeline.get(idx) {
+            Some(&record) => self.decode(record).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn decode(&self, (location, lifetime): (RecordLocation, usize)) -> Result<Sample> {
+        let entry_type = &self.index.entries()[lifetime].start.entry_type;
+        let record = self.reader.record_at(location.offset)?;
+        Ok(Sample {
+            timestamp: location.timestamp,
+            value: record.decode_as(entry_type)?,
+        })
+    }
+}
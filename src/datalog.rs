@@ -1,16 +1,25 @@
-use anyhow::{anyhow, Result};
+use crate::entry_type::{DecodedValue, EntryType};
+use crate::error::{Error, Result};
+use crate::quirks::Quirks;
 use byteorder::{LittleEndian, ReadBytesExt};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
 
-const CONTROL_START: u8 = 0;
-const CONTROL_FINISH: u8 = 1;
-const CONTROL_SET_METADATA: u8 = 2;
+/// Control record type byte for Start records.
+pub const CONTROL_START: u8 = 0;
+/// Control record type byte for Finish records.
+pub const CONTROL_FINISH: u8 = 1;
+/// Control record type byte for Set Metadata records.
+pub const CONTROL_SET_METADATA: u8 = 2;
 
 #[derive(Debug, Clone)]
 pub struct StartRecordData {
     pub entry: u32,
     pub name: String,
     pub type_name: String,
+    /// `type_name` parsed when the Start record was read
+    pub entry_type: EntryType,
     pub metadata: String,
 }
 
@@ -20,6 +29,12 @@ pub struct MetadataRecordData {
     pub metadata: String,
 }
 
+/// A record as stored in the log.
+///
+/// The `get_*` accessors interpret the payload as one WPILog type. Multi-byte
+/// values are little-endian, floats are IEEE 754, and strings are UTF-8.
+/// Accessors check the payload length but not the entry's declared type; use
+/// [`decode_as`](Self::decode_as) to decode by declared type.
 #[derive(Debug, Clone)]
 pub struct DataLogRecord {
     pub entry: u32,
@@ -56,7 +71,7 @@ impl DataLogRecord {
 
     pub fn get_start_data(&self) -> Result<StartRecordData> {
         if !self.is_start() {
-            return Err(anyhow!("Not a start record"));
+            return Err(Error::InvalidEntry("Not a start record".to_string()));
         }
 
         let mut cursor = Cursor::new(&self.data);
@@ -70,6 +85,7 @@ impl DataLogRecord {
         Ok(StartRecordData {
             entry,
             name,
+            entry_type: EntryType::parse(&type_name),
             type_name,
             metadata,
         })
@@ -77,7 +93,7 @@ impl DataLogRecord {
 
     pub fn get_finish_entry(&self) -> Result<u32> {
         if !self.is_finish() {
-            return Err(anyhow!("Not a finish record"));
+            return Err(Error::InvalidEntry("Not a finish record".to_string()));
         }
 
         let mut cursor = Cursor::new(&self.data[1..5]);
@@ -86,7 +102,7 @@ impl DataLogRecord {
 
     pub fn get_set_metadata_data(&self) -> Result<MetadataRecordData> {
         if !self.is_set_metadata() {
-            return Err(anyhow!("Not a set metadata record"));
+            return Err(Error::InvalidEntry("Not a set metadata record".to_string()));
         }
 
         let mut cursor = Cursor::new(&self.data[1..5]);
@@ -96,45 +112,168 @@ impl DataLogRecord {
         Ok(MetadataRecordData { entry, metadata })
     }
 
+    /// Decode the payload according to the entry's declared type.
+    ///
+    /// Unlike the `get_*` accessors, which only check that the payload length
+    /// is plausible, this validates the payload against `entry_type` and returns
+    /// [`Error::TypeMismatch`] naming the declared type when they disagree.
+    /// Struct, protobuf and raw payloads are returned undecoded.
+    pub fn decode_as(&self, entry_type: &EntryType) -> Result<DecodedValue> {
+        let len = self.data.len();
+        let mismatch = |expected: &str| {
+            Error::TypeMismatch(format!(
+                "declared type {} needs {}, got {} bytes",
+                entry_type, expected, len
+            ))
+        };
+
+        Ok(match entry_type {
+            EntryType::Boolean if len == 1 => DecodedValue::Boolean(self.data[0] != 0),
+            EntryType::Boolean => return Err(mismatch("a 1-byte payload")),
+            EntryType::Int64 if len == 8 => DecodedValue::Int64(self.get_integer()?),
+            EntryType::Int64 => return Err(mismatch("an 8-byte payload")),
+            EntryType::Float if len == 4 => DecodedValue::Float(self.get_float()?),
+            EntryType::Float => return Err(mismatch("a 4-byte payload")),
+            EntryType::Double if len == 8 => DecodedValue::Double(self.get_double()?),
+            EntryType::Double => return Err(mismatch("an 8-byte payload")),
+            EntryType::String | EntryType::Json | EntryType::StructSchema => {
+                let text = std::str::from_utf8(&self.data)
+                    .map_err(|_| mismatch("a UTF-8 payload"))?;
+                DecodedValue::String(text.to_string())
+            }
+            EntryType::Msgpack => DecodedValue::Msgpack(self.get_msgpack()?),
+            EntryType::BooleanArray => DecodedValue::BooleanArray(self.get_boolean_array()),
+            EntryType::Int64Array if len.is_multiple_of(8) => {
+                DecodedValue::Int64Array(self.get_integer_array()?)
+            }
+            EntryType::Int64Array => return Err(mismatch("a multiple of 8 bytes")),
+            EntryType::FloatArray if len.is_multiple_of(4) => {
+                DecodedValue::FloatArray(self.get_float_array()?)
+            }
+            EntryType::FloatArray => return Err(mismatch("a multiple of 4 bytes")),
+            EntryType::DoubleArray if len.is_multiple_of(8) => {
+                DecodedValue::DoubleArray(self.get_double_array()?)
+            }
+            EntryType::DoubleArray => return Err(mismatch("a multiple of 8 bytes")),
+            EntryType::StringArray => DecodedValue::StringArray(
+                self.get_string_array()
+                    .map_err(|_| mismatch("a length-prefixed string array"))?,
+            ),
+            EntryType::Struct(_)
+            | EntryType::StructArray(_)
+            | EntryType::Proto(_)
+            | EntryType::Raw(_) => DecodedValue::Raw(self.data.clone()),
+        })
+    }
+
+    /// Like [`decode_as`](Self::decode_as), but invalid UTF-8 in `string`,
+    /// `json` and `string[]` payloads is replaced with U+FFFD instead of
+    /// failing. Returns whether anything was replaced.
+    pub fn decode_as_lossy(&self, entry_type: &EntryType) -> Result<(DecodedValue, bool)> {
+        match entry_type {
+            EntryType::String | EntryType::Json | EntryType::StructSchema => {
+                let text = String::from_utf8_lossy(&self.data);
+                let replaced = matches!(text, Cow::Owned(_));
+                Ok((DecodedValue::String(text.into_owned()), replaced))
+            }
+            EntryType::StringArray => {
+                let (strings, replaced) = self.string_array(true).map_err(|_| {
+                    Error::TypeMismatch(format!(
+                        "declared type {} needs a length-prefixed string array, got {} bytes",
+                        entry_type,
+                        self.data.len()
+                    ))
+                })?;
+                Ok((DecodedValue::StringArray(strings), replaced))
+            }
+            _ => Ok((self.decode_as(entry_type)?, false)),
+        }
+    }
+
+    /// 1-byte boolean; any non-zero byte is `true`.
     pub fn get_boolean(&self) -> Result<bool> {
         if self.data.len() != 1 {
-            return Err(anyhow!("Not a boolean"));
+            return Err(Error::ParseError("Not a boolean".to_string()));
         }
         Ok(self.data[0] != 0)
     }
 
+    /// 8-byte little-endian two's complement integer (`int64`).
     pub fn get_integer(&self) -> Result<i64> {
         if self.data.len() != 8 {
-            return Err(anyhow!("Not an integer"));
+            return Err(Error::ParseError("Not an integer".to_string()));
         }
         let mut cursor = Cursor::new(&self.data);
         Ok(cursor.read_i64::<LittleEndian>()?)
     }
 
+    /// 4-byte little-endian IEEE 754 single (`float`).
     pub fn get_float(&self) -> Result<f32> {
         if self.data.len() != 4 {
-            return Err(anyhow!("Not a float"));
+            return Err(Error::ParseError("Not a float".to_string()));
         }
         let mut cursor = Cursor::new(&self.data);
         Ok(cursor.read_f32::<LittleEndian>()?)
     }
 
+    /// 8-byte little-endian IEEE 754 double (`double`).
     pub fn get_double(&self) -> Result<f64> {
         if self.data.len() != 8 {
-            return Err(anyhow!("Not a double"));
+            return Err(Error::ParseError("Not a double".to_string()));
         }
         let mut cursor = Cursor::new(&self.data);
         Ok(cursor.read_f64::<LittleEndian>()?)
     }
 
+    /// 1-byte two's complement integer (`int8`).
+    pub fn get_int8(&self) -> Result<i8> {
+        if self.data.len() != 1 {
+            return Err(Error::ParseError("Not an int8".to_string()));
+        }
+        Ok(self.data[0] as i8)
+    }
+
+    /// 1-byte unsigned integer (`uint8`).
+    pub fn get_uint8(&self) -> Result<u8> {
+        if self.data.len() != 1 {
+            return Err(Error::ParseError("Not a uint8".to_string()));
+        }
+        Ok(self.data[0])
+    }
+
+    /// 2-byte little-endian two's complement integer (`int16`).
+    pub fn get_int16(&self) -> Result<i16> {
+        if self.data.len() != 2 {
+            return Err(Error::ParseError("Not an int16".to_string()));
+        }
+        Ok(i16::from_le_bytes([self.data[0], self.data[1]]))
+    }
+
+    /// 2-byte little-endian unsigned integer (`uint16`).
+    pub fn get_uint16(&self) -> Result<u16> {
+        if self.data.len() != 2 {
+            return Err(Error::ParseError("Not a uint16".to_string()));
+        }
+        Ok(u16::from_le_bytes([self.data[0], self.data[1]]))
+    }
+
+    /// 2-byte little-endian IEEE 754 half-precision float (`float16`),
+    /// widened to `f32`. Logged by some vendor libraries to save space.
+    pub fn get_f16(&self) -> Result<f32> {
+        if self.data.len() != 2 {
+            return Err(Error::ParseError("Not a float16".to_string()));
+        }
+        Ok(half::f16::from_le_bytes([self.data[0], self.data[1]]).to_f32())
+    }
+
+    /// UTF-8 text (`string`, `json`).
     pub fn get_string(&self) -> Result<String> {
-        String::from_utf8(self.data.clone())
-            .map_err(|e| anyhow!("Invalid UTF-8: {}", e))
+        Ok(String::from_utf8(self.data.clone())?)
     }
 
     pub fn get_msgpack(&self) -> Result<rmpv::Value> {
         rmpv::decode::read_value(&mut Cursor::new(&self.data))
-            .map_err(|e| anyhow!("MsgPack decode error: {}", e))
+            .map_err(|e| Error::ParseError(format!("MsgPack decode error: {}", e)))
     }
 
     pub fn get_boolean_array(&self) -> Vec<bool> {
@@ -142,8 +281,8 @@ impl DataLogRecord {
     }
 
     pub fn get_integer_array(&self) -> Result<Vec<i64>> {
-        if self.data.len() % 8 != 0 {
-            return Err(anyhow!("Not an integer array"));
+        if !self.data.len().is_multiple_of(8) {
+            return Err(Error::ParseError("Not an integer array".to_string()));
         }
         let mut result = Vec::with_capacity(self.data.len() / 8);
         let mut cursor = Cursor::new(&self.data);
@@ -154,8 +293,8 @@ impl DataLogRecord {
     }
 
     pub fn get_float_array(&self) -> Result<Vec<f32>> {
-        if self.data.len() % 4 != 0 {
-            return Err(anyhow!("Not a float array"));
+        if !self.data.len().is_multiple_of(4) {
+            return Err(Error::ParseError("Not a float array".to_string()));
         }
         let mut result = Vec::with_capacity(self.data.len() / 4);
         let mut cursor = Cursor::new(&self.data);
@@ -166,8 +305,8 @@ impl DataLogRecord {
     }
 
     pub fn get_double_array(&self) -> Result<Vec<f64>> {
-        if self.data.len() % 8 != 0 {
-            return Err(anyhow!("Not a double array"));
+        if !self.data.len().is_multiple_of(8) {
+            return Err(Error::ParseError("Not a double array".to_string()));
         }
         let mut result = Vec::with_capacity(self.data.len() / 8);
         let mut cursor = Cursor::new(&self.data);
@@ -178,52 +317,193 @@ impl DataLogRecord {
     }
 
     pub fn get_string_array(&self) -> Result<Vec<String>> {
+        Ok(self.string_array(false)?.0)
+    }
+
+    /// Strings of a `string[]` payload, with invalid UTF-8 replaced if
+    /// `lossy`, and whether anything was replaced.
+    fn string_array(&self, lossy: bool) -> Result<(Vec<String>, bool)> {
         let mut cursor = Cursor::new(&self.data);
         let size = cursor.read_u32::<LittleEndian>()? as usize;
 
         if size > (self.data.len() - 4) / 4 {
-            return Err(anyhow!("Not a string array"));
+            return Err(Error::ParseError("Not a string array".to_string()));
         }
 
         let mut result = Vec::with_capacity(size);
         let mut pos = 4;
+        let mut replaced = false;
 
         for _ in 0..size {
-            let (s, new_pos) = read_inner_string(&self.data, pos)?;
+            let (bytes, new_pos) = read_inner_bytes(&self.data, pos)?;
+            let s = match String::from_utf8(bytes.to_vec()) {
+                Ok(s) => s,
+                Err(_) if lossy => {
+                    replaced = true;
+                    String::from_utf8_lossy(bytes).into_owned()
+                }
+                Err(e) => return Err(e.into()),
+            };
             result.push(s);
             pos = new_pos;
         }
 
-        Ok(result)
+        Ok((result, replaced))
+    }
+
+    /// Describe the record for troubleshooting: a header line, a hex dump of
+    /// the payload, and every type the payload could plausibly be read as.
+    ///
+    /// The record does not know its entry's declared type, so data payloads
+    /// are shown as each type whose length fits; control records are decoded.
+    ///
+    /// ```text
+    /// entry 3 @ 1200000 us, 8-byte payload
+    ///   0000  00 00 00 00 00 00 f8 3f                           |.......?|
+    ///   int64: 4609434218613702656
+    ///   double: 1.5
+    /// ```
+    pub fn debug_dump(&self) -> String {
+        let mut out = format!(
+            "entry {} @ {} us, {}-byte payload\n",
+            self.entry,
+            self.timestamp,
+            self.data.len()
+        );
+        out.push_str(&hex_dump(&self.data, "  "));
+        for (type_name, value) in self.interpretations() {
+            out.push_str(&format!("  {}: {}\n", type_name, value));
+        }
+        out
+    }
+
+    /// Readings of the payload shown by [`debug_dump`](Self::debug_dump).
+    fn interpretations(&self) -> Vec<(&'static str, String)> {
+        let mut readings = Vec::new();
+        if self.is_control() {
+            let control = if self.is_start() {
+                self.get_start_data().map(|start| {
+                    format!(
+                        "Start entry {} {:?} type {:?} metadata {:?}",
+                        start.entry, start.name, start.type_name, start.metadata
+                    )
+                })
+            } else if self.is_finish() {
+                self.get_finish_entry().map(|entry| format!("Finish entry {}", entry))
+            } else if self.is_set_metadata() {
+                self.get_set_metadata_data()
+                    .map(|metadata| format!("SetMetadata entry {} {:?}", metadata.entry, metadata.metadata))
+            } else {
+                Err(Error::InvalidRecord("unknown control record type".to_string()))
+            };
+            readings.push(("control", control.unwrap_or_else(|e| format!("invalid ({})", e))));
+            return readings;
+        }
+
+        let mut push = |type_name, value: Result<String>| {
+            if let Ok(value) = value {
+                readings.push((type_name, value));
+            }
+        };
+        match self.data.len() {
+            1 => {
+                push("boolean", self.get_boolean().map(|v| v.to_string()));
+                push("int8", self.get_int8().map(|v| v.to_string()));
+                push("uint8", self.get_uint8().map(|v| v.to_string()));
+            }
+            2 => {
+                push("int16", self.get_int16().map(|v| v.to_string()));
+                push("uint16", self.get_uint16().map(|v| v.to_string()));
+                push("float16", self.get_f16().map(|v| v.to_string()));
+            }
+            4 => push("float", self.get_float().map(|v| v.to_string())),
+            8 => {
+                push("int64", self.get_integer().map(|v| v.to_string()));
+                push("double", self.get_double().map(|v| v.to_string()));
+            }
+            _ => {}
+        }
+        if self.data.len() > 8 {
+            if self.data.len().is_multiple_of(8) {
+                push("int64[]", self.get_integer_array().map(|v| format!("{:?}", v)));
+                push("double[]", self.get_double_array().map(|v| format!("{:?}", v)));
+            }
+            if self.data.len().is_multiple_of(4) {
+                push("float[]", self.get_float_array().map(|v| format!("{:?}", v)));
+            }
+        }
+        if let Ok(strings) = self.get_string_array() {
+            if !strings.is_empty() {
+                push("string[]", Ok(format!("{:?}", strings)));
+            }
+        }
+        if let Ok(text) = std::str::from_utf8(&self.data) {
+            if !text.is_empty() && !text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+                push("string", Ok(format!("{:?}", text)));
+            }
+        }
+        readings
     }
 }
 
-fn read_inner_string(data: &[u8], pos: usize) -> Result<(String, usize)> {
-    if pos + 4 > data.len() {
-        return Err(anyhow!("Invalid string size position"));
+/// Format `bytes` as hex dump lines of 16 bytes with offsets and printable
+/// ASCII, each line starting with `indent`.
+pub fn hex_dump(bytes: &[u8], indent: &str) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+            .collect();
+        out.push_str(&format!("{}{:04x}  {:<47}  |{}|\n", indent, line * 16, hex.join(" "), ascii));
     }
+    out
+}
 
-    let mut cursor = Cursor::new(&data[pos..pos + 4]);
-    let size = cursor.read_u32::<LittleEndian>()? as usize;
-    let end = pos + 4 + size;
+fn read_inner_string(data: &[u8], pos: usize) -> Result<(String, usize)> {
+    let (bytes, end) = read_inner_bytes(data, pos)?;
+    Ok((String::from_utf8(bytes.to_vec())?, end))
+}
 
-    if end > data.len() {
-        return Err(anyhow!("Invalid string size"));
+/// The bytes of a length-prefixed string at `pos` and the offset past them.
+fn read_inner_bytes(data: &[u8], pos: usize) -> Result<(&[u8], usize)> {
+    if data.len().saturating_sub(pos) < 4 {
+        return Err(Error::ParseError("Invalid string size position".to_string()));
     }
 
-    let s = String::from_utf8(data[pos + 4..end].to_vec())
-        .map_err(|e| anyhow!("Invalid UTF-8 in string: {}", e))?;
+    let mut cursor = Cursor::new(&data[pos..pos + 4]);
+    let size = cursor.read_u32::<LittleEndian>()? as usize;
+    let end = match (pos + 4).checked_add(size) {
+        Some(end) if end <= data.len() => end,
+        _ => return Err(Error::ParseError("Invalid string size".to_string())),
+    };
 
-    Ok((s, end))
+    Ok((&data[pos + 4..end], end))
 }
 
 pub struct DataLogReader<'a> {
     data: &'a [u8],
+    quirks: Quirks,
 }
 
 impl<'a> DataLogReader<'a> {
     pub fn new(data: &'a [u8]) -> Self {
-        Self { data }
+        Self {
+            data,
+            quirks: Quirks::default(),
+        }
+    }
+
+    /// Tolerate the given off-spec record layouts when walking records.
+    pub fn with_quirks(mut self, quirks: Quirks) -> Self {
+        self.quirks = quirks;
+        self
+    }
+
+    /// Quirks tolerated by this reader.
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
     }
 
     pub fn is_valid(&self) -> bool {
@@ -248,78 +528,361 @@ impl<'a> DataLogReader<'a> {
         let mut cursor = Cursor::new(&self.data[8..12]);
         let size = cursor.read_u32::<LittleEndian>().unwrap_or(0) as usize;
 
-        if 12 + size > self.data.len() {
+        if size > self.data.len() - 12 {
             return String::new();
         }
 
         String::from_utf8(self.data[12..12 + size].to_vec()).unwrap_or_default()
     }
 
+    /// Iterate over all records.
+    ///
+    /// If the log ends partway through a record, the iterator yields a final
+    /// [`Error::Truncated`]; call [`DataLogIterator::allow_truncated`] to stop
+    /// quietly instead.
     pub fn records(&self) -> Result<DataLogIterator<'a>> {
+        Ok(DataLogIterator {
+            data: self.data,
+            pos: self.first_record_pos()?,
+            record_start: 0,
+            allow_truncated: false,
+            entry_filter: None,
+            started: None,
+            quirks: self.quirks,
+        })
+    }
+
+    /// Iterate over control records and the data records of `entry_ids`.
+    ///
+    /// Data records of other entries are skipped by advancing past them, without
+    /// copying their payloads. The set can be changed during iteration with
+    /// [`DataLogIterator::entry_filter_mut`], e.g. to follow Start records.
+    pub fn records_filtered(&self, entry_ids: &HashSet<u32>) -> Result<DataLogIterator<'a>> {
+        let mut records = self.records()?;
+        records.entry_filter = Some(entry_ids.clone());
+        Ok(records)
+    }
+
+    /// Walk all records, calling `visitor` for each one.
+    ///
+    /// Unlike [`records`](Self::records), data record payloads are passed as
+    /// slices into the log buffer, so no per-record allocation is made. Only
+    /// control records are decoded. Data records for entries without an active
+    /// Start record are skipped, and a partial record at the end of the log
+    /// ends the walk.
+    pub fn visit<V: RecordVisitor>(&self, visitor: &mut V) -> Result<()> {
+        let mut entries: HashMap<u32, StartRecordData> = HashMap::new();
+        let mut pos = self.first_record_pos()?;
+
+        loop {
+            let header = match read_record_header(self.data, pos, self.quirks) {
+                Ok(Some(header)) => header,
+                Ok(None) | Err(Error::Truncated { .. }) => break,
+                Err(e) => return Err(e),
+            };
+            pos = header.payload_end;
+            let payload = &self.data[header.payload_start..header.payload_end];
+
+            if header.entry != 0 {
+                if let Some(entry) = entries.get(&header.entry) {
+                    visitor.on_data(entry, header.timestamp, payload)?;
+                }
+                continue;
+            }
+
+            let record = DataLogRecord {
+                entry: 0,
+                timestamp: header.timestamp,
+                data: payload.to_vec(),
+            };
+            if record.is_start() {
+                let start = record.get_start_data()?;
+                visitor.on_start(&start, header.timestamp)?;
+                entries.insert(start.entry, start);
+            } else if record.is_finish() {
+                let entry = record.get_finish_entry()?;
+                visitor.on_finish(entry, header.timestamp)?;
+                entries.remove(&entry);
+            } else if record.is_set_metadata() {
+                let metadata = record.get_set_metadata_data()?;
+                visitor.on_set_metadata(&metadata, header.timestamp)?;
+                if let Some(entry) = entries.get_mut(&metadata.entry) {
+                    entry.metadata = metadata.metadata;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decode the record whose header starts at byte `offset`.
+    ///
+    /// Offsets come from a [`RecordIndex`](crate::index::RecordIndex) or
+    /// [`DataLogIterator::position`].
+    pub fn record_at(&self, offset: usize) -> Result<DataLogRecord> {
+        let header = read_record_header(self.data, offset, self.quirks)?
+            .ok_or_else(|| Error::ParseError(format!("No complete record at offset {}", offset)))?;
+
+        Ok(DataLogRecord {
+            entry: header.entry,
+            timestamp: header.timestamp,
+            data: self.data[header.payload_start..header.payload_end].to_vec(),
+        })
+    }
+
+    /// The raw log bytes.
+    pub(crate) fn bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Byte offset of the first record (just past the header and extra header).
+    pub(crate) fn first_record_pos(&self) -> Result<usize> {
         if !self.is_valid() {
-            return Err(anyhow!("Not a valid WPILOG file"));
+            return Err(Error::InvalidFormat("Not a valid WPILOG file".to_string()));
         }
 
         let mut cursor = Cursor::new(&self.data[8..12]);
         let extra_header_size = cursor.read_u32::<LittleEndian>()? as usize;
-        let start_pos = 12 + extra_header_size;
+        let available = self.data.len() - 12;
+        if extra_header_size > available {
+            return Err(Error::Truncated {
+                offset: 12,
+                needed: extra_header_size,
+                available,
+            });
+        }
+        Ok(12 + extra_header_size)
+    }
+}
 
-        Ok(DataLogIterator {
-            data: self.data,
-            pos: start_pos,
-        })
+/// Callbacks for [`DataLogReader::visit`].
+///
+/// All methods except [`on_data`](Self::on_data) default to doing nothing.
+/// Returning an error stops the walk and propagates the error.
+pub trait RecordVisitor {
+    /// Called for each Start control record.
+    fn on_start(&mut self, _start: &StartRecordData, _timestamp: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for each Finish control record.
+    fn on_finish(&mut self, _entry: u32, _timestamp: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for each Set Metadata control record.
+    fn on_set_metadata(&mut self, _metadata: &MetadataRecordData, _timestamp: u64) -> Result<()> {
+        Ok(())
     }
+
+    /// Called for each data record of an active entry with its raw payload.
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()>;
 }
 
 pub struct DataLogIterator<'a> {
     data: &'a [u8],
     pos: usize,
+    record_start: usize,
+    allow_truncated: bool,
+    entry_filter: Option<HashSet<u32>>,
+    /// Entry IDs with an active Start record, if orphans are yielded
+    started: Option<HashSet<u32>>,
+    quirks: Quirks,
+}
+
+impl DataLogIterator<'_> {
+    /// Byte offset of the next record in the log.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Byte offset of the header of the record returned last.
+    pub fn record_start(&self) -> usize {
+        self.record_start
+    }
+
+    /// End iteration without an error if the log is cut off mid-record, as
+    /// happens when a robot loses power while logging.
+    pub fn allow_truncated(mut self) -> Self {
+        self.allow_truncated = true;
+        self
+    }
+
+    /// With an entry filter, also yield data records of entry IDs that have
+    /// no active Start record, so orphans can still be counted.
+    pub fn with_orphans(mut self) -> Self {
+        self.started = Some(HashSet::new());
+        self
+    }
+
+    /// Entry IDs whose data records are yielded, if created with
+    /// [`DataLogReader::records_filtered`].
+    pub fn entry_filter_mut(&mut self) -> Option<&mut HashSet<u32>> {
+        self.entry_filter.as_mut()
+    }
 }
 
 impl<'a> Iterator for DataLogIterator<'a> {
     type Item = Result<DataLogRecord>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.data.len() < self.pos + 4 {
-            return None;
+        loop {
+            let header = match read_record_header(self.data, self.pos, self.quirks) {
+                Ok(Some(header)) => header,
+                Ok(None) => return None,
+                Err(Error::Truncated { .. }) if self.allow_truncated => return None,
+                Err(e) => {
+                    self.pos = self.data.len();
+                    return Some(Err(e));
+                }
+            };
+            self.record_start = self.pos + header.padding;
+            self.pos = header.payload_end;
+
+            let payload = &self.data[header.payload_start..header.payload_end];
+            if let Some(started) = &mut self.started {
+                // Follow Start and Finish records: control type, then entry ID
+                if let (0, [kind @ (CONTROL_START | CONTROL_FINISH), a, b, c, d, ..]) = (header.entry, payload) {
+                    let entry = u32::from_le_bytes([*a, *b, *c, *d]);
+                    if *kind == CONTROL_START {
+                        started.insert(entry);
+                    } else {
+                        started.remove(&entry);
+                    }
+                }
+            }
+            if let Some(filter) = &self.entry_filter {
+                let orphan = self.started.as_ref().is_some_and(|started| !started.contains(&header.entry));
+                if header.entry != 0 && !filter.contains(&header.entry) && !orphan {
+                    continue;
+                }
+            }
+
+            return Some(Ok(DataLogRecord {
+                entry: header.entry,
+                timestamp: header.timestamp,
+                data: payload.to_vec(),
+            }));
         }
+    }
+}
 
-        let header_byte = self.data[self.pos];
-        let entry_len = ((header_byte & 0x3) + 1) as usize;
-        let size_len = (((header_byte >> 2) & 0x3) + 1) as usize;
-        let timestamp_len = (((header_byte >> 4) & 0x7) + 1) as usize;
-        let header_len = 1 + entry_len + size_len + timestamp_len;
+/// Decoded record header with payload bounds.
+pub(crate) struct RecordHeader {
+    pub(crate) entry: u32,
+    pub(crate) timestamp: u64,
+    pub(crate) payload_start: usize,
+    pub(crate) payload_end: usize,
+    /// Padding bytes skipped before the header
+    pub(crate) padding: usize,
+    /// Whether the size field ran past the end of the log
+    pub(crate) oversized: bool,
+}
 
-        if self.data.len() < self.pos + header_len {
-            return None;
-        }
+/// Decode the record header at `pos`, tolerating `quirks`.
+///
+/// Returns `Ok(None)` at the end of the log, [`Error::Truncated`] if the
+/// remaining bytes cannot hold the complete record, and
+/// [`Error::InvalidRecord`] if its size overflows the address space.
+pub(crate) fn read_record_header(data: &[u8], pos: usize, quirks: Quirks) -> Result<Option<RecordHeader>> {
+    let mut padding = 0;
+    if quirks.padded_records {
+        padding = data.get(pos..).map_or(0, |rest| rest.iter().take_while(|&&b| b == 0).count());
+    }
+    let pos = pos + padding;
+    if pos >= data.len() {
+        return Ok(None);
+    }
+    let available = data.len() - pos;
+    let truncated = |needed: usize| Error::Truncated {
+        offset: pos,
+        needed,
+        available,
+    };
+
+    let header_byte = data[pos];
+    let entry_len = ((header_byte & 0x3) + 1) as usize;
+    let size_len = (((header_byte >> 2) & 0x3) + 1) as usize;
+    let timestamp_len = (((header_byte >> 4) & 0x7) + 1) as usize;
+    let header_len = 1 + entry_len + size_len + timestamp_len;
+
+    if available < header_len {
+        return Err(truncated(header_len));
+    }
 
-        let entry = read_varint(&self.data[self.pos + 1..], entry_len);
-        let size = read_varint(&self.data[self.pos + 1 + entry_len..], size_len) as usize;
-        let timestamp = read_varint(&self.data[self.pos + 1 + entry_len + size_len..], timestamp_len);
+    let entry = read_varint(&data[pos + 1..], entry_len);
+    let size = read_varint(&data[pos + 1 + entry_len..], size_len);
+    let timestamp = read_varint(&data[pos + 1 + entry_len + size_len..], timestamp_len);
+
+    let record_len = usize::try_from(size)
+        .ok()
+        .and_then(|size| size.checked_add(header_len))
+        .ok_or_else(|| {
+            Error::InvalidRecord(format!("payload size {} at offset {} is too large", size, pos))
+        })?;
+    let oversized = available < record_len;
+    if oversized && !quirks.oversized_lengths {
+        return Err(truncated(record_len));
+    }
 
-        if self.data.len() < self.pos + header_len + size {
-            return None;
-        }
+    Ok(Some(RecordHeader {
+        entry: entry as u32,
+        timestamp,
+        payload_start: pos + header_len,
+        payload_end: pos + record_len.min(available),
+        padding,
+        oversized,
+    }))
+}
 
-        let data = self.data[self.pos + header_len..self.pos + header_len + size].to_vec();
+/// Append a record with the smallest header that fits its fields.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidRecord`] if the payload is longer than the
+/// 4-byte size field of a record header can describe.
+pub(crate) fn write_record(out: &mut Vec<u8>, entry: u32, timestamp: u64, payload: &[u8]) -> Result<()> {
+    let size = length_prefix(payload.len(), "payload")?;
+    let entry_len = varint_len(entry as u64);
+    let size_len = varint_len(size as u64);
+    let timestamp_len = varint_len(timestamp);
+
+    out.push(((entry_len - 1) | ((size_len - 1) << 2) | ((timestamp_len - 1) << 4)) as u8);
+    out.extend_from_slice(&(entry as u64).to_le_bytes()[..entry_len]);
+    out.extend_from_slice(&(size as u64).to_le_bytes()[..size_len]);
+    out.extend_from_slice(&timestamp.to_le_bytes()[..timestamp_len]);
+    out.extend_from_slice(payload);
+    Ok(())
+}
 
-        let record = DataLogRecord {
-            entry: entry as u32,
-            timestamp,
-            data,
-        };
+/// Payload of a Start control record.
+pub(crate) fn start_payload(entry: u32, name: &str, type_name: &str, metadata: &str) -> Result<Vec<u8>> {
+    let mut payload = vec![CONTROL_START];
+    payload.extend_from_slice(&entry.to_le_bytes());
+    for (field, what) in [(name, "entry name"), (type_name, "type name"), (metadata, "metadata")] {
+        payload.extend_from_slice(&length_prefix(field.len(), what)?.to_le_bytes());
+        payload.extend_from_slice(field.as_bytes());
+    }
+    Ok(payload)
+}
 
-        self.pos += header_len + size;
+/// `len` as the 4-byte length of a header or payload field, failing for
+/// lengths a WPILOG file cannot store.
+pub(crate) fn length_prefix(len: usize, what: &str) -> Result<u32> {
+    u32::try_from(len).map_err(|_| {
+        Error::InvalidRecord(format!("{} of {} bytes is too long for a WPILOG file", what, len))
+    })
+}
 
-        Some(Ok(record))
-    }
+/// Number of bytes needed to store `value` as a little-endian varint (at least 1).
+fn varint_len(value: u64) -> usize {
+    (8 - value.leading_zeros() as usize / 8).max(1)
 }
 
 fn read_varint(data: &[u8], len: usize) -> u64 {
     let mut val = 0u64;
-    for i in 0..len {
-        val |= (data[i] as u64) << (i * 8);
+    for (i, &byte) in data.iter().take(len).enumerate() {
+        val |= (byte as u64) << (i * 8);
     }
     val
 }
@@ -334,4 +897,14 @@ mod tests {
         assert_eq!(read_varint(&data, 1), 1);
         assert_eq!(read_varint(&data, 4), 1);
     }
+
+    #[test]
+    fn test_length_prefix_fits_size_field() {
+        assert_eq!(length_prefix(u32::MAX as usize, "payload").unwrap(), u32::MAX);
+        assert_eq!(varint_len(u32::MAX as u64), 4);
+        assert!(matches!(
+            length_prefix(u32::MAX as usize + 1, "payload"),
+            Err(Error::InvalidRecord(_))
+        ));
+    }
 }
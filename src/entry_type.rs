@@ -0,0 +1,178 @@
+//! Declared entry types and typed record values.
+//!
+//! A Start record declares each entry's type as a string (`"double"`,
+//! `"struct:Pose2d[]"`, ...). [`EntryType`] is that string parsed once, and
+//! [`DecodedValue`] is a data payload decoded according to it.
+
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Type declared by an entry's Start record.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EntryType {
+    Boolean,
+    Int64,
+    Float,
+    Double,
+    String,
+    Json,
+    Msgpack,
+    BooleanArray,
+    Int64Array,
+    FloatArray,
+    DoubleArray,
+    StringArray,
+    /// Struct schema definition (`structschema`)
+    StructSchema,
+    /// Struct value; holds the schema name including the `struct:` prefix
+    Struct(String),
+    /// Array of structs; holds the schema name including the `struct:` prefix
+    StructArray(String),
+    /// Protobuf message; holds the full type string (e.g., `proto:Pose2d`)
+    Proto(String),
+    /// Raw bytes or any unrecognized type; holds the type string
+    Raw(String),
+}
+
+impl EntryType {
+    /// Parse a declared type string. Unknown types become [`EntryType::Raw`].
+    pub fn parse(type_name: &str) -> Self {
+        match type_name {
+            "boolean" => EntryType::Boolean,
+            "int64" => EntryType::Int64,
+            "float" => EntryType::Float,
+            "double" => EntryType::Double,
+            "string" => EntryType::String,
+            "json" => EntryType::Json,
+            "msgpack" => EntryType::Msgpack,
+            "boolean[]" => EntryType::BooleanArray,
+            "int64[]" => EntryType::Int64Array,
+            "float[]" => EntryType::FloatArray,
+            "double[]" => EntryType::DoubleArray,
+            "string[]" => EntryType::StringArray,
+            "structschema" => EntryType::StructSchema,
+            t if t.starts_with("struct:") => match t.strip_suffix("[]") {
+                Some(schema) => EntryType::StructArray(schema.to_string()),
+                None => EntryType::Struct(t.to_string()),
+            },
+            t if t.contains("proto") => EntryType::Proto(t.to_string()),
+            t => EntryType::Raw(t.to_string()),
+        }
+    }
+
+    /// Whether an empty payload is a valid value of this type, e.g. an empty
+    /// string or array. Fixed-size scalars, msgpack and single structs need
+    /// at least one byte.
+    pub fn allows_empty_payload(&self) -> bool {
+        !matches!(
+            self,
+            EntryType::Boolean
+                | EntryType::Int64
+                | EntryType::Float
+                | EntryType::Double
+                | EntryType::Msgpack
+                | EntryType::Struct(_)
+        )
+    }
+
+    /// What would decode values of this type, if this library writes them as
+    /// nulls; `None` for decoded types.
+    pub fn unsupported_hint(&self) -> Option<&'static str> {
+        match self {
+            EntryType::Proto(_) => Some(
+                "protobuf payloads are not decoded; log the value with its struct \
+                 serializer instead, or export the payloads with WpilogReader::read_blobs (--blobs)",
+            ),
+            EntryType::Raw(name) if name == "raw" => Some(
+                "raw payloads are not decoded; export them with WpilogReader::read_blobs (--blobs)",
+            ),
+            EntryType::Raw(_) => Some(
+                "unrecognized (vendor) type; export its payloads with WpilogReader::read_blobs \
+                 (--blobs) or decode them with a RecordVisitor on WpilogReader::low_level_reader",
+            ),
+            _ => None,
+        }
+    }
+
+    /// Whether values of this type are arrays.
+    pub fn is_array(&self) -> bool {
+        matches!(
+            self,
+            EntryType::BooleanArray
+                | EntryType::Int64Array
+                | EntryType::FloatArray
+                | EntryType::DoubleArray
+                | EntryType::StringArray
+                | EntryType::StructArray(_)
+        )
+    }
+}
+
+impl fmt::Display for EntryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EntryType::Boolean => "boolean",
+            EntryType::Int64 => "int64",
+            EntryType::Float => "float",
+            EntryType::Double => "double",
+            EntryType::String => "string",
+            EntryType::Json => "json",
+            EntryType::Msgpack => "msgpack",
+            EntryType::BooleanArray => "boolean[]",
+            EntryType::Int64Array => "int64[]",
+            EntryType::FloatArray => "float[]",
+            EntryType::DoubleArray => "double[]",
+            EntryType::StringArray => "string[]",
+            EntryType::StructSchema => "structschema",
+            EntryType::StructArray(schema) => return write!(f, "{}[]", schema),
+            EntryType::Struct(name) | EntryType::Proto(name) | EntryType::Raw(name) => name,
+        };
+        f.write_str(name)
+    }
+}
+
+impl From<&str> for EntryType {
+    fn from(type_name: &str) -> Self {
+        EntryType::parse(type_name)
+    }
+}
+
+/// A data payload decoded according to its [`EntryType`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedValue {
+    Boolean(bool),
+    Int64(i64),
+    Float(f32),
+    Double(f64),
+    /// `string`, `json` and `structschema` payloads
+    String(String),
+    Msgpack(rmpv::Value),
+    BooleanArray(Vec<bool>),
+    Int64Array(Vec<i64>),
+    FloatArray(Vec<f32>),
+    DoubleArray(Vec<f64>),
+    StringArray(Vec<String>),
+    /// Undecoded payload of struct, protobuf and raw entries
+    Raw(Vec<u8>),
+}
+
+impl DecodedValue {
+    /// Convert to JSON. Msgpack values use their debug representation and raw
+    /// payloads become arrays of bytes.
+    pub fn to_json(&self) -> Value {
+        match self {
+            DecodedValue::Boolean(v) => json!(v),
+            DecodedValue::Int64(v) => json!(v),
+            DecodedValue::Float(v) => json!(v),
+            DecodedValue::Double(v) => json!(v),
+            DecodedValue::String(v) => json!(v),
+            DecodedValue::Msgpack(v) => json!(format!("{:?}", v)),
+            DecodedValue::BooleanArray(v) => json!(v),
+            DecodedValue::Int64Array(v) => json!(v),
+            DecodedValue::FloatArray(v) => json!(v),
+            DecodedValue::DoubleArray(v) => json!(v),
+            DecodedValue::StringArray(v) => json!(v),
+            DecodedValue::Raw(v) => json!(v),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Error types for the WPILog parser library.
 
+use serde::Serialize;
 use std::fmt;
 
 /// Result type alias for WPILog operations.
@@ -20,15 +21,52 @@ pub enum Error {
     /// Data parsing error (e.g., wrong data type, corrupted data)
     ParseError(String),
 
+    /// Payload does not match the entry's declared type
+    TypeMismatch(String),
+
+    /// The log ends partway through a record or header
+    Truncated {
+        /// Byte offset where the incomplete item starts
+        offset: usize,
+        /// Bytes the item needs
+        needed: usize,
+        /// Bytes left in the log from `offset`
+        available: usize,
+    },
+
+    /// A record header whose fields cannot describe a valid record
+    InvalidRecord(String),
+
     /// Schema inference or validation error
     SchemaError(String),
 
+    /// An entry's declared type is recognized but its values cannot be decoded
+    UnsupportedType {
+        entry: String,
+        type_name: String,
+        /// What would decode the entry's values
+        hint: String,
+    },
+
     /// Output format error (e.g., Parquet write error)
     OutputError(String),
 
     /// UTF-8 encoding/decoding error
     Utf8Error(std::string::FromUtf8Error),
 
+    /// JSON encoding/decoding error
+    Json(serde_json::Error),
+
+    /// Arrow error while building record batches
+    Arrow(arrow::error::ArrowError),
+
+    /// Parquet error while writing output files
+    Parquet(parquet::errors::ParquetError),
+
+    /// Invalid or contradictory options, such as a reader configured to both
+    /// pivot and produce long rows
+    Config(String),
+
     /// Generic error with message
     Other(String),
 }
@@ -40,19 +78,81 @@ impl fmt::Display for Error {
             Error::Io(err) => write!(f, "I/O error: {}", err),
             Error::InvalidEntry(msg) => write!(f, "Invalid entry: {}", msg),
             Error::ParseError(msg) => write!(f, "Parse error: {}", msg),
+            Error::TypeMismatch(msg) => write!(f, "Type mismatch: {}", msg),
+            Error::Truncated {
+                offset,
+                needed,
+                available,
+            } => write!(
+                f,
+                "Truncated log: record at offset {} needs {} bytes, only {} available",
+                offset, needed, available
+            ),
+            Error::InvalidRecord(msg) => write!(f, "Invalid record: {}", msg),
             Error::SchemaError(msg) => write!(f, "Schema error: {}", msg),
+            Error::UnsupportedType {
+                entry,
+                type_name,
+                hint,
+            } => write!(f, "Unsupported type: entry '{}' has type {}: {}", entry, type_name, hint),
             Error::OutputError(msg) => write!(f, "Output error: {}", msg),
             Error::Utf8Error(err) => write!(f, "UTF-8 error: {}", err),
+            Error::Json(err) => write!(f, "JSON error: {}", err),
+            Error::Arrow(err) => write!(f, "Arrow error: {}", err),
+            Error::Parquet(err) => write!(f, "Parquet error: {}", err),
+            Error::Config(msg) => write!(f, "Invalid configuration: {}", msg),
             Error::Other(msg) => write!(f, "{}", msg),
         }
     }
 }
 
+/// A problem that does not stop a log from being read but leaves some of its
+/// data out of the output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Warning {
+    /// An entry's type is recognized but not decoded, so its column holds only
+    /// nulls
+    UnsupportedType {
+        entry: String,
+        type_name: String,
+        /// What would decode the entry's values
+        hint: String,
+    },
+    /// Values of an entry held invalid UTF-8, replaced with U+FFFD because of
+    /// [`Quirks::lossy_utf8`](crate::Quirks::lossy_utf8)
+    InvalidUtf8 {
+        entry: String,
+        /// Number of records with replaced text
+        records: u64,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::UnsupportedType {
+                entry,
+                type_name,
+                hint,
+            } => write!(f, "Entry '{}' ({}) is written as nulls: {}", entry, type_name, hint),
+            Warning::InvalidUtf8 { entry, records } => write!(
+                f,
+                "Entry '{}' has invalid UTF-8 in {} record(s); replaced with U+FFFD",
+                entry, records
+            ),
+        }
+    }
+}
+
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Error::Io(err) => Some(err),
             Error::Utf8Error(err) => Some(err),
+            Error::Json(err) => Some(err),
+            Error::Arrow(err) => Some(err),
+            Error::Parquet(err) => Some(err),
             _ => None,
         }
     }
@@ -70,8 +170,20 @@ impl From<std::string::FromUtf8Error> for Error {
     }
 }
 
-impl From<anyhow::Error> for Error {
-    fn from(err: anyhow::Error) -> Self {
-        Error::Other(err.to_string())
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl From<arrow::error::ArrowError> for Error {
+    fn from(err: arrow::error::ArrowError) -> Self {
+        Error::Arrow(err)
+    }
+}
+
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        Error::Parquet(err)
     }
 }
@@ -0,0 +1,231 @@
+//! Arrow layout for long-format rows.
+//!
+//! Long (tidy) output has one row per record with a fixed set of columns, so
+//! files stay narrow no matter how many entries a log has. Each value type from
+//! [`NestedValue`](crate::models::NestedValue) gets its own nullable column and
+//! exactly one of them is set per row; entry names are dictionary-encoded.
+//!
+//! | column               | type                      |
+//! |----------------------|---------------------------|
+//! | `timestamp`          | `Float64`                 |
+//! | `entry`              | `UInt32`                  |
+//! | `entry_name`         | `Dictionary(Int32, Utf8)` |
+//! | `type`               | `Utf8`                    |
+//! | `loop_count`         | `Int64`                   |
+//! | `value_double`       | `Float64`                 |
+//! | `value_float`        | `Float32`                 |
+//! | `value_int64`        | `Int64`                   |
+//! | `value_string`       | `Utf8`                    |
+//! | `value_bool`         | `Boolean`                 |
+//! | `value_json`         | `Utf8`                    |
+//! | `value_double_array` | `List(Float64)`           |
+//! | `value_float_array`  | `List(Float32)`           |
+//! | `value_int64_array`  | `List(Int64)`             |
+//! | `value_bool_array`   | `List(Boolean)`           |
+//! | `value_string_array` | `List(Utf8)`              |
+//! | `value_object`       | `Utf8`                    |
+//!
+//! `value_object` holds JSON text for values with no typed column: decoded
+//! structs, msgpack values and JSON that is not an object, such as nested
+//! lists. See [`Formatter::long_struct_fields`](crate::formatter::Formatter::long_struct_fields)
+//! to write struct fields as typed rows instead.
+//!
+//! With [`PathSegments`], entry names are also split on `/` into dictionary-
+//! encoded columns placed after `entry_name`, so outputs can be grouped by
+//! subsystem or device without string-splitting downstream.
+
+use arrow::array::{
+    ArrayRef, BooleanArray, BooleanBuilder, Float32Array, Float32Builder, Float64Array,
+    Float64Builder, Int64Array, Int64Builder, ListBuilder, RecordBatch, StringArray,
+    StringBuilder, StringDictionaryBuilder, UInt32Array,
+};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema, SchemaRef};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::models::LongRow;
+
+fn list_of(item: DataType) -> DataType {
+    DataType::List(Arc::new(Field::new("item", item, true)))
+}
+
+fn dictionary_utf8() -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+}
+
+/// Hierarchical columns built from the `/`-separated segments of entry names.
+///
+/// The last column always holds the last segment (the signal), and leading
+/// columns take leading segments. Names with more segments than columns have
+/// the extra middle segments joined with `/` into the second-to-last column;
+/// names with fewer leave middle columns null. With the columns `subsystem`,
+/// `device` and `signal`:
+///
+/// | entry name                   | subsystem | device          | signal     |
+/// |------------------------------|-----------|-----------------|------------|
+/// | `/Drive/Left/Velocity`       | `Drive`   | `Left`          | `Velocity` |
+/// | `/Drive/Gyro`                | `Drive`   | null            | `Gyro`     |
+/// | `/Drive/Module0/Steer/Angle` | `Drive`   | `Module0/Steer` | `Angle`    |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSegments {
+    columns: Vec<String>,
+}
+
+impl PathSegments {
+    /// Split names into the given columns. Must name at least one column.
+    pub fn new<S: Into<String>>(columns: impl IntoIterator<Item = S>) -> Result<Self> {
+        let columns: Vec<String> = columns.into_iter().map(Into::into).collect();
+        if columns.is_empty() {
+            return Err(Error::SchemaError(
+                "Path segment columns need at least one name".to_string(),
+            ));
+        }
+        Ok(Self { columns })
+    }
+
+    /// The `subsystem`, `device` and `signal` columns.
+    pub fn subsystem_device_signal() -> Self {
+        Self {
+            columns: vec!["subsystem".into(), "device".into(), "signal".into()],
+        }
+    }
+
+    /// Column names, in order.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Values of each column for the entry `name`.
+    pub fn split(&self, name: &str) -> Vec<Option<String>> {
+        let segments: Vec<&str> = name.split('/').filter(|s| !s.is_empty()).collect();
+        let mut values = vec![None; self.columns.len()];
+        let Some((leaf, parents)) = segments.split_last() else {
+            return values;
+        };
+        let (leaf_column, parent_columns) = values.split_last_mut().expect("at least one column");
+        *leaf_column = Some(leaf.to_string());
+        let last_parent = parent_columns.len().saturating_sub(1);
+        for (i, column) in parent_columns.iter_mut().enumerate() {
+            if i == last_parent && parents.len() > i {
+                *column = Some(parents[i..].join("/"));
+            } else if let Some(segment) = parents.get(i) {
+                *column = Some(segment.to_string());
+            }
+        }
+        values
+    }
+}
+
+/// Schema shared by every long-format file.
+pub fn long_schema() -> SchemaRef {
+    long_schema_with(None)
+}
+
+/// Long-format schema, with path segment columns after `entry_name` if given.
+pub fn long_schema_with(segments: Option<&PathSegments>) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("timestamp", DataType::Float64, false),
+        Field::new("entry", DataType::UInt32, false),
+        Field::new("entry_name", dictionary_utf8(), false),
+    ];
+    for column in segments.map(PathSegments::columns).unwrap_or_default() {
+        fields.push(Field::new(column, dictionary_utf8(), true));
+    }
+    fields.extend([
+        Field::new("type", DataType::Utf8, false),
+        Field::new("loop_count", DataType::Int64, false),
+        Field::new("value_double", DataType::Float64, true),
+        Field::new("value_float", DataType::Float32, true),
+        Field::new("value_int64", DataType::Int64, true),
+        Field::new("value_string", DataType::Utf8, true),
+        Field::new("value_bool", DataType::Boolean, true),
+        Field::new("value_json", DataType::Utf8, true),
+        Field::new("value_double_array", list_of(DataType::Float64), true),
+        Field::new("value_float_array", list_of(DataType::Float32), true),
+        Field::new("value_int64_array", list_of(DataType::Int64), true),
+        Field::new("value_bool_array", list_of(DataType::Boolean), true),
+        Field::new("value_string_array", list_of(DataType::Utf8), true),
+        Field::new("value_object", DataType::Utf8, true),
+    ]);
+    Arc::new(Schema::new(fields))
+}
+
+/// Build a record batch with [`long_schema`] from long-format rows.
+pub fn long_record_batch(rows: &[LongRow]) -> Result<RecordBatch> {
+    long_record_batch_with(rows, None)
+}
+
+/// Build a record batch with [`long_schema_with`] from long-format rows.
+pub fn long_record_batch_with(rows: &[LongRow], segments: Option<&PathSegments>) -> Result<RecordBatch> {
+    let values = || rows.iter().map(|r| r.value.as_ref());
+
+    let mut names = StringDictionaryBuilder::<Int32Type>::new();
+    for row in rows {
+        names.append_value(&row.entry_name);
+    }
+
+    let mut segment_columns: Vec<StringDictionaryBuilder<Int32Type>> = segments
+        .map(|s| s.columns().iter().map(|_| StringDictionaryBuilder::new()).collect())
+        .unwrap_or_default();
+    if let Some(segments) = segments {
+        let mut split: HashMap<&str, Vec<Option<String>>> = HashMap::new();
+        for row in rows {
+            let values = split
+                .entry(&row.entry_name)
+                .or_insert_with(|| segments.split(&row.entry_name));
+            for (builder, value) in segment_columns.iter_mut().zip(values.iter()) {
+                builder.append_option(value.as_deref());
+            }
+        }
+    }
+
+    let json: Vec<Option<String>> = rows
+        .iter()
+        .map(|r| r.json.as_ref().map(serde_json::to_string).transpose())
+        .collect::<std::result::Result<_, _>>()?;
+    let objects: Vec<Option<String>> = values()
+        .map(|v| v.and_then(|v| v.object.as_ref()).map(serde_json::to_string).transpose())
+        .collect::<std::result::Result<_, _>>()?;
+
+    let mut double_arrays = ListBuilder::new(Float64Builder::new());
+    let mut float_arrays = ListBuilder::new(Float32Builder::new());
+    let mut int64_arrays = ListBuilder::new(Int64Builder::new());
+    let mut bool_arrays = ListBuilder::new(BooleanBuilder::new());
+    let mut string_arrays = ListBuilder::new(StringBuilder::new());
+    for v in values() {
+        double_arrays.append_option(v.and_then(|v| v.double_array.as_ref()).map(|a| a.iter().copied().map(Some)));
+        float_arrays.append_option(v.and_then(|v| v.float_array.as_ref()).map(|a| a.iter().copied().map(Some)));
+        int64_arrays.append_option(v.and_then(|v| v.int64_array.as_ref()).map(|a| a.iter().copied().map(Some)));
+        bool_arrays.append_option(v.and_then(|v| v.boolean_array.as_ref()).map(|a| a.iter().copied().map(Some)));
+        string_arrays.append_option(v.and_then(|v| v.string_array.as_ref()).map(|a| a.iter().map(Some)));
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| r.timestamp))),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.entry))),
+        Arc::new(names.finish()),
+    ];
+    for mut builder in segment_columns {
+        columns.push(Arc::new(builder.finish()));
+    }
+    let value_columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.type_name.as_str()))),
+        Arc::new(Int64Array::from_iter_values(rows.iter().map(|r| r.loop_count as i64))),
+        Arc::new(Float64Array::from_iter(values().map(|v| v.and_then(|v| v.double)))),
+        Arc::new(Float32Array::from_iter(values().map(|v| v.and_then(|v| v.float)))),
+        Arc::new(Int64Array::from_iter(values().map(|v| v.and_then(|v| v.int64)))),
+        Arc::new(StringArray::from_iter(values().map(|v| v.and_then(|v| v.string.as_deref())))),
+        Arc::new(BooleanArray::from_iter(values().map(|v| v.and_then(|v| v.boolean)))),
+        Arc::new(StringArray::from_iter(json)),
+        Arc::new(double_arrays.finish()),
+        Arc::new(float_arrays.finish()),
+        Arc::new(int64_arrays.finish()),
+        Arc::new(bool_arrays.finish()),
+        Arc::new(string_arrays.finish()),
+        Arc::new(StringArray::from_iter(objects)),
+    ];
+    columns.extend(value_columns);
+
+    Ok(RecordBatch::try_new(long_schema_with(segments), columns)?)
+}
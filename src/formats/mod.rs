@@ -1 +1,2 @@
+pub mod long;
 pub mod parquet;
@@ -1,116 +1,612 @@
-use anyhow::Result;
 use arrow::array::{
-    ArrayRef, BooleanArray, Float32Array, Float64Array, Int64Array, RecordBatch,
+    Array, ArrayRef, AsArray, BooleanArray, Float32Array, Float64Array, Int64Array, RecordBatch,
     StringArray, UInt32Array, ListBuilder, Float64Builder, Int64Builder, Float32Builder,
-    BooleanBuilder, StringBuilder,
+    BooleanBuilder, GenericStringBuilder, LargeStringArray, ListArray, OffsetSizeTrait,
+    StringDictionaryBuilder, StructArray,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::compute::cast;
+use arrow::datatypes::{
+    DataType, Field, FieldRef, Fields, Float32Type, Float64Type, Int32Type, Int64Type, Schema, SchemaRef,
+    UInt32Type,
 };
-use arrow::datatypes::{DataType, Field, Schema};
 use log::info;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::ArrowWriter;
-use parquet::file::properties::WriterProperties;
-use std::collections::HashMap;
-use std::fs::{create_dir_all, File};
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use parquet::schema::types::ColumnPath;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::thread;
+
+use crate::error::{Error, Result};
+use crate::formats::long::{long_record_batch_with, PathSegments};
+use crate::models::{LongRow, WideRow};
+use crate::sink::{DirectorySink, OutputSink};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Most text bytes a `Utf8` array can hold, as its offsets are `i32`.
+pub const MAX_UTF8_BYTES: usize = i32::MAX as usize;
+
+/// Parquet type forced on a wide-format column, overriding inference.
+///
+/// Values that cannot be represented in the chosen type are written as nulls;
+/// `String` and `Dictionary` columns hold the text form of any value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnType {
+    Boolean,
+    Int64,
+    Float32,
+    Float64,
+    String,
+    /// Dictionary-encoded strings, for low-cardinality text such as modes
+    Dictionary,
+}
+
+impl ColumnType {
+    /// Arrow type of columns written with this override.
+    pub fn data_type(self) -> DataType {
+        match self {
+            ColumnType::Boolean => DataType::Boolean,
+            ColumnType::Int64 => DataType::Int64,
+            ColumnType::Float32 => DataType::Float32,
+            ColumnType::Float64 => DataType::Float64,
+            ColumnType::String => DataType::Utf8,
+            ColumnType::Dictionary => {
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+            }
+        }
+    }
+}
+
+impl FromStr for ColumnType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "boolean" | "bool" => Ok(ColumnType::Boolean),
+            "int64" => Ok(ColumnType::Int64),
+            "float32" | "float" => Ok(ColumnType::Float32),
+            "float64" | "double" => Ok(ColumnType::Float64),
+            "string" | "utf8" => Ok(ColumnType::String),
+            "dictionary" => Ok(ColumnType::Dictionary),
+            _ => Err(Error::SchemaError(format!("Unknown column type '{}'", s))),
+        }
+    }
+}
+
+/// Order of the value columns that follow the fixed columns of a wide-format
+/// file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnOrder {
+    /// Sorted by name
+    #[default]
+    Alphabetical,
+    /// In the order their entries were started in the log (by entry ID, which
+    /// WPILib assigns in start order), columns of one entry by name
+    Declaration,
+    /// The listed columns first, in the given order, then the rest by name
+    Explicit(Vec<String>),
+}
 
-use crate::models::WideRow;
+impl FromStr for ColumnOrder {
+    type Err = Error;
+
+    /// Parse `alphabetical` or `declaration`; explicit orders are built from
+    /// a list of names.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "alphabetical" | "name" => Ok(ColumnOrder::Alphabetical),
+            "declaration" | "start" => Ok(ColumnOrder::Declaration),
+            _ => Err(Error::SchemaError(format!("Unknown column order '{}'", s))),
+        }
+    }
+}
+
+/// Compression codec of Parquet files.
+///
+/// Zstandard gives the smallest files, Snappy and LZ4 the fastest writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParquetCompression {
+    /// No compression, the Parquet writer's default
+    #[default]
+    Uncompressed,
+    Snappy,
+    /// LZ4 in the `LZ4_RAW` encoding
+    Lz4,
+    /// Zstandard at a level from 1 (fastest) to 22 (smallest)
+    Zstd(i32),
+}
+
+impl ParquetCompression {
+    /// The Parquet codec.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] for a Zstandard level outside 1 to 22.
+    pub fn codec(self) -> Result<Compression> {
+        Ok(match self {
+            ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Lz4 => Compression::LZ4_RAW,
+            ParquetCompression::Zstd(level) => {
+                Compression::ZSTD(ZstdLevel::try_new(level).map_err(|_| {
+                    Error::Config(format!("zstd level must be from 1 to 22, not {}", level))
+                })?)
+            }
+        })
+    }
+}
+
+impl FromStr for ParquetCompression {
+    type Err = Error;
+
+    /// Parse `none`, `snappy`, `lz4`, `zstd` (level 3) or `zstd:LEVEL`.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.to_ascii_lowercase();
+        let compression = match s.split_once(':') {
+            Some(("zstd", level)) => ParquetCompression::Zstd(level.parse().map_err(|_| {
+                Error::Config(format!("Invalid zstd level '{}'", level))
+            })?),
+            _ => match s.as_str() {
+                "none" | "uncompressed" => ParquetCompression::Uncompressed,
+                "snappy" => ParquetCompression::Snappy,
+                "lz4" => ParquetCompression::Lz4,
+                "zstd" => ParquetCompression::Zstd(3),
+                _ => return Err(Error::Config(format!("Unknown compression '{}'", s))),
+            },
+        };
+        compression.codec()?;
+        Ok(compression)
+    }
+}
+
+/// How much column statistics Parquet files carry.
+///
+/// Statistics let query engines skip row groups (`Chunk`) or pages (`Page`)
+/// that cannot match a filter, at the cost of a little file size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatisticsLevel {
+    /// No statistics
+    None,
+    /// Per row group
+    Chunk,
+    /// Per row group and page, the Parquet writer's default
+    #[default]
+    Page,
+}
+
+impl From<StatisticsLevel> for EnabledStatistics {
+    fn from(level: StatisticsLevel) -> Self {
+        match level {
+            StatisticsLevel::None => EnabledStatistics::None,
+            StatisticsLevel::Chunk => EnabledStatistics::Chunk,
+            StatisticsLevel::Page => EnabledStatistics::Page,
+        }
+    }
+}
+
+impl FromStr for StatisticsLevel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(StatisticsLevel::None),
+            "chunk" | "row-group" => Ok(StatisticsLevel::Chunk),
+            "page" => Ok(StatisticsLevel::Page),
+            _ => Err(Error::Config(format!("Unknown statistics level '{}'", s))),
+        }
+    }
+}
+
+/// Value columns gathered while inferring a wide-format schema.
+#[derive(Default)]
+struct ColumnInference {
+    /// Column names in the order they were first seen
+    columns: Vec<String>,
+    types: HashMap<String, DataType>,
+    /// Entry ID of the row each column was first seen in
+    declared_by: HashMap<String, u32>,
+    /// String columns too large for `Utf8` in at least one chunk
+    large: HashSet<String>,
+}
 
 pub struct ParquetFormatter {
-    output_directory: String,
+    sink: Arc<dyn OutputSink>,
     chunk_size: usize,
+    properties: WriterProperties,
+    threads: usize,
+    target_file_size: Option<usize>,
+    path_segments: Option<PathSegments>,
+    column_types: HashMap<String, ColumnType>,
+    first_chunk: usize,
+    large_string_threshold: usize,
+    max_bytes_in_flight: Option<usize>,
+    column_order: ColumnOrder,
+    constant_columns: Vec<(String, String)>,
+    schema: Option<SchemaRef>,
 }
 
 impl ParquetFormatter {
     pub fn new(output_directory: String, chunk_size: usize) -> Self {
         Self {
-            output_directory,
+            sink: Arc::new(DirectorySink::new(output_directory)),
             chunk_size,
+            properties: WriterProperties::builder().build(),
+            threads: 1,
+            target_file_size: None,
+            path_segments: None,
+            column_types: HashMap::new(),
+            first_chunk: 0,
+            large_string_threshold: MAX_UTF8_BYTES,
+            max_bytes_in_flight: None,
+            column_order: ColumnOrder::Alphabetical,
+            constant_columns: Vec::new(),
+            schema: None,
+        }
+    }
+
+    /// Write files to `sink` instead of the output directory.
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// Add a string column holding `value` in every row, e.g. the event,
+    /// match or robot a log was recorded on.
+    ///
+    /// Constant columns follow the fixed columns of wide-format files and end
+    /// long-format files, in the order they were added; adding a name again
+    /// replaces its value. Writing fails if a constant column has the name of
+    /// another column.
+    pub fn with_constant_column(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        match self.constant_columns.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(column) => column.1 = value,
+            None => self.constant_columns.push((name, value)),
         }
+        self
+    }
+
+    /// Order wide-format value columns by `order` instead of by name.
+    /// See [`ColumnOrder`].
+    pub fn with_column_order(mut self, order: ColumnOrder) -> Self {
+        self.column_order = order;
+        self
+    }
+
+    /// Cap the estimated memory of the Arrow arrays built for one chunk at
+    /// `bytes`, splitting chunks that would exceed it into more files.
+    ///
+    /// Every value column of a chunk holds a slot for each of its rows, so a
+    /// chunk mixing many wide array entries costs far more than its values
+    /// alone; the estimate counts those slots too. Applies on top of
+    /// [`chunk_size`](Self::new) or [`with_target_file_size`](Self::with_target_file_size).
+    /// Every chunk holds at least one row.
+    pub fn with_max_bytes_in_flight(mut self, bytes: usize) -> Self {
+        self.max_bytes_in_flight = Some(bytes);
+        self
+    }
+
+    /// Write string columns whose text in one chunk exceeds `bytes` as
+    /// `LargeUtf8` (64-bit offsets) instead of `Utf8`.
+    ///
+    /// Defaults to [`MAX_UTF8_BYTES`], so only columns that would overflow
+    /// `Utf8`, such as large JSON dumps, are switched. List-of-string columns
+    /// switch their items the same way.
+    pub fn with_large_string_threshold(mut self, bytes: usize) -> Self {
+        self.large_string_threshold = bytes;
+        self
+    }
+
+    /// Number chunk files from `index` instead of 0, to add files next to
+    /// those of an earlier conversion.
+    pub fn with_first_chunk_index(mut self, index: usize) -> Self {
+        self.first_chunk = index;
+        self
+    }
+
+    /// Force the type of wide-format columns by name instead of inferring it.
+    /// See [`ColumnType`].
+    pub fn with_column_types(mut self, column_types: HashMap<String, ColumnType>) -> Self {
+        self.column_types = column_types;
+        self
+    }
+
+    /// Write every wide-format file with `schema` instead of inferring one.
+    ///
+    /// Fields are filled by name: the fixed columns, constant columns and
+    /// value columns, with values cast to the field's type and missing values
+    /// written as nulls. Writing fails if a row has a value column the schema
+    /// lacks. Long-format files keep their fixed layout.
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Split rows into chunks of roughly `bytes` each instead of a fixed row
+    /// count.
+    ///
+    /// The size of each row is estimated from its uncompressed values, so a
+    /// chunk of large arrays holds far fewer rows than a chunk of booleans.
+    /// Every chunk holds at least one row.
+    pub fn with_target_file_size(mut self, bytes: usize) -> Self {
+        self.target_file_size = Some(bytes);
+        self
+    }
+
+    /// Write up to `threads` chunk files concurrently.
+    ///
+    /// `0` uses the number of available CPUs. Each worker holds one encoded
+    /// chunk in memory at a time.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        self
+    }
+
+    /// Add hierarchical columns split from entry names to long-format output.
+    /// See [`PathSegments`].
+    pub fn with_path_segments(mut self, segments: PathSegments) -> Self {
+        self.path_segments = Some(segments);
+        self
+    }
+
+    /// Use the given Parquet writer properties for every chunk file.
+    pub fn with_writer_properties(mut self, properties: WriterProperties) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Enable bloom filters on the `type` and `entry` columns and page-level
+    /// statistics (column/offset indexes) on `timestamp`.
+    ///
+    /// This lets query engines such as DuckDB skip row groups and pages when
+    /// filtering by entry or time range. Applied on top of the current writer
+    /// properties.
+    pub fn with_lookup_indexes(mut self) -> Self {
+        self.properties = self
+            .properties
+            .into_builder()
+            .set_column_bloom_filter_enabled(ColumnPath::from("type"), true)
+            .set_column_bloom_filter_enabled(ColumnPath::from("entry"), true)
+            .set_column_statistics_enabled(ColumnPath::from("timestamp"), EnabledStatistics::Page)
+            .build();
+        self
     }
 
     pub fn convert(&self, rows: &[WideRow]) -> Result<()> {
-        if rows.is_empty() {
-            anyhow::bail!("No valid records to write to Parquet");
+        self.convert_chunks(&self.plan_chunks(rows))
+    }
+
+    /// Split rows into the chunks that [`convert`](Self::convert) writes, one
+    /// file per chunk.
+    pub fn plan_chunks<'a>(&self, rows: &'a [WideRow]) -> Vec<&'a [WideRow]> {
+        let chunks = match self.target_file_size {
+            Some(target) => chunks_by_size(rows, target),
+            None => rows.chunks(self.chunk_size).collect(),
+        };
+        match self.max_bytes_in_flight {
+            Some(max_bytes) => chunks
+                .into_iter()
+                .flat_map(|chunk| chunks_by_memory(chunk, max_bytes))
+                .collect(),
+            None => chunks,
         }
+    }
 
-        create_dir_all(&self.output_directory)?;
+    /// Write pre-planned chunks, one file per chunk.
+    ///
+    /// Every file has the same schema: the one set with
+    /// [`with_schema`](Self::with_schema), or else
+    /// [`unified_schema`](Self::unified_schema) of all the chunks.
+    pub fn convert_chunks(&self, chunks: &[&[WideRow]]) -> Result<()> {
+        if chunks.iter().all(|chunk| chunk.is_empty()) {
+            return Err(Error::OutputError(
+                "No valid records to write to Parquet".to_string(),
+            ));
+        }
+
+        let schema = match &self.schema {
+            Some(schema) => Arc::clone(schema),
+            None => self.unified_schema(chunks)?,
+        };
 
-        let total_chunks = (rows.len() + self.chunk_size - 1) / self.chunk_size;
         info!(
             "Generated a total of {} chunks, will now create that total amount of files.",
-            total_chunks
+            chunks.len()
         );
 
-        for (i, chunk) in rows.chunks(self.chunk_size).enumerate() {
-            info!(
-                "Writing chunk {}/{}, {} rows",
-                i + 1,
-                total_chunks,
-                chunk.len()
-            );
-
-            let output_path = Path::new(&self.output_directory)
-                .join(format!("file_part{:03}.parquet", i));
+        let threads = self.threads.clamp(1, chunks.len());
 
-            self.write_chunk_to_parquet(chunk, &output_path)?;
+        if threads == 1 {
+            for (i, chunk) in chunks.iter().enumerate() {
+                self.write_chunk(i, chunks.len(), chunk, &schema)?;
+            }
+        } else {
+            self.write_chunks_parallel(chunks, threads, &schema)?;
         }
 
         info!("All chunks have been written");
         Ok(())
     }
 
-    fn write_chunk_to_parquet(&self, rows: &[WideRow], output_path: &Path) -> Result<()> {
-        // Build schema and infer types in a single pass
-        let (all_columns, column_types) = self.infer_schema_single_pass(rows);
+    /// Write rows arriving in batches, holding at most one batch plus one
+    /// partial chunk in memory.
+    ///
+    /// Chunks are planned as in [`plan_chunks`](Self::plan_chunks) and written
+    /// sequentially. Returns the number of rows in each file written.
+    ///
+    /// Files are written before all rows are seen, so without
+    /// [`with_schema`](Self::with_schema) each file gets the schema of the
+    /// files before it, [extended](Self::extend_schema) with the columns new
+    /// in its rows: columns keep one type, but later files may have columns
+    /// earlier ones lack. Pass the batches to
+    /// [`streamed_schema`](Self::streamed_schema) first if they can be read
+    /// twice.
+    pub fn convert_batches<I>(&self, batches: I) -> Result<Vec<usize>>
+    where
+        I: IntoIterator<Item = Result<Vec<WideRow>>>,
+    {
+        let mut schema = self.schema.clone();
+        let written = self.stream_chunks(batches, |index, chunk| {
+            info!("Writing chunk {}, {} rows", index + 1, chunk.len());
+            let chunk_schema = match (&self.schema, &schema) {
+                (Some(fixed), _) => Arc::clone(fixed),
+                (None, Some(previous)) => self.extend_schema(previous, chunk)?,
+                (None, None) => self.unified_schema(&[chunk])?,
+            };
+            self.write_chunk_to_parquet(chunk, &self.chunk_name(index), &chunk_schema)?;
+            schema = Some(chunk_schema);
+            Ok(())
+        })?;
 
-        let mut fields = vec![
-            Field::new("timestamp", DataType::Float64, false),
-            Field::new("entry", DataType::UInt32, false),
-            Field::new("type", DataType::Utf8, false),
-            Field::new("loop_count", DataType::Int64, false),
-        ];
+        info!("All chunks have been written");
+        Ok(written)
+    }
 
-        // Add dynamic fields with inferred types (already sorted)
-        for col_name in &all_columns {
-            let data_type = column_types.get(col_name).cloned().unwrap_or(DataType::Utf8);
-            fields.push(Field::new(col_name.as_str(), data_type, true));
-        }
+    /// The schema shared by every file [`convert_batches`](Self::convert_batches)
+    /// would write for `batches`, inferred as in
+    /// [`unified_schema`](Self::unified_schema).
+    pub fn streamed_schema<I>(&self, batches: I) -> Result<SchemaRef>
+    where
+        I: IntoIterator<Item = Result<Vec<WideRow>>>,
+    {
+        let mut inference = ColumnInference::default();
+        self.stream_chunks(batches, |_, chunk| {
+            self.infer_columns(&mut inference, chunk);
+            Ok(())
+        })?;
+        self.inferred_schema(inference)
+    }
 
-        let schema = Arc::new(Schema::new(fields));
+    /// Plan the chunks of rows arriving in batches and hand each to `write`
+    /// with its index. Returns the number of rows in each chunk.
+    fn stream_chunks<I, F>(&self, batches: I, mut write: F) -> Result<Vec<usize>>
+    where
+        I: IntoIterator<Item = Result<Vec<WideRow>>>,
+        F: FnMut(usize, &[WideRow]) -> Result<()>,
+    {
+        let mut written = Vec::new();
+        let mut buffer: Vec<WideRow> = Vec::new();
 
-        // Build arrays with pre-allocated capacity
-        let num_rows = rows.len();
-        let mut timestamp_vec = Vec::with_capacity(num_rows);
-        let mut entry_vec = Vec::with_capacity(num_rows);
-        let mut type_vec = Vec::with_capacity(num_rows);
-        let mut loop_count_vec = Vec::with_capacity(num_rows);
+        for batch in batches {
+            buffer.extend(batch?);
 
-        for row in rows {
-            timestamp_vec.push(row.timestamp);
-            entry_vec.push(row.entry);
-            type_vec.push(row.type_name.as_str());
-            loop_count_vec.push(row.loop_count as i64);
+            // The last planned chunk may still grow with the next batch
+            let chunks = self.plan_chunks(&buffer);
+            let complete = chunks.len().saturating_sub(1);
+            let mut consumed = 0;
+            for chunk in &chunks[..complete] {
+                write(written.len(), chunk)?;
+                written.push(chunk.len());
+                consumed += chunk.len();
+            }
+            buffer.drain(..consumed);
         }
 
-        let timestamps: ArrayRef = Arc::new(Float64Array::from(timestamp_vec));
-        let entries: ArrayRef = Arc::new(UInt32Array::from(entry_vec));
-        let types: ArrayRef = Arc::new(StringArray::from(type_vec));
-        let loop_counts: ArrayRef = Arc::new(Int64Array::from(loop_count_vec));
+        if !buffer.is_empty() {
+            write(written.len(), &buffer)?;
+            written.push(buffer.len());
+        }
+
+        if written.is_empty() {
+            return Err(Error::OutputError(
+                "No valid records to write to Parquet".to_string(),
+            ));
+        }
 
-        let mut arrays: Vec<ArrayRef> = vec![timestamps, entries, types, loop_counts];
+        Ok(written)
+    }
 
-        // Add dynamic columns with proper types
-        for col_name in &all_columns {
-            let data_type = column_types.get(col_name).cloned().unwrap_or(DataType::Utf8);
-            let array = self.build_typed_array(rows, col_name, &data_type)?;
-            arrays.push(array);
+    /// Write long-format rows, `chunk_size` rows per file, using the fixed
+    /// [`long_schema_with`](crate::formats::long::long_schema_with) layout.
+    pub fn convert_long(&self, rows: &[LongRow]) -> Result<()> {
+        if rows.is_empty() {
+            return Err(Error::OutputError(
+                "No valid records to write to Parquet".to_string(),
+            ));
         }
 
-        let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+        let total_chunks = rows.len().div_ceil(self.chunk_size);
+        for (i, chunk) in rows.chunks(self.chunk_size).enumerate() {
+            info!("Writing chunk {}/{}, {} rows", i + 1, total_chunks, chunk.len());
 
-        let file = File::create(output_path)?;
-        let props = WriterProperties::builder().build();
-        let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+            let batch = long_record_batch_with(chunk, self.path_segments.as_ref())?;
+            let batch = self.stamp_constant_columns(batch, None)?;
+            let file = self.sink.create(&self.chunk_name(i))?;
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(self.properties.clone()))?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+
+        info!("All chunks have been written");
+        Ok(())
+    }
+
+    fn chunk_name(&self, index: usize) -> String {
+        chunk_file_name(self.first_chunk + index)
+    }
+
+    /// Write chunks from a shared queue on `threads` scoped workers.
+    ///
+    /// Workers stop picking up new chunks once any chunk fails; the first error
+    /// is returned.
+    fn write_chunks_parallel(&self, chunks: &[&[WideRow]], threads: usize, schema: &SchemaRef) -> Result<()> {
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| -> Result<()> {
+                        while !failed.load(Ordering::Relaxed) {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(chunk) = chunks.get(i) else {
+                                break;
+                            };
+                            if let Err(e) = self.write_chunk(i, chunks.len(), chunk, schema) {
+                                failed.store(true, Ordering::Relaxed);
+                                return Err(e);
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+
+            workers.into_iter().try_for_each(|worker| {
+                worker
+                    .join()
+                    .map_err(|_| Error::OutputError("Chunk writer thread panicked".to_string()))?
+            })
+        })
+    }
+
+    fn write_chunk(&self, index: usize, total_chunks: usize, rows: &[WideRow], schema: &SchemaRef) -> Result<()> {
+        info!(
+            "Writing chunk {}/{}, {} rows",
+            index + 1,
+            total_chunks,
+            rows.len()
+        );
+
+        self.write_chunk_to_parquet(rows, &self.chunk_name(index), schema)
+    }
+
+    fn write_chunk_to_parquet(&self, rows: &[WideRow], name: &str, schema: &SchemaRef) -> Result<()> {
+        let batch = self.record_batch_with_schema(rows, schema)?;
+
+        let file = self.sink.create(name)?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(self.properties.clone()))?;
 
         writer.write(&batch)?;
         writer.close()?;
@@ -118,9 +614,186 @@ impl ParquetFormatter {
         Ok(())
     }
 
-    fn infer_schema_single_pass(&self, rows: &[WideRow]) -> (Vec<String>, HashMap<String, DataType>) {
-        let mut column_types = HashMap::new();
-        let mut column_order = Vec::new();
+    /// Build the wide-format Arrow batch that a chunk of `rows` is written as:
+    /// the fixed `timestamp`, `entry`, `type` and `loop_count` columns followed
+    /// by one typed column per value column, sorted by name unless another
+    /// [`ColumnOrder`] is set.
+    ///
+    /// The schema is inferred from `rows` alone; see
+    /// [`record_batch_with_schema`](Self::record_batch_with_schema) to share
+    /// one between batches. String columns too large for `Utf8` are written as
+    /// `LargeUtf8`; see
+    /// [`with_large_string_threshold`](Self::with_large_string_threshold).
+    pub fn record_batch(&self, rows: &[WideRow]) -> Result<RecordBatch> {
+        self.record_batch_with_schema(rows, &self.unified_schema(&[rows])?)
+    }
+
+    /// Build the wide-format Arrow batch of `rows` with the fields of
+    /// `schema`, filled as described in [`with_schema`](Self::with_schema).
+    pub fn record_batch_with_schema(&self, rows: &[WideRow], schema: &SchemaRef) -> Result<RecordBatch> {
+        let names: HashSet<&str> = schema.fields().iter().map(|field| field.name().as_str()).collect();
+        for name in self.constant_columns.iter().map(|(name, _)| name) {
+            if !names.contains(name.as_str()) {
+                return Err(Error::SchemaError(format!(
+                    "Constant column '{}' is not in the Parquet schema",
+                    name
+                )));
+            }
+        }
+        for col_name in rows.iter().flat_map(|row| row.data.keys()) {
+            if !names.contains(col_name.as_str()) {
+                return Err(Error::SchemaError(format!(
+                    "Column '{}' is not in the Parquet schema",
+                    col_name
+                )));
+            }
+            if self.constant_columns.iter().any(|(name, _)| name == col_name) {
+                return Err(Error::SchemaError(format!(
+                    "Constant column '{}' has the name of a logged or fixed column",
+                    col_name
+                )));
+            }
+        }
+
+        let arrays = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let array: ArrayRef = match field.name().as_str() {
+                    "timestamp" => Arc::new(Float64Array::from_iter_values(rows.iter().map(|row| row.timestamp))),
+                    "entry" => Arc::new(UInt32Array::from_iter_values(rows.iter().map(|row| row.entry))),
+                    "type" => Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.type_name.as_str()))),
+                    "loop_count" => Arc::new(Int64Array::from_iter_values(
+                        rows.iter().map(|row| row.loop_count as i64),
+                    )),
+                    name => match self.constant_columns.iter().find(|(constant, _)| constant == name) {
+                        Some((_, value)) => Arc::new(StringArray::from(vec![value.as_str(); rows.len()])),
+                        None => self.build_typed_array(rows, name, field.data_type())?,
+                    },
+                };
+                // Strings of a small chunk may fit `Utf8` where the schema
+                // asks for `LargeUtf8`, and supplied schemas may ask for any type
+                if array.data_type() == field.data_type() {
+                    Ok(array)
+                } else {
+                    Ok(cast(&array, field.data_type())?)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RecordBatch::try_new(Arc::clone(schema), arrays)?)
+    }
+
+    /// Infer one wide-format schema covering every row of `chunks`, so the
+    /// files they are written to can be read together.
+    ///
+    /// Every value column of any chunk is included, typed by its first
+    /// non-null value unless [`with_column_types`](Self::with_column_types)
+    /// says otherwise. A string column is `LargeUtf8` if its text in any one
+    /// chunk exceeds the [large string threshold](Self::with_large_string_threshold).
+    pub fn unified_schema(&self, chunks: &[&[WideRow]]) -> Result<SchemaRef> {
+        let mut inference = ColumnInference::default();
+        for chunk in chunks {
+            self.infer_columns(&mut inference, chunk);
+        }
+        self.inferred_schema(inference)
+    }
+
+    /// `base` with the value columns of `rows` it lacks appended, typed as
+    /// in [`unified_schema`](Self::unified_schema). Columns already in `base`
+    /// keep their type, so files written with the result can be read with
+    /// files written with `base`.
+    pub fn extend_schema(&self, base: &SchemaRef, rows: &[WideRow]) -> Result<SchemaRef> {
+        let inferred = self.unified_schema(&[rows])?;
+        let added: Vec<_> = inferred
+            .fields()
+            .iter()
+            .filter(|field| base.column_with_name(field.name()).is_none())
+            .cloned()
+            .collect();
+        if added.is_empty() {
+            return Ok(Arc::clone(base));
+        }
+        let fields: Vec<_> = base.fields().iter().cloned().chain(added).collect();
+        Ok(Arc::new(Schema::new_with_metadata(fields, base.metadata().clone())))
+    }
+
+    /// Insert the constant columns into `batch` before column `index`, or
+    /// after the last column if `None`.
+    fn stamp_constant_columns(&self, batch: RecordBatch, index: Option<usize>) -> Result<RecordBatch> {
+        if self.constant_columns.is_empty() {
+            return Ok(batch);
+        }
+
+        let schema = batch.schema();
+        let index = index.unwrap_or(schema.fields().len());
+        let mut fields: Vec<_> = schema.fields().iter().cloned().collect();
+        let mut arrays = batch.columns().to_vec();
+        for (offset, (name, value)) in self.constant_columns.iter().enumerate() {
+            if schema.column_with_name(name).is_some() {
+                return Err(Error::SchemaError(format!(
+                    "Constant column '{}' has the name of a logged or fixed column",
+                    name
+                )));
+            }
+            fields.insert(index + offset, Arc::new(Field::new(name.as_str(), DataType::Utf8, false)));
+            let array: ArrayRef = Arc::new(StringArray::from(vec![value.as_str(); batch.num_rows()]));
+            arrays.insert(index + offset, array);
+        }
+        Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+    }
+
+    /// The schema of the columns gathered in `inference`: the fixed columns,
+    /// the constant columns and the value columns in [`ColumnOrder`].
+    fn inferred_schema(&self, inference: ColumnInference) -> Result<SchemaRef> {
+        let ColumnInference {
+            mut columns,
+            mut types,
+            declared_by,
+            large,
+        } = inference;
+
+        // Sort column names for consistent output
+        columns.sort();
+        match &self.column_order {
+            ColumnOrder::Alphabetical => {}
+            ColumnOrder::Declaration => columns.sort_by_key(|col_name| declared_by[col_name]),
+            ColumnOrder::Explicit(first) => columns.sort_by_key(|col_name| {
+                first.iter().position(|name| name == col_name).unwrap_or(first.len())
+            }),
+        }
+
+        let mut fields = vec![
+            Field::new("timestamp", DataType::Float64, false),
+            Field::new("entry", DataType::UInt32, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("loop_count", DataType::Int64, false),
+        ];
+        for col_name in columns {
+            let data_type = match types.remove(&col_name) {
+                Some(DataType::Utf8) if large.contains(&col_name) => DataType::LargeUtf8,
+                Some(DataType::List(item))
+                    if item.data_type() == &DataType::Utf8 && large.contains(&col_name) =>
+                {
+                    DataType::List(Arc::new(Field::new("item", DataType::LargeUtf8, true)))
+                }
+                Some(data_type) => data_type,
+                // Only columns of empty arrays have no type
+                None => DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            };
+            fields.push(Field::new(col_name, data_type, true));
+        }
+
+        let schema = Arc::new(Schema::new(fields));
+        Ok(self.stamp_constant_columns(RecordBatch::new_empty(schema), Some(4))?.schema())
+    }
+
+    /// Add the value columns of `rows` not yet in `inference` and note which
+    /// string columns are too large for `Utf8` in this chunk.
+    fn infer_columns(&self, inference: &mut ColumnInference, rows: &[WideRow]) {
+        let column_types = &mut inference.types;
+        let column_order = &mut inference.columns;
+        let declared_by = &mut inference.declared_by;
 
         for row in rows {
             for (col_name, value) in &row.data {
@@ -129,6 +802,13 @@ impl ParquetFormatter {
                     continue;
                 }
 
+                if let Some(column_type) = self.column_types.get(col_name) {
+                    column_types.insert(col_name.clone(), column_type.data_type());
+                    column_order.push(col_name.clone());
+                    declared_by.insert(col_name.clone(), row.entry);
+                    continue;
+                }
+
                 if !value.is_null() {
                     let data_type = match value {
                         serde_json::Value::Bool(_) => DataType::Boolean,
@@ -160,26 +840,60 @@ impl ParquetFormatter {
                                     serde_json::Value::String(_) => {
                                         DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
                                     }
+                                    // Struct arrays as lists of structs
+                                    serde_json::Value::Object(object) => DataType::List(Arc::new(Field::new(
+                                        "item",
+                                        DataType::Struct(struct_fields(object)),
+                                        true,
+                                    ))),
                                     _ => DataType::Utf8, // Complex nested types as JSON
                                 }
                             } else {
-                                // Empty array - default to string list
-                                DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+                                // Empty arrays do not tell the item type, so wait
+                                // for one that does; columns that only ever hold
+                                // empty arrays become string lists
+                                if !declared_by.contains_key(col_name) {
+                                    column_order.push(col_name.clone());
+                                    declared_by.insert(col_name.clone(), row.entry);
+                                }
+                                continue;
                             }
                         }
                         serde_json::Value::Object(_) => DataType::Utf8, // Store JSON objects as strings
                         serde_json::Value::Null => continue, // Skip nulls
                     };
                     column_types.insert(col_name.clone(), data_type);
-                    column_order.push(col_name.clone());
+                    if !declared_by.contains_key(col_name) {
+                        column_order.push(col_name.clone());
+                        declared_by.insert(col_name.clone(), row.entry);
+                    }
                 }
             }
         }
 
-        // Sort column names for consistent output
-        column_order.sort();
-
-        (column_order, column_types)
+        for (col_name, data_type) in column_types.iter() {
+            if inference.large.contains(col_name) {
+                continue;
+            }
+            let bytes: usize = match data_type {
+                DataType::Utf8 => rows
+                    .iter()
+                    .filter_map(|row| row.data.get(col_name))
+                    .map(cell_text_len)
+                    .sum(),
+                DataType::List(item) if item.data_type() == &DataType::Utf8 => rows
+                    .iter()
+                    .filter_map(|row| row.data.get(col_name)?.as_array())
+                    .flatten()
+                    .filter_map(|elem| elem.as_str())
+                    .map(str::len)
+                    .sum(),
+                _ => continue,
+            };
+            if bytes > self.large_string_threshold {
+                inference.large.insert(col_name.clone());
+            }
+        }
     }
 
     fn build_typed_array(&self, rows: &[WideRow], col_name: &str, data_type: &DataType) -> Result<ArrayRef> {
@@ -224,7 +938,7 @@ impl ParquetFormatter {
                 // Build ListArray based on element type
                 match field.data_type() {
                     DataType::Boolean => {
-                        let mut builder = ListBuilder::new(BooleanBuilder::new());
+                        let mut builder = ListBuilder::with_capacity(BooleanBuilder::new(), rows.len());
                         for row in rows {
                             if let Some(value) = row.data.get(col_name) {
                                 if let Some(arr) = value.as_array() {
@@ -242,7 +956,7 @@ impl ParquetFormatter {
                         Ok(Arc::new(builder.finish()))
                     }
                     DataType::Int64 => {
-                        let mut builder = ListBuilder::new(Int64Builder::new());
+                        let mut builder = ListBuilder::with_capacity(Int64Builder::new(), rows.len());
                         for row in rows {
                             if let Some(value) = row.data.get(col_name) {
                                 if let Some(arr) = value.as_array() {
@@ -260,7 +974,7 @@ impl ParquetFormatter {
                         Ok(Arc::new(builder.finish()))
                     }
                     DataType::Float64 => {
-                        let mut builder = ListBuilder::new(Float64Builder::new());
+                        let mut builder = ListBuilder::with_capacity(Float64Builder::new(), rows.len());
                         for row in rows {
                             if let Some(value) = row.data.get(col_name) {
                                 if let Some(arr) = value.as_array() {
@@ -278,7 +992,7 @@ impl ParquetFormatter {
                         Ok(Arc::new(builder.finish()))
                     }
                     DataType::Float32 => {
-                        let mut builder = ListBuilder::new(Float32Builder::new());
+                        let mut builder = ListBuilder::with_capacity(Float32Builder::new(), rows.len());
                         for row in rows {
                             if let Some(value) = row.data.get(col_name) {
                                 if let Some(arr) = value.as_array() {
@@ -296,23 +1010,21 @@ impl ParquetFormatter {
                         Ok(Arc::new(builder.finish()))
                     }
                     DataType::Utf8 => {
-                        let mut builder = ListBuilder::new(StringBuilder::new());
-                        for row in rows {
-                            if let Some(value) = row.data.get(col_name) {
-                                if let Some(arr) = value.as_array() {
-                                    for elem in arr {
-                                        builder.values().append_option(elem.as_str());
-                                    }
-                                    builder.append(true);
-                                } else {
-                                    builder.append(false);
-                                }
-                            } else {
-                                builder.append(false);
-                            }
+                        let bytes: usize = rows
+                            .iter()
+                            .filter_map(|row| row.data.get(col_name)?.as_array())
+                            .flatten()
+                            .filter_map(|elem| elem.as_str())
+                            .map(str::len)
+                            .sum();
+                        if bytes > self.large_string_threshold {
+                            Ok(string_list_array::<i64>(rows, col_name))
+                        } else {
+                            Ok(string_list_array::<i32>(rows, col_name))
                         }
-                        Ok(Arc::new(builder.finish()))
                     }
+                    DataType::LargeUtf8 => Ok(string_list_array::<i64>(rows, col_name)),
+                    DataType::Struct(fields) => struct_list_array(rows, col_name, field, fields),
                     _ => {
                         // Unsupported list element type, fallback to JSON string
                         let values: Vec<Option<String>> = rows
@@ -321,27 +1033,377 @@ impl ParquetFormatter {
                                 r.data.get(col_name).map(|v| serde_json::to_string(v).unwrap_or_default())
                             })
                             .collect();
-                        Ok(Arc::new(StringArray::from(values)))
+                        Ok(self.string_array(values))
                     }
                 }
             }
-            DataType::Utf8 | _ => {
+            DataType::Dictionary(_, _) => {
+                let mut builder = StringDictionaryBuilder::<Int32Type>::with_capacity(rows.len(), 0, 0);
+                for row in rows {
+                    builder.append_option(row.data.get(col_name).map(cell_text));
+                }
+                Ok(Arc::new(builder.finish()))
+            }
+            _ => {
                 let values: Vec<Option<String>> = rows
                     .iter()
-                    .map(|r| {
-                        r.data.get(col_name).map(|v| match v {
-                            serde_json::Value::Null => "null".to_string(),
-                            serde_json::Value::Bool(b) => b.to_string(),
-                            serde_json::Value::Number(n) => n.to_string(),
-                            serde_json::Value::String(s) => s.clone(),
-                            serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
-                                serde_json::to_string(v).unwrap_or_default()
-                            }
-                        })
-                    })
+                    .map(|r| r.data.get(col_name).map(cell_text))
                     .collect();
-                Ok(Arc::new(StringArray::from(values)))
+                Ok(self.string_array(values))
+            }
+        }
+    }
+
+    /// A `Utf8` array of `values`, or `LargeUtf8` if their text exceeds the
+    /// large string threshold.
+    fn string_array(&self, values: Vec<Option<String>>) -> ArrayRef {
+        let bytes: usize = values.iter().flatten().map(String::len).sum();
+        if bytes > self.large_string_threshold {
+            Arc::new(LargeStringArray::from(values))
+        } else {
+            Arc::new(StringArray::from(values))
+        }
+    }
+}
+
+/// Fields of a list-of-struct item, typed after the values of `object`.
+fn struct_fields(object: &serde_json::Map<String, serde_json::Value>) -> Fields {
+    object
+        .iter()
+        .map(|(key, value)| {
+            let data_type = match value {
+                serde_json::Value::Bool(_) => DataType::Boolean,
+                serde_json::Value::Number(n) if n.is_i64() && !n.is_f64() => DataType::Int64,
+                serde_json::Value::Number(_) => DataType::Float64,
+                _ => DataType::Utf8,
+            };
+            Field::new(key.as_str(), data_type, true)
+        })
+        .collect()
+}
+
+/// A list-of-struct column from arrays of JSON objects, such as decoded
+/// `struct:<Name>[]` values.
+fn struct_list_array(rows: &[WideRow], col_name: &str, item: &FieldRef, fields: &Fields) -> Result<ArrayRef> {
+    let mut offsets = Vec::with_capacity(rows.len() + 1);
+    offsets.push(0i32);
+    let mut valid = Vec::with_capacity(rows.len());
+    let mut elements = Vec::new();
+    for row in rows {
+        match row.data.get(col_name).and_then(|value| value.as_array()) {
+            Some(arr) => {
+                elements.extend(arr.iter().map(|elem| elem.as_object()));
+                valid.push(true);
             }
+            None => valid.push(false),
+        }
+        offsets.push(i32::try_from(elements.len()).map_err(|_| {
+            Error::OutputError(format!("Column '{}' has too many struct elements for one chunk", col_name))
+        })?);
+    }
+
+    let children = fields
+        .iter()
+        .map(|field| {
+            let values: Vec<Option<&serde_json::Value>> = elements
+                .iter()
+                .map(|elem| elem.and_then(|object| object.get(field.name())))
+                .collect();
+            json_values_array(&values, field.data_type())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let nulls = NullBuffer::from(elements.iter().map(Option::is_some).collect::<Vec<_>>());
+    let structs = StructArray::try_new_with_length(fields.clone(), children, Some(nulls), elements.len())?;
+
+    Ok(Arc::new(ListArray::try_new(
+        Arc::clone(item),
+        OffsetBuffer::new(offsets.into()),
+        Arc::new(structs),
+        Some(NullBuffer::from(valid)),
+    )?))
+}
+
+/// An array of `data_type` holding `values`, with missing and JSON null
+/// values as nulls.
+fn json_values_array(values: &[Option<&serde_json::Value>], data_type: &DataType) -> Result<ArrayRef> {
+    let array: ArrayRef = match data_type {
+        DataType::Boolean => Arc::new(BooleanArray::from(
+            values.iter().map(|v| v.and_then(|v| v.as_bool())).collect::<Vec<_>>(),
+        )),
+        DataType::Int64 => Arc::new(Int64Array::from(
+            values.iter().map(|v| v.and_then(|v| v.as_i64())).collect::<Vec<_>>(),
+        )),
+        DataType::Float64 => Arc::new(Float64Array::from(
+            values.iter().map(|v| v.and_then(|v| v.as_f64())).collect::<Vec<_>>(),
+        )),
+        _ => Arc::new(StringArray::from(
+            values
+                .iter()
+                .map(|v| v.filter(|v| !v.is_null()).map(cell_text))
+                .collect::<Vec<_>>(),
+        )),
+    };
+    if array.data_type() == data_type {
+        Ok(array)
+    } else {
+        Ok(cast(&array, data_type)?)
+    }
+}
+
+/// A list-of-strings column with `O`-sized offsets for the items.
+fn string_list_array<O: OffsetSizeTrait>(rows: &[WideRow], col_name: &str) -> ArrayRef {
+    let mut builder = ListBuilder::with_capacity(GenericStringBuilder::<O>::new(), rows.len());
+    for row in rows {
+        if let Some(value) = row.data.get(col_name) {
+            if let Some(arr) = value.as_array() {
+                for elem in arr {
+                    builder.values().append_option(elem.as_str());
+                }
+                builder.append(true);
+            } else {
+                builder.append(false);
+            }
+        } else {
+            builder.append(false);
+        }
+    }
+    Arc::new(builder.finish())
+}
+
+/// Length of [`cell_text`] of `value`, without copying strings.
+fn cell_text_len(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::String(s) => s.len(),
+        value => cell_text(value).len(),
+    }
+}
+
+/// Text written to string columns for any JSON value.
+fn cell_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            serde_json::to_string(value).unwrap_or_default()
         }
     }
 }
+
+/// Split rows into chunks of roughly `target` bytes of estimated row size.
+fn chunks_by_size(rows: &[WideRow], target: usize) -> Vec<&[WideRow]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0;
+    for (i, row) in rows.iter().enumerate() {
+        bytes += estimated_row_size(row);
+        if bytes >= target {
+            chunks.push(&rows[start..=i]);
+            start = i + 1;
+            bytes = 0;
+        }
+    }
+    if start < rows.len() {
+        chunks.push(&rows[start..]);
+    }
+    chunks
+}
+
+/// Bytes a row takes in a column it has no value in (a null slot).
+const SLOT_BYTES: usize = 8;
+
+/// Split rows before the arrays built for a chunk would exceed `max_bytes`:
+/// the estimated row sizes plus a slot per row in each column of the chunk.
+fn chunks_by_memory(rows: &[WideRow], max_bytes: usize) -> Vec<&[WideRow]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut columns: HashSet<&str> = HashSet::new();
+    let mut bytes = 0;
+    for (i, row) in rows.iter().enumerate() {
+        columns.extend(row.data.keys().map(String::as_str));
+        bytes += estimated_row_size(row);
+        let in_flight = bytes + (i + 1 - start) * columns.len() * SLOT_BYTES;
+        if in_flight > max_bytes && i > start {
+            chunks.push(&rows[start..i]);
+            start = i;
+            columns = row.data.keys().map(String::as_str).collect();
+            bytes = estimated_row_size(row);
+        }
+    }
+    if start < rows.len() {
+        chunks.push(&rows[start..]);
+    }
+    chunks
+}
+
+/// Rough uncompressed size of a row in bytes, used for size-targeted chunking.
+fn estimated_row_size(row: &WideRow) -> usize {
+    // timestamp + entry + loop_count + type
+    let fixed = 8 + 4 + 8 + row.type_name.len();
+    fixed + row.data.values().map(estimated_value_size).sum::<usize>()
+}
+
+fn estimated_value_size(value: &serde_json::Value) -> usize {
+    use serde_json::Value;
+
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 8,
+        Value::String(s) => s.len(),
+        // Offsets plus the child values
+        Value::Array(items) => 4 + items.iter().map(estimated_value_size).sum::<usize>(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| key.len() + estimated_value_size(value))
+            .sum(),
+    }
+}
+
+/// File name of the chunk file with index `index`, e.g. `file_part003.parquet`.
+pub fn chunk_file_name(index: usize) -> String {
+    format!("file_part{:03}.parquet", index)
+}
+
+/// Read the Arrow schema of the Parquet file at `path`, e.g. to write other
+/// logs with [`ParquetFormatter::with_schema`] to the same dataset.
+pub fn read_parquet_schema(path: &Path) -> Result<SchemaRef> {
+    let file = File::open(path)?;
+    Ok(Arc::clone(ParquetRecordBatchReaderBuilder::try_new(file)?.schema()))
+}
+
+/// Read `file_part*.parquet` files written by [`ParquetFormatter`] back into rows.
+///
+/// Files are read in name order. Null cells are omitted from each row's data map,
+/// mirroring how rows are produced by the reader. Values that were stored as JSON
+/// strings (struct objects, msgpack) are returned as strings.
+pub fn read_parquet_directory(directory: &Path) -> Result<Vec<WideRow>> {
+    let mut paths: Vec<_> = std::fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("parquet"))
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        return Err(Error::InvalidFormat(format!(
+            "No Parquet files found in {}",
+            directory.display()
+        )));
+    }
+
+    let mut rows = Vec::new();
+    for path in paths {
+        let file = File::open(&path)?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+        for batch in reader {
+            append_batch_rows(&batch?, &mut rows)?;
+        }
+    }
+
+    Ok(rows)
+}
+
+fn append_batch_rows(batch: &RecordBatch, rows: &mut Vec<WideRow>) -> Result<()> {
+    let column = |name: &str| {
+        batch
+            .column_by_name(name)
+            .ok_or_else(|| Error::SchemaError(format!("Missing required column '{}'", name)))
+    };
+    let wrong_type = |name: &str, expected: DataType| {
+        let found = batch.column_by_name(name).map(|array| array.data_type().to_string());
+        Error::SchemaError(format!(
+            "Column '{}' is {}, expected {}",
+            name,
+            found.unwrap_or_default(),
+            expected
+        ))
+    };
+    let timestamps = column("timestamp")?
+        .as_primitive_opt::<Float64Type>()
+        .ok_or_else(|| wrong_type("timestamp", DataType::Float64))?;
+    let entries = column("entry")?
+        .as_primitive_opt::<UInt32Type>()
+        .ok_or_else(|| wrong_type("entry", DataType::UInt32))?;
+    let types = column("type")?
+        .as_string_opt::<i32>()
+        .ok_or_else(|| wrong_type("type", DataType::Utf8))?;
+    let loop_counts = column("loop_count")?
+        .as_primitive_opt::<Int64Type>()
+        .ok_or_else(|| wrong_type("loop_count", DataType::Int64))?;
+
+    let schema = batch.schema();
+    let data_columns: Vec<(&str, &ArrayRef)> = schema
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .filter(|(field, _)| !matches!(field.name().as_str(), "timestamp" | "entry" | "type" | "loop_count"))
+        .map(|(field, array)| (field.name().as_str(), array))
+        .collect();
+
+    rows.reserve(batch.num_rows());
+    for i in 0..batch.num_rows() {
+        let mut row = WideRow::new(
+            timestamps.value(i),
+            entries.value(i),
+            types.value(i).to_string(),
+            u64::try_from(loop_counts.value(i)).map_err(|_| {
+                Error::SchemaError(format!("Negative loop_count {} in row {}", loop_counts.value(i), i))
+            })?,
+        );
+        for (name, array) in &data_columns {
+            if !array.is_null(i) {
+                row.insert(name.to_string(), array_value_to_json(array.as_ref(), i)?);
+            }
+        }
+        rows.push(row);
+    }
+
+    Ok(())
+}
+
+fn array_value_to_json(array: &dyn Array, i: usize) -> Result<serde_json::Value> {
+    if array.is_null(i) {
+        return Ok(serde_json::Value::Null);
+    }
+
+    let value = match array.data_type() {
+        DataType::Boolean => serde_json::json!(array.as_boolean().value(i)),
+        DataType::Int64 => serde_json::json!(array.as_primitive::<Int64Type>().value(i)),
+        DataType::Float32 => serde_json::json!(array.as_primitive::<Float32Type>().value(i)),
+        DataType::Float64 => serde_json::json!(array.as_primitive::<Float64Type>().value(i)),
+        DataType::Utf8 => serde_json::json!(array.as_string::<i32>().value(i)),
+        DataType::LargeUtf8 => serde_json::json!(array.as_string::<i64>().value(i)),
+        DataType::Dictionary(_, _) => {
+            let dictionary = array.as_any_dictionary();
+            let key = dictionary.normalized_keys()[i];
+            return array_value_to_json(dictionary.values().as_ref(), key);
+        }
+        DataType::Struct(_) => {
+            let array = array.as_struct();
+            let object = array
+                .column_names()
+                .into_iter()
+                .zip(array.columns())
+                .map(|(name, column)| Ok((name.to_string(), array_value_to_json(column.as_ref(), i)?)))
+                .collect::<Result<serde_json::Map<_, _>>>()?;
+            serde_json::Value::Object(object)
+        }
+        DataType::List(_) => {
+            let values = array.as_list::<i32>().value(i);
+            let items = (0..values.len())
+                .map(|j| array_value_to_json(values.as_ref(), j))
+                .collect::<Result<Vec<_>>>()?;
+            serde_json::Value::Array(items)
+        }
+        other => {
+            return Err(Error::SchemaError(format!(
+                "Unsupported Parquet column type: {}",
+                other
+            )))
+        }
+    };
+
+    Ok(value)
+}
@@ -1,50 +1,196 @@
-use anyhow::{anyhow, Result};
-use byteorder::{LittleEndian, ReadBytesExt};
 use memmap2::Mmap;
 use serde_json::json;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
-use std::io::Cursor;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::ops::Range;
+
+use crate::catalog::metadata_alias;
+use crate::datalog::{DataLogIterator, DataLogReader, DataLogRecord, StartRecordData};
+use crate::entry_type::{DecodedValue, EntryType};
+use crate::error::{Error, Result, Warning};
+use crate::models::{DerivedSchema, LongRow, OutputFormat, ResolvedField, WideRow};
+use crate::msgpack::{msgpack_to_json, MsgpackSchema};
+use crate::profile::EntrySelector;
+use crate::progress::{ProgressPhase, ProgressTracker};
+use crate::quirks::{EmptyPayloads, Quirks};
+use crate::record_error::{RecordAction, RecordError, RecordErrorHandler};
+use crate::record_id::{RecordId, RECORD_ID_COLUMN};
+use crate::reduce::Subsample;
+use crate::structs::{decode_struct, decode_struct_array, register_schema, resolve_struct};
+pub use crate::structs::{
+    canonical_struct_name, convert_struct_schema_to_columns, MAX_STRUCT_DEPTH, MAX_STRUCT_FIELDS,
+};
+
+/// Mutable state of one pass over a log.
+///
+/// Each read creates its own context, so any number of formatters can parse
+/// logs concurrently in one process.
+#[derive(Debug, Clone, Default)]
+pub struct ParseContext {
+    /// Number of `/Timestamp` records seen so far, stamped on each row as
+    /// `loop_count`
+    pub loop_count: u64,
+    /// Data records seen so far per entry ID, for subsampling
+    pub entry_records: HashMap<u32, u64>,
+    /// Byte offset of the header of the record being decoded
+    pub record_offset: usize,
+    /// Struct types whose schema, and every nested schema, has been logged
+    ready_structs: HashSet<String>,
+    /// Struct records read before their schema, in log order
+    deferred: Vec<DeferredRecord>,
+    /// Records with invalid UTF-8 replaced, per entry name
+    invalid_utf8: BTreeMap<String, u64>,
+}
 
-use crate::datalog::{DataLogReader, DataLogRecord, StartRecordData};
-use crate::models::{DerivedSchema, DerivedSchemaColumn, LongRow, OutputFormat, WideRow};
+/// A struct record held until its schema is logged, with the state it is
+/// decoded in.
+#[derive(Debug, Clone)]
+struct DeferredRecord {
+    record: DataLogRecord,
+    entry: StartRecordData,
+    offset: usize,
+    position: usize,
+    loop_count: u64,
+}
 
-static LOOP_COUNT: AtomicU64 = AtomicU64::new(0);
+impl ParseContext {
+    /// Loop count for a record of `entry`, advancing it past `/Timestamp` records.
+    fn next_loop_count(&mut self, entry: &StartRecordData) -> u64 {
+        let loop_count = self.loop_count;
+        if entry.name == "/Timestamp" {
+            self.loop_count += 1;
+        }
+        loop_count
+    }
 
-pub fn sanitize_column_name(name: &str) -> String {
-    name.to_string()
+    /// 0-based index of the next record of `entry`, advancing the count.
+    fn next_entry_record(&mut self, entry: u32) -> u64 {
+        let count = self.entry_records.entry(entry).or_insert(0);
+        *count += 1;
+        *count - 1
+    }
 }
 
-pub fn convert_struct_schema_to_columns(schema_str: &str) -> Result<Vec<DerivedSchemaColumn>> {
-    let mut columns = Vec::new();
+/// Rows a pass decodes data records into: [`WideRow`]s, or [`LongRow`]s
+/// for long output.
+pub trait PassRow: Sized {
+    /// Decode a data record of `entry`.
+    fn decode(
+        formatter: &Formatter,
+        record: &DataLogRecord,
+        entry: &StartRecordData,
+        context: &mut ParseContext,
+    ) -> Result<Vec<Self>>;
 
-    for part in schema_str.split(';') {
-        let part = part.trim();
-        if part.is_empty() {
-            continue;
-        }
+    /// The row written for an empty payload under [`EmptyPayloads::Raw`].
+    fn empty(formatter: &Formatter, record: &DataLogRecord, entry: &StartRecordData, context: &mut ParseContext)
+        -> Self;
 
-        // Handle enum inline
-        if part.starts_with("enum") {
-            if let Some(pos) = part.find('}') {
-                let type_and_name = part[pos + 1..].trim();
-                if let Some((typ, name)) = type_and_name.split_once(' ') {
-                    columns.push(DerivedSchemaColumn {
-                        name: name.to_string(),
-                        type_name: typ.to_string(),
-                    });
-                }
+    /// Tag the row with the ID of the record it was decoded from.
+    fn set_record_id(&mut self, id: RecordId);
+}
+
+impl PassRow for WideRow {
+    fn decode(
+        formatter: &Formatter,
+        record: &DataLogRecord,
+        entry: &StartRecordData,
+        context: &mut ParseContext,
+    ) -> Result<Vec<Self>> {
+        Ok(vec![formatter.parse_record_wide(record, entry, context)?])
+    }
+
+    fn empty(formatter: &Formatter, record: &DataLogRecord, entry: &StartRecordData, context: &mut ParseContext) -> Self {
+        let mut row = WideRow::new(
+            formatter.seconds(record.timestamp),
+            record.entry,
+            entry.type_name.clone(),
+            context.next_loop_count(entry),
+        );
+        row.insert(sanitize_column_name(&entry.name), json!(null));
+        row
+    }
+
+    fn set_record_id(&mut self, id: RecordId) {
+        self.insert(RECORD_ID_COLUMN.to_string(), json!(id.to_string()));
+    }
+}
+
+impl PassRow for LongRow {
+    fn decode(
+        formatter: &Formatter,
+        record: &DataLogRecord,
+        entry: &StartRecordData,
+        context: &mut ParseContext,
+    ) -> Result<Vec<Self>> {
+        formatter.parse_records_long(record, entry, context)
+    }
+
+    fn empty(formatter: &Formatter, record: &DataLogRecord, entry: &StartRecordData, context: &mut ParseContext) -> Self {
+        let mut row = LongRow::new(
+            formatter.seconds(record.timestamp),
+            record.entry,
+            entry.type_name.clone(),
+            context.next_loop_count(entry),
+        );
+        row.entry_name = entry.name.clone();
+        row
+    }
+
+    /// Long rows have no record ID column; readers reject `record_ids` with
+    /// long output.
+    fn set_record_id(&mut self, _id: RecordId) {}
+}
+
+/// A pass over a log in progress, advanced one record at a time by
+/// [`Formatter::step_pass`] so rows can be pulled lazily instead of pushed.
+pub struct LogPass<'a> {
+    records: DataLogIterator<'a>,
+    infer_schema_only: bool,
+    /// Only decode the entry IDs in `selected`
+    filter_entries: bool,
+    finished: bool,
+    context: ParseContext,
+    entries: HashMap<u32, StartRecordData>,
+    /// Entry name -> (column written for each type it was started with,
+    /// Start records seen)
+    generations: HashMap<String, (Vec<(EntryType, String)>, u32)>,
+    /// Entry ID -> data records seen before its Start record, with their offsets
+    orphans: HashMap<u32, Vec<(usize, DataLogRecord)>>,
+    /// Records held in `orphans`, across entry IDs
+    buffered_orphans: usize,
+    orphan_counts: BTreeMap<u32, usize>,
+    /// Entry ID -> name and type of its latest Start record, as logged
+    logged_as: HashMap<u32, (String, String)>,
+    selected: HashSet<u32>,
+}
+
+impl LogPass<'_> {
+    /// Stop the pass early, e.g. after an error; later steps read nothing.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+/// Most data records held at once by [`Formatter::buffer_orphans`]; later
+/// orphans are only counted.
+pub const MAX_BUFFERED_ORPHANS: usize = 100_000;
+
+pub fn sanitize_column_name(name: &str) -> String {
+    name.to_string()
+}
+
+/// Insert `value` under `column`, or with `depth` levels left, each key of a
+/// non-empty object under `column/key`.
+fn flatten_json(row: &mut WideRow, column: String, value: serde_json::Value, depth: usize) {
+    match value {
+        serde_json::Value::Object(fields) if depth > 0 && !fields.is_empty() => {
+            for (key, value) in fields {
+                flatten_json(row, format!("{}/{}", column, key), value, depth - 1);
             }
-        } else if let Some((typ, name)) = part.split_once(' ') {
-            columns.push(DerivedSchemaColumn {
-                name: name.to_string(),
-                type_name: typ.to_string(),
-            });
         }
+        value => row.insert(column, value),
     }
-
-    Ok(columns)
 }
 
 pub struct Formatter {
@@ -52,7 +198,74 @@ pub struct Formatter {
     pub output_directory: String,
     pub output_format: OutputFormat,
     pub metrics_names: HashSet<String>,
-    pub struct_schemas: Vec<DerivedSchema>,
+    /// Struct schemas registered so far in the pass, keyed by
+    /// [canonical name](canonical_struct_name)
+    pub struct_schemas: BTreeMap<String, DerivedSchema>,
+    /// Suffix reused entry names whose type changed with `#<generation>`
+    pub generation_columns: bool,
+    /// Name columns after the `alias` key in the entry's Start metadata
+    pub alias_columns: bool,
+    /// Receives progress updates for the schema and data passes
+    pub progress: Option<ProgressTracker>,
+    /// Hold data records that arrive before their entry's Start record and
+    /// decode them once it appears, up to [`MAX_BUFFERED_ORPHANS`] records.
+    /// Records after an entry's Finish are only decoded if the ID is started
+    /// again with the same name and type.
+    pub buffer_orphans: bool,
+    /// Data records with no Start record for their entry ID, by entry ID,
+    /// after the last pass. Counted whether or not orphans are buffered.
+    pub orphaned_records: BTreeMap<u32, usize>,
+    /// Byte offset of the partial record the log ends with, if it was cut off
+    pub truncated_at: Option<usize>,
+    /// Only decode entries with these names (as logged, before aliasing or
+    /// generation suffixes) in the data pass
+    pub entry_names: Option<HashSet<String>>,
+    /// Only decode entries matching this selector in the data pass; combined
+    /// with `entry_names` when both are set
+    pub entry_selector: Option<EntrySelector>,
+    /// Parse `json` entries instead of keeping their raw text, flattening
+    /// nested object keys into `entry/key` columns up to this many levels
+    /// (0 keeps the parsed value in the entry's own column)
+    pub json_depth: Option<usize>,
+    /// Sample up to this many payloads of each msgpack entry in the schema
+    /// pass and write regular maps as typed `entry/key` columns; irregular
+    /// payloads become JSON strings
+    pub msgpack_sample: Option<usize>,
+    /// Msgpack pseudo-schemas by entry name, from the schema pass
+    pub msgpack_schemas: HashMap<String, MsgpackSchema>,
+    /// Number of rows the data pass is expected to emit, used to pre-size the
+    /// row vector (e.g. from a [`QuickScan`](crate::QuickScan))
+    pub expected_rows: Option<usize>,
+    /// Off-spec record layouts to tolerate
+    pub quirks: Quirks,
+    /// Only decode data records with timestamps in this range (microseconds)
+    /// in the data pass
+    pub time_range: Option<Range<u64>>,
+    /// Only decode data records that end after this byte offset in the data
+    /// pass, e.g. the records appended since a log was last converted
+    pub start_offset: Option<usize>,
+    /// Problems found by the last data pass, such as entries whose type is not
+    /// decoded; each is also logged as a warning
+    pub warnings: Vec<Warning>,
+    /// Only decode every Nth data record of each entry in the data pass
+    pub subsample: Option<Subsample>,
+    /// Hash of the log being read, to stamp each row with its [`RecordId`]
+    /// in a `record_id` column
+    pub record_id_log: Option<[u8; 8]>,
+    /// Timestamp (microseconds) written as time zero; earlier records get
+    /// negative timestamps
+    pub time_origin: u64,
+    /// Write each primitive field of a struct entry as its own long row,
+    /// named `entry/field`, instead of one row with the struct as an object
+    pub long_struct_fields: bool,
+    /// Fail the data pass with [`Error::UnsupportedType`] instead of writing
+    /// null columns for entries whose type is not decoded
+    pub deny_unsupported_types: bool,
+    /// Leave entries whose type is not decoded out of the data pass
+    pub exclude_undecoded: bool,
+    /// Decides what to do with data records that fail to decode; without it
+    /// the data pass fails
+    pub(crate) on_record_error: Option<RecordErrorHandler>,
 }
 
 impl Formatter {
@@ -66,99 +279,118 @@ impl Formatter {
             output_directory,
             output_format,
             metrics_names: HashSet::new(),
-            struct_schemas: Vec::new(),
+            struct_schemas: BTreeMap::new(),
+            generation_columns: false,
+            alias_columns: false,
+            progress: None,
+            buffer_orphans: false,
+            orphaned_records: BTreeMap::new(),
+            truncated_at: None,
+            entry_names: None,
+            entry_selector: None,
+            json_depth: None,
+            msgpack_sample: None,
+            msgpack_schemas: HashMap::new(),
+            expected_rows: None,
+            quirks: Quirks::default(),
+            time_range: None,
+            start_offset: None,
+            warnings: Vec::new(),
+            subsample: None,
+            long_struct_fields: false,
+            time_origin: 0,
+            record_id_log: None,
+            deny_unsupported_types: false,
+            exclude_undecoded: false,
+            on_record_error: None,
         }
     }
 
+    /// Row timestamp in seconds of a record timestamp, relative to `time_origin`.
+    fn seconds(&self, timestamp: u64) -> f64 {
+        (timestamp as i64 - self.time_origin as i64) as f64 / 1_000_000.0
+    }
+
     pub fn parse_record_wide(
         &self,
         record: &DataLogRecord,
         entry: &StartRecordData,
+        context: &mut ParseContext,
     ) -> Result<WideRow> {
         let mut row = WideRow::new(
-            record.timestamp as f64 / 1_000_000.0,
+            self.seconds(record.timestamp),
             record.entry,
             entry.type_name.clone(),
-            LOOP_COUNT.load(Ordering::Relaxed),
+            context.next_loop_count(entry),
         );
 
-        if entry.name == "/Timestamp" {
-            LOOP_COUNT.fetch_add(1, Ordering::Relaxed);
-        }
-
         let sanitized_name = sanitize_column_name(&entry.name);
 
-        match entry.type_name.as_str() {
-            "double" => {
-                row.insert(sanitized_name, json!(record.get_double()?));
-            }
-            "float" => {
-                row.insert(sanitized_name, json!(record.get_float()?));
-            }
-            "int64" => {
-                row.insert(sanitized_name, json!(record.get_integer()?));
-            }
-            "string" | "json" => {
-                row.insert(sanitized_name, json!(record.get_string()?));
-            }
-            "boolean" => {
-                row.insert(sanitized_name, json!(record.get_boolean()?));
-            }
-            "boolean[]" => {
-                row.insert(sanitized_name, json!(record.get_boolean_array()));
-            }
-            "double[]" => {
-                row.insert(sanitized_name, json!(record.get_double_array()?));
-            }
-            "float[]" => {
-                row.insert(sanitized_name, json!(record.get_float_array()?));
-            }
-            "int64[]" => {
-                row.insert(sanitized_name, json!(record.get_integer_array()?));
+        match &entry.entry_type {
+            EntryType::Json if self.json_depth.is_some() => {
+                let text = self.decode_value(record, entry, context)?.to_json();
+                // Text that is not valid JSON is kept as-is
+                let value = match text.as_str().map(serde_json::from_str::<serde_json::Value>) {
+                    Some(Ok(value)) => value,
+                    _ => text,
+                };
+                flatten_json(&mut row, sanitized_name, value, self.json_depth.unwrap_or(0));
             }
-            "string[]" => {
-                row.insert(sanitized_name, json!(record.get_string_array()?));
+            EntryType::Boolean
+            | EntryType::Int64
+            | EntryType::Float
+            | EntryType::Double
+            | EntryType::String
+            | EntryType::Json
+            | EntryType::BooleanArray
+            | EntryType::Int64Array
+            | EntryType::FloatArray
+            | EntryType::DoubleArray
+            | EntryType::StringArray => {
+                let value = self.decode_value(record, entry, context)?;
+                row.insert(sanitized_name, value.to_json());
             }
-            "msgpack" => {
-                row.insert(sanitized_name, json!(format!("{:?}", record.get_msgpack()?)));
+            EntryType::Msgpack => {
+                let value = record.get_msgpack()?;
+                if self.msgpack_sample.is_none() {
+                    row.insert(sanitized_name, json!(format!("{:?}", value)));
+                } else {
+                    let flattened = self
+                        .msgpack_schemas
+                        .get(&entry.name)
+                        .is_some_and(|schema| schema.flatten_into(&mut row, &sanitized_name, &value));
+                    if !flattened {
+                        row.insert(sanitized_name, json!(msgpack_to_json(&value).to_string()));
+                    }
+                }
             }
-            "structschema" => {
+            EntryType::StructSchema => {
                 let _columns = convert_struct_schema_to_columns(&record.get_string()?)?;
                 let _schema_name = entry
                     .name
                     .split(".schema/")
                     .nth(1)
-                    .ok_or_else(|| anyhow!("Invalid schema name format"))?;
+                    .ok_or_else(|| Error::SchemaError("Invalid schema name format".to_string()))?;
 
-                // Store schema for later use
-                // Note: we'd need to use interior mutability or restructure to modify self here
+                // The schema itself is registered by the pass that read it
                 row.insert(sanitized_name, json!(null));
             }
-            type_name if type_name.starts_with("struct:") => {
-                // Remove [] suffix if present to get schema name
-                let schema_name = if type_name.ends_with("[]") {
-                    &type_name[..type_name.len() - 2]
-                } else {
-                    type_name
-                };
-
-                let schema = self
-                    .struct_schemas
-                    .iter()
-                    .find(|s| s.name == schema_name)
-                    .ok_or_else(|| anyhow!("No struct schema found for: {}", schema_name))?;
-
+            EntryType::Struct(schema_name) => {
+                let struct_data = decode_struct(&self.struct_schemas, schema_name, &record.data)?;
                 if record.data.is_empty() {
                     row.insert(entry.name.clone(), json!(null));
                 } else {
-                    let (struct_data, _bytes_consumed) = unpack_struct(&schema.columns, &record.data, 0, "", &self.struct_schemas)?;
                     row.insert(entry.name.clone(), json!(struct_data));
                 }
             }
-            type_name if type_name.contains("proto") => {
+            EntryType::StructArray(schema_name) => {
+                let elements = decode_struct_array(&self.struct_schemas, schema_name, &record.data)?;
+                row.insert(entry.name.clone(), json!(elements));
+            }
+            EntryType::Proto(_) => {
                 row.insert(sanitized_name, json!(null)); // Proto data stored as bytes
             }
-            _ => {
+            EntryType::Raw(_) => {
                 row.insert(sanitized_name, json!(null));
             }
         }
@@ -170,40 +402,96 @@ impl Formatter {
         &self,
         record: &DataLogRecord,
         entry: &StartRecordData,
+        context: &mut ParseContext,
     ) -> Result<LongRow> {
         let mut row = LongRow::new(
-            record.timestamp as f64 / 1_000_000.0,
+            self.seconds(record.timestamp),
             record.entry,
             entry.type_name.clone(),
-            LOOP_COUNT.load(Ordering::Relaxed),
+            context.next_loop_count(entry),
         );
-
-        if entry.name == "/Timestamp" {
-            LOOP_COUNT.fetch_add(1, Ordering::Relaxed);
-        }
+        row.entry_name = entry.name.clone();
 
         if let Some(ref mut value) = row.value {
-            match entry.type_name.as_str() {
-                "double" => value.double = Some(record.get_double()?),
-                "int64" => value.int64 = Some(record.get_integer()?),
-                "string" => value.string = Some(record.get_string()?),
-                "json" => {
-                    let json_str = record.get_string()?;
-                    row.json = Some(serde_json::from_str(&json_str)?);
+            match &entry.entry_type {
+                EntryType::Json => {
+                    if let DecodedValue::String(json_str) = self.decode_value(record, entry, context)? {
+                        // Text that is not valid JSON is kept as a string
+                        match serde_json::from_str(&json_str) {
+                            Ok(serde_json::Value::Object(object)) => row.json = Some(object.into_iter().collect()),
+                            Ok(other) => value.object = Some(other),
+                            Err(_) => value.string = Some(json_str),
+                        }
+                    }
                 }
-                "boolean" => value.boolean = Some(record.get_boolean()?),
-                "boolean[]" => value.boolean_array = Some(record.get_boolean_array()),
-                "double[]" => value.double_array = Some(record.get_double_array()?),
-                "float[]" => value.float_array = Some(record.get_float_array()?),
-                "int64[]" => value.int64_array = Some(record.get_integer_array()?),
-                "string[]" => value.string_array = Some(record.get_string_array()?),
-                _ => {}
+                EntryType::Struct(schema_name) if !record.data.is_empty() => {
+                    let struct_data = decode_struct(&self.struct_schemas, schema_name, &record.data)?;
+                    value.object = Some(json!(struct_data));
+                }
+                EntryType::StructArray(schema_name) => {
+                    let elements = decode_struct_array(&self.struct_schemas, schema_name, &record.data)?;
+                    value.object = Some(json!(elements));
+                }
+                EntryType::StructSchema | EntryType::Struct(_) | EntryType::Proto(_) | EntryType::Raw(_) => {}
+                _ => match self.decode_value(record, entry, context)? {
+                    DecodedValue::Boolean(v) => value.boolean = Some(v),
+                    DecodedValue::Int64(v) => value.int64 = Some(v),
+                    DecodedValue::Float(v) => value.float = Some(v),
+                    DecodedValue::Double(v) => value.double = Some(v),
+                    DecodedValue::String(text) => value.string = Some(text),
+                    DecodedValue::Msgpack(v) => value.object = Some(msgpack_to_json(&v)),
+                    DecodedValue::BooleanArray(v) => value.boolean_array = Some(v),
+                    DecodedValue::Int64Array(v) => value.int64_array = Some(v),
+                    DecodedValue::FloatArray(v) => value.float_array = Some(v),
+                    DecodedValue::DoubleArray(v) => value.double_array = Some(v),
+                    DecodedValue::StringArray(v) => value.string_array = Some(v),
+                    DecodedValue::Raw(_) => {}
+                },
             }
         }
 
         Ok(row)
     }
 
+    /// Decode one data record into long rows: a single row, or with
+    /// `long_struct_fields` one row per primitive field of a struct entry.
+    pub fn parse_records_long(
+        &self,
+        record: &DataLogRecord,
+        entry: &StartRecordData,
+        context: &mut ParseContext,
+    ) -> Result<Vec<LongRow>> {
+        let row = self.parse_record_long(record, entry, context)?;
+        match &entry.entry_type {
+            EntryType::Struct(schema_name) if self.long_struct_fields => {
+                Ok(row.split_struct_fields(&self.resolved_schema(schema_name)?))
+            }
+            _ => Ok(vec![row]),
+        }
+    }
+
+    /// Decode `record` as the declared type of `entry`, replacing invalid
+    /// UTF-8 in text under the [`lossy_utf8`](Quirks::lossy_utf8) quirk.
+    fn decode_value(
+        &self,
+        record: &DataLogRecord,
+        entry: &StartRecordData,
+        context: &mut ParseContext,
+    ) -> Result<DecodedValue> {
+        if !self.quirks.lossy_utf8 {
+            return record
+                .decode_as(&entry.entry_type)
+                .map_err(|e| with_entry_context(e, entry));
+        }
+        let (value, replaced) = record
+            .decode_as_lossy(&entry.entry_type)
+            .map_err(|e| with_entry_context(e, entry))?;
+        if replaced {
+            *context.invalid_utf8.entry(entry.name.clone()).or_insert(0) += 1;
+        }
+        Ok(value)
+    }
+
     pub fn read_wpilog(&mut self, infer_schema_only: bool) -> Result<Vec<WideRow>> {
         let file = File::open(&self.wpilog_file)?;
         let mmap = unsafe { Mmap::map(&file)? };
@@ -211,153 +499,463 @@ impl Formatter {
     }
 
     pub fn read_wpilog_from_bytes(&mut self, data: &[u8], infer_schema_only: bool) -> Result<Vec<WideRow>> {
-        let mut records = Vec::new();
-        let mut entries: HashMap<u32, StartRecordData> = HashMap::new();
+        let mut records = match self.expected_rows {
+            Some(rows) if !infer_schema_only => Vec::with_capacity(rows),
+            _ => Vec::new(),
+        };
+        self.stream_wpilog_from_bytes(data, infer_schema_only, |row| {
+            records.push(row);
+            Ok(())
+        })?;
+        Ok(records)
+    }
+
+    /// Like [`read_wpilog_from_bytes`](Self::read_wpilog_from_bytes), passing
+    /// each row to `sink` as it is decoded instead of collecting them.
+    pub fn stream_wpilog_from_bytes<R, F>(&mut self, data: &[u8], infer_schema_only: bool, mut sink: F) -> Result<()>
+    where
+        R: PassRow,
+        F: FnMut(R) -> Result<()>,
+    {
+        let mut pass = self.begin_pass(data, infer_schema_only)?;
+        while self.step_pass(&mut pass, &mut sink)? {}
+        Ok(())
+    }
 
-        let reader = DataLogReader::new(data);
+    /// Start a pass over `data`, to be advanced with
+    /// [`step_pass`](Self::step_pass).
+    pub fn begin_pass<'a>(&mut self, data: &'a [u8], infer_schema_only: bool) -> Result<LogPass<'a>> {
+        let reader = DataLogReader::new(data).with_quirks(self.quirks);
 
         if !reader.is_valid() {
-            return Err(anyhow!("Not a valid WPILOG file"));
+            return Err(Error::InvalidFormat("Not a valid WPILOG file".to_string()));
         }
 
-        for record_result in reader.records()? {
-            let record = record_result?;
-
-            if record.is_start() {
-                let data = record.get_start_data()?;
-                entries.insert(data.entry, data);
-            } else if record.is_finish() {
-                let entry = record.get_finish_entry()?;
-                entries.remove(&entry);
-            } else if !record.is_control() {
-                if let Some(entry) = entries.get(&record.entry) {
-                    if infer_schema_only {
-                        if entry.type_name == "structschema" {
-                            let _columns = convert_struct_schema_to_columns(&record.get_string()?)?;
-                            let _schema_name = entry
-                                .name
-                                .split(".schema/")
-                                .nth(1)
-                                .ok_or_else(|| anyhow!("Invalid schema name format"))?;
-
-                            self.struct_schemas.push(DerivedSchema {
-                                name: _schema_name.to_string(),
-                                columns: _columns,
-                            });
-                        }
-                    } else {
-                        // Skip struct schema definition records in data pass
-                        if entry.type_name != "structschema" {
-                            let parsed_data = self.parse_record_wide(&record, entry)?;
-                            self.metrics_names.insert(entry.name.clone());
-                            records.push(parsed_data);
-                        }
-                    }
-                }
-            }
+        if let Some(progress) = &mut self.progress {
+            progress.phase(if infer_schema_only {
+                ProgressPhase::InferringSchema
+            } else {
+                ProgressPhase::DecodingRecords
+            });
         }
 
-        Ok(records)
-    }
+        // Entries this pass decodes: struct schemas for the schema pass, the
+        // selected names (if any) for the data pass
+        let filter_entries = infer_schema_only
+            || self.entry_names.is_some()
+            || self.entry_selector.is_some()
+            || self.exclude_undecoded;
+        let selected: HashSet<u32> = HashSet::new();
+
+        self.truncated_at = None;
+        if !infer_schema_only {
+            self.warnings.clear();
+        }
+        // Skip other entries' payloads without copying them. Orphans have no
+        // Start yet, so they are yielded to be counted and buffered.
+        let records = if filter_entries {
+            reader.records_filtered(&selected)?.with_orphans()
+        } else {
+            reader.records()?
+        };
 
-    pub fn reset_loop_count() {
-        LOOP_COUNT.store(0, Ordering::Relaxed);
+        Ok(LogPass {
+            records,
+            infer_schema_only,
+            filter_entries,
+            finished: false,
+            context: ParseContext::default(),
+            entries: HashMap::new(),
+            generations: HashMap::new(),
+            orphans: HashMap::new(),
+            buffered_orphans: 0,
+            orphan_counts: BTreeMap::new(),
+            logged_as: HashMap::new(),
+            selected,
+        })
     }
-}
 
-/// Unpack a struct from binary data, matching Python implementation
-///
-/// Supports only: double, float, int32, int64, and nested structs
-/// Does NOT support: arrays, strings, booleans, or other integer types within structs
-fn unpack_struct(
-    columns: &[DerivedSchemaColumn],
-    data: &[u8],
-    mut offset: usize,
-    prefix: &str,
-    schemas: &[DerivedSchema],
-) -> Result<(HashMap<String, serde_json::Value>, usize)> {
-    let mut result = HashMap::new();
-
-    for col in columns {
-        let key = if prefix.is_empty() {
-            col.name.clone()
-        } else {
-            format!("{}.{}", prefix, col.name)
+    /// Read the next record of `pass`, passing the rows it completes to
+    /// `sink`. Returns `false` once the log is exhausted.
+    pub fn step_pass<R, F>(&mut self, pass: &mut LogPass<'_>, sink: &mut F) -> Result<bool>
+    where
+        R: PassRow,
+        F: FnMut(R) -> Result<()>,
+    {
+        if pass.finished {
+            return Ok(false);
+        }
+        let infer_schema_only = pass.infer_schema_only;
+        let record = match pass.records.next() {
+            Some(Ok(record)) => record,
+            // Keep everything before the cut; callers can check `truncated_at`
+            Some(Err(Error::Truncated { offset, .. })) => {
+                self.truncated_at = Some(offset);
+                return self.finish_pass(pass, sink);
+            }
+            Some(Err(e)) => return Err(e),
+            None => return self.finish_pass(pass, sink),
         };
+        // The data pass reports as rows are emitted
+        if infer_schema_only {
+            if let Some(progress) = &mut self.progress {
+                progress.advance(pass.records.position() as u64);
+            }
+        }
 
-        match col.type_name.as_str() {
-            "double" => {
-                if data.is_empty() {
-                    result.insert(key, json!(null));
+        if record.is_start() {
+            let mut data = record.get_start_data()?;
+            // Orphans of a finished entry only belong to a new Start of the
+            // same entry; another name or type would decode them wrongly
+            let previous = pass
+                .logged_as
+                .insert(data.entry, (data.name.clone(), data.type_name.clone()));
+            let adopts_orphans =
+                previous.is_none_or(|(name, type_name)| name == data.name && type_name == data.type_name);
+            let is_selected = if infer_schema_only {
+                data.entry_type == EntryType::StructSchema
+                    || (data.entry_type == EntryType::Msgpack && self.msgpack_sample.is_some())
+            } else {
+                // Schemas are registered as they are read
+                data.entry_type == EntryType::StructSchema
+                    || !(self.exclude_undecoded && data.entry_type.unsupported_hint().is_some())
+                    && self
+                        .entry_names
+                        .as_ref()
+                        .is_none_or(|names| names.contains(&data.name))
+                    && self
+                        .entry_selector
+                        .as_ref()
+                        .is_none_or(|selector| selector.matches(&data.name))
+            };
+            // Loop counts advance on `/Timestamp` records, so the data pass
+            // reads them even when their rows are not wanted
+            let is_read = is_selected || (!infer_schema_only && data.name == "/Timestamp");
+            for (ids, keep) in [(Some(&mut pass.selected), is_selected), (pass.records.entry_filter_mut(), is_read)] {
+                let Some(ids) = ids else { continue };
+                if keep {
+                    ids.insert(data.entry);
                 } else {
-                    if offset + 8 > data.len() {
-                        return Err(anyhow!(
-                            "Not enough data for double at offset {}, need 8 bytes but only {} available",
-                            offset, data.len() - offset
-                        ));
-                    }
-                    let mut cursor = Cursor::new(&data[offset..offset + 8]);
-                    let val = cursor.read_f64::<LittleEndian>()?;
-                    result.insert(key, json!(val));
-                    offset += 8;
+                    ids.remove(&data.entry);
                 }
             }
-            "float" => {
-                if data.is_empty() {
-                    result.insert(key, json!(null));
-                } else {
-                    if offset + 4 > data.len() {
-                        return Err(anyhow!("Not enough data for float at offset {}", offset));
-                    }
-                    let mut cursor = Cursor::new(&data[offset..offset + 4]);
-                    let val = cursor.read_f32::<LittleEndian>()?;
-                    result.insert(key, json!(val));
-                    offset += 4;
+
+            if self.alias_columns {
+                if let Some(alias) = metadata_alias(&data.metadata) {
+                    data.name = alias;
                 }
             }
-            "int32" => {
-                if data.is_empty() {
-                    result.insert(key, json!(null));
-                } else {
-                    if offset + 4 > data.len() {
-                        return Err(anyhow!("Not enough data for int32 at offset {}", offset));
+            if self.generation_columns {
+                let (columns, generation) = pass.generations.entry(data.name.clone()).or_default();
+                *generation += 1;
+                // Reuse the column of any earlier generation with this type
+                match columns.iter().find(|(entry_type, _)| *entry_type == data.entry_type) {
+                    Some((_, column)) => data.name = column.clone(),
+                    None => {
+                        if !columns.is_empty() {
+                            data.name = format!("{}#{}", data.name, generation);
+                        }
+                        columns.push((data.entry_type.clone(), data.name.clone()));
                     }
-                    let mut cursor = Cursor::new(&data[offset..offset + 4]);
-                    let val = cursor.read_i32::<LittleEndian>()?;
-                    result.insert(key, json!(val));
-                    offset += 4;
                 }
             }
-            "int64" => {
-                if data.is_empty() {
-                    result.insert(key, json!(null));
-                } else {
-                    if offset + 8 > data.len() {
-                        return Err(anyhow!("Not enough data for int64 at offset {}", offset));
+
+            // Decode records that were waiting for this Start. Dropped ones,
+            // and any past the buffer limit, stay counted as orphans.
+            if let Some(waiting) = pass.orphans.remove(&data.entry).filter(|_| adopts_orphans) {
+                match pass.orphan_counts.get_mut(&data.entry) {
+                    Some(count) if *count > waiting.len() => *count -= waiting.len(),
+                    _ => {
+                        pass.orphan_counts.remove(&data.entry);
                     }
-                    let mut cursor = Cursor::new(&data[offset..offset + 8]);
-                    let val = cursor.read_i64::<LittleEndian>()?;
-                    result.insert(key, json!(val));
-                    offset += 8;
+                }
+                pass.buffered_orphans -= waiting.len();
+                let position = pass.records.position();
+                for (offset, orphan) in waiting.iter().filter(|_| is_selected) {
+                    pass.context.record_offset = *offset;
+                    self.handle_data_record(orphan, &data, infer_schema_only, position, &mut pass.context, sink)?;
                 }
             }
-            // Handle nested struct
-            _ => {
-                // Find nested schema - try with and without "struct:" prefix
-                let nested_schema = schemas
-                    .iter()
-                    .find(|s| {
-                        s.name.strip_prefix("struct:") == Some(&col.type_name) || s.name == col.type_name
-                    })
-                    .ok_or_else(|| anyhow!("No nested schema found for: {}", col.type_name))?;
-
-                let (nested_result, new_offset) = unpack_struct(&nested_schema.columns, data, offset, &key, schemas)?;
-                result.extend(nested_result);
-                offset = new_offset;
+            if !infer_schema_only && is_selected {
+                self.check_supported(&data)?;
             }
+            pass.entries.insert(data.entry, data);
+        } else if record.is_finish() {
+            let entry = record.get_finish_entry()?;
+            pass.entries.remove(&entry);
+        } else if !record.is_control() {
+            if let Some(entry) = pass.entries.get(&record.entry) {
+                if !pass.filter_entries || pass.selected.contains(&record.entry) {
+                    let position = pass.records.position();
+                    pass.context.record_offset = pass.records.record_start();
+                    self.handle_data_record(&record, entry, infer_schema_only, position, &mut pass.context, sink)?;
+                } else if !infer_schema_only {
+                    // Keep loop counts the same as in a full read
+                    pass.context.next_loop_count(entry);
+                }
+            } else {
+                *pass.orphan_counts.entry(record.entry).or_insert(0) += 1;
+                if self.buffer_orphans && pass.buffered_orphans < MAX_BUFFERED_ORPHANS {
+                    let offset = pass.records.record_start();
+                    pass.orphans.entry(record.entry).or_default().push((offset, record));
+                    pass.buffered_orphans += 1;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Record the outcome of a pass that reached the end of its log.
+    fn finish_pass<R, F>(&mut self, pass: &mut LogPass<'_>, sink: &mut F) -> Result<bool>
+    where
+        R: PassRow,
+        F: FnMut(R) -> Result<()>,
+    {
+        pass.finished = true;
+        self.orphaned_records = std::mem::take(&mut pass.orphan_counts);
+
+        if !pass.infer_schema_only {
+            // Struct records whose schema never appeared fail to decode
+            for deferred in std::mem::take(&mut pass.context.deferred) {
+                self.decode_deferred(deferred, &mut pass.context, sink)?;
+            }
+            for (entry, records) in std::mem::take(&mut pass.context.invalid_utf8) {
+                let warning = Warning::InvalidUtf8 { entry, records };
+                log::warn!("{}", warning);
+                self.warnings.push(warning);
+            }
+            if let Some(progress) = &mut self.progress {
+                progress.advance(pass.records.position() as u64);
+                progress.set_entries(self.metrics_names.len() as u64);
+                progress.complete();
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Warn about, or with `deny_unsupported_types` reject, an entry whose
+    /// values are written as nulls because its type is not decoded.
+    fn check_supported(&mut self, entry: &StartRecordData) -> Result<()> {
+        let Some(hint) = entry.entry_type.unsupported_hint() else {
+            return Ok(());
+        };
+        if self.deny_unsupported_types {
+            return Err(Error::UnsupportedType {
+                entry: entry.name.clone(),
+                type_name: entry.type_name.clone(),
+                hint: hint.to_string(),
+            });
+        }
+        let warning = Warning::UnsupportedType {
+            entry: entry.name.clone(),
+            type_name: entry.type_name.clone(),
+            hint: hint.to_string(),
         };
+        if !self.warnings.contains(&warning) {
+            log::warn!("{}", warning);
+            self.warnings.push(warning);
+        }
+        Ok(())
+    }
+
+    /// Flattened layout of a registered struct schema.
+    ///
+    /// `name` may include the `struct:` prefix. Nested structs are expanded in
+    /// place, so the result lists the primitive fields, with dotted names and
+    /// byte offsets, that a struct entry of this type is decoded into.
+    pub fn resolved_schema(&self, name: &str) -> Result<Vec<ResolvedField>> {
+        resolve_struct(&self.struct_schemas, name)
+    }
+
+    /// Register the struct schema logged in `record`.
+    fn register_struct_schema(&mut self, record: &DataLogRecord, entry: &StartRecordData) -> Result<()> {
+        let definition = record.get_string()?;
+        let schema_name = entry
+            .name
+            .split(".schema/")
+            .nth(1)
+            .ok_or_else(|| Error::SchemaError("Invalid schema name format".to_string()))?;
+
+        register_schema(&mut self.struct_schemas, schema_name, &definition)
+    }
+
+    /// Whether records of `entry_type` can be decoded: anything but a struct
+    /// whose schema, or a schema nested in it, has not been registered yet.
+    fn struct_schema_ready(&self, entry_type: &EntryType, context: &mut ParseContext) -> bool {
+        let (EntryType::Struct(name) | EntryType::StructArray(name)) = entry_type else {
+            return true;
+        };
+        if context.ready_structs.contains(name) {
+            return true;
+        }
+        // Registered schemas never change, so a resolvable struct stays so
+        let ready = self.resolved_schema(name).is_ok();
+        if ready {
+            context.ready_structs.insert(name.clone());
+        }
+        ready
+    }
+
+    /// Decode a deferred struct record as it would have been when it was read.
+    fn decode_deferred<R, F>(&mut self, deferred: DeferredRecord, context: &mut ParseContext, sink: &mut F) -> Result<()>
+    where
+        R: PassRow,
+        F: FnMut(R) -> Result<()>,
+    {
+        // Mark it ready so a schema that never appeared fails to decode
+        // instead of being deferred again
+        if let EntryType::Struct(name) | EntryType::StructArray(name) = &deferred.entry.entry_type {
+            context.ready_structs.insert(name.clone());
+        }
+        let (offset, loop_count) = (context.record_offset, context.loop_count);
+        context.record_offset = deferred.offset;
+        context.loop_count = deferred.loop_count;
+        let result = self.handle_data_record(&deferred.record, &deferred.entry, false, deferred.position, context, sink);
+        context.record_offset = offset;
+        context.loop_count = loop_count;
+        result
+    }
+
+    /// What to do with `record` after it failed to decode with `error`.
+    fn record_error_action(
+        &self,
+        record: &DataLogRecord,
+        entry: &StartRecordData,
+        context: &ParseContext,
+        error: &Error,
+    ) -> RecordAction {
+        match &self.on_record_error {
+            Some(handler) => handler.decide(&RecordError {
+                entry: &entry.name,
+                entry_id: record.entry,
+                type_name: &entry.type_name,
+                timestamp: record.timestamp,
+                offset: context.record_offset,
+                error,
+            }),
+            None => RecordAction::Abort,
+        }
     }
 
-    Ok((result, offset))
+    /// Decode one data record of `entry`, or collect its struct schema during
+    /// the schema pass.
+    ///
+    /// In the data pass struct schemas are registered as they are read, and
+    /// struct records that arrive before their schema are held until it is,
+    /// so their rows follow the schema record but keep their own timestamps.
+    fn handle_data_record<R, F>(
+        &mut self,
+        record: &DataLogRecord,
+        entry: &StartRecordData,
+        infer_schema_only: bool,
+        position: usize,
+        context: &mut ParseContext,
+        sink: &mut F,
+    ) -> Result<()>
+    where
+        R: PassRow,
+        F: FnMut(R) -> Result<()>,
+    {
+        if infer_schema_only {
+            if entry.entry_type == EntryType::StructSchema {
+                self.register_struct_schema(record, entry)?;
+            } else if entry.entry_type == EntryType::Msgpack {
+                let limit = self.msgpack_sample.unwrap_or(0);
+                let schema = self.msgpack_schemas.entry(entry.name.clone()).or_default();
+                if schema.samples() < limit {
+                    // Undecodable payloads count against the schema
+                    match record.get_msgpack() {
+                        Ok(value) => schema.observe(&value),
+                        Err(_) => schema.observe(&rmpv::Value::Nil),
+                    }
+                }
+            }
+        } else if entry.entry_type == EntryType::StructSchema {
+            self.register_struct_schema(record, entry)?;
+            // Decode the records that were waiting for it, in log order
+            let (ready, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut context.deferred)
+                .into_iter()
+                .partition(|deferred| self.struct_schema_ready(&deferred.entry.entry_type, context));
+            context.deferred = waiting;
+            for deferred in ready {
+                self.decode_deferred(deferred, context, sink)?;
+            }
+        } else if !self.struct_schema_ready(&entry.entry_type, context) {
+            context.deferred.push(DeferredRecord {
+                record: record.clone(),
+                entry: entry.clone(),
+                offset: context.record_offset,
+                position,
+                loop_count: context.loop_count,
+            });
+        } else {
+            let in_range = self
+                .time_range
+                .as_ref()
+                .is_none_or(|range| range.contains(&record.timestamp));
+            let converted = self.start_offset.is_some_and(|offset| position <= offset);
+            if in_range {
+                let sampled_out = self
+                    .subsample
+                    .is_some_and(|subsample| !subsample.keeps(context.next_entry_record(record.entry)));
+                if converted || sampled_out {
+                    // Keep loop counts the same as in a full read
+                    context.next_loop_count(entry);
+                } else {
+                    let loop_count = context.loop_count;
+                    let decoded = if record.data.is_empty() && !entry.entry_type.allows_empty_payload() {
+                        match self.quirks.empty_payloads {
+                            EmptyPayloads::Error => Err(Error::TypeMismatch(format!(
+                                "entry '{}': empty payload for declared type {}",
+                                entry.name, entry.entry_type
+                            ))),
+                            EmptyPayloads::Skip => return Ok(()),
+                            EmptyPayloads::Raw => Ok(vec![R::empty(self, record, entry, context)]),
+                        }
+                    } else {
+                        R::decode(self, record, entry, context)
+                    };
+                    let rows = match decoded {
+                        Ok(rows) => rows,
+                        Err(error) => {
+                            // Undo any loop count advanced before decoding failed
+                            context.loop_count = loop_count;
+                            match self.record_error_action(record, entry, context, &error) {
+                                RecordAction::Abort => return Err(error),
+                                RecordAction::Skip => {
+                                    context.next_loop_count(entry);
+                                    return Ok(());
+                                }
+                                RecordAction::Null => vec![R::empty(self, record, entry, context)],
+                            }
+                        }
+                    };
+                    self.metrics_names.insert(entry.name.clone());
+                    for mut row in rows {
+                        if let Some(log) = self.record_id_log {
+                            row.set_record_id(RecordId::new(log, context.record_offset as u64));
+                        }
+                        sink(row)?;
+                    }
+                    if let Some(progress) = &mut self.progress {
+                        progress.record(position as u64);
+                    }
+                }
+            } else {
+                // Keep loop counts the same as in a full read
+                context.next_loop_count(entry);
+            }
+        }
+        Ok(())
+    }
 }
 
+/// Name the entry in type-mismatch errors, e.g. "entry '/Arm/Angle': declared
+/// type double needs an 8-byte payload, got 4 bytes".
+fn with_entry_context(error: Error, entry: &StartRecordData) -> Error {
+    match error {
+        Error::TypeMismatch(msg) => Error::TypeMismatch(format!("entry '{}': {}", entry.name, msg)),
+        other => other,
+    }
+}
@@ -0,0 +1,286 @@
+//! Record index for direct access to an entry's records.
+//!
+//! Building the index walks record headers only; data payloads are not copied or
+//! decoded. Each entry lifetime (Start..Finish) lists the offset and timestamp of
+//! its data records, so one entry can be read without decoding the rest of the
+//! log. [`QuickScan`] walks the headers the same way to count records without
+//! keeping their locations.
+
+use crate::datalog::{read_record_header, DataLogReader, DataLogRecord, StartRecordData};
+use crate::error::{Error, Result};
+use crate::entry_type::EntryType;
+use crate::quirks::{EmptyPayloads, QuirkReport};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Location of one data record in the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordLocation {
+    /// Record timestamp (microseconds)
+    pub timestamp: u64,
+    /// Byte offset of the record header, for [`DataLogReader::record_at`]
+    pub offset: usize,
+}
+
+/// The data records of one entry lifetime.
+#[derive(Debug, Clone)]
+pub struct IndexedEntry {
+    /// The Start record that opened this lifetime
+    pub start: StartRecordData,
+    /// Timestamp of the Start record (microseconds)
+    pub start_timestamp: u64,
+    /// Data records in log order
+    pub records: Vec<RecordLocation>,
+}
+
+/// Index of every entry lifetime and its data records.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::WpilogReader;
+///
+/// let reader = WpilogReader::from_file("data.wpilog")?;
+/// let index = reader.index()?;
+/// let low_level = reader.low_level_reader();
+///
+/// for entry in index.find("/Drive/LeftVelocity") {
+///     for location in &entry.records {
+///         let value = low_level.record_at(location.offset)?.get_double()?;
+///         println!("{}: {}", location.timestamp, value);
+///     }
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RecordIndex {
+    entries: Vec<IndexedEntry>,
+}
+
+impl RecordIndex {
+    /// Build an index by walking the record headers of a log.
+    pub fn build(reader: &DataLogReader<'_>) -> Result<Self> {
+        let data = reader.bytes();
+        let mut entries: Vec<IndexedEntry> = Vec::new();
+        let mut active: HashMap<u32, usize> = HashMap::new();
+        let mut pos = reader.first_record_pos()?;
+
+        loop {
+            let header = match read_record_header(data, pos, reader.quirks()) {
+                Ok(Some(header)) => header,
+                Ok(None) | Err(Error::Truncated { .. }) => break,
+                Err(e) => return Err(e),
+            };
+            let offset = pos;
+            pos = header.payload_end;
+
+            if header.entry != 0 {
+                if let Some(&idx) = active.get(&header.entry) {
+                    entries[idx].records.push(RecordLocation {
+                        timestamp: header.timestamp,
+                        offset,
+                    });
+                }
+                continue;
+            }
+
+            let record = DataLogRecord {
+                entry: 0,
+                timestamp: header.timestamp,
+                data: data[header.payload_start..header.payload_end].to_vec(),
+            };
+            if record.is_start() {
+                let start = record.get_start_data()?;
+                active.insert(start.entry, entries.len());
+                entries.push(IndexedEntry {
+                    start,
+                    start_timestamp: header.timestamp,
+                    records: Vec::new(),
+                });
+            } else if record.is_finish() {
+                active.remove(&record.get_finish_entry()?);
+            } else if record.is_set_metadata() {
+                let metadata = record.get_set_metadata_data()?;
+                if let Some(&idx) = active.get(&metadata.entry) {
+                    entries[idx].start.metadata = metadata.metadata;
+                }
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// All entry lifetimes in the order their Start records appeared.
+    pub fn entries(&self) -> &[IndexedEntry] {
+        &self.entries
+    }
+
+    /// All lifetimes of the entry with the given name.
+    pub fn find<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a IndexedEntry> + 'a {
+        self.entries.iter().filter(move |e| e.start.name == name)
+    }
+
+    /// Total number of indexed data records.
+    pub fn record_count(&self) -> usize {
+        self.entries.iter().map(|e| e.records.len()).sum()
+    }
+}
+
+/// Record counts and time range of a log, from a header-only walk.
+///
+/// Only Start and Finish payloads are decoded, so a scan takes a fraction of
+/// the time of a full read. Built by
+/// [`WpilogReader::quick_scan`](crate::WpilogReader::quick_scan).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuickScan {
+    /// Number of records, including control records
+    pub record_count: u64,
+    /// Number of data records, including those with no active Start record
+    pub data_record_count: u64,
+    /// Data records per entry name, across all lifetimes
+    pub entry_counts: BTreeMap<String, u64>,
+    /// Payload bytes of the data records per entry name, across all
+    /// lifetimes; see [`entry_sizes`](Self::entry_sizes)
+    #[serde(default)]
+    pub entry_bytes: BTreeMap<String, u64>,
+    /// Timestamp of the first record (microseconds)
+    pub start_timestamp: Option<u64>,
+    /// Timestamp of the last record (microseconds)
+    pub end_timestamp: Option<u64>,
+    /// Offset of a partial record at the end of the log, if any
+    pub truncated_at: Option<usize>,
+    /// Off-spec records tolerated because of the reader's
+    /// [`Quirks`](crate::Quirks)
+    pub quirks: QuirkReport,
+}
+
+impl QuickScan {
+    /// Scan the record headers of a log.
+    pub fn from_reader(reader: &DataLogReader<'_>) -> Result<Self> {
+        let data = reader.bytes();
+        let mut scan = QuickScan::default();
+        let mut active: HashMap<u32, (String, EntryType)> = HashMap::new();
+        let mut pos = reader.first_record_pos()?;
+
+        loop {
+            let header = match read_record_header(data, pos, reader.quirks()) {
+                Ok(Some(header)) => header,
+                Ok(None) => break,
+                Err(Error::Truncated { offset, .. }) => {
+                    scan.truncated_at = Some(offset);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            scan.quirks.padding_bytes += header.padding as u64;
+            scan.quirks.oversized_records += header.oversized as u64;
+            pos = header.payload_end;
+            scan.record_count += 1;
+            scan.start_timestamp.get_or_insert(header.timestamp);
+            scan.end_timestamp = Some(header.timestamp);
+
+            let empty = header.payload_start == header.payload_end;
+            if header.entry != 0 {
+                scan.data_record_count += 1;
+                if let Some((name, entry_type)) = active.get(&header.entry) {
+                    if empty
+                        && !entry_type.allows_empty_payload()
+                        && reader.quirks().empty_payloads != EmptyPayloads::Error
+                    {
+                        scan.quirks.empty_payloads += 1;
+                    }
+                    let bytes = (header.payload_end - header.payload_start) as u64;
+                    match scan.entry_counts.get_mut(name) {
+                        Some(count) => *count += 1,
+                        None => {
+                            scan.entry_counts.insert(name.clone(), 1);
+                        }
+                    }
+                    match scan.entry_bytes.get_mut(name) {
+                        Some(total) => *total += bytes,
+                        None => {
+                            scan.entry_bytes.insert(name.clone(), bytes);
+                        }
+                    }
+                }
+                continue;
+            }
+            if empty {
+                scan.quirks.empty_control_records += 1;
+                continue;
+            }
+
+            let record = DataLogRecord {
+                entry: 0,
+                timestamp: header.timestamp,
+                data: data[header.payload_start..header.payload_end].to_vec(),
+            };
+            if record.is_start() {
+                let start = record.get_start_data()?;
+                active.insert(start.entry, (start.name, start.entry_type));
+            } else if record.is_finish() {
+                active.remove(&record.get_finish_entry()?);
+            }
+        }
+
+        Ok(scan)
+    }
+
+    /// Time between the first and last record, in seconds.
+    pub fn duration_secs(&self) -> f64 {
+        match (self.start_timestamp, self.end_timestamp) {
+            (Some(start), Some(end)) => end.saturating_sub(start) as f64 / 1_000_000.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Data records of the entries whose names satisfy `selected`.
+    pub fn count_matching<F: Fn(&str) -> bool>(&self, selected: F) -> u64 {
+        self.entry_counts
+            .iter()
+            .filter(|(name, _)| selected(name))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Payload bytes of the data records of all entries.
+    pub fn data_bytes(&self) -> u64 {
+        self.entry_bytes.values().sum()
+    }
+
+    /// Records and payload bytes of each entry, largest first, to see which
+    /// signals dominate the log's size.
+    pub fn entry_sizes(&self) -> Vec<EntrySize> {
+        let total = self.data_bytes();
+        let mut sizes: Vec<_> = self
+            .entry_bytes
+            .iter()
+            .map(|(name, &bytes)| EntrySize {
+                name: name.clone(),
+                records: self.entry_counts.get(name).copied().unwrap_or(0),
+                bytes,
+                share: if total == 0 { 0.0 } else { bytes as f64 / total as f64 },
+            })
+            .collect();
+        sizes.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        sizes
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Space taken by one entry's data records, from [`QuickScan::entry_sizes`].
+///
+/// Only payloads are counted; each record also has a 4 to 17 byte header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntrySize {
+    pub name: String,
+    pub records: u64,
+    /// Payload bytes summed over the entry's records
+    pub bytes: u64,
+    /// Fraction of all data payload bytes, from 0 to 1
+    pub share: f64,
+}
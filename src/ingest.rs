@@ -0,0 +1,324 @@
+//! Rolling conversion of a directory of logs.
+//!
+//! [`IngestDaemon`] watches a directory for new `.wpilog` files, converts each
+//! one to Parquet under `OUT_ROOT/filename=<stem>/`, verifies the export, and
+//! appends a [`ManifestEntry`] to `OUT_ROOT/manifest.jsonl`. Raw logs can be
+//! deleted once their conversion has been verified.
+//!
+//! A log is only picked up once it has not been modified for
+//! [`min_age`](IngestDaemon::min_age), so files still being copied off the
+//! robot are left alone. In [`incremental`](IngestDaemon::incremental) mode
+//! logs are instead converted while they grow: each scan converts only the
+//! records appended since the last one into new chunk files and records them
+//! in a new manifest entry.
+
+use crate::error::{Error, Result};
+use crate::formats::parquet::{chunk_file_name, read_parquet_schema, ParquetFormatter};
+use crate::reader::WpilogReader;
+use crate::summary::LogSummary;
+use crate::verify::verify_export;
+use crate::writer::{ParquetWriter, WriteStats};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// File name of the manifest inside the output root.
+pub const MANIFEST_FILE: &str = "manifest.jsonl";
+
+/// One converted log, as recorded in the manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// File name of the source log
+    pub file: String,
+    /// Directory the Parquet files were written to
+    pub output_dir: PathBuf,
+    /// Unix time the conversion finished (seconds)
+    pub converted_at: u64,
+    /// Whether the raw log was deleted after conversion
+    pub pruned: bool,
+    /// Rows and chunk files written by this conversion
+    pub stats: WriteStats,
+    /// Summary of the whole log at the time of this conversion
+    pub summary: LogSummary,
+    /// Byte offset just past the last converted record (`None` in entries
+    /// written before offsets were tracked)
+    #[serde(default)]
+    pub converted_bytes: Option<u64>,
+    /// Index of the first chunk file written; conversions of appended records
+    /// continue the numbering of the previous one
+    #[serde(default)]
+    pub first_chunk: usize,
+}
+
+/// How far a log has been converted, from its latest manifest entry.
+#[derive(Debug, Clone, Copy)]
+struct Converted {
+    bytes: Option<u64>,
+    next_chunk: usize,
+}
+
+impl From<&ManifestEntry> for Converted {
+    fn from(entry: &ManifestEntry) -> Self {
+        Self {
+            bytes: entry.converted_bytes,
+            next_chunk: entry.first_chunk + entry.stats.num_chunks,
+        }
+    }
+}
+
+/// Read all entries of a manifest file. A missing file has no entries.
+pub fn read_manifest<P: AsRef<Path>>(path: P) -> Result<Vec<ManifestEntry>> {
+    let file = match fs::File::open(path.as_ref()) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            entries.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(entries)
+}
+
+/// Watches a directory and converts new logs as they appear.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use wpilog_parser::IngestDaemon;
+///
+/// IngestDaemon::new("/mnt/robot-logs", "/data/parquet")
+///     .poll_interval(Duration::from_secs(30))
+///     .prune_raw(true)
+///     .run()?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct IngestDaemon {
+    in_dir: PathBuf,
+    out_root: PathBuf,
+    poll_interval: Duration,
+    min_age: Duration,
+    chunk_size: usize,
+    prune_raw: bool,
+    incremental: bool,
+    /// Logs already in the manifest, loaded on the first scan
+    converted: HashMap<String, Converted>,
+    manifest_loaded: bool,
+    /// Logs that failed to convert, with the size they had at the time
+    failed: HashSet<(String, u64)>,
+}
+
+impl IngestDaemon {
+    /// Create a daemon converting logs from `in_dir` into `out_root`.
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(in_dir: P, out_root: Q) -> Self {
+        Self {
+            in_dir: in_dir.as_ref().to_path_buf(),
+            out_root: out_root.as_ref().to_path_buf(),
+            poll_interval: Duration::from_secs(10),
+            min_age: Duration::from_secs(5),
+            chunk_size: 50_000,
+            prune_raw: false,
+            incremental: false,
+            converted: HashMap::new(),
+            manifest_loaded: false,
+            failed: HashSet::new(),
+        }
+    }
+
+    /// Time between directory scans in [`run`](Self::run) (default: 10 seconds).
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// How long a log must go unmodified before it is converted (default: 5 seconds).
+    pub fn min_age(mut self, age: Duration) -> Self {
+        self.min_age = age;
+        self
+    }
+
+    /// Rows per Parquet file (default: 50,000).
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = size;
+        self
+    }
+
+    /// Delete each raw log after its conversion has been verified (default: off).
+    pub fn prune_raw(mut self, enabled: bool) -> Self {
+        self.prune_raw = enabled;
+        self
+    }
+
+    /// Convert logs while they are still being written, then only the records
+    /// appended since on later scans (default: off).
+    ///
+    /// Each conversion adds chunk files after the existing ones and appends a
+    /// manifest entry with the new byte offset. [`min_age`](Self::min_age) is
+    /// not applied, raw logs are never pruned since they may still grow, and
+    /// only a log's first conversion is verified, as verification compares
+    /// against the whole output directory.
+    pub fn incremental(mut self, enabled: bool) -> Self {
+        self.incremental = enabled;
+        self
+    }
+
+    /// Path of the manifest file.
+    pub fn manifest_path(&self) -> PathBuf {
+        self.out_root.join(MANIFEST_FILE)
+    }
+
+    /// Scan forever, sleeping [`poll_interval`](Self::poll_interval) between scans.
+    ///
+    /// Failed conversions are logged and retried only after the log changes
+    /// size; errors scanning the directory or writing the manifest stop the
+    /// daemon.
+    pub fn run(&mut self) -> Result<()> {
+        loop {
+            self.run_once()?;
+            thread::sleep(self.poll_interval);
+        }
+    }
+
+    /// Scan the input directory once and convert every new, settled log.
+    ///
+    /// Returns the manifest entries written by this scan.
+    pub fn run_once(&mut self) -> Result<Vec<ManifestEntry>> {
+        if !self.in_dir.is_dir() {
+            return Err(Error::InvalidFormat(format!(
+                "'{}' is not a valid directory",
+                self.in_dir.display()
+            )));
+        }
+        fs::create_dir_all(&self.out_root)?;
+        if !self.manifest_loaded {
+            // Later entries of a log supersede earlier ones
+            for entry in read_manifest(self.manifest_path())? {
+                self.converted.insert(entry.file.clone(), Converted::from(&entry));
+            }
+            self.manifest_loaded = true;
+        }
+
+        let mut candidates: Vec<(PathBuf, String, u64)> = Vec::new();
+        for dir_entry in fs::read_dir(&self.in_dir)? {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("wpilog") {
+                continue;
+            }
+            let Some(file) = path.file_name().and_then(|name| name.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let metadata = fs::metadata(&path)?;
+            let age = metadata
+                .modified()?
+                .elapsed()
+                .unwrap_or(Duration::ZERO);
+            let due = match self.converted.get(&file) {
+                Some(converted) => {
+                    self.incremental && converted.bytes.is_some_and(|bytes| metadata.len() > bytes)
+                }
+                None => self.incremental || age >= self.min_age,
+            };
+            if !due || self.failed.contains(&(file.clone(), metadata.len())) {
+                continue;
+            }
+            candidates.push((path, file, metadata.len()));
+        }
+        candidates.sort();
+
+        let mut written = Vec::new();
+        for (path, file, size) in candidates {
+            match self.convert(&path, &file) {
+                Ok(Some(entry)) => {
+                    self.append_to_manifest(&entry)?;
+                    if entry.pruned {
+                        fs::remove_file(&path)?;
+                    }
+                    log::info!("Converted {}: {}", file, entry.stats.summary());
+                    self.converted.insert(file, Converted::from(&entry));
+                    written.push(entry);
+                }
+                // Nothing complete to convert yet
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("Failed to convert {}: {}", file, e);
+                    self.failed.insert((file, size));
+                }
+            }
+        }
+        Ok(written)
+    }
+
+    /// Convert a new log, or the records appended to a converted one.
+    /// Returns `None` if an incremental conversion found no complete records.
+    fn convert(&self, path: &Path, file: &str) -> Result<Option<ManifestEntry>> {
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+        let output_dir = self.out_root.join(format!("filename={}", stem));
+        let previous = self.converted.get(file).copied();
+        // Leftovers from an interrupted attempt would fail verification
+        if previous.is_none() && output_dir.exists() {
+            fs::remove_dir_all(&output_dir)?;
+        }
+
+        // Not mapped: the log may still be written, or replaced by a new copy
+        let reader = WpilogReader::from_file_unmapped(path)?;
+        let offset = previous.and_then(|converted| converted.bytes).unwrap_or(0);
+        let (records, end) = reader.read_appended(offset as usize)?;
+        if records.is_empty() && self.incremental {
+            return Ok(None);
+        }
+        let summary = reader.summarize()?;
+
+        fs::create_dir_all(&output_dir)?;
+        let first_chunk = previous.map_or(0, |converted| converted.next_chunk);
+        let mut writer = ParquetWriter::new(&output_dir)
+            .chunk_size(self.chunk_size)
+            .first_chunk_index(first_chunk);
+        if let Some(last_chunk) = first_chunk.checked_sub(1) {
+            // Appended records keep the column types of the files before them
+            let schema = read_parquet_schema(&output_dir.join(chunk_file_name(last_chunk)))?;
+            writer = writer.schema(ParquetFormatter::new(String::new(), 1).extend_schema(&schema, &records)?);
+        }
+        let stats = writer.write_with_stats(&records)?;
+
+        if previous.is_none() {
+            let report = verify_export(&records, &output_dir)?;
+            if !report.is_ok() {
+                return Err(Error::OutputError(format!(
+                    "Export verification failed:\n{}",
+                    report.diff()
+                )));
+            }
+        }
+
+        Ok(Some(ManifestEntry {
+            file: file.to_string(),
+            output_dir,
+            converted_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            pruned: self.prune_raw && !self.incremental,
+            stats,
+            summary,
+            converted_bytes: Some(end as u64),
+            first_chunk,
+        }))
+    }
+
+    fn append_to_manifest(&self, entry: &ManifestEntry) -> Result<()> {
+        let mut manifest = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.manifest_path())?;
+        writeln!(manifest, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+}
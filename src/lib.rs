@@ -61,7 +61,8 @@
 //!
 //! ### Low-Level Access
 //!
-//! For performance-critical applications or custom parsing logic:
+//! For performance-critical applications or custom parsing logic. The record
+//! types and control constants are available from [`low_level`]:
 //!
 //! ```no_run
 //! use wpilog_parser::WpilogReader;
@@ -116,17 +117,94 @@
 //! ```
 
 // Public API modules
+pub mod align;
+pub mod anomaly;
+pub mod archive;
+pub mod batches;
+pub mod blobs;
+pub mod budget;
+pub mod catalog;
+pub mod contract;
+pub mod cursor;
+pub mod entry_type;
 pub mod error;
+pub mod index;
+pub mod ingest;
+pub mod log_writer;
+pub mod low_level;
+pub mod msgpack;
+#[cfg(feature = "nt4")]
+pub mod nt4;
+pub mod pivot;
+pub mod presence;
+pub mod profile;
+pub mod progress;
+pub mod provenance;
+pub mod quirks;
 pub mod reader;
+pub mod record_error;
+pub mod record_id;
+pub mod reduce;
+pub mod replay;
+pub mod rewrite;
+pub mod scan;
+pub mod series;
+pub mod sink;
+pub mod snapshot;
+pub mod spill;
+#[cfg(feature = "datafusion")]
+pub mod sql;
+pub mod stats;
+pub mod structs;
+pub mod summary;
+pub mod timeline;
+pub mod tracking;
+pub mod usage;
+pub mod verify;
 pub mod writer;
 
 // Re-export commonly used types
-pub use error::{Error, Result};
-pub use reader::{WpilogReader, WpilogReaderBuilder};
-pub use writer::{ParquetWriter, ParquetWriterBuilder, WriteStats};
+pub use align::LogAligner;
+pub use anomaly::{Anomaly, AnomalyDetector, AnomalyKind};
+pub use archive::{ArchivedLog, LogSet};
+pub use batches::ArrowConverter;
+pub use blobs::{Blob, BlobStore};
+pub use budget::{EntryAction, EntryAdvice, SizeAdvice, SizeBudget};
+pub use catalog::{EntryCatalog, EntryLifetime};
+pub use contract::{ContractReport, ContractViolation, EntryContract, SchemaContract};
+pub use cursor::{LogCursor, Sample};
+pub use entry_type::{DecodedValue, EntryType};
+pub use formats::parquet::{ColumnOrder, ColumnType, ParquetCompression, StatisticsLevel};
+pub use error::{Error, Result, Warning};
+pub use index::{EntrySize, QuickScan, RecordIndex};
+pub use ingest::{IngestDaemon, ManifestEntry};
+pub use log_writer::DataLogWriter;
+pub use pivot::{FillPolicy, Pivot};
+pub use presence::{PresenceChange, PresenceMatrix};
+pub use profile::{EntryPattern, EntrySelector, FilterProfile, ProfileSet};
+pub use progress::{ProgressPhase, ProgressTracker, ProgressUpdate};
+pub use provenance::Provenance;
+pub use quirks::{EmptyPayloads, QuirkReport, Quirks};
+pub use reader::{FromParquet, Rows, TimeOrigin, WpilogReader, WpilogReaderBuilder};
+pub use record_error::{RecordAction, RecordError};
+pub use record_id::RecordId;
+pub use reduce::{ChangeFilter, Downsample, Subsample};
+pub use replay::Replayer;
+pub use rewrite::{verify_checksums, ChecksumReport, LogRewriter, RewrittenLog};
+pub use scan::Scan;
+pub use series::TimeSeries;
+pub use sink::{DirectorySink, MemorySink, OutputSink};
+pub use snapshot::{check_golden_dir, GoldenReport, LogSnapshot};
+pub use spill::SpillStore;
+pub use summary::{LogSummary, MatchInfo};
+pub use timeline::{CommandSpan, CommandTimeline};
+pub use tracking::{StepResponse, TrackingAnalysis, TrackingPair, TrackingReport};
+pub use usage::{UsageReport, UsageSummary};
+pub use verify::{verify_export, VerifyReport};
+pub use writer::{JsonlWriter, ParquetWriter, ParquetWriterBuilder, WriteStats};
 
 // Re-export models for users who need them
-pub use models::{OutputFormat, WideRow};
+pub use models::{LongRow, OutputFormat, WideRow};
 
 // Internal modules (public but not part of the high-level API)
 pub mod datalog;
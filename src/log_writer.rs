@@ -0,0 +1,282 @@
+//! Encoding of `.wpilog` files.
+//!
+//! [`DataLogWriter`] writes the file header and records with the smallest
+//! header encoding that fits each record, as WPILib's `DataLog` does, so this
+//! crate can produce logs as well as read them: synthetic logs for tests,
+//! filtered copies of a log, or logs from non-robot tools.
+//!
+//! # Examples
+//!
+//! ```
+//! use wpilog_parser::{DataLogWriter, WpilogReader};
+//!
+//! let mut writer = DataLogWriter::new(Vec::new())?;
+//! let voltage = writer.start("/Robot/Voltage", "double", "", 0)?;
+//! writer.append_double(voltage, 1_000_000, 12.3)?;
+//! let data = writer.into_inner()?;
+//!
+//! let rows = WpilogReader::from_bytes(data)?.read_all()?;
+//! assert_eq!(rows[0].data["/Robot/Voltage"], 12.3);
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+
+use crate::datalog::{length_prefix, start_payload, write_record, CONTROL_FINISH, CONTROL_SET_METADATA};
+use crate::entry_type::EntryType;
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Format version written in the header (1.0).
+pub const WPILOG_VERSION: u16 = 0x0100;
+
+/// Writer for the WPILog binary format.
+///
+/// Entries are started with [`start`](Self::start), which assigns their IDs,
+/// and values are appended with the typed `append_*` methods, which check the
+/// entry's declared type. Timestamps are in microseconds. Output is written to
+/// `W` as records are added; wrap unbuffered sinks in a [`BufWriter`], as
+/// [`create`](Self::create) does.
+pub struct DataLogWriter<W: Write> {
+    out: W,
+    /// Types of the entries started and not yet finished, by ID
+    entries: HashMap<u32, EntryType>,
+    next_entry: u32,
+    /// Reused encoding buffers
+    payload: Vec<u8>,
+    record: Vec<u8>,
+}
+
+impl DataLogWriter<BufWriter<File>> {
+    /// Create (or truncate) the log file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> DataLogWriter<W> {
+    /// Write a log with no extra header to `out`.
+    pub fn new(out: W) -> Result<Self> {
+        Self::with_extra_header(out, "")
+    }
+
+    /// Write a log whose header carries `extra_header`, conventionally a
+    /// description of the program that wrote it.
+    pub fn with_extra_header(mut out: W, extra_header: &str) -> Result<Self> {
+        out.write_all(b"WPILOG")?;
+        out.write_all(&WPILOG_VERSION.to_le_bytes())?;
+        out.write_all(&length_prefix(extra_header.len(), "extra header")?.to_le_bytes())?;
+        out.write_all(extra_header.as_bytes())?;
+        Ok(Self {
+            out,
+            entries: HashMap::new(),
+            next_entry: 1,
+            payload: Vec::new(),
+            record: Vec::new(),
+        })
+    }
+
+    /// Start an entry called `name` of type `type_name` (e.g. `double`,
+    /// `struct:Pose2d`), returning its entry ID.
+    pub fn start(
+        &mut self,
+        name: &str,
+        type_name: &str,
+        metadata: &str,
+        timestamp: u64,
+    ) -> Result<u32> {
+        let entry = self.next_entry;
+        let payload = start_payload(entry, name, type_name, metadata)?;
+        self.next_entry = self
+            .next_entry
+            .checked_add(1)
+            .ok_or_else(|| Error::InvalidEntry("Entry IDs exhausted".to_string()))?;
+        self.write(0, timestamp, &payload)?;
+        self.entries.insert(entry, EntryType::parse(type_name));
+        Ok(entry)
+    }
+
+    /// Finish `entry`; its ID is not reused.
+    pub fn finish(&mut self, entry: u32, timestamp: u64) -> Result<()> {
+        self.entry_type(entry)?;
+        let mut payload = vec![CONTROL_FINISH];
+        payload.extend_from_slice(&entry.to_le_bytes());
+        self.write(0, timestamp, &payload)?;
+        self.entries.remove(&entry);
+        Ok(())
+    }
+
+    /// Replace the metadata of `entry`.
+    pub fn set_metadata(&mut self, entry: u32, metadata: &str, timestamp: u64) -> Result<()> {
+        self.entry_type(entry)?;
+        let mut payload = vec![CONTROL_SET_METADATA];
+        payload.extend_from_slice(&entry.to_le_bytes());
+        payload.extend_from_slice(&length_prefix(metadata.len(), "metadata")?.to_le_bytes());
+        payload.extend_from_slice(metadata.as_bytes());
+        self.write(0, timestamp, &payload)
+    }
+
+    /// Append an already encoded payload to `entry`, whatever its type, e.g.
+    /// a struct or msgpack value.
+    ///
+    /// Fails with [`Error::InvalidRecord`] for payloads longer than
+    /// `u32::MAX` bytes, which a record header cannot describe.
+    pub fn append_raw(&mut self, entry: u32, timestamp: u64, payload: &[u8]) -> Result<()> {
+        self.entry_type(entry)?;
+        self.write(entry, timestamp, payload)
+    }
+
+    /// Append a value to a `boolean` entry.
+    pub fn append_boolean(&mut self, entry: u32, timestamp: u64, value: bool) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::Boolean], |buf| {
+            buf.push(value as u8)
+        })
+    }
+
+    /// Append a value to an `int64` entry.
+    pub fn append_int64(&mut self, entry: u32, timestamp: u64, value: i64) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::Int64], |buf| {
+            buf.extend_from_slice(&value.to_le_bytes())
+        })
+    }
+
+    /// Append a value to a `float` entry.
+    pub fn append_float(&mut self, entry: u32, timestamp: u64, value: f32) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::Float], |buf| {
+            buf.extend_from_slice(&value.to_le_bytes())
+        })
+    }
+
+    /// Append a value to a `double` entry.
+    pub fn append_double(&mut self, entry: u32, timestamp: u64, value: f64) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::Double], |buf| {
+            buf.extend_from_slice(&value.to_le_bytes())
+        })
+    }
+
+    /// Append a value to a `string` or `json` entry.
+    pub fn append_string(&mut self, entry: u32, timestamp: u64, value: &str) -> Result<()> {
+        self.append(
+            entry,
+            timestamp,
+            &[EntryType::String, EntryType::Json],
+            |buf| buf.extend_from_slice(value.as_bytes()),
+        )
+    }
+
+    /// Append a value to a `boolean[]` entry.
+    pub fn append_boolean_array(
+        &mut self,
+        entry: u32,
+        timestamp: u64,
+        values: &[bool],
+    ) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::BooleanArray], |buf| {
+            buf.extend(values.iter().map(|&value| value as u8))
+        })
+    }
+
+    /// Append a value to an `int64[]` entry.
+    pub fn append_int64_array(&mut self, entry: u32, timestamp: u64, values: &[i64]) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::Int64Array], |buf| {
+            values
+                .iter()
+                .for_each(|value| buf.extend_from_slice(&value.to_le_bytes()))
+        })
+    }
+
+    /// Append a value to a `float[]` entry.
+    pub fn append_float_array(&mut self, entry: u32, timestamp: u64, values: &[f32]) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::FloatArray], |buf| {
+            values
+                .iter()
+                .for_each(|value| buf.extend_from_slice(&value.to_le_bytes()))
+        })
+    }
+
+    /// Append a value to a `double[]` entry.
+    pub fn append_double_array(
+        &mut self,
+        entry: u32,
+        timestamp: u64,
+        values: &[f64],
+    ) -> Result<()> {
+        self.append(entry, timestamp, &[EntryType::DoubleArray], |buf| {
+            values
+                .iter()
+                .for_each(|value| buf.extend_from_slice(&value.to_le_bytes()))
+        })
+    }
+
+    /// Append a value to a `string[]` entry.
+    pub fn append_string_array<S: AsRef<str>>(
+        &mut self,
+        entry: u32,
+        timestamp: u64,
+        values: &[S],
+    ) -> Result<()> {
+        let count = length_prefix(values.len(), "string array")?;
+        for value in values {
+            length_prefix(value.as_ref().len(), "string")?;
+        }
+        self.append(entry, timestamp, &[EntryType::StringArray], |buf| {
+            buf.extend_from_slice(&count.to_le_bytes());
+            for value in values {
+                let value = value.as_ref();
+                buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+                buf.extend_from_slice(value.as_bytes());
+            }
+        })
+    }
+
+    /// Flush buffered output.
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.out.flush()?)
+    }
+
+    /// Flush and return the underlying writer, e.g. the bytes of an
+    /// in-memory log.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.flush()?;
+        Ok(self.out)
+    }
+
+    /// Declared type of a started entry.
+    fn entry_type(&self, entry: u32) -> Result<&EntryType> {
+        self.entries
+            .get(&entry)
+            .ok_or_else(|| Error::InvalidEntry(format!("Entry {} is not started", entry)))
+    }
+
+    /// Encode a value of one of the `expected` types with `encode` and append it.
+    fn append<F>(
+        &mut self,
+        entry: u32,
+        timestamp: u64,
+        expected: &[EntryType],
+        encode: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&mut Vec<u8>),
+    {
+        let entry_type = self.entry_type(entry)?;
+        if !expected.contains(entry_type) {
+            return Err(Error::TypeMismatch(format!(
+                "entry {} is declared as {}, not {}",
+                entry, entry_type, expected[0]
+            )));
+        }
+        self.payload.clear();
+        encode(&mut self.payload);
+        self.record.clear();
+        write_record(&mut self.record, entry, timestamp, &self.payload)?;
+        Ok(self.out.write_all(&self.record)?)
+    }
+
+    fn write(&mut self, entry: u32, timestamp: u64, payload: &[u8]) -> Result<()> {
+        self.record.clear();
+        write_record(&mut self.record, entry, timestamp, payload)?;
+        Ok(self.out.write_all(&self.record)?)
+    }
+}
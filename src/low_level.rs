@@ -0,0 +1,29 @@
+//! Low-level access to the WPILog binary format.
+//!
+//! This module re-exports the record-level types used by [`WpilogReader::low_level_reader`]
+//! so external tools can work with raw records without depending on internal
+//! module paths.
+//!
+//! [`WpilogReader::low_level_reader`]: crate::WpilogReader::low_level_reader
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wpilog_parser::low_level::{DataLogReader, CONTROL_START};
+//!
+//! let data = std::fs::read("data.wpilog")?;
+//! let reader = DataLogReader::new(&data);
+//!
+//! for record in reader.records()? {
+//!     let record = record?;
+//!     if record.is_control() && record.data.first() == Some(&CONTROL_START) {
+//!         println!("{}", record.get_start_data()?.name);
+//!     }
+//! }
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+
+pub use crate::datalog::{
+    hex_dump, DataLogIterator, DataLogReader, DataLogRecord, MetadataRecordData, RecordVisitor,
+    StartRecordData, CONTROL_FINISH, CONTROL_SET_METADATA, CONTROL_START,
+};
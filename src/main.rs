@@ -1,14 +1,48 @@
 //! Command-line interface for the WPILog parser.
 //!
-//! This binary provides a simple CLI for converting .wpilog files to Parquet format.
+//! This binary provides a simple CLI for converting .wpilog files to Parquet format,
+//! plus subcommands for inspecting logs without converting them.
+
+mod cli;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use cli::align::AlignArgs;
+use cli::convert::ConvertFileArgs;
+use cli::daemon::DaemonArgs;
+use cli::dump::DumpArgs;
+use cli::extract::ExtractArgs;
+use cli::replay::ReplayArgs;
+use cli::sizes::SizesArgs;
+use cli::timeline::TimelineArgs;
+use cli::usage::UsageArgs;
+use cli::grep::GrepArgs;
+use cli::histogram::HistogramArgs;
+use cli::inspect::{InspectArgs, SampleArgs};
+use cli::pid::PidArgs;
+use cli::presence::PresenceArgs;
+use cli::validate::ValidateArgs;
 use log::{info, LevelFilter};
 use std::fs;
-use std::path::Path;
-use std::time::Instant;
-use wpilog_parser::{ParquetWriter, WpilogReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::time::{Duration, Instant};
+use wpilog_parser::formats::parquet::read_parquet_schema;
+use wpilog_parser::profile::DEFAULT_PROFILE_FILE;
+use wpilog_parser::{
+    verify_export, ArchivedLog, BlobStore, ColumnOrder, FilterProfile, ParquetWriter, ProfileSet, ProgressUpdate,
+    LogSet, OutputFormat, ParquetCompression, Provenance, Quirks, RecordAction, SpillStore, StatisticsLevel, Subsample,
+    TimeOrigin, WpilogReaderBuilder,
+};
+
+#[cfg(feature = "progress")]
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+#[cfg(feature = "progress")]
+use wpilog_parser::ProgressPhase;
+#[cfg(feature = "progress")]
+use std::sync::mpsc::Receiver;
+#[cfg(feature = "progress")]
+use std::thread::{self, JoinHandle};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -16,30 +50,375 @@ use wpilog_parser::{ParquetWriter, WpilogReader};
     version,
     about = "Convert .wpilog files to Parquet format",
     long_about = "A high-performance parser for WPILib data log files (.wpilog) with output to Apache Parquet.\n\n\
-                  Parquet files are columnar, compressed, and optimized for analytics queries."
+                  Parquet files are columnar, compressed, and optimized for analytics queries.",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
 )]
-struct Args {
-    /// Directory containing .wpilog files
-    #[arg(value_name = "IN_DIR")]
-    in_dir: String,
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    convert: ConvertArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert one log (or stdin) to CSV or Parquet on stdout
+    Convert(ConvertFileArgs),
+    /// Print the first N data records of a log
+    Head(InspectArgs),
+    /// Print the last N data records of a log
+    Tail(InspectArgs),
+    /// Print N data records chosen at random from a log
+    Sample(SampleArgs),
+    /// Search string and JSON entries for a regular expression
+    Grep(GrepArgs),
+    /// Write one entry as a two-column (timestamp, value) file
+    Extract(ExtractArgs),
+    /// List the entries taking up the most space in a log, or fit it to a size budget
+    Sizes(SizesArgs),
+    /// Print the distribution of one entry's values (time in state for booleans/strings)
+    Histogram(HistogramArgs),
+    /// Report setpoint tracking error (RMSE, overshoot, settling time)
+    Pid(PidArgs),
+    /// Show which commands the scheduler ran when, as a text Gantt chart
+    Timeline(TimelineArgs),
+    /// Total enabled time, enable cycles and brownouts of a log or directory
+    Usage(UsageArgs),
+    /// Show which entries appeared or disappeared across a directory of logs
+    Presence(PresenceArgs),
+    /// Convert several logs onto a shared relative-time axis for overlaying runs
+    Align(AlignArgs),
+    /// Print every record with its offset, optionally with raw bytes
+    Dump(DumpArgs),
+    /// Check checksum records, or check logs against an expected-entries contract
+    Validate(ValidateArgs),
+    /// Play a log back at the pace it was recorded
+    Replay(ReplayArgs),
+    /// Watch a directory, converting and optionally pruning new logs
+    Daemon(DaemonArgs),
+}
+
+/// Where `--blobs` writes the payloads of undecoded entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum BlobOutput {
+    /// `blobs.parquet` with a binary `data` column
+    Parquet,
+    /// `blobs/<entry>/<timestamp>.bin` files
+    Files,
+}
+
+/// Options for the default conversion mode.
+#[derive(Args, Debug)]
+struct ConvertArgs {
+    /// Directory containing .wpilog files, or a .zip/.tar/.tar.gz archive of them
+    #[arg(value_name = "IN_DIR", required = true)]
+    in_dir: Option<String>,
 
     /// Root output directory for converted Parquet files
-    #[arg(short, long, value_name = "OUT_ROOT")]
-    out_root: String,
+    #[arg(short, long, value_name = "OUT_ROOT", required = true)]
+    out_root: Option<String>,
 
     /// Number of rows per Parquet file chunk
     #[arg(long, default_value = "50000")]
     chunk_size: usize,
+
+    /// Re-read the written Parquet and cross-check it against the source log
+    #[arg(long)]
+    verify: bool,
+
+    /// Split files at this many bytes of estimated data instead of by row count
+    #[arg(long, value_name = "BYTES")]
+    target_file_size: Option<usize>,
+
+    /// Split chunks whose Arrow arrays would need more than this many bytes
+    #[arg(long, value_name = "BYTES")]
+    max_chunk_memory: Option<usize>,
+
+    /// Compression codec: none, snappy, lz4, zstd or zstd:LEVEL
+    #[arg(long, value_name = "CODEC")]
+    compression: Option<ParquetCompression>,
+
+    /// Disable dictionary encoding, for faster writes of mostly unique values
+    #[arg(long)]
+    no_dictionary: bool,
+
+    /// Maximum rows per Parquet row group
+    #[arg(long, value_name = "ROWS")]
+    row_group_size: Option<usize>,
+
+    /// Column statistics to write: none, chunk or page
+    #[arg(long, value_name = "LEVEL")]
+    statistics: Option<StatisticsLevel>,
+
+    /// Write every file with the schema of this Parquet file, so outputs of
+    /// different logs can be read as one dataset
+    #[arg(long, value_name = "PARQUET_FILE")]
+    schema_from: Option<PathBuf>,
+
+    /// Order of value columns: alphabetical or declaration (entry start order)
+    #[arg(long, value_name = "ORDER", default_value = "alphabetical")]
+    column_order: ColumnOrder,
+
+    /// Put these columns first, in this order, then the rest alphabetically
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',', conflicts_with = "column_order")]
+    first_columns: Vec<String>,
+
+    /// Add a column holding VALUE in every row, e.g. `match=Q42` (repeatable)
+    #[arg(long = "constant-column", value_name = "NAME=VALUE", value_parser = parse_constant_column)]
+    constant_columns: Vec<(String, String)>,
+
+    /// Add source_file, source_sha256 and converted_at columns to every row
+    #[arg(long)]
+    add_provenance: bool,
+
+    /// Stage decoded rows in compressed temporary files instead of memory
+    #[arg(long, conflicts_with = "verify")]
+    spill: bool,
+
+    /// Directory for spill files (defaults to the system temp directory)
+    #[arg(long, value_name = "DIR", requires = "spill")]
+    spill_dir: Option<String>,
+
+    /// Number of threads writing chunk files (0 = all CPUs)
+    #[arg(long, default_value = "1")]
+    threads: usize,
+
+    /// Apply a named filter profile (entry selectors, downsampling, output format)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// JSON file defining filter profiles
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_PROFILE_FILE, requires = "profile")]
+    profile_file: String,
+
+    /// Tolerate off-spec records from third-party loggers (zero padding
+    /// between records, sizes past the end of the log)
+    #[arg(long)]
+    quirks: bool,
+
+    /// Fail instead of writing null columns for entries whose type is not
+    /// decoded (protobuf, raw and vendor types)
+    #[arg(long)]
+    strict_types: bool,
+
+    /// What to do with records that fail to decode: abort (default), skip
+    /// them, or write them as nulls; skipped and nulled records are logged
+    #[arg(long, value_name = "ACTION")]
+    on_record_error: Option<RecordAction>,
+
+    /// Write entries whose type is not decoded to a blob store next to the
+    /// chunk files instead of as null columns
+    #[arg(long, value_enum, value_name = "STORE")]
+    blobs: Option<BlobOutput>,
+
+    /// Convert only a sample of each entry's records for a quick preview,
+    /// as a percentage (`1%`) or every Nth record (`100`)
+    #[arg(long, value_name = "RATE")]
+    sample: Option<Subsample>,
+
+    /// Convert only the first SECONDS of each log
+    #[arg(long, value_name = "SECONDS", conflicts_with = "tail_duration")]
+    head_duration: Option<f64>,
+
+    /// Convert only the last SECONDS of each log
+    #[arg(long, value_name = "SECONDS")]
+    tail_duration: Option<f64>,
+
+    /// Add a record_id column tracing each row to its record in the log
+    #[arg(long)]
+    record_ids: bool,
+
+    /// Re-zero timestamps to first-record, first-enabled, auto-start, or a log
+    /// time in seconds
+    #[arg(long, value_name = "ORIGIN")]
+    time_origin: Option<TimeOrigin>,
+
+    /// Show interactive progress bars instead of per-file log output
+    #[cfg(feature = "progress")]
+    #[arg(long)]
+    progress: bool,
 }
 
-fn convert_one_file(input_file: &Path, output_dir: &Path, chunk_size: usize) -> Result<()> {
-    let file_name = input_file.to_string_lossy();
+/// Per-file and overall progress bars for interactive terminals.
+#[cfg(feature = "progress")]
+struct ProgressUi {
+    multi: MultiProgress,
+    overall: ProgressBar,
+}
+
+#[cfg(feature = "progress")]
+impl ProgressUi {
+    fn new(total_files: usize) -> Self {
+        let multi = MultiProgress::new();
+        let overall = multi.add(ProgressBar::new(total_files as u64));
+        overall.set_style(
+            ProgressStyle::with_template("{prefix:>10} [{bar:40}] {pos}/{len} files ({elapsed})")
+                .expect("valid template")
+                .progress_chars("=> "),
+        );
+        overall.set_prefix("Total");
+        Self { multi, overall }
+    }
+
+    /// Drive a bar for one file from the reader's progress updates until the
+    /// channel closes.
+    fn track_file(&self, updates: Receiver<ProgressUpdate>) -> JoinHandle<()> {
+        let bar = self.multi.insert_before(&self.overall, ProgressBar::new(0));
+        bar.set_style(
+            ProgressStyle::with_template("{prefix:>10} [{bar:40}] {bytes}/{total_bytes} {msg}")
+                .expect("valid template")
+                .progress_chars("=> "),
+        );
+        bar.set_prefix("Decoding");
+
+        thread::spawn(move || {
+            for update in updates {
+                match update {
+                    ProgressUpdate::Started { total_bytes } => {
+                        bar.set_length(total_bytes);
+                        bar.set_position(0);
+                    }
+                    ProgressUpdate::PhaseChanged { phase } => {
+                        bar.set_prefix(match phase {
+                            ProgressPhase::InferringSchema => "Schema",
+                            ProgressPhase::DecodingRecords => "Decoding",
+                        });
+                        bar.set_position(0);
+                        bar.set_message("");
+                    }
+                    ProgressUpdate::Progress {
+                        bytes_processed,
+                        records,
+                        total_records,
+                        ..
+                    } => {
+                        bar.set_position(bytes_processed);
+                        bar.set_message(match total_records {
+                            Some(total) => format!("{}/{} records", records, total),
+                            None => format!("{} records", records),
+                        });
+                    }
+                    ProgressUpdate::Complete {
+                        records,
+                        elapsed_secs,
+                        rows_per_sec,
+                        ..
+                    } => {
+                        bar.set_position(bar.length().unwrap_or(0));
+                        bar.set_message(format!(
+                            "{} records in {:.1}s ({:.0}/s), writing",
+                            records, elapsed_secs, rows_per_sec
+                        ));
+                    }
+                }
+            }
+            bar.finish_and_clear();
+        })
+    }
+
+    fn file_done(&self, name: &str, result: &Result<()>) {
+        let line = match result {
+            Ok(()) => format!("✓ {}", name),
+            Err(e) => format!("✗ {}: {}", name, e),
+        };
+        let _ = self.multi.println(line);
+        self.overall.inc(1);
+    }
+
+    fn finish(&self) {
+        self.overall.finish();
+    }
+}
+
+/// Parse a `NAME=VALUE` constant column.
+fn parse_constant_column(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("expected NAME=VALUE, got '{}'", s)),
+    }
+}
+
+/// A log to convert: a file in the input directory, or a log read out of an
+/// input archive.
+enum LogInput {
+    File(PathBuf),
+    Archived(ArchivedLog),
+}
+
+impl LogInput {
+    /// Name for the `filename=<stem>` output directory.
+    fn stem(&self) -> &str {
+        match self {
+            LogInput::File(path) => path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown"),
+            LogInput::Archived(log) => log.stem(),
+        }
+    }
+}
+
+fn convert_one_file(
+    input: &LogInput,
+    output_dir: &Path,
+    args: &ConvertArgs,
+    profile: Option<&FilterProfile>,
+    progress: Option<Sender<ProgressUpdate>>,
+) -> Result<()> {
+    let file_name = match input {
+        LogInput::File(path) => path.to_string_lossy(),
+        LogInput::Archived(log) => log.name().into(),
+    };
     info!("📄 Processing: {}", file_name);
 
     let start_time = Instant::now();
 
     // Read the WPILog file
-    let reader = WpilogReader::from_file(input_file)?;
+    let mut builder = WpilogReaderBuilder::new();
+    if let Some(profile) = profile {
+        builder = builder.profile(profile.clone());
+    }
+    if let Some(sender) = progress {
+        builder = builder.progress(sender);
+    }
+    if args.quirks {
+        builder = builder.quirks(Quirks::all());
+    }
+    if let Some(sample) = args.sample {
+        builder = builder.subsample(sample);
+    }
+    if let Some(seconds) = args.head_duration {
+        builder = builder.head_duration(Duration::try_from_secs_f64(seconds)?);
+    }
+    if let Some(seconds) = args.tail_duration {
+        builder = builder.tail_duration(Duration::try_from_secs_f64(seconds)?);
+    }
+    if let Some(origin) = args.time_origin {
+        builder = builder.time_origin(origin);
+    }
+    if args.record_ids {
+        builder = builder.record_ids(true);
+    }
+    if let Some(action) = args.on_record_error {
+        builder = builder.on_record_error(move |failure| {
+            let outcome = match action {
+                RecordAction::Skip => "skipped",
+                RecordAction::Null => "written as null",
+                RecordAction::Abort => return action,
+            };
+            log::warn!(
+                "   ├─ Record of '{}' at byte {} {}: {}",
+                failure.entry, failure.offset, outcome, failure.error
+            );
+            action
+        });
+    }
+    builder = builder
+        .deny_unsupported_types(args.strict_types)
+        .exclude_undecoded(args.blobs.is_some());
+    let reader = match input {
+        LogInput::File(path) => builder.from_file(path)?,
+        LogInput::Archived(log) => log.reader_with(builder)?,
+    };
 
     info!("   ├─ Version: {:#06x}", reader.version());
 
@@ -48,8 +427,105 @@ fn convert_one_file(input_file: &Path, output_dir: &Path, chunk_size: usize) ->
         info!("   ├─ Extra header: {}", extra_header);
     }
 
+    if let Some(output) = args.blobs {
+        let store = match output {
+            BlobOutput::Parquet => BlobStore::parquet(output_dir.join("blobs.parquet")),
+            BlobOutput::Files => BlobStore::directory(output_dir.join("blobs")),
+        };
+        let blobs = store.write(&reader.read_blobs()?)?;
+        if blobs > 0 {
+            info!("   ├─ Wrote {} blob(s) of undecoded entries", blobs);
+        }
+    }
+
+    let mut writer = ParquetWriter::new(output_dir)
+        .chunk_size(args.chunk_size)
+        .threads(args.threads);
+    if let Some(bytes) = args.target_file_size {
+        writer = writer.target_file_size(bytes);
+    }
+    if let Some(bytes) = args.max_chunk_memory {
+        writer = writer.max_bytes_in_flight(bytes);
+    }
+    if let Some(compression) = args.compression {
+        writer = writer.compression(compression);
+    }
+    if args.no_dictionary {
+        writer = writer.dictionary(false);
+    }
+    if let Some(rows) = args.row_group_size {
+        writer = writer.max_row_group_size(rows);
+    }
+    if let Some(level) = args.statistics {
+        writer = writer.statistics(level);
+    }
+    if let Some(path) = &args.schema_from {
+        writer = writer.schema(read_parquet_schema(path)?);
+    }
+    writer = if args.first_columns.is_empty() {
+        writer.column_order(args.column_order.clone())
+    } else {
+        writer.column_order(ColumnOrder::Explicit(args.first_columns.clone()))
+    };
+    let mut constant_columns = args.constant_columns.clone();
+    if args.add_provenance {
+        let provenance = match input {
+            LogInput::File(path) => Provenance::of_file(path)?,
+            LogInput::Archived(log) => Provenance::of_bytes(log.file_name(), log.data()),
+        };
+        constant_columns.extend(
+            provenance
+                .columns()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+    }
+    for (name, value) in &constant_columns {
+        writer = writer.constant_column(name.as_str(), value.as_str());
+    }
+
+    // Profiles may ask for the long layout, one row per record
+    if reader.output_format() == OutputFormat::Long {
+        if args.spill || args.verify {
+            anyhow::bail!("--spill and --verify are not supported for long output");
+        }
+
+        let t0 = Instant::now();
+        let records = reader.read_all_long()?;
+        info!("   ├─ Read {} long rows in {:.2?}", records.len(), t0.elapsed());
+
+        let t1 = Instant::now();
+        let stats = writer.write_long(&records)?;
+        info!("   ├─ Wrote Parquet in {:.2?}", t1.elapsed());
+        info!("   ├─ {}", stats.summary());
+        info!("   └─ ✓ Total time: {:.2?}\n", start_time.elapsed());
+        return Ok(());
+    }
+
+    if args.spill {
+        let mut store = match &args.spill_dir {
+            Some(dir) => SpillStore::new_in(dir)?,
+            None => SpillStore::new()?,
+        };
+
+        let t0 = Instant::now();
+        let rows = reader.read_to_spill(&mut store)?;
+        info!(
+            "   ├─ Staged {} records in {} spill file(s) in {:.2?}",
+            rows,
+            store.spilled_batches(),
+            t0.elapsed()
+        );
+
+        let t1 = Instant::now();
+        let stats = writer.write_spilled(&store)?;
+        info!("   ├─ Wrote Parquet in {:.2?}", t1.elapsed());
+        info!("   ├─ {}", stats.summary());
+        info!("   └─ ✓ Total time: {:.2?}\n", start_time.elapsed());
+        return Ok(());
+    }
+
     let t0 = Instant::now();
-    let (records, formatter) = reader.read_all_with_metadata()?;
+    let (mut records, formatter) = reader.read_all_with_metadata()?;
     info!(
         "   ├─ Read {} records in {:.2?}",
         records.len(),
@@ -59,44 +535,126 @@ fn convert_one_file(input_file: &Path, output_dir: &Path, chunk_size: usize) ->
         "   ├─ Found {} unique metrics",
         formatter.metrics_names.len()
     );
+    if let Some(offset) = formatter.truncated_at {
+        log::warn!("   ├─ Log is truncated; ignored a partial record at byte {}", offset);
+    }
+    let orphaned: usize = formatter.orphaned_records.values().sum();
+    if orphaned > 0 {
+        log::warn!(
+            "   ├─ Skipped {} record(s) for {} entry ID(s) with no Start record",
+            orphaned,
+            formatter.orphaned_records.len()
+        );
+    }
 
     // Write to Parquet
     let t1 = Instant::now();
-    let stats = ParquetWriter::new(output_dir)
-        .chunk_size(chunk_size)
-        .write_with_stats(&records)?;
+    let stats = writer.write_with_stats(&records)?;
 
     info!("   ├─ Wrote Parquet in {:.2?}", t1.elapsed());
     info!("   ├─ {}", stats.summary());
+
+    if args.verify {
+        let t2 = Instant::now();
+        // The export holds the constant columns in every row
+        for record in &mut records {
+            for (name, value) in &constant_columns {
+                record.data.insert(name.clone(), value.as_str().into());
+            }
+        }
+        let report = verify_export(&records, output_dir)?;
+        if !report.is_ok() {
+            anyhow::bail!("Export verification failed:\n{}", report.diff());
+        }
+        info!(
+            "   ├─ Verified {} rows ({} sampled) in {:.2?}",
+            report.exported_rows,
+            report.sampled_rows,
+            t2.elapsed()
+        );
+    }
     info!("   └─ ✓ Total time: {:.2?}\n", start_time.elapsed());
 
     Ok(())
 }
 
 fn main() -> Result<()> {
-    // Initialize logger
+    let cli = Cli::parse();
+
+    #[cfg(feature = "progress")]
+    let show_progress = cli.command.is_none() && cli.convert.progress;
+    #[cfg(not(feature = "progress"))]
+    let show_progress = false;
+
+    // Initialize logger; progress bars replace the per-file info lines
     env_logger::Builder::new()
-        .filter_level(LevelFilter::Info)
+        .filter_level(if show_progress {
+            LevelFilter::Warn
+        } else {
+            LevelFilter::Info
+        })
         .format_timestamp(None)
         .init();
 
-    let args = Args::parse();
+    let result = match &cli.command {
+        Some(Command::Convert(args)) => cli::convert::convert(args),
+        Some(Command::Head(args)) => cli::inspect::head(args),
+        Some(Command::Tail(args)) => cli::inspect::tail(args),
+        Some(Command::Sample(args)) => cli::inspect::sample(args),
+        Some(Command::Grep(args)) => cli::grep::grep(args),
+        Some(Command::Extract(args)) => cli::extract::extract(args),
+        Some(Command::Sizes(args)) => cli::sizes::sizes(args),
+        Some(Command::Histogram(args)) => cli::histogram::histogram(args),
+        Some(Command::Pid(args)) => cli::pid::pid(args),
+        Some(Command::Timeline(args)) => cli::timeline::timeline(args),
+        Some(Command::Usage(args)) => cli::usage::usage(args),
+        Some(Command::Presence(args)) => cli::presence::presence(args),
+        Some(Command::Align(args)) => cli::align::align(args),
+        Some(Command::Dump(args)) => cli::dump::dump(args),
+        Some(Command::Validate(args)) => cli::validate::validate(args),
+        Some(Command::Replay(args)) => cli::replay::replay(args),
+        Some(Command::Daemon(args)) => cli::daemon::daemon(args),
+        None => return convert_all(&cli.convert, show_progress),
+    };
 
-    let in_path = Path::new(&args.in_dir);
-    let out_path = Path::new(&args.out_root);
-
-    if !in_path.is_dir() {
-        anyhow::bail!("'{}' is not a valid directory", args.in_dir);
+    match result {
+        Err(e) if cli::is_broken_pipe(&e) => Ok(()),
+        result => result,
     }
+}
+
+fn convert_all(args: &ConvertArgs, show_progress: bool) -> Result<()> {
+    // Both are required by clap unless a subcommand was given
+    let in_dir = args.in_dir.as_deref().unwrap_or_default();
+    let out_root = args.out_root.as_deref().unwrap_or_default();
 
-    // Find all .wpilog files
-    let wpilog_files: Vec<_> = fs::read_dir(in_path)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().extension().and_then(|ext| ext.to_str()) == Some("wpilog"))
-        .collect();
+    let in_path = Path::new(in_dir);
+    let out_path = Path::new(out_root);
+
+    // Find all .wpilog files, in the directory or inside the archive
+    let wpilog_files: Vec<LogInput> = if in_path.is_dir() {
+        fs::read_dir(in_path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("wpilog"))
+            .map(LogInput::File)
+            .collect()
+    } else if in_path.is_file() {
+        LogSet::from_archive(in_path)?
+            .into_iter()
+            .map(LogInput::Archived)
+            .collect()
+    } else {
+        anyhow::bail!("'{}' is not a valid directory or archive", in_dir);
+    };
+
+    let profile = match &args.profile {
+        Some(name) => Some(ProfileSet::from_file(&args.profile_file)?.get(name)?.clone()),
+        None => None,
+    };
 
     if wpilog_files.is_empty() {
-        info!("No .wpilog files found in {}", args.in_dir);
+        info!("No .wpilog files found in {}", in_dir);
         return Ok(());
     }
 
@@ -108,21 +666,32 @@ fn main() -> Result<()> {
     info!(
         "📂 Found {} .wpilog file(s) in {}",
         wpilog_files.len(),
-        args.in_dir
+        in_dir
     );
-    info!("📁 Output directory: {}", args.out_root);
+    info!("📁 Output directory: {}", out_root);
     info!("📊 Chunk size: {} rows per file", args.chunk_size);
+    if let Some(name) = &args.profile {
+        info!("🔎 Filter profile: {}", name);
+    }
+    if let Some(sample) = args.sample {
+        info!("🎲 Sampling every {} record(s) per entry", sample.every());
+    }
+    if let Some(seconds) = args.head_duration {
+        info!("⏱  Converting the first {}s of each log", seconds);
+    }
+    if let Some(seconds) = args.tail_duration {
+        info!("⏱  Converting the last {}s of each log", seconds);
+    }
     info!("");
 
     let total_start = Instant::now();
 
+    #[cfg(feature = "progress")]
+    let ui = show_progress.then(|| ProgressUi::new(wpilog_files.len()));
+
     // Process each file
-    for (idx, entry) in wpilog_files.iter().enumerate() {
-        let input_file = entry.path();
-        let file_name = input_file
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown");
+    for (idx, input) in wpilog_files.iter().enumerate() {
+        let file_name = input.stem();
 
         info!("[{}/{}]", idx + 1, wpilog_files.len());
 
@@ -130,14 +699,35 @@ fn main() -> Result<()> {
         let output_dir = out_path.join(format!("filename={}", file_name));
         fs::create_dir_all(&output_dir)?;
 
+        let (sender, receiver) = mpsc::channel();
+        let sender = show_progress.then_some(sender);
+        #[cfg(feature = "progress")]
+        let tracker = ui.as_ref().map(|ui| ui.track_file(receiver));
+        #[cfg(not(feature = "progress"))]
+        drop(receiver);
+
         // Convert the file
-        if let Err(e) = convert_one_file(&input_file, &output_dir, args.chunk_size) {
+        let result = convert_one_file(input, &output_dir, args, profile.as_ref(), sender);
+
+        #[cfg(feature = "progress")]
+        if let (Some(ui), Some(tracker)) = (&ui, tracker) {
+            let _ = tracker.join();
+            ui.file_done(file_name, &result);
+            continue;
+        }
+
+        if let Err(e) = result {
             log::error!("   └─ ✗ Error: {}", e);
             log::error!("");
             continue;
         }
     }
 
+    #[cfg(feature = "progress")]
+    if let Some(ui) = &ui {
+        ui.finish();
+    }
+
     info!("═══════════════════════════════════════════");
     info!("🏁 All files processed in {:.2?}", total_start.elapsed());
     info!("");
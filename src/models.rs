@@ -8,8 +8,10 @@ pub enum FileFormat {
     Json,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
+    #[default]
     Wide,
     Long,
 }
@@ -19,6 +21,12 @@ pub struct DerivedSchemaColumn {
     pub name: String,
     #[serde(rename = "type")]
     pub type_name: String,
+    /// Length of a fixed-size array field, e.g. `4` for `double values[4]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array_len: Option<usize>,
+    /// Width in bits of a bit-field, e.g. `1` for `bool enabled:1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bit_width: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,7 +35,24 @@ pub struct DerivedSchema {
     pub columns: Vec<DerivedSchemaColumn>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// A primitive struct field after nested structs are expanded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedField {
+    /// Dotted path from the top-level struct (e.g., `translation.x`)
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Byte offset within the struct payload
+    pub offset: usize,
+    /// Size in bytes
+    ///
+    /// Array fields are typed and sized as the whole array (`double[4]`,
+    /// 32 bytes); bit-fields share the offset and size of the integer
+    /// holding them.
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WideRow {
     pub timestamp: f64,
     pub entry: u32,
@@ -38,9 +63,10 @@ pub struct WideRow {
     pub data: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct NestedValue {
     pub double: Option<f64>,
+    pub float: Option<f32>,
     pub int64: Option<i64>,
     pub string: Option<String>,
     pub boolean: Option<bool>,
@@ -49,12 +75,37 @@ pub struct NestedValue {
     pub float_array: Option<Vec<f32>>,
     pub int64_array: Option<Vec<i64>>,
     pub string_array: Option<Vec<String>>,
+    /// Values with no typed column: decoded structs, msgpack values and JSON
+    /// that is not an object, including nested lists
+    pub object: Option<serde_json::Value>,
+}
+
+impl NestedValue {
+    /// Value of a primitive struct field of `type_name`, as decoded into JSON.
+    pub fn struct_field(type_name: &str, value: &serde_json::Value) -> Self {
+        let mut nested = Self::default();
+        match type_name {
+            "double" => nested.double = value.as_f64(),
+            "float" | "float16" => nested.float = value.as_f64().map(|v| v as f32),
+            "int8" | "uint8" | "int16" | "uint16" | "int32" | "uint32" | "int64" | "uint64" => {
+                nested.int64 = value.as_i64()
+            }
+            "bool" => nested.boolean = value.as_bool(),
+            name if name == "char" || name.starts_with("char[") => {
+                nested.string = value.as_str().map(str::to_string)
+            }
+            _ => nested.object = Some(value.clone()).filter(|v| !v.is_null()),
+        }
+        nested
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct LongRow {
     pub timestamp: f64,
     pub entry: u32,
+    /// Name of the entry, as written to the `entry_name` column
+    pub entry_name: String,
     #[serde(rename = "type")]
     pub type_name: String,
     pub json: Option<HashMap<String, serde_json::Value>>,
@@ -83,20 +134,37 @@ impl LongRow {
         Self {
             timestamp,
             entry,
+            entry_name: String::new(),
             type_name,
             json: None,
-            value: Some(NestedValue {
-                double: None,
-                int64: None,
-                string: None,
-                boolean: None,
-                boolean_array: None,
-                double_array: None,
-                float_array: None,
-                int64_array: None,
-                string_array: None,
-            }),
+            value: Some(NestedValue::default()),
             loop_count,
         }
     }
+
+    /// Split a decoded struct value into one row per primitive field, named
+    /// `entry_name/field` and typed after the field.
+    ///
+    /// `fields` is the struct's [resolved schema](crate::formatter::Formatter::resolved_schema).
+    /// Rows without a struct value are returned as they are.
+    pub fn split_struct_fields(&self, fields: &[ResolvedField]) -> Vec<LongRow> {
+        let Some(serde_json::Value::Object(object)) = self.value.as_ref().and_then(|v| v.object.as_ref()) else {
+            return vec![self.clone()];
+        };
+        fields
+            .iter()
+            .map(|field| {
+                let value = object.get(&field.name).unwrap_or(&serde_json::Value::Null);
+                LongRow {
+                    timestamp: self.timestamp,
+                    entry: self.entry,
+                    entry_name: format!("{}/{}", self.entry_name, field.name),
+                    type_name: field.type_name.clone(),
+                    json: None,
+                    value: Some(NestedValue::struct_field(&field.type_name, value)),
+                    loop_count: self.loop_count,
+                }
+            })
+            .collect()
+    }
 }
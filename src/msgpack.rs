@@ -0,0 +1,172 @@
+//! Typed columns for msgpack entries.
+//!
+//! Vendor telemetry is often logged as msgpack maps with the same keys in
+//! every record. [`MsgpackSchema`] samples an entry's payloads during the
+//! schema pass; if every sample is a flat map with the same keys and
+//! consistently typed scalar values, the data pass writes each key to its own
+//! typed `entry/key` column. Irregular payloads fall back to JSON strings.
+
+use crate::models::WideRow;
+use rmpv::Value;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Type of a synthesized msgpack column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Boolean,
+    Integer,
+    Float,
+    String,
+}
+
+impl ColumnKind {
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Boolean(_) => Some(ColumnKind::Boolean),
+            Value::Integer(_) => Some(ColumnKind::Integer),
+            Value::F32(_) | Value::F64(_) => Some(ColumnKind::Float),
+            Value::String(_) => Some(ColumnKind::String),
+            _ => None,
+        }
+    }
+
+    /// Kind covering both `self` and `other`; integers widen to floats.
+    fn merge(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (a, b) if a == b => Some(a),
+            (ColumnKind::Integer, ColumnKind::Float) | (ColumnKind::Float, ColumnKind::Integer) => {
+                Some(ColumnKind::Float)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Pseudo-schema derived from sampled msgpack payloads of one entry.
+#[derive(Debug, Clone, Default)]
+pub struct MsgpackSchema {
+    samples: usize,
+    fields: BTreeMap<String, ColumnKind>,
+    irregular: bool,
+}
+
+impl MsgpackSchema {
+    /// Number of payloads sampled so far.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Add a sampled payload to the schema.
+    pub fn observe(&mut self, value: &Value) {
+        let first = self.samples == 0;
+        self.samples += 1;
+        if self.irregular {
+            return;
+        }
+        match flat_fields(value) {
+            Some(fields) if first => self.fields = fields,
+            Some(fields) if fields.len() == self.fields.len() => {
+                for (key, kind) in fields {
+                    match self.fields.get_mut(&key) {
+                        Some(known) => match known.merge(kind) {
+                            Some(merged) => *known = merged,
+                            None => self.irregular = true,
+                        },
+                        None => self.irregular = true,
+                    }
+                }
+            }
+            _ => self.irregular = true,
+        }
+    }
+
+    /// Column types by key, if every sample had the same keys and types.
+    pub fn fields(&self) -> Option<&BTreeMap<String, ColumnKind>> {
+        (self.samples > 0 && !self.irregular && !self.fields.is_empty()).then_some(&self.fields)
+    }
+
+    /// Write `value` into `entry/key` columns of `row`. Returns `false`, leaving
+    /// `row` untouched, if the schema is irregular or `value` does not fit it.
+    pub fn flatten_into(&self, row: &mut WideRow, column: &str, value: &Value) -> bool {
+        let Some(fields) = self.fields() else {
+            return false;
+        };
+        let Some(map) = value.as_map() else {
+            return false;
+        };
+        if map.len() != fields.len() {
+            return false;
+        }
+
+        let mut cells = Vec::with_capacity(map.len());
+        for (key, value) in map {
+            let Some(kind) = key.as_str().and_then(|key| fields.get(key)) else {
+                return false;
+            };
+            let cell = match (kind, value) {
+                (ColumnKind::Boolean, Value::Boolean(b)) => json!(b),
+                (ColumnKind::Integer, Value::Integer(i)) => match i.as_i64() {
+                    Some(i) => json!(i),
+                    None => return false,
+                },
+                (ColumnKind::Float, Value::Integer(_) | Value::F32(_) | Value::F64(_)) => {
+                    match value.as_f64().or_else(|| value.as_i64().map(|i| i as f64)) {
+                        Some(f) => json!(f),
+                        None => return false,
+                    }
+                }
+                (ColumnKind::String, Value::String(s)) => match s.as_str() {
+                    Some(s) => json!(s),
+                    None => return false,
+                },
+                _ => return false,
+            };
+            cells.push((format!("{}/{}", column, key.as_str().unwrap_or_default()), cell));
+        }
+        for (column, cell) in cells {
+            row.insert(column, cell);
+        }
+        true
+    }
+}
+
+/// Key -> kind of a map whose keys are strings and values are scalars.
+fn flat_fields(value: &Value) -> Option<BTreeMap<String, ColumnKind>> {
+    value
+        .as_map()?
+        .iter()
+        .map(|(key, value)| Some((key.as_str()?.to_string(), ColumnKind::of(value)?)))
+        .collect()
+}
+
+/// Convert a msgpack value to JSON. Binary payloads become arrays of bytes,
+/// map keys are stringified and extension values become null.
+pub fn msgpack_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Nil | Value::Ext(..) => serde_json::Value::Null,
+        Value::Boolean(b) => json!(b),
+        Value::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(i), _) => json!(i),
+            (None, Some(u)) => json!(u),
+            _ => serde_json::Value::Null,
+        },
+        Value::F32(f) => json!(f),
+        Value::F64(f) => json!(f),
+        Value::String(s) => json!(String::from_utf8_lossy(s.as_bytes())),
+        Value::Binary(bytes) => json!(bytes),
+        Value::Array(items) => serde_json::Value::Array(items.iter().map(msgpack_to_json).collect()),
+        Value::Map(entries) => serde_json::Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = match key.as_str() {
+                        Some(key) => key.to_string(),
+                        None => msgpack_to_json(key).to_string(),
+                    };
+                    (key, msgpack_to_json(value))
+                })
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,264 @@
+//! Republishing replayed logs to a NetworkTables 4 server.
+//!
+//! [`Nt4Publisher`] is a [`RecordVisitor`] that announces every entry as an NT4
+//! topic and forwards its values, so dashboards such as Shuffleboard or
+//! AdvantageScope's live mode can show a recorded match through their normal
+//! NetworkTables connection. Drive it with [`Replayer::visit`](crate::Replayer::visit)
+//! to publish at the logged pace.
+//!
+//! Values are stamped with the server's current time, so a replay looks like
+//! live data. Entries recorded from NetworkTables by `DataLogManager` (`NT:`
+//! prefix) are published under their original topic name.
+//!
+//! Requires the `nt4` feature.
+
+use crate::datalog::{DataLogRecord, RecordVisitor, StartRecordData};
+use crate::entry_type::{DecodedValue, EntryType};
+use crate::error::{Error, Result};
+use rmpv::Value;
+use serde_json::json;
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::HeaderValue;
+use tungstenite::{Message, WebSocket};
+
+/// Default NT4 server port.
+pub const NT4_PORT: u16 = 5810;
+
+/// How often incoming messages are drained so the server's pings are answered.
+const DRAIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Publishes log entries as NT4 topics.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::nt4::Nt4Publisher;
+/// use wpilog_parser::{Replayer, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("match.wpilog")?;
+/// let mut publisher = Nt4Publisher::connect("localhost", "wpilog-replay")?;
+/// Replayer::new(&reader).visit(&mut publisher)?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub struct Nt4Publisher {
+    socket: WebSocket<TcpStream>,
+    /// Local clock used for time synchronization
+    clock: Instant,
+    /// Server time minus local time (microseconds)
+    server_offset: i64,
+    /// Log entry ID -> publisher ID
+    pubuids: HashMap<u32, i64>,
+    next_pubuid: i64,
+    last_drain: Instant,
+}
+
+impl Nt4Publisher {
+    /// Connect to the NT4 server on `host` (port 5810) as `client_name` and
+    /// synchronize clocks with it.
+    pub fn connect(host: &str, client_name: &str) -> Result<Self> {
+        Self::connect_to_port(host, NT4_PORT, client_name)
+    }
+
+    /// Like [`connect`](Self::connect), for a server listening on `port`
+    /// (e.g. a simulator or a test server) instead of [`NT4_PORT`].
+    pub fn connect_to_port(host: &str, port: u16, client_name: &str) -> Result<Self> {
+        let stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        stream.set_nodelay(true)?;
+
+        let mut request = format!("ws://{}:{}/nt/{}", host, port, client_name)
+            .into_client_request()
+            .map_err(nt4_error)?;
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_static("v4.1.networktables.first.wpi.edu, networktables.first.wpi.edu"),
+        );
+        let (socket, _) = tungstenite::client::client(request, stream)
+            .map_err(|e| Error::OutputError(format!("NT4 handshake failed: {}", e)))?;
+
+        let mut publisher = Self {
+            socket,
+            clock: Instant::now(),
+            server_offset: 0,
+            pubuids: HashMap::new(),
+            next_pubuid: 1,
+            last_drain: Instant::now(),
+        };
+        publisher.sync_time()?;
+        publisher
+            .socket
+            .get_ref()
+            .set_read_timeout(Some(Duration::from_millis(1)))?;
+        Ok(publisher)
+    }
+
+    fn local_micros(&self) -> i64 {
+        self.clock.elapsed().as_micros() as i64
+    }
+
+    /// Measure the server clock offset with an NT4 time-sync round trip.
+    fn sync_time(&mut self) -> Result<()> {
+        let sent = self.local_micros();
+        self.send_value(-1, 0, 2, Value::from(sent))?;
+
+        loop {
+            let Message::Binary(frame) = self.socket.read().map_err(nt4_error)? else {
+                continue;
+            };
+            let mut bytes = &frame[..];
+            while !bytes.is_empty() {
+                let message = rmpv::decode::read_value(&mut bytes)
+                    .map_err(|e| Error::ParseError(format!("Invalid NT4 message: {}", e)))?;
+                let Some([id, server_time, ..]) = message.as_array().map(Vec::as_slice) else {
+                    continue;
+                };
+                if id.as_i64() == Some(-1) {
+                    let server_time = server_time.as_i64().unwrap_or(0);
+                    let received = self.local_micros();
+                    // Assume the reply took half the round trip
+                    self.server_offset = server_time + (received - sent) / 2 - received;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn send_text(&mut self, messages: serde_json::Value) -> Result<()> {
+        self.socket
+            .send(Message::Text(messages.to_string().into()))
+            .map_err(nt4_error)
+    }
+
+    fn send_value(&mut self, pubuid: i64, timestamp: i64, type_id: u8, value: Value) -> Result<()> {
+        let message = Value::Array(vec![
+            Value::from(pubuid),
+            Value::from(timestamp),
+            Value::from(type_id),
+            value,
+        ]);
+        let mut frame = Vec::new();
+        rmpv::encode::write_value(&mut frame, &message)
+            .map_err(|e| Error::OutputError(format!("NT4 encode failed: {}", e)))?;
+        self.socket
+            .send(Message::Binary(frame.into()))
+            .map_err(nt4_error)
+    }
+
+    /// Read and discard pending server messages; tungstenite answers pings
+    /// while reading.
+    fn drain(&mut self) -> Result<()> {
+        if self.last_drain.elapsed() < DRAIN_INTERVAL {
+            return Ok(());
+        }
+        self.last_drain = Instant::now();
+        loop {
+            match self.socket.read() {
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+                {
+                    return Ok(());
+                }
+                Err(e) => return Err(nt4_error(e)),
+            }
+        }
+    }
+}
+
+impl RecordVisitor for Nt4Publisher {
+    fn on_start(&mut self, start: &StartRecordData, _timestamp: u64) -> Result<()> {
+        let pubuid = self.next_pubuid;
+        self.next_pubuid += 1;
+        if let Some(old) = self.pubuids.insert(start.entry, pubuid) {
+            self.send_text(json!([{"method": "unpublish", "params": {"pubuid": old}}]))?;
+        }
+
+        let name = start.name.strip_prefix("NT:").unwrap_or(&start.name);
+        self.send_text(json!([{
+            "method": "publish",
+            "params": {
+                "name": name,
+                "pubuid": pubuid,
+                "type": start.type_name,
+                "properties": {},
+            },
+        }]))
+    }
+
+    fn on_finish(&mut self, entry: u32, _timestamp: u64) -> Result<()> {
+        match self.pubuids.remove(&entry) {
+            Some(pubuid) => self.send_text(json!([{"method": "unpublish", "params": {"pubuid": pubuid}}])),
+            None => Ok(()),
+        }
+    }
+
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        self.drain()?;
+        let Some(&pubuid) = self.pubuids.get(&entry.entry) else {
+            return Ok(());
+        };
+        let (type_id, value) = match nt4_value(entry, timestamp, payload) {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("Not republishing a value of '{}': {}", entry.name, e);
+                return Ok(());
+            }
+        };
+        let now = self.local_micros() + self.server_offset;
+        self.send_value(pubuid, now, type_id, value)
+    }
+}
+
+/// NT4 data type ID and MessagePack value for a log payload.
+fn nt4_value(entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<(u8, Value)> {
+    let type_id = match entry.entry_type {
+        EntryType::Boolean => 0,
+        EntryType::Double => 1,
+        EntryType::Int64 => 2,
+        EntryType::Float => 3,
+        EntryType::String | EntryType::Json => 4,
+        EntryType::BooleanArray => 16,
+        EntryType::DoubleArray => 17,
+        EntryType::Int64Array => 18,
+        EntryType::FloatArray => 19,
+        EntryType::StringArray => 20,
+        // Everything else is sent as raw bytes under its declared type string
+        EntryType::Msgpack
+        | EntryType::StructSchema
+        | EntryType::Struct(_)
+        | EntryType::StructArray(_)
+        | EntryType::Proto(_)
+        | EntryType::Raw(_) => return Ok((5, Value::Binary(payload.to_vec()))),
+    };
+
+    let record = DataLogRecord {
+        entry: entry.entry,
+        timestamp,
+        data: payload.to_vec(),
+    };
+    let value = match record.decode_as(&entry.entry_type)? {
+        DecodedValue::Boolean(v) => Value::from(v),
+        DecodedValue::Int64(v) => Value::from(v),
+        DecodedValue::Float(v) => Value::from(v),
+        DecodedValue::Double(v) => Value::from(v),
+        DecodedValue::String(v) => Value::from(v),
+        DecodedValue::BooleanArray(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+        DecodedValue::Int64Array(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+        DecodedValue::FloatArray(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+        DecodedValue::DoubleArray(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+        DecodedValue::StringArray(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+        DecodedValue::Msgpack(v) => v,
+        DecodedValue::Raw(v) => Value::Binary(v),
+    };
+    Ok((type_id, value))
+}
+
+fn nt4_error(e: tungstenite::Error) -> Error {
+    match e {
+        tungstenite::Error::Io(e) => Error::Io(e),
+        e => Error::OutputError(format!("NT4: {}", e)),
+    }
+}
@@ -0,0 +1,166 @@
+//! Time-aligned ("pivoted") wide tables.
+//!
+//! The reader produces one sparse [`WideRow`] per record. [`Pivot`] merges those
+//! into one row per distinct timestamp and fills the gaps for each entry
+//! according to a [`FillPolicy`].
+
+use crate::models::WideRow;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// Entry ID used for pivoted rows, which merge values from several entries.
+pub const PIVOT_ENTRY: u32 = 0;
+
+/// Type name used for pivoted rows.
+pub const PIVOT_TYPE: &str = "pivot";
+
+/// How to fill an entry's cell at timestamps where it was not logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillPolicy {
+    /// Leave the cell empty
+    #[default]
+    Null,
+    /// Repeat the most recent value (subject to the forward-fill horizon)
+    ForwardFill,
+    /// Use a zero value for the column type (`0`, `false`, `""`, `[]`)
+    Zero,
+}
+
+/// Builds a time-aligned wide table from sparse per-record rows.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{FillPolicy, Pivot, WpilogReader};
+///
+/// let records = WpilogReader::from_file("data.wpilog")?.read_all()?;
+///
+/// let table = Pivot::new()
+///     .fill(FillPolicy::ForwardFill)
+///     .max_forward_fill(0.5)
+///     .entry_fill("/Robot/Enabled", FillPolicy::Zero)
+///     .apply(&records);
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Pivot {
+    default_fill: FillPolicy,
+    entry_fill: HashMap<String, FillPolicy>,
+    max_forward_fill: Option<f64>,
+}
+
+impl Pivot {
+    /// Create a pivot that leaves missing cells empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fill policy for entries without an explicit override.
+    pub fn fill(mut self, policy: FillPolicy) -> Self {
+        self.default_fill = policy;
+        self
+    }
+
+    /// Override the fill policy for a single entry.
+    pub fn entry_fill(mut self, entry: impl Into<String>, policy: FillPolicy) -> Self {
+        self.entry_fill.insert(entry.into(), policy);
+        self
+    }
+
+    /// Stop forward-filling a value once it is older than `seconds`.
+    ///
+    /// By default forward-filled values never expire.
+    pub fn max_forward_fill(mut self, seconds: f64) -> Self {
+        self.max_forward_fill = Some(seconds);
+        self
+    }
+
+    /// Fill policy in effect for `entry`.
+    pub fn policy_for(&self, entry: &str) -> FillPolicy {
+        self.entry_fill
+            .get(entry)
+            .copied()
+            .unwrap_or(self.default_fill)
+    }
+
+    /// Merge `records` into one row per distinct timestamp, in time order.
+    ///
+    /// Pivoted rows use [`PIVOT_ENTRY`] and [`PIVOT_TYPE`] for their entry and
+    /// type, and carry the loop count of the last record merged into them.
+    pub fn apply(&self, records: &[WideRow]) -> Vec<WideRow> {
+        let mut sorted: Vec<&WideRow> = records.iter().collect();
+        sorted.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+
+        let columns: BTreeSet<&String> = records.iter().flat_map(|r| r.data.keys()).collect();
+        let mut zero_values: HashMap<&str, Value> = HashMap::new();
+        for record in records {
+            for (column, value) in &record.data {
+                if !zero_values.contains_key(column.as_str()) {
+                    if let Some(zero) = zero_value(value) {
+                        zero_values.insert(column, zero);
+                    }
+                }
+            }
+        }
+
+        let mut rows: Vec<WideRow> = Vec::new();
+        // Column -> (timestamp, value) of the last logged sample
+        let mut last_seen: HashMap<&str, (f64, &Value)> = HashMap::new();
+
+        let mut i = 0;
+        while i < sorted.len() {
+            let timestamp = sorted[i].timestamp;
+            let mut row = WideRow::new(timestamp, PIVOT_ENTRY, PIVOT_TYPE.to_string(), 0);
+
+            while i < sorted.len() && sorted[i].timestamp == timestamp {
+                let record = sorted[i];
+                row.loop_count = record.loop_count;
+                for (column, value) in &record.data {
+                    row.insert(column.clone(), value.clone());
+                    last_seen.insert(column, (timestamp, value));
+                }
+                i += 1;
+            }
+
+            for column in &columns {
+                if row.data.contains_key(column.as_str()) {
+                    continue;
+                }
+                match self.policy_for(column) {
+                    FillPolicy::Null => {}
+                    FillPolicy::ForwardFill => {
+                        if let Some(&(seen_at, value)) = last_seen.get(column.as_str()) {
+                            let fresh = self
+                                .max_forward_fill
+                                .is_none_or(|horizon| timestamp - seen_at <= horizon);
+                            if fresh {
+                                row.insert((*column).clone(), value.clone());
+                            }
+                        }
+                    }
+                    FillPolicy::Zero => {
+                        if let Some(zero) = zero_values.get(column.as_str()) {
+                            row.insert((*column).clone(), zero.clone());
+                        }
+                    }
+                }
+            }
+
+            rows.push(row);
+        }
+
+        rows
+    }
+}
+
+/// Zero value matching the JSON type of `value`, if it has one.
+fn zero_value(value: &Value) -> Option<Value> {
+    match value {
+        Value::Bool(_) => Some(Value::Bool(false)),
+        Value::Number(n) if n.is_f64() => Some(serde_json::json!(0.0)),
+        Value::Number(_) => Some(serde_json::json!(0)),
+        Value::String(_) => Some(Value::String(String::new())),
+        Value::Array(_) => Some(Value::Array(Vec::new())),
+        Value::Object(_) | Value::Null => None,
+    }
+}
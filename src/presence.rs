@@ -0,0 +1,174 @@
+//! Which entries each log of a season contains.
+//!
+//! [`PresenceMatrix`] counts the data records of every entry name in every
+//! log, so analysis notebooks can be checked against renamed, added and
+//! removed signals as robot code changes. Only record headers are read (see
+//! [`QuickScan`](crate::QuickScan)), so a season of logs is scanned quickly.
+
+use crate::error::{Error, Result};
+use crate::reader::WpilogReader;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Record counts of entry names (rows) across logs (columns).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PresenceMatrix {
+    /// Log file names, in column order
+    pub logs: Vec<String>,
+    /// Entry names, sorted, in row order
+    pub entries: Vec<String>,
+    /// `counts[entry][log]`: data records of the entry in the log
+    pub counts: Vec<Vec<u64>>,
+}
+
+/// An entry appearing in or disappearing from the logs, in log order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PresenceChange {
+    pub entry: String,
+    /// Log the change is first seen in
+    pub log: String,
+    /// `true` if the entry appeared, `false` if it disappeared
+    pub appeared: bool,
+}
+
+impl PresenceMatrix {
+    /// Scan every `.wpilog` file in `dir`, ordered by file name (which is
+    /// chronological for WPILib's `FRC_<date>_<time>.wpilog` names).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::PresenceMatrix;
+    ///
+    /// let matrix = PresenceMatrix::from_directory("/data/season-logs")?;
+    /// for change in matrix.changes() {
+    ///     let verb = if change.appeared { "appeared" } else { "disappeared" };
+    ///     println!("{} {} in {}", change.entry, verb, change.log);
+    /// }
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn from_directory<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(Error::InvalidFormat(format!(
+                "'{}' is not a valid directory",
+                dir.display()
+            )));
+        }
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("wpilog"))
+            .collect();
+        paths.sort();
+        Self::from_files(paths)
+    }
+
+    /// Scan the given logs, in the order given.
+    ///
+    /// Logs that cannot be read are skipped with a warning.
+    pub fn from_files<I, P>(paths: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut scans: Vec<(String, BTreeMap<String, u64>)> = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            let file = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string());
+            match WpilogReader::from_file(path).and_then(|reader| reader.quick_scan()) {
+                Ok(scan) => scans.push((file, scan.entry_counts)),
+                Err(e) => log::warn!("Skipping {} in presence matrix: {}", file, e),
+            }
+        }
+
+        let entries: BTreeSet<&String> = scans.iter().flat_map(|(_, counts)| counts.keys()).collect();
+        let counts = entries
+            .iter()
+            .map(|entry| {
+                scans
+                    .iter()
+                    .map(|(_, counts)| counts.get(*entry).copied().unwrap_or(0))
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            entries: entries.into_iter().cloned().collect(),
+            counts,
+            logs: scans.into_iter().map(|(file, _)| file).collect(),
+        })
+    }
+
+    /// Records of `entry` per log, or `None` if no log contains it.
+    pub fn row(&self, entry: &str) -> Option<&[u64]> {
+        let index = self.entries.binary_search_by(|e| e.as_str().cmp(entry)).ok()?;
+        Some(&self.counts[index])
+    }
+
+    /// Entries present in every log.
+    pub fn common_entries(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .zip(&self.counts)
+            .filter(|(_, row)| row.iter().all(|&count| count > 0))
+            .map(|(entry, _)| entry.as_str())
+            .collect()
+    }
+
+    /// Every time an entry went from absent to present or back between
+    /// consecutive logs, by log then entry. An entry present in the first log
+    /// is not reported as appearing.
+    pub fn changes(&self) -> Vec<PresenceChange> {
+        let mut changes = Vec::new();
+        for (log_index, log) in self.logs.iter().enumerate().skip(1) {
+            for (entry, row) in self.entries.iter().zip(&self.counts) {
+                let before = row[log_index - 1] > 0;
+                let now = row[log_index] > 0;
+                if before != now {
+                    changes.push(PresenceChange {
+                        entry: entry.clone(),
+                        log: log.clone(),
+                        appeared: now,
+                    });
+                }
+            }
+        }
+        changes
+    }
+
+    /// Write the matrix as CSV: an `entry` column followed by one record
+    /// count column per log.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> Result<()> {
+        write!(out, "entry")?;
+        for log in &self.logs {
+            write!(out, ",{}", csv_quote(log))?;
+        }
+        writeln!(out)?;
+        for (entry, row) in self.entries.iter().zip(&self.counts) {
+            write!(out, "{}", csv_quote(entry))?;
+            for count in row {
+                write!(out, ",{}", count)?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+fn csv_quote(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,202 @@
+//! Named filter profiles.
+//!
+//! Teams usually look at the same slices of their logs over and over: drive
+//! signals for tuning, vision outputs for debugging. A [`FilterProfile`] names
+//! such a slice (entry selectors, downsampling and output format) and a
+//! [`ProfileSet`] stores several of them in a JSON config file:
+//!
+//! ```json
+//! {
+//!   "drive-analysis": {
+//!     "include": ["/Drive/*", "/Robot/BatteryVoltage"],
+//!     "exclude": ["/Drive/*/Debug*"],
+//!     "max_rate_hz": 50
+//!   },
+//!   "vision-debug": { "include": ["/Vision/*"], "output_format": "long" }
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::models::OutputFormat;
+use crate::reduce::Downsample;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Default profile config file name, looked up in the working directory by the CLI.
+pub const DEFAULT_PROFILE_FILE: &str = "wpilog-profiles.json";
+
+/// Include/exclude selectors for entry names.
+///
+/// Selectors are globs matched against the whole entry name as logged: `*`
+/// matches any run of characters (including `/`) and `?` matches one
+/// character. An entry is selected if it matches any include selector (or
+/// there are none) and no exclude selector. Prefix and regex selectors can be
+/// given as [`EntryPattern`]s.
+#[derive(Debug, Clone)]
+pub struct EntrySelector {
+    /// Groups of include selectors; a name must match one selector of each
+    include: Vec<Vec<Regex>>,
+    exclude: Vec<Regex>,
+}
+
+impl EntrySelector {
+    /// Compile include and exclude globs.
+    pub fn new<S: AsRef<str>>(include: &[S], exclude: &[S]) -> Result<Self> {
+        let globs = |globs: &[S]| -> Vec<EntryPattern> {
+            globs.iter().map(|glob| EntryPattern::Glob(glob.as_ref().to_string())).collect()
+        };
+        Self::from_patterns(&globs(include), &globs(exclude))
+    }
+
+    /// Compile include and exclude patterns of any kind.
+    pub fn from_patterns(include: &[EntryPattern], exclude: &[EntryPattern]) -> Result<Self> {
+        let include: Vec<Regex> = include.iter().map(EntryPattern::regex).collect::<Result<_>>()?;
+        Ok(Self {
+            include: if include.is_empty() { Vec::new() } else { vec![include] },
+            exclude: exclude.iter().map(EntryPattern::regex).collect::<Result<_>>()?,
+        })
+    }
+
+    /// Select only names selected by both `self` and `other`.
+    pub fn and(mut self, other: EntrySelector) -> Self {
+        self.include.extend(other.include);
+        self.exclude.extend(other.exclude);
+        self
+    }
+
+    /// Whether the entry named `name` is selected.
+    pub fn matches(&self, name: &str) -> bool {
+        self.include.iter().all(|group| group.iter().any(|re| re.is_match(name)))
+            && !self.exclude.iter().any(|re| re.is_match(name))
+    }
+}
+
+/// One entry-name selector for an [`EntrySelector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryPattern {
+    /// Names starting with this string, e.g. `/Drive/`
+    Prefix(String),
+    /// A glob matched against the whole name, as in [`EntrySelector::new`]
+    Glob(String),
+    /// A regular expression matched anywhere in the name, unless anchored
+    /// with `^`/`$`
+    Regex(String),
+}
+
+impl EntryPattern {
+    fn regex(&self) -> Result<Regex> {
+        match self {
+            Self::Prefix(prefix) => Ok(Regex::new(&format!("^{}", regex::escape(prefix)))
+                .expect("escaped prefix is a valid regex")),
+            Self::Glob(glob) => glob_regex(glob),
+            Self::Regex(pattern) => Regex::new(pattern)
+                .map_err(|e| Error::Other(format!("Invalid entry regex '{}': {}", pattern, e))),
+        }
+    }
+}
+
+fn glob_regex(glob: &str) -> Result<Regex> {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern)
+        .map_err(|e| Error::Other(format!("Invalid entry selector '{}': {}", glob, e)))
+}
+
+/// A named slice of a log: which entries to read, how densely, and in which
+/// output format.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterProfile {
+    /// Entry globs to keep (all entries when empty)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Entry globs to drop, applied after `include`
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Keep at most this many samples per second of each column
+    #[serde(default)]
+    pub max_rate_hz: Option<f64>,
+    /// Output format to read into
+    #[serde(default)]
+    pub output_format: Option<OutputFormat>,
+}
+
+impl FilterProfile {
+    /// Compile the profile's entry selectors. `None` if it selects everything.
+    pub fn selector(&self) -> Result<Option<EntrySelector>> {
+        if self.include.is_empty() && self.exclude.is_empty() {
+            return Ok(None);
+        }
+        EntrySelector::new(&self.include, &self.exclude).map(Some)
+    }
+
+    /// The profile's downsampling, if any.
+    pub fn downsample(&self) -> Option<Downsample> {
+        self.max_rate_hz.map(Downsample::max_rate)
+    }
+}
+
+/// Filter profiles by name, as stored in a JSON config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProfileSet {
+    profiles: BTreeMap<String, FilterProfile>,
+}
+
+impl ProfileSet {
+    /// Load profiles from a JSON config file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Parse profiles from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let profiles: Self = serde_json::from_str(json)?;
+        for (name, profile) in &profiles.profiles {
+            if profile.max_rate_hz.is_some_and(|hz| hz.is_nan() || hz <= 0.0) {
+                return Err(Error::Other(format!(
+                    "Profile '{}': max_rate_hz must be positive",
+                    name
+                )));
+            }
+            profile.selector()?;
+        }
+        Ok(profiles)
+    }
+
+    /// Save profiles as pretty-printed JSON.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Add or replace a profile.
+    pub fn insert(&mut self, name: impl Into<String>, profile: FilterProfile) {
+        self.profiles.insert(name.into(), profile);
+    }
+
+    /// Look up a profile by name.
+    pub fn get(&self, name: &str) -> Result<&FilterProfile> {
+        self.profiles.get(name).ok_or_else(|| {
+            Error::InvalidEntry(format!(
+                "Unknown filter profile '{}' (available: {})",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            ))
+        })
+    }
+
+    /// Profile names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+}
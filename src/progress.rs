@@ -0,0 +1,218 @@
+//! Progress reporting over channels.
+//!
+//! Long-running operations accept a [`Sender<ProgressUpdate>`](Sender) and send
+//! updates as they go, so a UI (progress bar, web dashboard, log line) can run
+//! on another thread. Updates are throttled to roughly one per percent of input,
+//! and a disconnected receiver is ignored.
+//!
+//! Reads walk the log twice, so progress is reported per [`ProgressPhase`]:
+//! each phase starts with [`ProgressUpdate::PhaseChanged`] and its byte counts
+//! run from zero to the log size.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::mpsc::Sender;
+use std::time::Instant;
+
+/// Number of progress updates sent over a full run (one per percent).
+const UPDATES_PER_RUN: u64 = 100;
+
+/// A pass over the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressPhase {
+    /// Collecting struct schemas before decoding
+    InferringSchema,
+    /// Decoding records into rows
+    DecodingRecords,
+}
+
+impl fmt::Display for ProgressPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ProgressPhase::InferringSchema => "Inferring schema",
+            ProgressPhase::DecodingRecords => "Decoding records",
+        })
+    }
+}
+
+/// A progress event sent by a long-running operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressUpdate {
+    /// Work started on `total_bytes` of input
+    Started { total_bytes: u64 },
+    /// A new pass over the input began; byte progress restarts from zero
+    PhaseChanged { phase: ProgressPhase },
+    /// The current phase reached `bytes_processed` after emitting `records` rows
+    /// out of `total_records`, when the total is known
+    Progress {
+        bytes_processed: u64,
+        total_bytes: u64,
+        records: u64,
+        total_records: Option<u64>,
+    },
+    /// Work finished after emitting `records` rows of `entries` distinct
+    /// entries, `elapsed_secs` after it started.
+    ///
+    /// The telemetry fields default to zero when deserializing updates
+    /// recorded before they were added.
+    Complete {
+        records: u64,
+        #[serde(default)]
+        entries: u64,
+        /// Bytes of input read by the last phase
+        #[serde(default)]
+        bytes_processed: u64,
+        /// Seconds since [`Started`](ProgressUpdate::Started)
+        #[serde(default)]
+        elapsed_secs: f64,
+        /// `records` per second of `elapsed_secs`
+        #[serde(default)]
+        rows_per_sec: f64,
+    },
+}
+
+impl ProgressUpdate {
+    /// Completed fraction of the current phase in `0.0..=1.0`, if this update
+    /// carries one. Record counts are used when the total is known, byte
+    /// offsets otherwise.
+    pub fn fraction(&self) -> Option<f64> {
+        match *self {
+            ProgressUpdate::Started { .. } | ProgressUpdate::PhaseChanged { .. } => Some(0.0),
+            ProgressUpdate::Progress {
+                records,
+                total_records: Some(total_records),
+                ..
+            } if total_records > 0 => Some((records as f64 / total_records as f64).min(1.0)),
+            ProgressUpdate::Progress {
+                bytes_processed,
+                total_bytes,
+                ..
+            } if total_bytes > 0 => Some((bytes_processed as f64 / total_bytes as f64).min(1.0)),
+            ProgressUpdate::Progress { .. } => None,
+            ProgressUpdate::Complete { .. } => Some(1.0),
+        }
+    }
+}
+
+/// Sends throttled [`ProgressUpdate`]s for a byte-oriented operation.
+#[derive(Debug, Clone)]
+pub struct ProgressTracker {
+    sender: Sender<ProgressUpdate>,
+    total_bytes: u64,
+    step: u64,
+    next_report: u64,
+    records: u64,
+    /// Rows the decoding pass will emit, from a header scan
+    total_records: Option<u64>,
+    /// Total reported for the current phase
+    phase_total: Option<u64>,
+    started: bool,
+    started_at: Instant,
+    bytes_processed: u64,
+    entries: u64,
+}
+
+impl ProgressTracker {
+    /// Create a tracker for `total_bytes` of input.
+    pub fn new(sender: Sender<ProgressUpdate>, total_bytes: u64) -> Self {
+        let step = (total_bytes / UPDATES_PER_RUN).max(1);
+        Self {
+            sender,
+            total_bytes,
+            step,
+            next_report: step,
+            records: 0,
+            total_records: None,
+            phase_total: None,
+            started: false,
+            started_at: Instant::now(),
+            bytes_processed: 0,
+            entries: 0,
+        }
+    }
+
+    /// Set the number of rows the [`DecodingRecords`](ProgressPhase::DecodingRecords)
+    /// phase will emit, so its updates carry a record total.
+    pub fn set_total_records(&mut self, total: u64) {
+        self.total_records = Some(total);
+    }
+
+    /// Set the number of distinct entries the work emitted rows for, sent
+    /// with [`ProgressUpdate::Complete`].
+    pub fn set_entries(&mut self, entries: u64) {
+        self.entries = entries;
+    }
+
+    /// Send [`ProgressUpdate::Started`] and start timing the work.
+    pub fn start(&mut self) {
+        self.started = true;
+        self.started_at = Instant::now();
+        self.records = 0;
+        self.bytes_processed = 0;
+        self.next_report = self.step;
+        self.send(ProgressUpdate::Started {
+            total_bytes: self.total_bytes,
+        });
+    }
+
+    /// Send [`ProgressUpdate::PhaseChanged`] and restart byte progress,
+    /// sending [`ProgressUpdate::Started`] first if this is the first phase.
+    pub fn phase(&mut self, phase: ProgressPhase) {
+        if !self.started {
+            self.start();
+        }
+        self.records = 0;
+        self.bytes_processed = 0;
+        self.next_report = self.step;
+        self.phase_total = match phase {
+            ProgressPhase::InferringSchema => None,
+            ProgressPhase::DecodingRecords => self.total_records,
+        };
+        self.send(ProgressUpdate::PhaseChanged { phase });
+    }
+
+    /// Count one emitted row, reporting if `bytes_processed` crossed the next step.
+    pub fn record(&mut self, bytes_processed: u64) {
+        self.records += 1;
+        self.advance(bytes_processed);
+    }
+
+    /// Report if `bytes_processed` crossed the next step, without counting a row.
+    pub fn advance(&mut self, bytes_processed: u64) {
+        self.bytes_processed = self.bytes_processed.max(bytes_processed);
+        if bytes_processed >= self.next_report {
+            self.next_report = bytes_processed + self.step;
+            self.send(ProgressUpdate::Progress {
+                bytes_processed,
+                total_bytes: self.total_bytes,
+                records: self.records,
+                total_records: self.phase_total,
+            });
+        }
+    }
+
+    /// Send [`ProgressUpdate::Complete`] with the time since
+    /// [`start`](Self::start), or since the tracker was created if it was
+    /// never started.
+    pub fn complete(&mut self) {
+        let elapsed_secs = self.started_at.elapsed().as_secs_f64();
+        self.send(ProgressUpdate::Complete {
+            records: self.records,
+            entries: self.entries,
+            bytes_processed: self.bytes_processed,
+            elapsed_secs,
+            rows_per_sec: if elapsed_secs > 0.0 {
+                self.records as f64 / elapsed_secs
+            } else {
+                0.0
+            },
+        });
+    }
+
+    fn send(&self, update: ProgressUpdate) {
+        // The receiver may have gone away; progress is best effort
+        let _ = self.sender.send(update);
+    }
+}
@@ -0,0 +1,103 @@
+//! Where converted rows came from, for traceability in merged datasets.
+//!
+//! [`Provenance`] records the source log's file name and SHA-256 and when it
+//! was converted. [`ParquetWriter::provenance`](crate::ParquetWriter::provenance)
+//! stamps it into every row as `source_file`, `source_sha256` and
+//! `converted_at` columns, so rows of many logs merged into one table can be
+//! traced back to the exact file they were read from.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source log of a conversion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// File name of the log, without its directory
+    pub source_file: String,
+    /// SHA-256 of the log's bytes, as lowercase hex
+    pub source_sha256: String,
+    /// When the conversion ran, as an RFC 3339 UTC timestamp
+    pub converted_at: String,
+}
+
+impl Provenance {
+    /// Hash the log at `path`, stamped with the current time.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::Provenance;
+    ///
+    /// let provenance = Provenance::of_file("logs/FRC_20250315_183201.wpilog")?;
+    /// println!("{} {}", provenance.source_file, provenance.source_sha256);
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn of_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut hasher = Sha256::new();
+        io::copy(&mut File::open(path)?, &mut hasher)?;
+        let source_file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Ok(Self::new(source_file, hasher.finalize().as_slice()))
+    }
+
+    /// Hash a log already in memory, e.g. one read from stdin, under the
+    /// given file name.
+    pub fn of_bytes(source_file: impl Into<String>, data: &[u8]) -> Self {
+        Self::new(source_file.into(), Sha256::digest(data).as_slice())
+    }
+
+    fn new(source_file: String, digest: &[u8]) -> Self {
+        let converted_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            source_file,
+            source_sha256: digest.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            converted_at: rfc3339(converted_at),
+        }
+    }
+
+    /// The provenance as `(column, value)` pairs, in column order.
+    pub fn columns(&self) -> [(&'static str, &str); 3] {
+        [
+            ("source_file", self.source_file.as_str()),
+            ("source_sha256", self.source_sha256.as_str()),
+            ("converted_at", self.converted_at.as_str()),
+        ]
+    }
+}
+
+/// Format Unix seconds as `YYYY-MM-DDTHH:MM:SSZ`.
+fn rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time / 60 % 60,
+        time % 60
+    )
+}
@@ -0,0 +1,123 @@
+//! Compatibility with off-spec logs from third-party writers.
+//!
+//! WPILib's `DataLog` writes records back to back with exact payload sizes,
+//! but some custom loggers (often Python scripts) do not. [`Quirks`] enables
+//! tolerances for those deviations one at a time. All are off by default, so
+//! an off-spec record is an error unless its quirk is enabled. When the
+//! high-level reader applies a quirk, it logs a warning saying how often.
+
+use serde::{Deserialize, Serialize};
+
+/// Tolerances for off-spec records, applied while walking record headers.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{Quirks, WpilogReaderBuilder};
+///
+/// let reader = WpilogReaderBuilder::new()
+///     .quirks(Quirks {
+///         padded_records: true,
+///         ..Quirks::default()
+///     })
+///     .from_file("python_logger.wpilog")?;
+/// let records = reader.read_all()?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quirks {
+    /// Skip zero bytes where a record header is expected, as written by
+    /// loggers that pad records to an alignment boundary. A genuine record
+    /// with a zero header byte (one-byte entry ID and size, timestamp under
+    /// 256 µs) would be skipped too, so only enable this for padded logs.
+    pub padded_records: bool,
+    /// End a record whose size field runs past the end of the log at the end
+    /// of the log, instead of reporting it as truncated.
+    pub oversized_lengths: bool,
+    /// What to do with data records whose payload is empty although their
+    /// type needs at least one byte (see [`EmptyPayloads`]).
+    pub empty_payloads: EmptyPayloads,
+    /// Replace invalid UTF-8 in `string`, `json` and `string[]` values with
+    /// U+FFFD instead of failing the read, for logs with garbage text such as
+    /// corrupted driver station messages. A warning counts the records
+    /// affected per entry.
+    #[serde(default)]
+    pub lossy_utf8: bool,
+}
+
+/// Handling of data records with an empty payload, which some loggers write
+/// for values they could not read.
+///
+/// Only types that cannot be empty are affected: `boolean`, `int64`,
+/// `float`, `double`, `msgpack` and single structs. An empty string, array
+/// or raw payload is a valid value and always read as one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyPayloads {
+    /// Fail the read with a type mismatch naming the entry
+    #[default]
+    Error,
+    /// Drop the record
+    Skip,
+    /// Keep the record as an undecoded value, written as null like the
+    /// values of raw and protobuf entries
+    Raw,
+}
+
+impl Quirks {
+    /// Every quirk enabled.
+    pub fn all() -> Self {
+        Self {
+            padded_records: true,
+            oversized_lengths: true,
+            empty_payloads: EmptyPayloads::Skip,
+            lossy_utf8: true,
+        }
+    }
+}
+
+/// How often each quirk was applied while walking a log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuirkReport {
+    /// Zero bytes skipped before records
+    pub padding_bytes: u64,
+    /// Records whose size field ran past the end of the log
+    pub oversized_records: u64,
+    /// Data records with empty payloads skipped or kept raw because of
+    /// [`Quirks::empty_payloads`]
+    #[serde(default)]
+    pub empty_payloads: u64,
+    /// Control records with empty payloads, which are ignored. Zero padding
+    /// read without [`Quirks::padded_records`] parses as these.
+    #[serde(default)]
+    pub empty_control_records: u64,
+}
+
+impl QuirkReport {
+    /// Whether no quirk was applied.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Log a warning for each quirk that was applied.
+    pub fn warn(&self) {
+        if self.padding_bytes > 0 {
+            log::warn!("Skipped {} padding bytes between records", self.padding_bytes);
+        }
+        if self.oversized_records > 0 {
+            log::warn!(
+                "Cut {} records with sizes past the end of the log short",
+                self.oversized_records
+            );
+        }
+        if self.empty_payloads > 0 {
+            log::warn!("Read {} data records with empty payloads", self.empty_payloads);
+        }
+        if self.empty_control_records > 0 {
+            log::warn!(
+                "Ignored {} empty control records; if the log is padded, enable the padded_records quirk",
+                self.empty_control_records
+            );
+        }
+    }
+}
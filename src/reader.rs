@@ -1,15 +1,39 @@
 //! High-level API for reading WPILog files.
 
-use crate::datalog::DataLogReader;
+use crate::blobs::{Blob, BlobCollector};
+use crate::catalog::EntryCatalog;
+use crate::cursor::LogCursor;
+use crate::datalog::{read_record_header, DataLogReader, DataLogRecord};
 use crate::error::{Error, Result};
-use crate::formatter::Formatter;
-use crate::models::{OutputFormat, WideRow};
-use std::fs::File;
+use crate::formats::parquet::read_parquet_directory;
+use crate::formatter::{Formatter, LogPass};
+use crate::index::{QuickScan, RecordIndex};
+use crate::models::{LongRow, OutputFormat, WideRow};
+use crate::pivot::Pivot;
+use crate::profile::{EntryPattern, EntrySelector, FilterProfile};
+use crate::progress::{ProgressTracker, ProgressUpdate};
+use crate::record_error::{RecordAction, RecordError, RecordErrorHandler};
+use crate::quirks::Quirks;
+use crate::record_id::{log_hash, RecordId};
+use crate::reduce::{Downsample, Subsample};
+use crate::scan::Scan;
+use crate::series::TimeSeries;
+use crate::spill::SpillStore;
+use crate::summary::LogSummary;
+use crate::timeline::CommandTimeline;
+use crate::usage::{first_enabled, UsageSummary};
+use memmap2::Mmap;
+use serde::de::DeserializeOwned;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
 use std::io::Read;
+use std::ops::{Deref, Range};
 use std::path::Path;
-use std::sync::atomic::{AtomicU64, Ordering};
-
-static GLOBAL_LOOP_COUNT: AtomicU64 = AtomicU64::new(0);
+use std::str::FromStr;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// A reader for WPILog files that provides a high-level API for parsing.
 ///
@@ -24,13 +48,158 @@ static GLOBAL_LOOP_COUNT: AtomicU64 = AtomicU64::new(0);
 /// # Ok::<(), wpilog_parser::Error>(())
 /// ```
 pub struct WpilogReader {
-    data: Vec<u8>,
+    data: LogData,
     formatter: Option<Formatter>,
+    options: ReadOptions,
+}
+
+/// Bytes of a log, mapped from its file or held in memory.
+enum LogData {
+    Owned(Vec<u8>),
+    Shared(Arc<[u8]>),
+    Static(&'static [u8]),
+    Mapped(Mmap),
+}
+
+impl LogData {
+    /// Map the file at `path`, or read it into memory where mapping fails,
+    /// e.g. for empty files or on file systems without mmap support.
+    fn open(path: &Path) -> Result<Self> {
+        let mut file = File::open(path)?;
+        // SAFETY: the map is read-only. `from_file` documents that the file
+        // must not be truncated or rewritten while the reader is alive; logs
+        // that are still being written are read with `from_file_unmapped`.
+        match unsafe { Mmap::map(&file) } {
+            Ok(mmap) => Ok(LogData::Mapped(mmap)),
+            Err(e) => {
+                log::debug!("Reading {} into memory, mmap failed: {}", path.display(), e);
+                let mut data = Vec::new();
+                file.read_to_end(&mut data)?;
+                Ok(LogData::Owned(data))
+            }
+        }
+    }
+
+    /// Read the file at `path` into memory.
+    fn read(path: &Path) -> Result<Self> {
+        Ok(LogData::Owned(fs::read(path)?))
+    }
+}
+
+impl Deref for LogData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            LogData::Owned(data) => data,
+            LogData::Shared(data) => data,
+            LogData::Static(data) => data,
+            LogData::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+/// Parsing options applied by [`WpilogReader`] when decoding records.
+#[derive(Debug, Clone, Default)]
+struct ReadOptions {
+    output_format: OutputFormat,
+    generation_columns: bool,
+    alias_columns: bool,
+    pivot: Option<Pivot>,
+    progress: Option<Sender<ProgressUpdate>>,
+    buffer_orphans: bool,
+    profile: Option<FilterProfile>,
+    json_depth: Option<usize>,
+    msgpack_sample: Option<usize>,
+    quirks: Quirks,
+    deny_unsupported_types: bool,
+    exclude_undecoded: bool,
+    subsample: Option<Subsample>,
+    window: Option<Window>,
+    time_origin: Option<TimeOrigin>,
+    record_ids: bool,
+    include: Vec<EntryPattern>,
+    exclude: Vec<EntryPattern>,
+    on_record_error: Option<RecordErrorHandler>,
+}
+
+/// Moment written as time zero when re-zeroing row timestamps.
+///
+/// Raw timestamps count from the robot's boot, so the same moment of two
+/// matches has unrelated times. Records before the origin get negative
+/// timestamps.
+///
+/// Parses from `first-record`, `first-enabled`, `auto-start`, or a raw log
+/// time in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeOrigin {
+    /// The first record of the log
+    FirstRecord,
+    /// The moment the robot was first enabled, as reported by the Driver
+    /// Station entries read by [`UsageSummary`]; the first record if it never
+    /// was
+    FirstEnabled,
+    /// The moment the robot was first enabled in autonomous, the start of a
+    /// match's auto period; the first record if it never was
+    AutoStart,
+    /// A raw log timestamp, in microseconds
+    Timestamp(u64),
+}
+
+impl FromStr for TimeOrigin {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "first-record" => Ok(TimeOrigin::FirstRecord),
+            "first-enabled" => Ok(TimeOrigin::FirstEnabled),
+            "auto-start" => Ok(TimeOrigin::AutoStart),
+            seconds => match seconds.parse::<f64>() {
+                Ok(seconds) if seconds >= 0.0 && seconds.is_finite() => {
+                    Ok(TimeOrigin::Timestamp((seconds * 1_000_000.0).round() as u64))
+                }
+                _ => Err(Error::Other(format!(
+                    "Invalid time origin '{}': expected first-record, first-enabled, auto-start or a time in seconds",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
+/// Part of a log to decode, measured from its first or last record.
+#[derive(Debug, Clone, Copy)]
+enum Window {
+    Head(Duration),
+    Tail(Duration),
+}
+
+impl Window {
+    /// Timestamps (microseconds) inside the window of a log spanning `scan`.
+    fn time_range(&self, scan: &QuickScan) -> Range<u64> {
+        let start = scan.start_timestamp.unwrap_or(0);
+        let end = scan.end_timestamp.unwrap_or(start);
+        match *self {
+            Window::Head(duration) => start..start.saturating_add(micros(duration)),
+            Window::Tail(duration) => end.saturating_sub(micros(duration))..end.saturating_add(1),
+        }
+    }
+}
+
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
 }
 
 impl WpilogReader {
     /// Create a new WPILog reader from a file path.
     ///
+    /// The file is memory-mapped, so records are parsed without copying the
+    /// log into memory; it is read into memory instead where mapping fails.
+    /// The file must not be truncated or rewritten while the reader is alive
+    /// (appending is fine): reading a mapped page past the new end of the
+    /// file crashes the process. Use
+    /// [`from_file_unmapped`](Self::from_file_unmapped) for such files.
+    ///
     /// # Arguments
     ///
     /// * `path` - Path to the .wpilog file
@@ -39,21 +208,20 @@ impl WpilogReader {
     ///
     /// Returns an error if the file cannot be read or is not a valid WPILog file.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut file = File::open(path.as_ref())?;
-        let mut data = Vec::new();
-        file.read_to_end(&mut data)?;
-
-        let reader = DataLogReader::new(&data);
-        if !reader.is_valid() {
-            return Err(Error::InvalidFormat(
-                "Not a valid WPILOG file".to_string(),
-            ));
-        }
+        Self::from_data(LogData::open(path.as_ref())?)
+    }
 
-        Ok(Self {
-            data,
-            formatter: None,
-        })
+    /// Create a new WPILog reader from a copy of the file at `path`.
+    ///
+    /// Unlike [`from_file`](Self::from_file), the file is read into memory,
+    /// so another process may truncate, rewrite or delete it meanwhile, e.g.
+    /// a log still being written by a robot or copied off one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid WPILog file.
+    pub fn from_file_unmapped<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_data(LogData::read(path.as_ref())?)
     }
 
     /// Create a new WPILog reader from raw bytes.
@@ -66,6 +234,45 @@ impl WpilogReader {
     ///
     /// Returns an error if the data is not a valid WPILog file.
     pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        Self::from_data(LogData::Owned(data))
+    }
+
+    /// Create a new WPILog reader from bytes shared with the caller, e.g. a
+    /// downloaded log also kept for upload elsewhere, without copying them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a valid WPILog file.
+    pub fn from_shared_bytes(data: Arc<[u8]>) -> Result<Self> {
+        Self::from_data(LogData::Shared(data))
+    }
+
+    /// Create a new WPILog reader from owned or `'static` bytes, e.g. a log
+    /// embedded with `include_bytes!`, without copying them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// static LOG: &[u8] = include_bytes!("../tests/golden/logs/synthetic_all_types.wpilog");
+    /// let records = WpilogReader::from_cow(Cow::Borrowed(LOG))?.read_all()?;
+    /// assert!(!records.is_empty());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a valid WPILog file.
+    pub fn from_cow(data: Cow<'static, [u8]>) -> Result<Self> {
+        Self::from_data(match data {
+            Cow::Borrowed(data) => LogData::Static(data),
+            Cow::Owned(data) => LogData::Owned(data),
+        })
+    }
+
+    fn from_data(data: LogData) -> Result<Self> {
         let reader = DataLogReader::new(&data);
         if !reader.is_valid() {
             return Err(Error::InvalidFormat(
@@ -76,9 +283,15 @@ impl WpilogReader {
         Ok(Self {
             data,
             formatter: None,
+            options: ReadOptions::default(),
         })
     }
 
+    /// Output format the reader was configured for.
+    pub fn output_format(&self) -> OutputFormat {
+        self.options.output_format
+    }
+
     /// Get the WPILog file version.
     ///
     /// Returns the version number as a 16-bit integer (e.g., 0x0100 for version 1.0).
@@ -95,6 +308,201 @@ impl WpilogReader {
         reader.get_extra_header()
     }
 
+    /// Build a catalog of entry lifetimes (Start/Finish timestamps, reuse
+    /// generations, and per-lifetime record counts).
+    ///
+    /// Only control records are decoded, so this is much cheaper than a full read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let reader = WpilogReader::from_file("data.wpilog")?;
+    /// let catalog = reader.catalog()?;
+    ///
+    /// for name in catalog.restarted_entries() {
+    ///     println!("{} was started more than once", name);
+    /// }
+    /// for lifetime in catalog.unfinished() {
+    ///     println!("{} was never finished", lifetime.name);
+    /// }
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn catalog(&self) -> Result<EntryCatalog> {
+        EntryCatalog::from_reader(&self.low_level_reader())
+    }
+
+    /// Summarize the log for a file browser: header, time span, counts,
+    /// namespaces, schemas, match info and warnings. See [`LogSummary`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let summary = WpilogReader::from_file("data.wpilog")?.summarize()?;
+    /// println!("{:.1}s, {} entries", summary.duration_secs, summary.entry_count);
+    /// if let Some(event) = summary.match_info.and_then(|m| m.event_name) {
+    ///     println!("event: {}", event);
+    /// }
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn summarize(&self) -> Result<LogSummary> {
+        LogSummary::from_reader(&self.low_level_reader())
+    }
+
+    /// Rebuild which commands ran when from the command scheduler entries.
+    /// See [`CommandTimeline`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let timeline = WpilogReader::from_file("match.wpilog")?.command_timeline()?;
+    /// for span in &timeline.spans {
+    ///     let duration = span.duration_secs(timeline.log_end_secs);
+    ///     println!("{:>8.3} {:>8.3} {}", span.start_secs, duration, span.command);
+    /// }
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn command_timeline(&self) -> Result<CommandTimeline> {
+        CommandTimeline::from_reader(&self.low_level_reader())
+    }
+
+    /// Add up enabled time (auto, teleop, test), enable cycles and brownouts.
+    /// See [`UsageSummary`].
+    pub fn usage(&self) -> Result<UsageSummary> {
+        UsageSummary::from_reader(&self.low_level_reader())
+    }
+
+    /// Collect the payloads of entries whose type is not decoded (`proto:`,
+    /// `raw` and vendor types), in log order. See [`BlobStore`](crate::BlobStore).
+    pub fn read_blobs(&self) -> Result<Vec<Blob>> {
+        let mut collector = BlobCollector::default();
+        self.low_level_reader().visit(&mut collector)?;
+        Ok(collector.blobs)
+    }
+
+    /// Build an index of record offsets per entry lifetime.
+    ///
+    /// Only record headers are walked, so this is much cheaper than a full read.
+    /// See [`RecordIndex`].
+    pub fn index(&self) -> Result<RecordIndex> {
+        RecordIndex::build(&self.low_level_reader())
+    }
+
+    /// Count records per entry and find the log's time range without decoding
+    /// data payloads. See [`QuickScan`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let scan = WpilogReader::from_file("data.wpilog")?.quick_scan()?;
+    /// println!("{} records over {:.1}s", scan.record_count, scan.duration_secs());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn quick_scan(&self) -> Result<QuickScan> {
+        QuickScan::from_reader(&self.low_level_reader())
+    }
+
+    /// Start a query that decodes only selected entries and time ranges.
+    /// See [`Scan`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let reader = WpilogReader::from_file("data.wpilog")?;
+    /// let batch = reader
+    ///     .scan()
+    ///     .select(["/Drive/*"])
+    ///     .filter_time(15_000_000..30_000_000)
+    ///     .to_arrow()?;
+    /// println!("{} rows, {} columns", batch.num_rows(), batch.num_columns());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn scan(&self) -> Scan<'_> {
+        Scan::new(self)
+    }
+
+    /// Create a [`LogCursor`] for seeking through the log by timestamp.
+    ///
+    /// Only record headers are walked up front; values are decoded when the
+    /// cursor is queried.
+    pub fn cursor(&self) -> Result<LogCursor<'_>> {
+        LogCursor::new(self.low_level_reader())
+    }
+
+    /// Read the records of a single entry.
+    ///
+    /// Only the named entry's payloads are decoded. Rows are returned in log
+    /// order across all lifetimes of the entry; an unknown name yields no rows.
+    /// See [`read_entries`](Self::read_entries).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let reader = WpilogReader::from_file("data.wpilog")?;
+    /// for row in reader.read_entry("/Drive/LeftVelocity")? {
+    ///     println!("{}: {}", row.timestamp, row.data["/Drive/LeftVelocity"]);
+    /// }
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn read_entry(&self, name: &str) -> Result<Vec<WideRow>> {
+        self.read_entries(&[name])
+    }
+
+    /// Read one entry as a typed [`TimeSeries`], e.g. for plotting.
+    ///
+    /// Only the entry's records are decoded, as in
+    /// [`read_entry`](Self::read_entry). Each value is converted to `T` with
+    /// serde: `f64` or `bool` for scalars, `Vec<f64>` for arrays, and a map
+    /// or `#[derive(Deserialize)]` struct keyed by field name for structs.
+    /// Use `Option<T>` for entries that may log empty values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if a value does not convert to `T`, or
+    /// an error if the log cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::collections::HashMap;
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let reader = WpilogReader::from_file("data.wpilog")?;
+    /// let voltage = reader.entry_series::<f64>("/Robot/BatteryVoltage")?;
+    /// let pose = reader.entry_series::<HashMap<String, f64>>("/Drive/Pose")?;
+    /// for (t, fields) in pose.iter() {
+    ///     println!("{:.2}s: x={}", t, fields["translation.x"]);
+    /// }
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn entry_series<T: DeserializeOwned>(&self, name: &str) -> Result<TimeSeries<T>> {
+        TimeSeries::from_rows(name, self.read_entry(name)?)
+    }
+
+    /// Read the records of the named entries, in log order.
+    ///
+    /// Names are matched as logged, before aliasing. Data records of other
+    /// entries are skipped without copying or decoding their payloads, so this
+    /// is much faster than filtering the output of [`read_all`](Self::read_all)
+    /// when only a few entries are needed.
+    pub fn read_entries(&self, names: &[&str]) -> Result<Vec<WideRow>> {
+        let mut formatter = self.data_formatter(Some(names))?;
+
+        let records = formatter.read_wpilog_from_bytes(&self.data, false)?;
+        Ok(self.downsample(records))
+    }
+
     /// Read all records from the WPILog file in wide format.
     ///
     /// In wide format, each row contains a timestamp and all metric values at that timestamp.
@@ -119,28 +527,7 @@ impl WpilogReader {
     /// # Ok::<(), wpilog_parser::Error>(())
     /// ```
     pub fn read_all(mut self) -> Result<Vec<WideRow>> {
-        // Reset global loop count
-        GLOBAL_LOOP_COUNT.store(0, Ordering::Relaxed);
-
-        let mut formatter = Formatter::new(
-            String::new(), // file path not used anymore
-            String::new(), // output_directory not used
-            OutputFormat::Wide,
-        );
-
-        // First pass: infer schema
-        formatter
-            .read_wpilog_from_bytes(&self.data, true)
-            .map_err(|e| Error::SchemaError(e.to_string()))?;
-
-        // Reset loop count for second pass
-        Formatter::reset_loop_count();
-
-        // Second pass: read data
-        let records = formatter
-            .read_wpilog_from_bytes(&self.data, false)
-            .map_err(|e| Error::ParseError(e.to_string()))?;
-
+        let (records, formatter) = self.read_with_formatter()?;
         self.formatter = Some(formatter);
         Ok(records)
     }
@@ -154,37 +541,479 @@ impl WpilogReader {
     ///
     /// A tuple of (records, formatter) where formatter contains additional metadata.
     pub fn read_all_with_metadata(self) -> Result<(Vec<WideRow>, Formatter)> {
-        // Reset global loop count
-        GLOBAL_LOOP_COUNT.store(0, Ordering::Relaxed);
+        self.read_with_formatter()
+    }
+
+    /// Read all records in long format: one row per data record, holding the
+    /// entry's name and its typed value.
+    ///
+    /// Long rows suit tidy-data tools and keep every record's type, at the
+    /// cost of more rows; write them with
+    /// [`ParquetWriter::write_long`](crate::ParquetWriter::write_long).
+    /// Works whatever the configured [`output_format`](WpilogReaderBuilder::output_format).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a pivot or record IDs are configured, as
+    /// both only apply to wide rows, or an error if the file cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ParquetWriter, WpilogReader};
+    ///
+    /// let rows = WpilogReader::from_file("data.wpilog")?.read_all_long()?;
+    /// for row in rows.iter().take(5) {
+    ///     println!("{} {} {:?}", row.timestamp, row.entry_name, row.value);
+    /// }
+    /// ParquetWriter::new("./output").write_long(&rows)?;
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn read_all_long(&self) -> Result<Vec<LongRow>> {
+        if self.options.pivot.is_some() {
+            return Err(Error::Config(
+                "pivot builds wide rows and cannot produce long output".to_string(),
+            ));
+        }
+        if self.options.record_ids {
+            return Err(Error::Config(
+                "record_ids are written to wide rows only".to_string(),
+            ));
+        }
+
+        let scan = self.quick_scan()?;
+        let mut formatter = self.configure_formatter(None, &scan)?;
+        formatter.output_format = OutputFormat::Long;
+
+        let mut records = Vec::with_capacity(formatter.expected_rows.unwrap_or(0));
+        formatter.stream_wpilog_from_bytes(&self.data, false, |row: LongRow| {
+            records.push(row);
+            Ok(())
+        })?;
+        formatter.progress = None;
+
+        Ok(match self.options.profile.as_ref().and_then(FilterProfile::downsample) {
+            Some(downsample) => downsample.apply_long(&records),
+            None => records,
+        })
+    }
+
+    /// Read the records appended to a growing log since byte `offset`, the
+    /// offset returned by the previous call (0 for the first).
+    ///
+    /// Returns the new rows and the offset just past the last complete
+    /// record; a record still being written at the end of the log is left for
+    /// the next call. The whole log is still walked, so entries started
+    /// before `offset` decode and loop counts continue as in a full read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let (rows, offset) = WpilogReader::from_file_unmapped("live.wpilog")?.read_appended(0)?;
+    /// // ... later, after the log grew
+    /// let (new_rows, offset) = WpilogReader::from_file_unmapped("live.wpilog")?.read_appended(offset)?;
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn read_appended(&self, offset: usize) -> Result<(Vec<WideRow>, usize)> {
+        let mut formatter = self.data_formatter(None)?;
+        formatter.expected_rows = None;
+        formatter.start_offset = Some(offset);
+
+        let records = formatter.read_wpilog_from_bytes(&self.data, false)?;
+        let end = formatter.truncated_at.unwrap_or(self.data.len());
+        Ok((self.downsample(records), end))
+    }
+
+    /// Decode all records into a disk-backed [`SpillStore`] instead of memory.
+    ///
+    /// Use this for logs whose decoded rows do not fit in RAM, then write the
+    /// store with [`ParquetWriter::write_spilled`](crate::ParquetWriter::write_spilled).
+    /// Returns the number of rows staged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a pivot is configured (pivoting needs all
+    /// rows in memory), or an error if the file cannot be parsed or the spill files written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{SpillStore, WpilogReader};
+    ///
+    /// let mut store = SpillStore::new()?;
+    /// let rows = WpilogReader::from_file("data.wpilog")?.read_to_spill(&mut store)?;
+    /// println!("Staged {} rows in {} files", rows, store.spilled_batches());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn read_to_spill(&self, store: &mut SpillStore) -> Result<usize> {
+        let before = store.len();
+        self.stream(|row| store.push(row))?;
+        store.flush()?;
+
+        Ok(store.len() - before)
+    }
+
+    /// Decode records one at a time, passing each row to `sink` instead of
+    /// collecting them.
+    ///
+    /// Memory use stays flat regardless of log size. Returning an error from
+    /// `sink` stops decoding and propagates the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a pivot is configured (pivoting needs all
+    /// rows in memory), or an error if the file cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::WpilogReader;
+    ///
+    /// let reader = WpilogReader::from_file("data.wpilog")?;
+    /// let mut max_voltage = f64::MIN;
+    /// reader.stream(|row| {
+    ///     if let Some(v) = row.data.get("/Robot/Voltage").and_then(|v| v.as_f64()) {
+    ///         max_voltage = max_voltage.max(v);
+    ///     }
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn stream<F>(&self, mut sink: F) -> Result<()>
+    where
+        F: FnMut(WideRow) -> Result<()>,
+    {
+        if self.options.pivot.is_some() {
+            return Err(Error::Config(
+                "Pivot is not supported when streaming records".to_string(),
+            ));
+        }
+
+        let mut formatter = self.data_formatter(None)?;
+        match self.options.profile.as_ref().and_then(FilterProfile::downsample) {
+            Some(downsample) => {
+                let mut last_kept = HashMap::new();
+                formatter.stream_wpilog_from_bytes(&self.data, false, |row| {
+                    match downsample.filter_row(&mut last_kept, row) {
+                        Some(row) => sink(row),
+                        None => Ok(()),
+                    }
+                })
+            }
+            None => formatter.stream_wpilog_from_bytes(&self.data, false, sink),
+        }
+    }
 
+    /// Decode rows lazily as the returned iterator is advanced.
+    ///
+    /// Only the rows completed by the record just read are held, so a
+    /// multi-GB log can be converted chunk by chunk, e.g. with
+    /// [`ParquetWriter::write_rows`](crate::ParquetWriter::write_rows). The
+    /// msgpack sampling pass, if any, runs before this returns. Decoding stops after the
+    /// first error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a pivot is configured (pivoting needs all
+    /// rows in memory), or an error if the msgpack sampling pass fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ParquetWriter, WpilogReader};
+    ///
+    /// let reader = WpilogReader::from_file("data.wpilog")?;
+    /// let stats = ParquetWriter::new("./output").write_rows(reader.rows()?)?;
+    /// println!("{}", stats.summary());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn rows(&self) -> Result<Rows<'_>> {
+        if self.options.pivot.is_some() {
+            return Err(Error::Config(
+                "Pivot is not supported when streaming records".to_string(),
+            ));
+        }
+
+        let mut formatter = self.data_formatter(None)?;
+        let pass = formatter.begin_pass(&self.data, false)?;
+        Ok(Rows {
+            formatter,
+            pass,
+            pending: VecDeque::new(),
+            downsample: self.options.profile.as_ref().and_then(FilterProfile::downsample),
+            last_kept: HashMap::new(),
+        })
+    }
+
+    /// Read the data records of entries matching `selector` with timestamps
+    /// in `time_range`, for [`Scan`].
+    ///
+    /// Matching names are resolved from a header scan, so nothing is decoded
+    /// when no entry matches; otherwise only the matching entries' payloads
+    /// are decoded, and only within the time range.
+    pub(crate) fn read_scan(
+        &self,
+        selector: Option<&EntrySelector>,
+        time_range: Option<Range<u64>>,
+    ) -> Result<Vec<WideRow>> {
+        let scan = self.quick_scan()?;
+        let names: Vec<&str> = scan
+            .entry_counts
+            .keys()
+            .map(String::as_str)
+            .filter(|name| selector.is_none_or(|selector| selector.matches(name)))
+            .collect();
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut formatter = self.data_formatter_with_scan(Some(&names), &scan)?;
+        formatter.time_range = match (formatter.time_range.take(), time_range) {
+            (Some(window), Some(range)) => {
+                Some(window.start.max(range.start)..window.end.min(range.end))
+            }
+            (window, range) => range.or(window),
+        };
+        let records = formatter.read_wpilog_from_bytes(&self.data, false)?;
+        Ok(self.downsample(records))
+    }
+
+    /// Create a formatter for a data pass from the reader options, sampling
+    /// msgpack payloads first if [`msgpack_columns`](WpilogReaderBuilder::msgpack_columns)
+    /// is set.
+    ///
+    /// `names` restricts the data pass to the named entries. A header scan
+    /// sizes the row vector and the progress totals for the rows selected.
+    fn data_formatter(&self, names: Option<&[&str]>) -> Result<Formatter> {
+        self.data_formatter_with_scan(names, &self.quick_scan()?)
+    }
+
+    /// [`data_formatter`](Self::data_formatter) with an existing header scan.
+    fn data_formatter_with_scan(&self, names: Option<&[&str]>, scan: &QuickScan) -> Result<Formatter> {
+        if self.options.output_format == OutputFormat::Long {
+            return Err(Error::Config(
+                "reader is configured for long output but wide rows were requested; use read_all_long".to_string(),
+            ));
+        }
+        self.configure_formatter(names, scan)
+    }
+
+    /// [`data_formatter_with_scan`](Self::data_formatter_with_scan) for either
+    /// output format.
+    fn configure_formatter(&self, names: Option<&[&str]>, scan: &QuickScan) -> Result<Formatter> {
         let mut formatter = Formatter::new(
-            String::new(),
-            String::new(),
+            String::new(), // file path not used anymore
+            String::new(), // output_directory not used
             OutputFormat::Wide,
         );
+        formatter.generation_columns = self.options.generation_columns;
+        formatter.alias_columns = self.options.alias_columns;
+        formatter.buffer_orphans = self.options.buffer_orphans;
+        formatter.json_depth = self.options.json_depth;
+        formatter.msgpack_sample = self.options.msgpack_sample;
+        formatter.quirks = self.options.quirks;
+        formatter.deny_unsupported_types = self.options.deny_unsupported_types;
+        formatter.exclude_undecoded = self.options.exclude_undecoded;
+        formatter.on_record_error = self.options.on_record_error.clone();
+        formatter.subsample = self.options.subsample;
+        formatter.time_range = self.options.window.map(|window| window.time_range(scan));
+        if let Some(origin) = self.options.time_origin {
+            formatter.time_origin = self.time_origin(origin, scan)?;
+        }
+        if self.options.record_ids {
+            formatter.record_id_log = Some(self.log_hash());
+        }
+        if let Some(profile) = &self.options.profile {
+            formatter.entry_selector = profile.selector()?;
+        }
+        if !self.options.include.is_empty() || !self.options.exclude.is_empty() {
+            let selector = EntrySelector::from_patterns(&self.options.include, &self.options.exclude)?;
+            formatter.entry_selector = Some(match formatter.entry_selector.take() {
+                Some(profile) => profile.and(selector),
+                None => selector,
+            });
+        }
+
+        scan.quirks.warn();
+        let expected_rows = scan.count_matching(|name| {
+            names.is_none_or(|names| names.contains(&name))
+                && formatter
+                    .entry_selector
+                    .as_ref()
+                    .is_none_or(|selector| selector.matches(name))
+        });
+        formatter.expected_rows = Some(expected_rows as usize);
+        if let Some(sender) = self.options.progress.clone() {
+            let mut progress = ProgressTracker::new(sender, self.data.len() as u64);
+            progress.set_total_records(expected_rows);
+            formatter.progress = Some(progress);
+        }
+
+        // Struct schemas are registered by the data pass as they are read;
+        // only msgpack columns need sampling ahead of it
+        if formatter.msgpack_sample.is_some() {
+            formatter.read_wpilog_from_bytes(&self.data, true)?;
+        }
+
+        if let Some(names) = names {
+            formatter.entry_names = Some(names.iter().map(|name| name.to_string()).collect());
+        }
+
+        Ok(formatter)
+    }
+
+    /// The hash of this log used in its [`RecordId`]s.
+    pub fn log_hash(&self) -> [u8; 8] {
+        log_hash(&self.data)
+    }
+
+    /// Read back the record a [`RecordId`] points to, e.g. from the
+    /// `record_id` column of an export made with
+    /// [`record_ids`](WpilogReaderBuilder::record_ids).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ID belongs to another log or does not point at
+    /// a complete record.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{RecordId, WpilogReader};
+    ///
+    /// let id: RecordId = "3f1a9c0de4b27756:10482".parse()?;
+    /// let record = WpilogReader::from_file("data.wpilog")?.record_at(&id)?;
+    /// println!("entry {} at {} us", record.entry, record.timestamp);
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn record_at(&self, id: &RecordId) -> Result<DataLogRecord> {
+        if id.log != self.log_hash() {
+            return Err(Error::Other(format!("Record ID {} belongs to another log", id)));
+        }
+        let offset = usize::try_from(id.offset)
+            .ok()
+            .filter(|&offset| offset >= self.low_level_reader().first_record_pos().unwrap_or(0));
+        let header = match offset {
+            Some(offset) => read_record_header(&self.data, offset, self.options.quirks)?,
+            None => None,
+        }
+        .ok_or_else(|| Error::InvalidRecord(format!("No record at offset {}", id.offset)))?;
+        Ok(DataLogRecord {
+            entry: header.entry,
+            timestamp: header.timestamp,
+            data: self.data[header.payload_start..header.payload_end].to_vec(),
+        })
+    }
 
-        // First pass: infer schema
-        formatter
-            .read_wpilog_from_bytes(&self.data, true)
-            .map_err(|e| Error::SchemaError(e.to_string()))?;
+    /// Raw timestamp (microseconds) of `origin` in this log.
+    fn time_origin(&self, origin: TimeOrigin, scan: &QuickScan) -> Result<u64> {
+        let first_record = scan.start_timestamp.unwrap_or(0);
+        match origin {
+            TimeOrigin::FirstRecord => Ok(first_record),
+            TimeOrigin::FirstEnabled | TimeOrigin::AutoStart => {
+                let autonomous = origin == TimeOrigin::AutoStart;
+                match first_enabled(&self.low_level_reader(), autonomous)? {
+                    Some(timestamp) => Ok(timestamp),
+                    None => {
+                        log::warn!(
+                            "Log was never enabled{}; timestamps count from its first record",
+                            if autonomous { " in autonomous" } else { "" }
+                        );
+                        Ok(first_record)
+                    }
+                }
+            }
+            TimeOrigin::Timestamp(timestamp) => Ok(timestamp),
+        }
+    }
 
-        // Reset loop count
-        Formatter::reset_loop_count();
+    /// Run the schema and data passes with a formatter configured from the reader options.
+    fn read_with_formatter(&self) -> Result<(Vec<WideRow>, Formatter)> {
+        let mut formatter = self.data_formatter(None)?;
 
-        // Second pass: read data
-        let records = formatter
-            .read_wpilog_from_bytes(&self.data, false)
-            .map_err(|e| Error::ParseError(e.to_string()))?;
+        // Data pass
+        let mut records = formatter.read_wpilog_from_bytes(&self.data, false)?;
+        // Disconnect so receivers see the end of the channel once the reader is gone
+        formatter.progress = None;
+
+        records = self.downsample(records);
+        if let Some(pivot) = &self.options.pivot {
+            records = pivot.apply(&records);
+        }
 
         Ok((records, formatter))
     }
 
+    /// Apply the profile's downsampling, if any.
+    fn downsample(&self, records: Vec<WideRow>) -> Vec<WideRow> {
+        match self.options.profile.as_ref().and_then(FilterProfile::downsample) {
+            Some(downsample) => downsample.apply(&records),
+            None => records,
+        }
+    }
+
     /// Get a low-level reader for advanced parsing operations.
     ///
     /// This gives you direct access to the underlying binary parser for
     /// custom parsing logic or performance-critical applications.
     pub fn low_level_reader(&self) -> DataLogReader<'_> {
-        DataLogReader::new(&self.data)
+        DataLogReader::new(&self.data).with_quirks(self.options.quirks)
+    }
+}
+
+/// Lazy iterator over the rows of a log, from [`WpilogReader::rows`].
+pub struct Rows<'a> {
+    formatter: Formatter,
+    pass: LogPass<'a>,
+    /// Rows completed by the last record read, such as buffered orphans
+    pending: VecDeque<WideRow>,
+    downsample: Option<Downsample>,
+    last_kept: HashMap<String, f64>,
+}
+
+impl Rows<'_> {
+    /// The formatter decoding the rows, for metadata such as
+    /// [`Formatter::warnings`] once the iterator is exhausted.
+    pub fn formatter(&self) -> &Formatter {
+        &self.formatter
+    }
+}
+
+impl Iterator for Rows<'_> {
+    type Item = Result<WideRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.pending.pop_front() {
+                let row = match &self.downsample {
+                    Some(downsample) => downsample.filter_row(&mut self.last_kept, row),
+                    None => Some(row),
+                };
+                match row {
+                    Some(row) => return Some(Ok(row)),
+                    None => continue,
+                }
+            }
+
+            let pending = &mut self.pending;
+            match self.formatter.step_pass(&mut self.pass, &mut |row| {
+                pending.push_back(row);
+                Ok(())
+            }) {
+                Ok(true) => {}
+                Ok(false) => {
+                    // Disconnect so receivers see the end of the channel
+                    self.formatter.progress = None;
+                    return None;
+                }
+                Err(e) => {
+                    self.pass.finish();
+                    return Some(Err(e));
+                }
+            }
+        }
     }
 }
 
@@ -201,33 +1030,402 @@ impl WpilogReader {
 /// # Ok::<(), wpilog_parser::Error>(())
 /// ```
 pub struct WpilogReaderBuilder {
-    output_format: OutputFormat,
+    options: ReadOptions,
 }
 
 impl WpilogReaderBuilder {
     /// Create a new reader builder with default options.
     pub fn new() -> Self {
         Self {
-            output_format: OutputFormat::Wide,
+            options: ReadOptions::default(),
         }
     }
 
     /// Set the output format (Wide or Long).
     ///
-    /// Default is Wide format.
+    /// Default is Wide format. The wide read methods, such as
+    /// [`read_all`](WpilogReader::read_all), fail with [`Error::Config`] on a
+    /// reader configured for long output rather than silently producing wide
+    /// rows.
     pub fn output_format(mut self, format: OutputFormat) -> Self {
-        self.output_format = format;
+        self.options.output_format = format;
+        self
+    }
+
+    /// Emit generation-suffixed columns for reused entry names.
+    ///
+    /// When an entry name is started again with a different type after a
+    /// Finish record, its values are written to `name#N` (where `N` is the
+    /// 1-based Start count for that name) instead of being merged into the
+    /// original column. A later generation with the type of an earlier one
+    /// goes back to that generation's column. Default is `false`.
+    pub fn generation_columns(mut self, enabled: bool) -> Self {
+        self.options.generation_columns = enabled;
+        self
+    }
+
+    /// Use metadata aliases as column names.
+    ///
+    /// Entries whose Start metadata is JSON with an `alias` key (e.g.,
+    /// `{"alias":"Left Drive Current"}`) are written under the alias instead of
+    /// the entry name. Use [`EntryCatalog::aliases`] for the full mapping.
+    /// Default is `false`.
+    pub fn alias_columns(mut self, enabled: bool) -> Self {
+        self.options.alias_columns = enabled;
+        self
+    }
+
+    /// Keep data records whose entry has not been started yet.
+    ///
+    /// Records for an entry ID are normally dropped until its Start record is
+    /// seen. With this enabled they are held and decoded when the Start record
+    /// arrives, so their rows follow it in the output but keep their own
+    /// timestamps. Records arriving after an entry's Finish record are only
+    /// decoded if its ID is started again with the same name and type, and at
+    /// most [`MAX_BUFFERED_ORPHANS`](crate::formatter::MAX_BUFFERED_ORPHANS)
+    /// records are held. Records that are not decoded, including those whose
+    /// Start never appears, are counted in [`Formatter::orphaned_records`].
+    /// Default is `false`.
+    pub fn buffer_orphans(mut self, enabled: bool) -> Self {
+        self.options.buffer_orphans = enabled;
+        self
+    }
+
+    /// Fail with [`Error::UnsupportedType`] when a decoded entry has a type
+    /// whose values would be written as nulls, such as `proto:` or vendor
+    /// types.
+    ///
+    /// Such entries are otherwise decoded as null columns and reported in
+    /// [`Formatter::warnings`] and the log, so an all-null column does not go
+    /// unnoticed. Default is `false`.
+    pub fn deny_unsupported_types(mut self, enabled: bool) -> Self {
+        self.options.deny_unsupported_types = enabled;
+        self
+    }
+
+    /// Only decode every Nth record of each entry, for a quick preview of a
+    /// conversion. See [`Subsample`]. Default is to read every record.
+    pub fn subsample(mut self, subsample: Subsample) -> Self {
+        self.options.subsample = Some(subsample);
+        self
+    }
+
+    /// Only decode records in the first `duration` of the log, measured from
+    /// its first record. Replaces any [`tail_duration`](Self::tail_duration).
+    pub fn head_duration(mut self, duration: Duration) -> Self {
+        self.options.window = Some(Window::Head(duration));
+        self
+    }
+
+    /// Only decode records in the last `duration` of the log, up to and
+    /// including its last record, e.g. the seconds before a failure. Replaces
+    /// any [`head_duration`](Self::head_duration).
+    pub fn tail_duration(mut self, duration: Duration) -> Self {
+        self.options.window = Some(Window::Tail(duration));
+        self
+    }
+
+    /// Re-zero row timestamps so that `origin` is time zero, e.g. to compare
+    /// matches from the moment the robot was enabled. See [`TimeOrigin`].
+    /// Default is to keep raw log timestamps.
+    pub fn time_origin(mut self, origin: TimeOrigin) -> Self {
+        self.options.time_origin = Some(origin);
+        self
+    }
+
+    /// Stamp each row with the [`RecordId`] of the record it was decoded
+    /// from, in a `record_id` column, so exported rows can be traced back to
+    /// their exact position in the log. Default is `false`.
+    pub fn record_ids(mut self, enabled: bool) -> Self {
+        self.options.record_ids = enabled;
+        self
+    }
+
+    /// Only decode entries whose names start with `prefix`, e.g. `/Drive/`.
+    ///
+    /// Other entries are skipped without decoding their records, so no rows
+    /// or columns are built for them. May be given several times, along with
+    /// [`include_glob`](Self::include_glob) and
+    /// [`include_regex`](Self::include_regex); an entry is read if it matches
+    /// any of them. Combines with a [`profile`](Self::profile)'s selectors.
+    pub fn include_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.options.include.push(EntryPattern::Prefix(prefix.into()));
+        self
+    }
+
+    /// Only decode entries whose names match `glob`, as in [`EntrySelector`].
+    /// See [`include_prefix`](Self::include_prefix).
+    pub fn include_glob(mut self, glob: impl Into<String>) -> Self {
+        self.options.include.push(EntryPattern::Glob(glob.into()));
+        self
+    }
+
+    /// Only decode entries whose names match the regular expression
+    /// `pattern` anywhere. An invalid pattern is reported when reading. See
+    /// [`include_prefix`](Self::include_prefix).
+    pub fn include_regex(mut self, pattern: impl Into<String>) -> Self {
+        self.options.include.push(EntryPattern::Regex(pattern.into()));
+        self
+    }
+
+    /// Skip entries whose names start with `prefix`, even if included.
+    pub fn exclude_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.options.exclude.push(EntryPattern::Prefix(prefix.into()));
+        self
+    }
+
+    /// Skip entries whose names match `glob`, even if included.
+    pub fn exclude_glob(mut self, glob: impl Into<String>) -> Self {
+        self.options.exclude.push(EntryPattern::Glob(glob.into()));
+        self
+    }
+
+    /// Skip entries whose names match the regular expression `pattern`
+    /// anywhere, e.g. `NT:/.*photonvision.*`, even if included.
+    pub fn exclude_regex(mut self, pattern: impl Into<String>) -> Self {
+        self.options.exclude.push(EntryPattern::Regex(pattern.into()));
+        self
+    }
+
+    /// Leave entries whose type is not decoded out of the rows instead of
+    /// writing them as null columns, e.g. when their payloads are exported
+    /// with [`read_blobs`](WpilogReader::read_blobs). They are then not
+    /// reported as warnings. Default is `false`.
+    pub fn exclude_undecoded(mut self, enabled: bool) -> Self {
+        self.options.exclude_undecoded = enabled;
+        self
+    }
+
+    /// Parse `json` entries into structured values instead of raw strings.
+    ///
+    /// Object keys are flattened into `entry/key` columns, recursing `depth`
+    /// levels; deeper objects and arrays stay as JSON values. With a depth of
+    /// 0 the parsed value is kept in the entry's own column. Text that is not
+    /// valid JSON is kept as a string. Default is to keep raw strings.
+    ///
+    /// For example, with depth 1 a `/Vision/Result` entry logging
+    /// `{"tv": true, "pose": {"x": 1.0}}` fills the columns
+    /// `/Vision/Result/tv` (`true`) and `/Vision/Result/pose` (`{"x": 1.0}`).
+    pub fn parse_json(mut self, depth: usize) -> Self {
+        self.options.json_depth = Some(depth);
+        self
+    }
+
+    /// Derive typed columns for msgpack entries from up to `sample` payloads each.
+    ///
+    /// If every sampled payload of an entry is a map with the same string keys
+    /// and consistently typed scalar values, each key is written to its own
+    /// `entry/key` column (integers widen to floats when both appear). Entries
+    /// with irregular payloads, and records that do not fit their entry's
+    /// sampled schema, are written as JSON strings. By default msgpack values
+    /// are written in a debug representation.
+    pub fn msgpack_columns(mut self, sample: usize) -> Self {
+        self.options.msgpack_sample = Some(sample);
+        self
+    }
+
+    /// Apply a [`FilterProfile`]: read only the entries its selectors match,
+    /// downsample to its maximum rate, and use its output format (if set).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ProfileSet, WpilogReaderBuilder};
+    ///
+    /// let profiles = ProfileSet::from_file("wpilog-profiles.json")?;
+    /// let records = WpilogReaderBuilder::new()
+    ///     .profile(profiles.get("drive-analysis")?.clone())
+    ///     .from_file("data.wpilog")?
+    ///     .read_all()?;
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn profile(mut self, profile: FilterProfile) -> Self {
+        if let Some(format) = profile.output_format {
+            self.options.output_format = format;
+        }
+        self.options.profile = Some(profile);
+        self
+    }
+
+    /// Produce a time-aligned table with one row per distinct timestamp.
+    ///
+    /// Missing cells are filled according to the pivot's fill policies.
+    /// See [`Pivot`] for details.
+    pub fn pivot(mut self, pivot: Pivot) -> Self {
+        self.options.pivot = Some(pivot);
+        self
+    }
+
+    /// Decide with `handler` what to do with each data record that fails to
+    /// decode, instead of failing the read.
+    ///
+    /// The handler is called with the entry, timestamp and offset of the
+    /// record and the error, and returns whether to skip the record, write it
+    /// as a null value or abort the read with the error. It applies to
+    /// decoding only: errors in the log's structure, such as a truncated
+    /// record, still fail the read. See [`record_error`](crate::record_error).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{RecordAction, WpilogReaderBuilder};
+    ///
+    /// let records = WpilogReaderBuilder::new()
+    ///     .on_record_error(|_| RecordAction::Null)
+    ///     .from_file("data.wpilog")?
+    ///     .read_all()?;
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn on_record_error<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&RecordError<'_>) -> RecordAction + Send + Sync + 'static,
+    {
+        self.options.on_record_error = Some(RecordErrorHandler::new(handler));
+        self
+    }
+
+    /// Tolerate off-spec records from third-party loggers. See [`Quirks`];
+    /// each quirk that is applied is reported with a warning.
+    pub fn quirks(mut self, quirks: Quirks) -> Self {
+        self.options.quirks = quirks;
         self
     }
 
+    /// Send [`ProgressUpdate`]s to `sender` while decoding records.
+    ///
+    /// Progress is reported in bytes of the log consumed by the data pass.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::mpsc;
+    /// use std::thread;
+    /// use wpilog_parser::{ProgressUpdate, WpilogReaderBuilder};
+    ///
+    /// let (sender, receiver) = mpsc::channel::<ProgressUpdate>();
+    /// let ui = thread::spawn(move || {
+    ///     for update in receiver {
+    ///         if let Some(fraction) = update.fraction() {
+    ///             println!("{:.0}%", fraction * 100.0);
+    ///         }
+    ///     }
+    /// });
+    ///
+    /// let records = WpilogReaderBuilder::new()
+    ///     .progress(sender)
+    ///     .from_file("data.wpilog")?
+    ///     .read_all()?;
+    /// ui.join().unwrap();
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn progress(mut self, sender: Sender<ProgressUpdate>) -> Self {
+        self.options.progress = Some(sender);
+        self
+    }
+
+    /// Check that the configured options can be combined.
+    ///
+    /// [`from_file`](Self::from_file) and [`from_bytes`](Self::from_bytes)
+    /// run this before opening the log, so a misconfigured reader fails
+    /// up front rather than partway through a read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] describing the first problem found, e.g. a
+    /// pivot combined with long output, or an invalid entry pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wpilog_parser::{OutputFormat, Pivot, WpilogReaderBuilder};
+    ///
+    /// let builder = WpilogReaderBuilder::new()
+    ///     .output_format(OutputFormat::Long)
+    ///     .pivot(Pivot::new());
+    /// assert!(builder.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let options = &self.options;
+        let config = |msg: &str| Err(Error::Config(msg.to_string()));
+
+        if options.pivot.is_some() {
+            if options.output_format == OutputFormat::Long {
+                return config("pivot builds one wide row per timestamp and cannot produce long output");
+            }
+            if options.record_ids {
+                return config("record_ids cannot be combined with pivot, as pivoted rows merge several records");
+            }
+        }
+        if options.record_ids && options.output_format == OutputFormat::Long {
+            return config("record_ids are written to wide rows only and cannot be combined with long output");
+        }
+        if let Some(Window::Head(duration) | Window::Tail(duration)) = options.window {
+            if duration.is_zero() {
+                return config("head_duration and tail_duration must be positive");
+            }
+        }
+        if options.msgpack_sample == Some(0) {
+            return config("msgpack_columns needs a sample of at least one payload");
+        }
+        if let Some(profile) = &options.profile {
+            if profile.max_rate_hz.is_some_and(|hz| hz.is_nan() || hz <= 0.0) {
+                return config("profile max_rate_hz must be positive");
+            }
+            profile.selector().map_err(|e| Error::Config(e.to_string()))?;
+        }
+        EntrySelector::from_patterns(&options.include, &options.exclude)
+            .map_err(|e| Error::Config(e.to_string()))?;
+        Ok(())
+    }
+
     /// Build a reader from a file path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the options are invalid (see
+    /// [`validate`](Self::validate)), or an error if the file cannot be read.
     pub fn from_file<P: AsRef<Path>>(self, path: P) -> Result<WpilogReader> {
-        WpilogReader::from_file(path)
+        self.validate()?;
+        let mut reader = WpilogReader::from_file(path)?;
+        reader.options = self.options;
+        Ok(reader)
     }
 
     /// Build a reader from raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the options are invalid (see
+    /// [`validate`](Self::validate)), or an error if the data is not a log.
     pub fn from_bytes(self, data: Vec<u8>) -> Result<WpilogReader> {
-        WpilogReader::from_bytes(data)
+        self.build(WpilogReader::from_bytes(data))
+    }
+
+    /// Build a reader from shared bytes; see
+    /// [`WpilogReader::from_shared_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// As for [`from_bytes`](Self::from_bytes).
+    pub fn from_shared_bytes(self, data: Arc<[u8]>) -> Result<WpilogReader> {
+        self.build(WpilogReader::from_shared_bytes(data))
+    }
+
+    /// Build a reader from owned or `'static` bytes; see
+    /// [`WpilogReader::from_cow`].
+    ///
+    /// # Errors
+    ///
+    /// As for [`from_bytes`](Self::from_bytes).
+    pub fn from_cow(self, data: Cow<'static, [u8]>) -> Result<WpilogReader> {
+        self.build(WpilogReader::from_cow(data))
+    }
+
+    fn build(self, reader: Result<WpilogReader>) -> Result<WpilogReader> {
+        self.validate()?;
+        let mut reader = reader?;
+        reader.options = self.options;
+        Ok(reader)
     }
 }
 
@@ -236,3 +1434,28 @@ impl Default for WpilogReaderBuilder {
         Self::new()
     }
 }
+
+/// Construct records from previously exported Parquet output.
+///
+/// Implemented for [`Records`](crate::Records) so analysis code can operate on
+/// either a `.wpilog` or its Parquet export.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{FromParquet, Records};
+///
+/// let records = Records::from_parquet("./output")?;
+/// println!("Loaded {} records", records.len());
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub trait FromParquet: Sized {
+    /// Read all `*.parquet` files in `directory` (in name order).
+    fn from_parquet<P: AsRef<Path>>(directory: P) -> Result<Self>;
+}
+
+impl FromParquet for Vec<WideRow> {
+    fn from_parquet<P: AsRef<Path>>(directory: P) -> Result<Self> {
+        read_parquet_directory(directory.as_ref())
+    }
+}
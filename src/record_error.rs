@@ -0,0 +1,93 @@
+//! Per-record policies for data records that fail to decode.
+//!
+//! By default a record that cannot be decoded as its entry's type fails the
+//! whole read. An application that would rather lose one value than one log
+//! can decide per error with
+//! [`WpilogReaderBuilder::on_record_error`](crate::WpilogReaderBuilder::on_record_error):
+//!
+//! ```no_run
+//! use wpilog_parser::{RecordAction, WpilogReaderBuilder};
+//!
+//! let records = WpilogReaderBuilder::new()
+//!     .on_record_error(|failure| {
+//!         eprintln!("{} at {} µs: {}", failure.entry, failure.timestamp, failure.error);
+//!         if failure.entry.starts_with("/Vision/") {
+//!             RecordAction::Skip
+//!         } else {
+//!             RecordAction::Abort
+//!         }
+//!     })
+//!     .from_file("data.wpilog")?
+//!     .read_all()?;
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A data record that failed to decode.
+#[derive(Debug)]
+pub struct RecordError<'a> {
+    /// Name of the record's entry, as logged
+    pub entry: &'a str,
+    pub entry_id: u32,
+    /// Type of the entry as declared in its Start record
+    pub type_name: &'a str,
+    /// Timestamp of the record, in microseconds
+    pub timestamp: u64,
+    /// Byte offset of the record's header in the log
+    pub offset: usize,
+    pub error: &'a Error,
+}
+
+/// What to do with a record that failed to decode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordAction {
+    /// Leave the record out of the output
+    Skip,
+    /// Write the record as a row with a null value
+    Null,
+    /// Fail the read with the error
+    #[default]
+    Abort,
+}
+
+impl FromStr for RecordAction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Ok(RecordAction::Skip),
+            "null" => Ok(RecordAction::Null),
+            "abort" => Ok(RecordAction::Abort),
+            _ => Err(Error::Config(format!("Unknown record error action '{}'", s))),
+        }
+    }
+}
+
+/// A callback deciding the [`RecordAction`] for each [`RecordError`].
+#[derive(Clone)]
+pub(crate) struct RecordErrorHandler(Arc<dyn Fn(&RecordError<'_>) -> RecordAction + Send + Sync>);
+
+impl RecordErrorHandler {
+    pub(crate) fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&RecordError<'_>) -> RecordAction + Send + Sync + 'static,
+    {
+        Self(Arc::new(handler))
+    }
+
+    pub(crate) fn decide(&self, error: &RecordError<'_>) -> RecordAction {
+        (self.0)(error)
+    }
+}
+
+impl fmt::Debug for RecordErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RecordErrorHandler")
+    }
+}
@@ -0,0 +1,72 @@
+//! Stable identities of records, for tracing exported rows back to their log.
+//!
+//! A [`RecordId`] combines a hash of the log's bytes with the byte offset of
+//! the record's header, so it is the same every time a log is converted and
+//! distinct across logs. With
+//! [`WpilogReaderBuilder::record_ids`](crate::WpilogReaderBuilder::record_ids)
+//! each row carries its ID in a `record_id` column, written as
+//! `<log hash>:<offset>`, e.g. `3f1a9c0de4b27756:10482`;
+//! [`WpilogReader::record_at`](crate::WpilogReader::record_at) reads the
+//! record back.
+
+use crate::error::{Error, Result};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// Name of the column holding each row's [`RecordId`].
+pub const RECORD_ID_COLUMN: &str = "record_id";
+
+/// Identity of one record of one log.
+///
+/// IDs order by log, then by position in the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RecordId {
+    /// First 8 bytes of the SHA-256 of the log, the first 16 hex digits of
+    /// [`Provenance::source_sha256`](crate::Provenance::source_sha256)
+    pub log: [u8; 8],
+    /// Byte offset of the record's header in the log
+    pub offset: u64,
+}
+
+impl RecordId {
+    /// ID of the record at `offset` in the log with the given [`log_hash`].
+    pub fn new(log: [u8; 8], offset: u64) -> Self {
+        Self { log, offset }
+    }
+}
+
+/// The hash of a log's bytes used in its [`RecordId`]s.
+pub fn log_hash(data: &[u8]) -> [u8; 8] {
+    let digest = Sha256::digest(data);
+    let mut log = [0; 8];
+    log.copy_from_slice(&digest[..8]);
+    log
+}
+
+impl fmt::Display for RecordId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.log {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ":{}", self.offset)
+    }
+}
+
+impl FromStr for RecordId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Other(format!("Invalid record ID '{}': expected <16 hex digits>:<offset>", s));
+        let (hash, offset) = s.split_once(':').ok_or_else(invalid)?;
+        if hash.len() != 16 || !hash.is_ascii() {
+            return Err(invalid());
+        }
+        let mut log = [0; 8];
+        for (i, byte) in log.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hash[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+        }
+        let offset = offset.parse().map_err(|_| invalid())?;
+        Ok(Self { log, offset })
+    }
+}
@@ -0,0 +1,287 @@
+//! Row reduction applied before export.
+//!
+//! Many entries (status flags, mode enums, slowly-changing sensors) are logged
+//! every loop but rarely change. [`ChangeFilter`] keeps only their change points
+//! plus the final sample, which can shrink outputs dramatically.
+
+use crate::error::{Error, Result};
+use crate::models::{LongRow, WideRow};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Keeps only value transitions for selected entries.
+///
+/// For each filtered column the first sample, every sample whose value differs
+/// from the previous kept one, and the last sample are kept; other cells are
+/// dropped. A row whose cells were all dropped is removed entirely.
+///
+/// Numeric entries can be filtered with an epsilon ("on-change" semantics, as
+/// NT4 does): a sample is dropped when it differs from the previous kept value
+/// by less than epsilon. Identical values are always considered unchanged.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{ChangeFilter, ParquetWriter, WpilogReader};
+///
+/// let records = WpilogReader::from_file("data.wpilog")?.read_all()?;
+///
+/// ParquetWriter::new("./output")
+///     .change_filter(ChangeFilter::new().booleans(true).entry("/Robot/Mode"))
+///     .write(&records)?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChangeFilter {
+    booleans: bool,
+    entries: HashSet<String>,
+    numeric_epsilon: Option<f64>,
+    entry_epsilons: HashMap<String, f64>,
+}
+
+/// How a filtered cell is compared against the previous kept value.
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    Exact,
+    Epsilon(f64),
+}
+
+impl ChangeFilter {
+    /// Create a filter that keeps every sample.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only change points for all boolean entries.
+    pub fn booleans(mut self, enabled: bool) -> Self {
+        self.booleans = enabled;
+        self
+    }
+
+    /// Keep only change points for the named entry (e.g., an enum logged as
+    /// `int64` or `string`).
+    pub fn entry(mut self, name: impl Into<String>) -> Self {
+        self.entries.insert(name.into());
+        self
+    }
+
+    /// Drop samples of all numeric scalar entries that changed by less than
+    /// `epsilon` since the last kept sample.
+    pub fn numeric_epsilon(mut self, epsilon: f64) -> Self {
+        self.numeric_epsilon = Some(epsilon);
+        self
+    }
+
+    /// Drop samples of the named numeric entry that changed by less than
+    /// `epsilon`. Overrides [`numeric_epsilon`](Self::numeric_epsilon).
+    pub fn entry_epsilon(mut self, name: impl Into<String>, epsilon: f64) -> Self {
+        self.entry_epsilons.insert(name.into(), epsilon);
+        self
+    }
+
+    fn rule(&self, column: &str, value: &Value) -> Option<Rule> {
+        if value.is_number() {
+            if let Some(&epsilon) = self.entry_epsilons.get(column) {
+                return Some(Rule::Epsilon(epsilon));
+            }
+        }
+        if (self.booleans && value.is_boolean()) || self.entries.contains(column) {
+            return Some(Rule::Exact);
+        }
+        match self.numeric_epsilon {
+            Some(epsilon) if value.is_number() => Some(Rule::Epsilon(epsilon)),
+            _ => None,
+        }
+    }
+
+    /// Apply the filter to `records`, preserving row order.
+    pub fn apply(&self, records: &[WideRow]) -> Vec<WideRow> {
+        // Index of the last row containing each filtered column (endpoints are kept)
+        let mut last_index: HashMap<&str, usize> = HashMap::new();
+        for (i, record) in records.iter().enumerate() {
+            for (column, value) in &record.data {
+                if self.rule(column, value).is_some() {
+                    last_index.insert(column, i);
+                }
+            }
+        }
+
+        let mut previous: HashMap<&str, &Value> = HashMap::new();
+        let mut output = Vec::with_capacity(records.len());
+
+        for (i, record) in records.iter().enumerate() {
+            let mut row = record.clone();
+            let mut dropped = false;
+
+            for (column, value) in &record.data {
+                let Some(rule) = self.rule(column, value) else {
+                    continue;
+                };
+                let changed = match previous.get(column.as_str()) {
+                    None => true,
+                    Some(prev) => has_changed(rule, prev, value),
+                };
+                if changed || last_index.get(column.as_str()) == Some(&i) {
+                    previous.insert(column, value);
+                } else {
+                    row.data.remove(column);
+                    dropped = true;
+                }
+            }
+
+            if !(dropped && row.data.is_empty()) {
+                output.push(row);
+            }
+        }
+
+        output
+    }
+}
+
+fn has_changed(rule: Rule, previous: &Value, value: &Value) -> bool {
+    match (rule, previous.as_f64(), value.as_f64()) {
+        (Rule::Epsilon(epsilon), Some(a), Some(b)) => {
+            let delta = (b - a).abs();
+            delta != 0.0 && delta >= epsilon
+        }
+        _ => previous != value,
+    }
+}
+
+/// Limits how often each column is sampled.
+///
+/// A cell is kept only if at least `1 / hz` seconds have passed since the last
+/// kept cell of the same column; the first sample of every column is kept.
+/// Rows left without cells are removed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Downsample {
+    min_interval: f64,
+}
+
+impl Downsample {
+    /// Keep at most `hz` samples per second of each column.
+    pub fn max_rate(hz: f64) -> Self {
+        Self {
+            min_interval: 1.0 / hz,
+        }
+    }
+
+    /// Apply the downsampling to `records`, preserving row order.
+    pub fn apply(&self, records: &[WideRow]) -> Vec<WideRow> {
+        let mut last_kept = HashMap::new();
+        records
+            .iter()
+            .filter_map(|record| self.filter_row(&mut last_kept, record.clone()))
+            .collect()
+    }
+
+    /// Apply the downsampling to long `records`, treating each entry as a
+    /// column.
+    pub fn apply_long(&self, records: &[LongRow]) -> Vec<LongRow> {
+        let mut last_kept: HashMap<&str, f64> = HashMap::new();
+        records
+            .iter()
+            .filter(|record| match last_kept.get(record.entry_name.as_str()) {
+                Some(&last) if record.timestamp - last < self.min_interval - 5e-7 => false,
+                _ => {
+                    last_kept.insert(&record.entry_name, record.timestamp);
+                    true
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Drop the cells of `row` that come too soon after the last kept cell of
+    /// their column, tracked in `last_kept`. Returns `None` if no cells remain.
+    pub(crate) fn filter_row(
+        &self,
+        last_kept: &mut HashMap<String, f64>,
+        mut row: WideRow,
+    ) -> Option<WideRow> {
+        row.data.retain(|column, _| match last_kept.get(column) {
+            // Half a microsecond of slack absorbs rounding in the seconds timestamps
+            Some(&last) if row.timestamp - last < self.min_interval - 5e-7 => false,
+            _ => {
+                last_kept.insert(column.clone(), row.timestamp);
+                true
+            }
+        });
+        (!row.data.is_empty()).then_some(row)
+    }
+}
+
+/// Keeps every Nth data record of each entry, for a small but representative
+/// preview of a conversion.
+///
+/// Records are counted per entry, and the first record of every entry is
+/// kept, so rarely logged entries still show up. Skipped records are not
+/// decoded, so a preview is much faster than a full read.
+///
+/// Parses from a percentage (`1%` keeps every 100th record) or a record
+/// interval (`50` keeps every 50th).
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{Subsample, WpilogReaderBuilder};
+///
+/// let preview = WpilogReaderBuilder::new()
+///     .subsample("1%".parse::<Subsample>()?)
+///     .from_file("data.wpilog")?
+///     .read_all()?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subsample {
+    every: u64,
+}
+
+impl Subsample {
+    /// Keep every `n`th record of each entry (`0` is treated as `1`).
+    pub fn every_nth(n: u64) -> Self {
+        Self { every: n.max(1) }
+    }
+
+    /// Keep about `fraction` (`0.01` for 1%) of each entry's records.
+    pub fn fraction(fraction: f64) -> Self {
+        Self::every_nth((1.0 / fraction).round() as u64)
+    }
+
+    /// Interval between kept records.
+    pub fn every(&self) -> u64 {
+        self.every
+    }
+
+    /// Whether to keep an entry's record with the given 0-based index.
+    pub(crate) fn keeps(&self, index: u64) -> bool {
+        index.is_multiple_of(self.every)
+    }
+}
+
+impl FromStr for Subsample {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            Error::Other(format!(
+                "Invalid sample '{}': expected a percentage such as 1% or a record interval such as 100",
+                s
+            ))
+        };
+        match s.trim().strip_suffix('%') {
+            Some(percent) => {
+                let percent: f64 = percent.trim().parse().map_err(|_| invalid())?;
+                if !(percent > 0.0 && percent <= 100.0) {
+                    return Err(invalid());
+                }
+                Ok(Self::fraction(percent / 100.0))
+            }
+            None => match s.trim().parse::<u64>() {
+                Ok(n) if n > 0 => Ok(Self::every_nth(n)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Real-time playback of a log.
+//!
+//! [`Replayer`] streams decoded rows and sleeps between them so they are
+//! delivered at the pace they were logged, optionally sped up or slowed down.
+//! Simulation and dashboard tools can consume a replay as if the robot were
+//! live.
+
+use crate::datalog::{MetadataRecordData, RecordVisitor, StartRecordData};
+use crate::error::{Error, Result};
+use crate::models::WideRow;
+use crate::reader::WpilogReader;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Replays a log's rows according to their timestamps.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::mpsc;
+/// use std::thread;
+/// use wpilog_parser::{Replayer, WpilogReader};
+///
+/// let (tx, rx) = mpsc::channel();
+/// let handle = thread::spawn(move || {
+///     let reader = WpilogReader::from_file("match.wpilog")?;
+///     Replayer::new(&reader).speed(2.0).run_to_channel(tx)
+/// });
+///
+/// for row in rx {
+///     println!("{:.3}: {:?}", row.timestamp, row.data);
+/// }
+/// handle.join().unwrap()?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub struct Replayer<'r> {
+    reader: &'r WpilogReader,
+    speed: f64,
+}
+
+impl<'r> Replayer<'r> {
+    /// Create a replayer that plays `reader` in real time.
+    pub fn new(reader: &'r WpilogReader) -> Self {
+        Self { reader, speed: 1.0 }
+    }
+
+    /// Playback speed multiplier; `2.0` plays twice as fast as real time.
+    /// `f64::INFINITY` emits rows without waiting.
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Replay the log, passing each row to `sink` when its time comes.
+    ///
+    /// The first row is emitted immediately and later rows are delayed by
+    /// their offset from it divided by the speed. Rows whose timestamp goes
+    /// backwards are emitted without waiting. Returning an error from `sink`
+    /// stops playback and propagates the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the speed is not positive, or if the log cannot be
+    /// parsed.
+    pub fn run<F>(&self, mut sink: F) -> Result<()>
+    where
+        F: FnMut(WideRow) -> Result<()>,
+    {
+        let mut pacer = Pacer::new(self.speed)?;
+        self.reader.stream(|row| {
+            pacer.wait(row.timestamp);
+            sink(row)
+        })
+    }
+
+    /// Replay the raw records of the log through a [`RecordVisitor`].
+    ///
+    /// Data records are paced like [`run`](Self::run); control records are
+    /// passed on as soon as they are reached. Payloads are not decoded, which
+    /// suits consumers that forward values as logged, such as
+    /// [`Nt4Publisher`](crate::nt4::Nt4Publisher).
+    pub fn visit<V: RecordVisitor>(&self, visitor: &mut V) -> Result<()> {
+        let mut paced = PacedVisitor {
+            inner: visitor,
+            pacer: Pacer::new(self.speed)?,
+        };
+        self.reader.low_level_reader().visit(&mut paced)
+    }
+
+    /// Replay the log into a channel. Playback stops quietly when the receiver
+    /// is dropped.
+    pub fn run_to_channel(&self, sender: Sender<WideRow>) -> Result<()> {
+        let mut disconnected = false;
+        let result = self.run(|row| {
+            sender.send(row).map_err(|_| {
+                disconnected = true;
+                Error::Other("Replay receiver disconnected".to_string())
+            })
+        });
+        if disconnected {
+            return Ok(());
+        }
+        result
+    }
+}
+
+/// Sleeps until each timestamp is due relative to the first one seen.
+struct Pacer {
+    speed: f64,
+    origin: Option<(Instant, f64)>,
+}
+
+impl Pacer {
+    fn new(speed: f64) -> Result<Self> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(Error::Other(format!(
+                "Replay speed must be positive, got {}",
+                speed
+            )));
+        }
+        Ok(Self { speed, origin: None })
+    }
+
+    /// Wait for `timestamp` (seconds).
+    fn wait(&mut self, timestamp: f64) {
+        let (started, first_timestamp) = *self.origin.get_or_insert((Instant::now(), timestamp));
+        let offset = (timestamp - first_timestamp) / self.speed;
+        if offset.is_finite() && offset > 0.0 {
+            let due = started + Duration::from_secs_f64(offset);
+            let now = Instant::now();
+            if due > now {
+                thread::sleep(due - now);
+            }
+        }
+    }
+}
+
+struct PacedVisitor<'v, V> {
+    inner: &'v mut V,
+    pacer: Pacer,
+}
+
+impl<V: RecordVisitor> RecordVisitor for PacedVisitor<'_, V> {
+    fn on_start(&mut self, start: &StartRecordData, timestamp: u64) -> Result<()> {
+        self.inner.on_start(start, timestamp)
+    }
+
+    fn on_finish(&mut self, entry: u32, timestamp: u64) -> Result<()> {
+        self.inner.on_finish(entry, timestamp)
+    }
+
+    fn on_set_metadata(&mut self, metadata: &MetadataRecordData, timestamp: u64) -> Result<()> {
+        self.inner.on_set_metadata(metadata, timestamp)
+    }
+
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        self.pacer.wait(timestamp as f64 / 1_000_000.0);
+        self.inner.on_data(entry, timestamp, payload)
+    }
+}
@@ -0,0 +1,393 @@
+//! Rewriting `.wpilog` files.
+//!
+//! [`LogRewriter`] trims a log to a time window and/or a set of entries and
+//! writes the surviving records to a new, smaller log. Entries left without any
+//! data records are dropped, and the remaining entry IDs are compacted into a
+//! dense range starting at 1.
+//!
+//! A rewrite can also add checksums so later corruption or tampering can be
+//! detected with [`verify_checksums`]. The extra header gets a
+//! [`CHECKSUM_HEADER_MARKER`] line, and a [`CHECKSUM_ENTRY`] entry of type
+//! `crc32` gets a record after every few records. Each checksum record's
+//! payload is the number of records it covers (`u32`) followed by the CRC-32
+//! of their bytes (`u32`), both little-endian. A block covers everything
+//! written since the previous checksum record; the first one also covers the
+//! file header.
+
+use crate::datalog::{length_prefix, start_payload, write_record, DataLogReader, DataLogRecord};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Extra header line declaring that a log carries checksum records.
+pub const CHECKSUM_HEADER_MARKER: &str = "wpilog-checksums=crc32";
+
+/// Name of the entry holding checksum records.
+pub const CHECKSUM_ENTRY: &str = "/.checksum";
+
+/// Declared type of the checksum entry.
+const CHECKSUM_TYPE: &str = "crc32";
+
+/// Trims a log and compacts its entry IDs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{LogRewriter, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("match.wpilog")?;
+/// let trimmed = LogRewriter::new()
+///     .time_range(15_000_000, 30_000_000)
+///     .entry("/Drive/LeftVelocity")
+///     .rewrite(&reader.low_level_reader())?;
+///
+/// std::fs::write("auto.wpilog", &trimmed.data)?;
+/// for (old, new) in &trimmed.entry_remap {
+///     println!("entry {} -> {}", old, new);
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogRewriter {
+    start: Option<u64>,
+    end: Option<u64>,
+    entries: HashSet<String>,
+    checksum_interval: Option<usize>,
+}
+
+/// Output of [`LogRewriter::rewrite`].
+#[derive(Debug, Clone)]
+pub struct RewrittenLog {
+    /// The rewritten log, including the original header
+    pub data: Vec<u8>,
+    /// Original entry ID -> entry ID in the rewritten log
+    pub entry_remap: BTreeMap<u32, u32>,
+    /// Original entry IDs dropped because none of their data records survived
+    pub dropped_entries: Vec<u32>,
+    /// Number of data records written
+    pub records: usize,
+}
+
+impl LogRewriter {
+    /// Create a rewriter that keeps every data record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only data records with `start <= timestamp < end` (microseconds).
+    pub fn time_range(mut self, start: u64, end: u64) -> Self {
+        self.start = Some(start);
+        self.end = Some(end);
+        self
+    }
+
+    /// Keep the named entry. When no entries are named, all are kept.
+    pub fn entry(mut self, name: impl Into<String>) -> Self {
+        self.entries.insert(name.into());
+        self
+    }
+
+    /// Write a checksum record after every `interval` records (1 checksums
+    /// each record). See the [module docs](self) for the layout.
+    pub fn checksums(mut self, interval: usize) -> Self {
+        self.checksum_interval = Some(interval.max(1));
+        self
+    }
+
+    fn keeps(&self, name: &str, timestamp: u64) -> bool {
+        (self.entries.is_empty() || self.entries.contains(name))
+            && self.start.is_none_or(|start| timestamp >= start)
+            && self.end.is_none_or(|end| timestamp < end)
+    }
+
+    /// Rewrite the log read by `reader`.
+    ///
+    /// Control records are kept with their original timestamps for every entry
+    /// that still has data, so values outside the time range keep their Start
+    /// record and metadata. Data records for entries that were never started
+    /// are dropped.
+    pub fn rewrite(&self, reader: &DataLogReader) -> Result<RewrittenLog> {
+        if !reader.is_valid() {
+            return Err(Error::InvalidFormat("Not a valid WPILOG file".to_string()));
+        }
+
+        // First pass: which entry lifetimes (numbered by Start record) have data left
+        let mut lifetimes = Lifetimes::default();
+        let mut starts: Vec<(u32, String)> = Vec::new();
+        let mut surviving: HashSet<usize> = HashSet::new();
+        for record in reader.records()?.allow_truncated() {
+            let record = record?;
+            if record.is_start() {
+                let start = record.get_start_data()?;
+                lifetimes.start(start.entry);
+                starts.push((start.entry, start.name));
+            } else if let Some(lifetime) = lifetimes.track(&record)? {
+                if !record.is_control() && self.keeps(&starts[lifetime].1, record.timestamp) {
+                    surviving.insert(lifetime);
+                }
+            }
+        }
+
+        // Dense IDs in order of each entry's first surviving Start record
+        let mut entry_remap = BTreeMap::new();
+        let mut dropped_entries = Vec::new();
+        for (lifetime, (entry, _)) in starts.iter().enumerate() {
+            if surviving.contains(&lifetime) && !entry_remap.contains_key(entry) {
+                let next = entry_remap.len() as u32 + 1;
+                entry_remap.insert(*entry, next);
+            }
+        }
+        for (entry, _) in &starts {
+            if !entry_remap.contains_key(entry) && !dropped_entries.contains(entry) {
+                dropped_entries.push(*entry);
+            }
+        }
+
+        // Second pass: copy the header and the surviving records
+        let mut data = reader.bytes()[..reader.first_record_pos()?].to_vec();
+        let mut checksums = self.checksum_interval.map(|interval| -> Result<ChecksumWriter> {
+            let entry = entry_remap.len() as u32 + 1;
+            let mut extra_header = reader.get_extra_header();
+            if !extra_header.is_empty() {
+                extra_header.push('\n');
+            }
+            extra_header.push_str(CHECKSUM_HEADER_MARKER);
+            data.truncate(8);
+            data.extend_from_slice(&length_prefix(extra_header.len(), "extra header")?.to_le_bytes());
+            data.extend_from_slice(extra_header.as_bytes());
+            let metadata = format!("{{\"interval\":{}}}", interval);
+            write_record(&mut data, 0, 0, &start_payload(entry, CHECKSUM_ENTRY, CHECKSUM_TYPE, &metadata)?)?;
+            Ok(ChecksumWriter {
+                entry,
+                interval,
+                block_start: 0,
+                block_records: 1,
+                last_timestamp: 0,
+            })
+        }).transpose()?;
+        let mut records = 0;
+        let mut lifetimes = Lifetimes::default();
+        for record in reader.records()?.allow_truncated() {
+            let record = record?;
+            let (lifetime, entry) = if record.is_start() {
+                let entry = record.get_start_data()?.entry;
+                (lifetimes.start(entry), entry)
+            } else {
+                match (lifetimes.track(&record)?, control_entry(&record)) {
+                    (Some(lifetime), Some(entry)) => (lifetime, entry),
+                    (Some(lifetime), None) => {
+                        if !self.keeps(&starts[lifetime].1, record.timestamp) {
+                            continue;
+                        }
+                        (lifetime, record.entry)
+                    }
+                    (None, _) => continue,
+                }
+            };
+            if !surviving.contains(&lifetime) {
+                continue;
+            }
+
+            let new_entry = entry_remap[&entry];
+            if record.is_control() {
+                // Control payloads carry the target entry ID in bytes 1..5
+                let mut payload = record.data.clone();
+                payload[1..5].copy_from_slice(&new_entry.to_le_bytes());
+                write_record(&mut data, 0, record.timestamp, &payload)?;
+            } else {
+                write_record(&mut data, new_entry, record.timestamp, &record.data)?;
+                records += 1;
+            }
+            if let Some(checksums) = &mut checksums {
+                checksums.record_written(&mut data, record.timestamp)?;
+            }
+        }
+        if let Some(checksums) = &mut checksums {
+            checksums.flush(&mut data)?;
+        }
+
+        Ok(RewrittenLog {
+            data,
+            entry_remap,
+            dropped_entries,
+            records,
+        })
+    }
+}
+
+/// Appends checksum records to a log being rewritten.
+struct ChecksumWriter {
+    entry: u32,
+    interval: usize,
+    /// Offset where the current block starts
+    block_start: usize,
+    /// Records written since the last checksum record
+    block_records: u32,
+    /// Timestamp of the last record written, reused by checksum records
+    last_timestamp: u64,
+}
+
+impl ChecksumWriter {
+    fn record_written(&mut self, data: &mut Vec<u8>, timestamp: u64) -> Result<()> {
+        self.block_records += 1;
+        self.last_timestamp = timestamp;
+        if self.block_records as usize >= self.interval {
+            self.flush(data)?;
+        }
+        Ok(())
+    }
+
+    /// Write a checksum record for the current block, if it has records.
+    fn flush(&mut self, data: &mut Vec<u8>) -> Result<()> {
+        if self.block_records == 0 {
+            return Ok(());
+        }
+        let mut payload = self.block_records.to_le_bytes().to_vec();
+        payload.extend_from_slice(&crc32fast::hash(&data[self.block_start..]).to_le_bytes());
+        write_record(data, self.entry, self.last_timestamp, &payload)?;
+        self.block_start = data.len();
+        self.block_records = 0;
+        Ok(())
+    }
+}
+
+/// Result of [`verify_checksums`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChecksumReport {
+    /// Whether the extra header declares checksums
+    pub declared: bool,
+    /// Number of checksum records whose block matched
+    pub verified_blocks: usize,
+    /// Byte offsets of checksum records whose block did not match
+    pub mismatches: Vec<usize>,
+    /// Records after the last checksum record, which no checksum covers
+    pub unverified_records: usize,
+    /// Error that ended the walk early, e.g. a corrupted record header
+    pub error: Option<String>,
+}
+
+impl ChecksumReport {
+    /// Whether the log declares checksums and every record matched one.
+    pub fn is_ok(&self) -> bool {
+        self.declared
+            && self.verified_blocks > 0
+            && self.mismatches.is_empty()
+            && self.unverified_records == 0
+            && self.error.is_none()
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Check the checksum records written by [`LogRewriter::checksums`].
+///
+/// A log without the [`CHECKSUM_HEADER_MARKER`] is reported as undeclared
+/// rather than failing, so callers can tell unprotected logs apart from
+/// corrupted ones.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{verify_checksums, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("trimmed.wpilog")?;
+/// let report = verify_checksums(&reader.low_level_reader())?;
+/// if !report.is_ok() {
+///     eprintln!("{}", report.to_json()?);
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub fn verify_checksums(reader: &DataLogReader) -> Result<ChecksumReport> {
+    let mut report = ChecksumReport {
+        declared: reader
+            .get_extra_header()
+            .lines()
+            .any(|line| line == CHECKSUM_HEADER_MARKER),
+        ..ChecksumReport::default()
+    };
+    let data = reader.bytes();
+    let mut checksum_entry = None;
+    let mut block_start = 0;
+    let mut block_records = 0u32;
+
+    let mut records = reader.records()?;
+    loop {
+        let offset = records.position();
+        let record = match records.next() {
+            Some(Ok(record)) => record,
+            Some(Err(e)) => {
+                report.error = Some(e.to_string());
+                break;
+            }
+            None => break,
+        };
+
+        if checksum_entry.is_some() && Some(record.entry) == checksum_entry {
+            let expected = (record.data.len() == 8).then(|| {
+                let count = u32::from_le_bytes(record.data[..4].try_into().unwrap_or_default());
+                let crc = u32::from_le_bytes(record.data[4..].try_into().unwrap_or_default());
+                (count, crc)
+            });
+            if expected == Some((block_records, crc32fast::hash(&data[block_start..offset]))) {
+                report.verified_blocks += 1;
+            } else {
+                report.mismatches.push(offset);
+            }
+            block_start = records.position();
+            block_records = 0;
+            continue;
+        }
+
+        if record.is_start() {
+            if let Ok(start) = record.get_start_data() {
+                if start.name == CHECKSUM_ENTRY && start.type_name == CHECKSUM_TYPE {
+                    checksum_entry = Some(start.entry);
+                }
+            }
+        }
+        block_records += 1;
+    }
+    report.unverified_records = block_records as usize;
+    Ok(report)
+}
+
+/// Target entry of a Finish or Set Metadata record.
+fn control_entry(record: &DataLogRecord) -> Option<u32> {
+    if record.is_finish() || record.is_set_metadata() {
+        Some(u32::from_le_bytes(record.data[1..5].try_into().ok()?))
+    } else {
+        None
+    }
+}
+
+/// Tracks which lifetime (0-based Start record index) each entry ID is in.
+#[derive(Default)]
+struct Lifetimes {
+    current: HashMap<u32, usize>,
+    count: usize,
+}
+
+impl Lifetimes {
+    fn start(&mut self, entry: u32) -> usize {
+        let lifetime = self.count;
+        self.current.insert(entry, lifetime);
+        self.count += 1;
+        lifetime
+    }
+
+    /// Lifetime a non-Start record belongs to, ending it on Finish.
+    fn track(&mut self, record: &DataLogRecord) -> Result<Option<usize>> {
+        if record.is_finish() {
+            return Ok(self.current.remove(&record.get_finish_entry()?));
+        }
+        let entry = match control_entry(record) {
+            Some(entry) => entry,
+            None if record.is_control() => return Ok(None),
+            None => record.entry,
+        };
+        Ok(self.current.get(&entry).copied())
+    }
+}
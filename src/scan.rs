@@ -0,0 +1,98 @@
+//! Query-style reads of selected entries and time ranges.
+//!
+//! [`Scan`] is the entry point for analytic reads: pick entries with globs,
+//! restrict the time range, and get the result as rows or as a typed Arrow
+//! batch. The scan is planned from a header scan of the log, so entries that
+//! are not selected are skipped without copying or decoding their payloads,
+//! and records outside the time range are never decoded.
+
+use crate::error::Result;
+use crate::formats::parquet::ParquetFormatter;
+use crate::models::WideRow;
+use crate::profile::EntrySelector;
+use crate::reader::WpilogReader;
+use arrow::record_batch::RecordBatch;
+use std::ops::{Bound, Range, RangeBounds};
+
+/// A read of selected entries and times, created by
+/// [`WpilogReader::scan`].
+///
+/// Reader options such as aliasing, filter profiles and quirks apply as for
+/// [`read_all`](WpilogReader::read_all); a profile's entry selectors are
+/// combined with [`select`](Self::select).
+pub struct Scan<'r> {
+    reader: &'r WpilogReader,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    time_range: Option<Range<u64>>,
+}
+
+impl<'r> Scan<'r> {
+    pub(crate) fn new(reader: &'r WpilogReader) -> Self {
+        Self {
+            reader,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            time_range: None,
+        }
+    }
+
+    /// Read only entries matching any of these globs (as in
+    /// [`EntrySelector`]). All entries are read if none are given.
+    pub fn select<I, S>(mut self, globs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.include.extend(globs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Skip entries matching any of these globs.
+    pub fn exclude<I, S>(mut self, globs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclude.extend(globs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Read only data records with timestamps in `range` (microseconds).
+    pub fn filter_time(mut self, range: impl RangeBounds<u64>) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => u64::MAX,
+        };
+        self.time_range = Some(start..end);
+        self
+    }
+
+    /// Run the scan, returning wide rows in log order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a glob is invalid or the log cannot be parsed.
+    pub fn to_rows(&self) -> Result<Vec<WideRow>> {
+        let selector = if self.include.is_empty() && self.exclude.is_empty() {
+            None
+        } else {
+            Some(EntrySelector::new(&self.include, &self.exclude)?)
+        };
+        self.reader.read_scan(selector.as_ref(), self.time_range.clone())
+    }
+
+    /// Run the scan, returning one Arrow batch in the same layout as the
+    /// Parquet output: `timestamp`, `entry`, `type` and `loop_count`, then one
+    /// typed column per selected entry (or struct field), sorted by name.
+    pub fn to_arrow(&self) -> Result<RecordBatch> {
+        let rows = self.to_rows()?;
+        ParquetFormatter::new(String::new(), rows.len().max(1)).record_batch(&rows)
+    }
+}
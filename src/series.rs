@@ -0,0 +1,73 @@
+//! Typed time series of a single entry, for plotting.
+//!
+//! [`WpilogReader::entry_series`](crate::WpilogReader::entry_series) decodes
+//! only the named entry, as [`read_entry`](crate::WpilogReader::read_entry)
+//! does, and converts each value to the requested type with serde, so scalar
+//! entries read as `f64` or `bool`, arrays as `Vec<f64>`, and struct entries
+//! as a map of field names or a `#[derive(Deserialize)]` struct.
+
+use crate::error::{Error, Result};
+use crate::models::WideRow;
+use crate::record_id::RECORD_ID_COLUMN;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Timestamps and values of one entry, in log order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSeries<T> {
+    /// Row timestamps in seconds, as in [`WideRow::timestamp`]
+    pub timestamps: Vec<f64>,
+    /// Value at each timestamp
+    pub values: Vec<T>,
+}
+
+impl<T> TimeSeries<T> {
+    /// Number of samples.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the series has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterate over `(timestamp, value)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (f64, &T)> {
+        self.timestamps.iter().copied().zip(&self.values)
+    }
+
+    /// Latest sample at or before `timestamp` (seconds), if any.
+    pub fn value_at(&self, timestamp: f64) -> Option<&T> {
+        let index = self.timestamps.partition_point(|&t| t <= timestamp);
+        index.checked_sub(1).map(|i| &self.values[i])
+    }
+}
+
+impl<T: DeserializeOwned> TimeSeries<T> {
+    /// Collect the rows decoded for entry `name`, converting each value.
+    pub(crate) fn from_rows(name: &str, rows: Vec<WideRow>) -> Result<Self> {
+        let mut series = Self {
+            timestamps: Vec::with_capacity(rows.len()),
+            values: Vec::with_capacity(rows.len()),
+        };
+        for row in rows {
+            let value = serde_json::from_value(row_value(row.data)).map_err(|e| {
+                Error::TypeMismatch(format!("entry '{}' at {}s: {}", name, row.timestamp, e))
+            })?;
+            series.timestamps.push(row.timestamp);
+            series.values.push(value);
+        }
+        Ok(series)
+    }
+}
+
+/// The value a row holds for its entry: its only column, or an object of
+/// its columns when a JSON value was flattened into several.
+fn row_value(mut data: std::collections::HashMap<String, Value>) -> Value {
+    data.remove(RECORD_ID_COLUMN);
+    if data.len() == 1 {
+        return data.into_values().next().unwrap_or(Value::Null);
+    }
+    Value::Object(data.into_iter().collect::<Map<String, Value>>())
+}
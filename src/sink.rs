@@ -0,0 +1,155 @@
+//! Destinations for written output files.
+//!
+//! Writers name each file they produce (e.g. `file_part000.parquet`) and ask
+//! an [`OutputSink`] for somewhere to put its bytes. [`DirectorySink`] writes
+//! into a directory on disk; [`MemorySink`] keeps the files in memory, for
+//! tests and targets without a filesystem such as WASM. Any closure mapping a
+//! file name to an [`io::Write`] is a sink too:
+//!
+//! ```no_run
+//! use std::io;
+//! use wpilog_parser::{ParquetWriter, WpilogReader};
+//!
+//! let records = WpilogReader::from_file("data.wpilog")?.read_all()?;
+//! ParquetWriter::to_sink(|name: &str| {
+//!     println!("writing {}", name);
+//!     Ok(io::sink())
+//! })
+//! .write(&records)?;
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+
+use crate::error::Result;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Factory for the files a writer produces.
+///
+/// Files may be created from several threads at once when writing in
+/// parallel, hence the `Send + Sync` bound.
+pub trait OutputSink: Send + Sync {
+    /// Open a new file called `name` for writing, replacing any file of that
+    /// name.
+    fn create(&self, name: &str) -> Result<Box<dyn Write + Send>>;
+}
+
+impl<F, W> OutputSink for F
+where
+    F: Fn(&str) -> io::Result<W> + Send + Sync,
+    W: Write + Send + 'static,
+{
+    fn create(&self, name: &str) -> Result<Box<dyn Write + Send>> {
+        Ok(Box::new(self(name)?))
+    }
+}
+
+/// Writes files into a directory, creating it if needed.
+#[derive(Debug, Clone)]
+pub struct DirectorySink {
+    directory: PathBuf,
+}
+
+impl DirectorySink {
+    /// Write files into `directory`.
+    pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The directory files are written into.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+}
+
+impl OutputSink for DirectorySink {
+    fn create(&self, name: &str) -> Result<Box<dyn Write + Send>> {
+        fs::create_dir_all(&self.directory)?;
+        Ok(Box::new(File::create(self.directory.join(name))?))
+    }
+}
+
+/// Keeps written files in memory, by name.
+///
+/// Clones share the same files, so keep a clone to read them back after the
+/// writer that consumed the sink is done.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{MemorySink, ParquetWriter, WpilogReader};
+///
+/// let records = WpilogReader::from_file("data.wpilog")?.read_all()?;
+/// let sink = MemorySink::new();
+/// ParquetWriter::to_sink(sink.clone()).write(&records)?;
+/// for (name, bytes) in sink.files() {
+///     println!("{}: {} bytes", name, bytes.len());
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    files: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+}
+
+impl MemorySink {
+    /// Create an empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A copy of every file written so far, by name.
+    pub fn files(&self) -> BTreeMap<String, Vec<u8>> {
+        self.lock().clone()
+    }
+
+    /// A copy of the file called `name`, if it has been written.
+    pub fn get(&self, name: &str) -> Option<Vec<u8>> {
+        self.lock().get(name).cloned()
+    }
+
+    /// Names of the files written so far, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        // A panicking writer leaves at worst a partial file behind
+        self.files.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl OutputSink for MemorySink {
+    fn create(&self, name: &str) -> Result<Box<dyn Write + Send>> {
+        self.lock().insert(name.to_string(), Vec::new());
+        Ok(Box::new(MemoryFile {
+            sink: self.clone(),
+            name: name.to_string(),
+        }))
+    }
+}
+
+/// A file being written into a [`MemorySink`].
+struct MemoryFile {
+    sink: MemorySink,
+    name: String,
+}
+
+impl Write for MemoryFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.sink
+            .lock()
+            .entry(self.name.clone())
+            .or_default()
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+//! Golden snapshots of parsed logs, for regression-testing the parser against
+//! real robot logs.
+//!
+//! A [`LogSnapshot`] is a deterministic JSON rendering of everything a log
+//! decodes to. [`check_golden_dir`] compares every `.wpilog` in a directory
+//! against committed `<name>.json` snapshots, so format edge cases from real
+//! robots stay covered; users can run it on their own logs after upgrading.
+
+use crate::error::{Error, Result};
+use crate::models::WideRow;
+use crate::reader::WpilogReader;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Maximum number of differences [`LogSnapshot::diff`] reports.
+const MAX_DIFFERENCES: usize = 20;
+
+/// Everything a log decodes to, in a stable order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogSnapshot {
+    /// WPILog format version
+    pub version: u16,
+    /// Extra header string
+    pub extra_header: String,
+    /// Decoded rows, in log order
+    pub rows: Vec<SnapshotRow>,
+}
+
+/// A decoded row with its values sorted by column name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotRow {
+    pub timestamp: f64,
+    pub entry: u32,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub loop_count: u64,
+    pub data: BTreeMap<String, Value>,
+}
+
+impl From<WideRow> for SnapshotRow {
+    fn from(row: WideRow) -> Self {
+        Self {
+            timestamp: row.timestamp,
+            entry: row.entry,
+            type_name: row.type_name,
+            loop_count: row.loop_count,
+            data: row.data.into_iter().collect(),
+        }
+    }
+}
+
+impl LogSnapshot {
+    /// Decode every record of the log read by `reader`.
+    pub fn of_reader(reader: WpilogReader) -> Result<Self> {
+        let version = reader.version();
+        let extra_header = reader.extra_header();
+        let rows = reader.read_all()?.into_iter().map(SnapshotRow::from).collect();
+        Ok(Self {
+            version,
+            extra_header,
+            rows,
+        })
+    }
+
+    /// Decode the log at `path` with default options.
+    pub fn of_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::of_reader(WpilogReader::from_file(path)?)
+    }
+
+    /// Pretty-printed JSON, as committed to a golden file.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)? + "\n")
+    }
+
+    /// Parse a snapshot written by [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Differences from the `expected` snapshot, one line each, up to the
+    /// first 20. Empty if the snapshots match.
+    pub fn diff(&self, expected: &LogSnapshot) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.version != expected.version {
+            differences.push(format!("version: {:#06x} != expected {:#06x}", self.version, expected.version));
+        }
+        if self.extra_header != expected.extra_header {
+            differences.push(format!(
+                "extra header: {:?} != expected {:?}",
+                self.extra_header, expected.extra_header
+            ));
+        }
+        if self.rows.len() != expected.rows.len() {
+            differences.push(format!("rows: {} != expected {}", self.rows.len(), expected.rows.len()));
+        }
+        for (i, (row, expected)) in self.rows.iter().zip(&expected.rows).enumerate() {
+            if differences.len() >= MAX_DIFFERENCES {
+                break;
+            }
+            if row != expected {
+                differences.push(format!(
+                    "row {}: {} != expected {}",
+                    i,
+                    serde_json::to_string(row).unwrap_or_default(),
+                    serde_json::to_string(expected).unwrap_or_default()
+                ));
+            }
+        }
+        differences.truncate(MAX_DIFFERENCES);
+        differences
+    }
+}
+
+/// Outcome of checking a directory of logs against golden snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GoldenReport {
+    /// Logs whose snapshot matched
+    pub matched: Vec<String>,
+    /// Differences of each log whose snapshot did not match
+    pub mismatched: BTreeMap<String, Vec<String>>,
+    /// Logs without a snapshot
+    pub missing: Vec<String>,
+    /// Snapshots written because `update` was set
+    pub updated: Vec<String>,
+}
+
+impl GoldenReport {
+    /// Whether every log had a matching snapshot (or had it updated).
+    pub fn is_ok(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Compare every `.wpilog` in `logs_dir` with `<file stem>.json` in
+/// `snapshots_dir`.
+///
+/// With `update`, snapshots that are missing or differ are (re)written
+/// instead of being reported, e.g. after an intended change in decoding.
+/// Logs that fail to parse are reported as mismatches.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::snapshot::check_golden_dir;
+///
+/// let report = check_golden_dir("tests/golden/logs", "tests/golden/snapshots", false)?;
+/// for (log, differences) in &report.mismatched {
+///     println!("{}:\n  {}", log, differences.join("\n  "));
+/// }
+/// assert!(report.is_ok());
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub fn check_golden_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    logs_dir: P,
+    snapshots_dir: Q,
+    update: bool,
+) -> Result<GoldenReport> {
+    let logs_dir = logs_dir.as_ref();
+    let snapshots_dir = snapshots_dir.as_ref();
+    if !logs_dir.is_dir() {
+        return Err(Error::InvalidFormat(format!(
+            "'{}' is not a valid directory",
+            logs_dir.display()
+        )));
+    }
+
+    let mut logs: Vec<_> = fs::read_dir(logs_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("wpilog"))
+        .collect();
+    logs.sort();
+
+    let mut report = GoldenReport::default();
+    for log in logs {
+        let name = log
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let golden = snapshots_dir.join(format!("{}.json", name));
+
+        let actual = match LogSnapshot::of_file(&log) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                report.mismatched.insert(name, vec![format!("failed to parse: {}", e)]);
+                continue;
+            }
+        };
+        // Compare values as parsed back from JSON, like the golden file's
+        let actual = LogSnapshot::from_json(&actual.to_json()?)?;
+        let differences = match fs::read_to_string(&golden) {
+            Ok(json) => actual.diff(&LogSnapshot::from_json(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if !update {
+                    report.missing.push(name);
+                    continue;
+                }
+                vec!["no snapshot".to_string()]
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if differences.is_empty() {
+            report.matched.push(name);
+        } else if update {
+            fs::create_dir_all(snapshots_dir)?;
+            fs::write(&golden, actual.to_json()?)?;
+            report.updated.push(name);
+        } else {
+            report.mismatched.insert(name, differences);
+        }
+    }
+    Ok(report)
+}
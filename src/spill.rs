@@ -0,0 +1,139 @@
+//! Disk-backed staging of decoded rows for conversions larger than RAM.
+//!
+//! Decoded rows take many times the space of the `.wpilog` they came from. A
+//! [`SpillStore`] stages them in ZSTD-compressed temporary files, one per batch,
+//! so a conversion only holds a single batch (plus one output chunk) in memory.
+//! The files are removed when the store is dropped.
+
+use crate::error::Result;
+use crate::models::WideRow;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Default number of rows staged per spill file.
+pub const DEFAULT_SPILL_BATCH_ROWS: usize = 100_000;
+
+/// ZSTD level used for spill files; favors speed over ratio.
+const SPILL_COMPRESSION_LEVEL: i32 = 3;
+
+/// Rows staged on disk in compressed batches.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{ParquetWriter, SpillStore, WpilogReader};
+///
+/// let mut store = SpillStore::new()?;
+/// WpilogReader::from_file("data.wpilog")?.read_to_spill(&mut store)?;
+///
+/// ParquetWriter::new("./output").write_spilled(&store)?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SpillStore {
+    directory: TempDir,
+    batch_rows: usize,
+    files: Vec<PathBuf>,
+    pending: Vec<WideRow>,
+    len: usize,
+}
+
+impl SpillStore {
+    /// Create a store in the system temporary directory.
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_directory(TempDir::new()?))
+    }
+
+    /// Create a store in a new temporary directory under `parent`.
+    ///
+    /// Useful when the system temporary directory is on a small partition.
+    pub fn new_in<P: AsRef<Path>>(parent: P) -> Result<Self> {
+        Ok(Self::with_directory(TempDir::new_in(parent)?))
+    }
+
+    fn with_directory(directory: TempDir) -> Self {
+        Self {
+            directory,
+            batch_rows: DEFAULT_SPILL_BATCH_ROWS,
+            files: Vec::new(),
+            pending: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Set the number of rows held in memory before a batch is spilled.
+    ///
+    /// Default is [`DEFAULT_SPILL_BATCH_ROWS`].
+    pub fn batch_rows(mut self, rows: usize) -> Self {
+        self.batch_rows = rows.max(1);
+        self
+    }
+
+    /// Stage a row, spilling the current batch once it is full.
+    pub fn push(&mut self, row: WideRow) -> Result<()> {
+        self.pending.push(row);
+        self.len += 1;
+        if self.pending.len() >= self.batch_rows {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write any rows still held in memory to a spill file.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let path = self
+            .directory
+            .path()
+            .join(format!("batch{:05}.jsonl.zst", self.files.len()));
+        let file = BufWriter::new(File::create(&path)?);
+        let mut encoder = zstd::Encoder::new(file, SPILL_COMPRESSION_LEVEL)?;
+        for row in self.pending.drain(..) {
+            serde_json::to_writer(&mut encoder, &row)?;
+            encoder.write_all(b"\n")?;
+        }
+        encoder.finish()?.flush()?;
+
+        self.files.push(path);
+        Ok(())
+    }
+
+    /// Total number of rows staged.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no rows have been staged.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of spill files written so far.
+    pub fn spilled_batches(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Read the staged rows back, one batch at a time, in insertion order.
+    ///
+    /// Rows not yet flushed are returned as the final batch.
+    pub fn batches(&self) -> impl Iterator<Item = Result<Vec<WideRow>>> + '_ {
+        self.files
+            .iter()
+            .map(|path| read_batch(path))
+            .chain((!self.pending.is_empty()).then(|| Ok(self.pending.clone())))
+    }
+}
+
+fn read_batch(path: &Path) -> Result<Vec<WideRow>> {
+    let decoder = zstd::Decoder::new(File::open(path)?)?;
+    let mut rows = Vec::new();
+    for line in BufReader::new(decoder).lines() {
+        rows.push(serde_json::from_str(&line?)?);
+    }
+    Ok(rows)
+}
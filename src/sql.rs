@@ -0,0 +1,372 @@
+//! SQL over raw logs with DataFusion.
+//!
+//! [`WpilogTable`] is a DataFusion `TableProvider` over one or more logs, so
+//! they can be queried without converting them first:
+//!
+//! ```sql
+//! SELECT name, avg(value) FROM log WHERE name LIKE '/Drive%' GROUP BY name
+//! ```
+//!
+//! The table is long-format, with one row per value:
+//!
+//! | column      | type      |                                               |
+//! |-------------|-----------|-----------------------------------------------|
+//! | `log`       | `Utf8`    | file name of the log (empty for [`WpilogTable::new`]) |
+//! | `timestamp` | `Float64` | seconds                                       |
+//! | `name`      | `Utf8`    | column name, as in the wide output            |
+//! | `type`      | `Utf8`    | declared entry type                           |
+//! | `value`     | `Float64` | numeric and boolean (1/0) values              |
+//! | `text`      | `Utf8`    | strings; arrays and objects as JSON           |
+//!
+//! Filters on `name` (`=`, `IN`, `LIKE 'prefix%'`) and on `timestamp`
+//! (comparisons, `BETWEEN`) are pushed into the parser through
+//! [`Scan`](crate::Scan), so unselected entries and records outside the time
+//! range are never decoded. Name filters are matched against entry names as
+//! logged, so readers should not use
+//! [`alias_columns`](crate::WpilogReaderBuilder::alias_columns).
+//!
+//! Requires the `datafusion` feature.
+
+use crate::error::Result;
+use crate::reader::WpilogReader;
+use arrow::array::{ArrayRef, Float64Builder, RecordBatch, StringBuilder};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use async_trait::async_trait;
+use datafusion::catalog::Session;
+use datafusion::common::{DataFusionError, Result as DataFusionResult, ScalarValue};
+use datafusion::datasource::memory::MemorySourceConfig;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::logical_expr::expr::InList;
+use datafusion::logical_expr::{Between, BinaryExpr, Expr, Like, Operator, TableProviderFilterPushDown};
+use datafusion::physical_plan::ExecutionPlan;
+use std::any::Any;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+/// DataFusion table over the records of one or more logs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use datafusion::prelude::SessionContext;
+/// use wpilog_parser::sql::WpilogTable;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let ctx = SessionContext::new();
+/// ctx.register_table("log", Arc::new(WpilogTable::from_files(["match.wpilog"])?))?;
+/// ctx.sql("SELECT avg(value) FROM log WHERE name LIKE '/Drive%'")
+///     .await?
+///     .show()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct WpilogTable {
+    logs: Vec<(String, WpilogReader)>,
+    schema: SchemaRef,
+}
+
+impl WpilogTable {
+    /// Table over a single log.
+    pub fn new(reader: WpilogReader) -> Self {
+        Self {
+            logs: vec![(String::new(), reader)],
+            schema: table_schema(),
+        }
+    }
+
+    /// Table over several logs, with each file name in the `log` column.
+    pub fn from_files<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Result<Self> {
+        let logs = paths
+            .into_iter()
+            .map(|path| {
+                let path = path.as_ref();
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                Ok((name, WpilogReader::from_file(path)?))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            logs,
+            schema: table_schema(),
+        })
+    }
+
+    /// Decode the rows of one log that may satisfy `pushdown`.
+    fn read_log(&self, log: &str, reader: &WpilogReader, pushdown: &Pushdown) -> Result<RecordBatch> {
+        let mut scan = reader.scan();
+        if !pushdown.names.is_empty() {
+            // Entry names are valid globs for themselves; `*` and `?` in a name
+            // only widen the selection, which the inexact pushdown allows
+            let entries: Vec<String> = reader
+                .quick_scan()?
+                .entry_counts
+                .into_keys()
+                .filter(|entry| pushdown.names.iter().all(|filter| filter.may_match(entry)))
+                .collect();
+            if entries.is_empty() {
+                return Ok(RecordBatch::new_empty(self.schema.clone()));
+            }
+            scan = scan.select(entries);
+        }
+        if let Some(range) = &pushdown.time_range {
+            scan = scan.filter_time(range.clone());
+        }
+
+        let rows = scan.to_rows()?;
+        let mut logs = StringBuilder::new();
+        let mut timestamps = Float64Builder::with_capacity(rows.len());
+        let mut names = StringBuilder::new();
+        let mut types = StringBuilder::new();
+        let mut values = Float64Builder::with_capacity(rows.len());
+        let mut texts = StringBuilder::new();
+        for row in &rows {
+            let mut cells: Vec<_> = row.data.iter().filter(|(_, value)| !value.is_null()).collect();
+            cells.sort_by(|a, b| a.0.cmp(b.0));
+            for (name, value) in cells {
+                logs.append_value(log);
+                timestamps.append_value(row.timestamp);
+                names.append_value(name);
+                types.append_value(&row.type_name);
+                match value {
+                    serde_json::Value::Bool(b) => values.append_value(if *b { 1.0 } else { 0.0 }),
+                    serde_json::Value::Number(n) => values.append_option(n.as_f64()),
+                    _ => values.append_null(),
+                }
+                match value {
+                    serde_json::Value::String(s) => texts.append_value(s),
+                    serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                        texts.append_value(value.to_string())
+                    }
+                    _ => texts.append_null(),
+                }
+            }
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(logs.finish()),
+            Arc::new(timestamps.finish()),
+            Arc::new(names.finish()),
+            Arc::new(types.finish()),
+            Arc::new(values.finish()),
+            Arc::new(texts.finish()),
+        ];
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl fmt::Debug for WpilogTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WpilogTable")
+            .field("logs", &self.logs.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl TableProvider for WpilogTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        Ok(filters
+            .iter()
+            .map(|filter| {
+                if NameFilter::from_expr(filter).is_some() || time_bounds(filter).is_some() {
+                    // The parser selects whole entries and may keep extra rows
+                    TableProviderFilterPushDown::Inexact
+                } else {
+                    TableProviderFilterPushDown::Unsupported
+                }
+            })
+            .collect())
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let pushdown = Pushdown::from_filters(filters);
+        let batches = self
+            .logs
+            .iter()
+            .map(|(log, reader)| self.read_log(log, reader, &pushdown))
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        Ok(MemorySourceConfig::try_new_exec(&[batches], self.schema.clone(), projection.cloned())?)
+    }
+}
+
+fn table_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("log", DataType::Utf8, false),
+        Field::new("timestamp", DataType::Float64, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("type", DataType::Utf8, false),
+        Field::new("value", DataType::Float64, true),
+        Field::new("text", DataType::Utf8, true),
+    ]))
+}
+
+/// Filters the parser can apply while reading.
+#[derive(Default)]
+struct Pushdown {
+    /// All must match an entry for it to be read
+    names: Vec<NameFilter>,
+    /// Microseconds
+    time_range: Option<Range<u64>>,
+}
+
+impl Pushdown {
+    fn from_filters(filters: &[Expr]) -> Self {
+        let mut pushdown = Self::default();
+        for filter in filters {
+            if let Some(names) = NameFilter::from_expr(filter) {
+                pushdown.names.push(names);
+            } else if let Some((low, high)) = time_bounds(filter) {
+                // Widen to whole microseconds; DataFusion re-applies the filter
+                let low = low.map_or(0, |low| (low * 1_000_000.0).floor().max(0.0) as u64);
+                let high = high.map_or(u64::MAX, |high| {
+                    ((high * 1_000_000.0).ceil().max(0.0) as u64).saturating_add(1)
+                });
+                let range = pushdown.time_range.get_or_insert(0..u64::MAX);
+                *range = range.start.max(low)..range.end.min(high);
+            }
+        }
+        pushdown
+    }
+}
+
+/// A filter on the `name` column.
+enum NameFilter {
+    /// `name = 'x'` or `name IN ('x', 'y')`
+    Exact(Vec<String>),
+    /// `name LIKE 'prefix%'`
+    Prefix(String),
+}
+
+impl NameFilter {
+    fn from_expr(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::BinaryExpr(BinaryExpr { left, op: Operator::Eq, right }) => {
+                match (column_name(left), column_name(right)) {
+                    (Some("name"), _) => Some(Self::Exact(vec![string_literal(right)?])),
+                    (_, Some("name")) => Some(Self::Exact(vec![string_literal(left)?])),
+                    _ => None,
+                }
+            }
+            Expr::InList(InList { expr, list, negated: false }) if column_name(expr) == Some("name") => {
+                Some(Self::Exact(list.iter().map(string_literal).collect::<Option<_>>()?))
+            }
+            Expr::Like(Like {
+                negated: false,
+                expr,
+                pattern,
+                escape_char: None,
+                case_insensitive: false,
+            }) if column_name(expr) == Some("name") => {
+                let pattern = string_literal(pattern)?;
+                let prefix = pattern.strip_suffix('%').unwrap_or(&pattern);
+                if prefix.contains(['%', '_']) {
+                    return None;
+                }
+                if prefix.len() == pattern.len() {
+                    Some(Self::Exact(vec![pattern]))
+                } else {
+                    Some(Self::Prefix(prefix.to_string()))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a column of the entry named `entry` could pass the filter.
+    /// Struct, JSON and msgpack entries are written to `entry/field` columns
+    /// and reused names with a new type to `entry#generation` columns.
+    fn may_match(&self, entry: &str) -> bool {
+        let derives = |column: &str| {
+            column == entry
+                || column
+                    .strip_prefix(entry)
+                    .is_some_and(|rest| rest.starts_with(['/', '#']))
+        };
+        match self {
+            Self::Exact(columns) => columns.iter().any(|column| derives(column)),
+            Self::Prefix(prefix) => entry.starts_with(prefix.as_str()) || derives(prefix),
+        }
+    }
+}
+
+/// Lower and upper bounds (seconds, inclusive) placed on `timestamp`.
+fn time_bounds(expr: &Expr) -> Option<(Option<f64>, Option<f64>)> {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            let (op, value) = if column_name(left) == Some("timestamp") {
+                (*op, number_literal(right)?)
+            } else if column_name(right) == Some("timestamp") {
+                (op.swap()?, number_literal(left)?)
+            } else {
+                return None;
+            };
+            match op {
+                Operator::Eq => Some((Some(value), Some(value))),
+                Operator::Gt | Operator::GtEq => Some((Some(value), None)),
+                Operator::Lt | Operator::LtEq => Some((None, Some(value))),
+                _ => None,
+            }
+        }
+        Expr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) if column_name(expr) == Some("timestamp") => {
+            Some((Some(number_literal(low)?), Some(number_literal(high)?)))
+        }
+        _ => None,
+    }
+}
+
+fn column_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Column(column) => Some(column.name.as_str()),
+        _ => None,
+    }
+}
+
+fn string_literal(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Literal(value, _) => value.try_as_str().flatten().map(str::to_string),
+        _ => None,
+    }
+}
+
+fn number_literal(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Literal(value, _) => match value.cast_to(&DataType::Float64).ok()? {
+            ScalarValue::Float64(value) => value,
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,634 @@
+//! Value distributions and windowed aggregates of single entries.
+//!
+//! [`histogram`] answers questions such as "how often were we at max
+//! current" without converting the log: numeric entries are counted into
+//! equal-width bins, while boolean and string entries (including enums logged
+//! as strings) report how long they spent in each state. [`percentiles`]
+//! estimates quantiles with a [`TDigest`], and [`rolling`] derives trailing
+//! mean/max channels that can be exported next to the raw rows. Only the
+//! selected entry's payloads are decoded.
+
+use crate::datalog::{DataLogRecord, RecordVisitor, StartRecordData};
+use crate::entry_type::{DecodedValue, EntryType};
+use crate::error::{Error, Result};
+use crate::models::WideRow;
+use crate::reader::WpilogReader;
+use serde::Serialize;
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+
+/// Distribution of an entry's values, from [`histogram`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Histogram {
+    /// Sample counts of a numeric entry in equal-width bins
+    Numeric(NumericHistogram),
+    /// Time spent in each value of a boolean or string entry
+    States(StateHistogram),
+}
+
+/// Sample counts in equal-width bins between the smallest and largest value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NumericHistogram {
+    pub min: f64,
+    pub max: f64,
+    /// Bins in ascending order; the last bin includes `max`
+    pub bins: Vec<Bin>,
+    /// Number of binned samples
+    pub samples: u64,
+    /// Samples that were NaN or infinite and could not be binned
+    pub non_finite: u64,
+}
+
+/// One bin of a [`NumericHistogram`], covering `lower..upper`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: u64,
+}
+
+/// Time spent in each state of an entry.
+///
+/// A sample's state lasts until the entry's next sample, or until the entry is
+/// finished or the log ends.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StateHistogram {
+    /// States by descending duration
+    pub states: Vec<StateTime>,
+    /// Total time covered by the samples, in seconds
+    pub total_secs: f64,
+}
+
+/// Duration and sample count of one state.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StateTime {
+    /// The value, as `true`/`false` for booleans
+    pub state: String,
+    pub samples: u64,
+    pub duration_secs: f64,
+}
+
+/// Compute the distribution of `entry`'s values.
+///
+/// Numeric entries (`double`, `float`, `int64`) are counted into `bins`
+/// equal-width bins, which takes two passes over the log's records: one for
+/// the value range and one for the counts. Boolean and string entries report
+/// time in state in one pass. Neither keeps the samples in memory.
+///
+/// # Errors
+///
+/// Returns an error if `bins` is 0, the entry is not in the log, or it has a
+/// type without a scalar value (arrays, structs, raw data).
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{stats, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("match.wpilog")?;
+/// let current = stats::histogram(&reader, "/PowerDistribution/TotalCurrent", 20)?;
+/// println!("{:#?}", current);
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub fn histogram(reader: &WpilogReader, entry: &str, bins: usize) -> Result<Histogram> {
+    if bins == 0 {
+        return Err(Error::Other("A histogram needs at least one bin".to_string()));
+    }
+
+    let mut range = RangePass {
+        entry,
+        entry_type: None,
+        min: f64::INFINITY,
+        max: f64::NEG_INFINITY,
+        non_finite: 0,
+        states: StatePass::default(),
+    };
+    reader.low_level_reader().visit(&mut range)?;
+
+    match range.entry_type {
+        None => Err(Error::InvalidEntry(format!("No records found for entry '{}'", entry))),
+        Some(EntryType::Boolean | EntryType::String) => Ok(Histogram::States(range.states.finish())),
+        Some(entry_type @ (EntryType::Double | EntryType::Float | EntryType::Int64)) => {
+            let (min, max) = if range.min <= range.max {
+                (range.min, range.max)
+            } else {
+                (0.0, 0.0)
+            };
+            let width = (max - min) / bins as f64;
+            let mut counts = CountPass {
+                entry,
+                entry_type,
+                min,
+                width,
+                counts: vec![0; bins],
+            };
+            if range.min <= range.max {
+                reader.low_level_reader().visit(&mut counts)?;
+            }
+            let bins = counts
+                .counts
+                .iter()
+                .enumerate()
+                .map(|(i, &count)| Bin {
+                    lower: min + width * i as f64,
+                    upper: if i + 1 == bins { max } else { min + width * (i + 1) as f64 },
+                    count,
+                })
+                .collect::<Vec<_>>();
+            Ok(Histogram::Numeric(NumericHistogram {
+                min,
+                max,
+                samples: bins.iter().map(|bin| bin.count).sum(),
+                bins,
+                non_finite: range.non_finite,
+            }))
+        }
+        Some(other) => Err(Error::TypeMismatch(format!(
+            "entry '{}' has type {}, which has no histogram",
+            entry, other
+        ))),
+    }
+}
+
+/// Scalar value of a numeric payload.
+fn numeric_value(entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<f64> {
+    match decode(entry, timestamp, payload)? {
+        DecodedValue::Double(v) => Ok(v),
+        DecodedValue::Float(v) => Ok(v as f64),
+        DecodedValue::Int64(v) => Ok(v as f64),
+        other => Err(Error::TypeMismatch(format!(
+            "entry '{}': expected a number, got {:?}",
+            entry.name, other
+        ))),
+    }
+}
+
+fn decode(entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<DecodedValue> {
+    DataLogRecord {
+        entry: entry.entry,
+        timestamp,
+        data: payload.to_vec(),
+    }
+    .decode_as(&entry.entry_type)
+}
+
+/// First pass: entry type, value range of numeric entries and time in state
+/// of the others.
+struct RangePass<'a> {
+    entry: &'a str,
+    entry_type: Option<EntryType>,
+    min: f64,
+    max: f64,
+    non_finite: u64,
+    states: StatePass,
+}
+
+impl RecordVisitor for RangePass<'_> {
+    fn on_finish(&mut self, entry: u32, timestamp: u64) -> Result<()> {
+        self.states.last_timestamp = self.states.last_timestamp.max(timestamp);
+        self.states.finish_entry(entry, timestamp);
+        Ok(())
+    }
+
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        self.states.last_timestamp = self.states.last_timestamp.max(timestamp);
+        if entry.name != self.entry {
+            return Ok(());
+        }
+        // A reused name keeps the type it was first logged with
+        if *self.entry_type.get_or_insert_with(|| entry.entry_type.clone()) != entry.entry_type {
+            return Ok(());
+        }
+        match &entry.entry_type {
+            EntryType::Double | EntryType::Float | EntryType::Int64 => {
+                let value = numeric_value(entry, timestamp, payload)?;
+                if value.is_finite() {
+                    self.min = self.min.min(value);
+                    self.max = self.max.max(value);
+                } else {
+                    self.non_finite += 1;
+                }
+            }
+            EntryType::Boolean | EntryType::String => {
+                let state = match decode(entry, timestamp, payload)? {
+                    DecodedValue::Boolean(v) => v.to_string(),
+                    DecodedValue::String(v) => v,
+                    _ => return Ok(()),
+                };
+                self.states.sample(entry.entry, timestamp, state);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Second pass over a numeric entry: bin counts.
+struct CountPass<'a> {
+    entry: &'a str,
+    entry_type: EntryType,
+    min: f64,
+    width: f64,
+    counts: Vec<u64>,
+}
+
+impl RecordVisitor for CountPass<'_> {
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        if entry.name != self.entry || entry.entry_type != self.entry_type {
+            return Ok(());
+        }
+        let value = numeric_value(entry, timestamp, payload)?;
+        if !value.is_finite() {
+            return Ok(());
+        }
+        let last = self.counts.len() - 1;
+        let bin = if self.width > 0.0 {
+            (((value - self.min) / self.width) as usize).min(last)
+        } else {
+            0
+        };
+        self.counts[bin] += 1;
+        Ok(())
+    }
+}
+
+/// Accumulates time in state. The entry name may be reused by several entry
+/// IDs over the log, each with its own current state.
+#[derive(Default)]
+struct StatePass {
+    /// Entry ID -> (state, timestamp it was entered)
+    current: HashMap<u32, (String, u64)>,
+    /// State -> (samples, microseconds)
+    totals: HashMap<String, (u64, u64)>,
+    last_timestamp: u64,
+}
+
+impl StatePass {
+    fn sample(&mut self, entry: u32, timestamp: u64, state: String) {
+        self.finish_entry(entry, timestamp);
+        self.totals.entry(state.clone()).or_default().0 += 1;
+        self.current.insert(entry, (state, timestamp));
+    }
+
+    /// End the current state of `entry` at `timestamp`.
+    fn finish_entry(&mut self, entry: u32, timestamp: u64) {
+        if let Some((state, since)) = self.current.remove(&entry) {
+            self.totals.entry(state).or_default().1 += timestamp.saturating_sub(since);
+        }
+    }
+
+    fn finish(mut self) -> StateHistogram {
+        let end = self.last_timestamp;
+        let open: Vec<u32> = self.current.keys().copied().collect();
+        for entry in open {
+            self.finish_entry(entry, end);
+        }
+
+        let mut states: Vec<StateTime> = self
+            .totals
+            .into_iter()
+            .map(|(state, (samples, micros))| StateTime {
+                state,
+                samples,
+                duration_secs: micros as f64 / 1_000_000.0,
+            })
+            .collect();
+        states.sort_by(|a, b| {
+            b.duration_secs
+                .total_cmp(&a.duration_secs)
+                .then_with(|| a.state.cmp(&b.state))
+        });
+        StateHistogram {
+            total_secs: states.iter().map(|state| state.duration_secs).sum(),
+            states,
+        }
+    }
+}
+
+/// Estimate the given quantiles (`0.0..=1.0`) of a numeric entry, in order.
+///
+/// Shorthand for [`digest`] followed by [`TDigest::quantile`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{stats, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("match.wpilog")?;
+/// let latency = stats::percentiles(&reader, "/Vision/LatencyMs", &[0.5, 0.9, 0.99])?;
+/// println!("p50 {:.1} ms, p99 {:.1} ms", latency[0], latency[2]);
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub fn percentiles(reader: &WpilogReader, entry: &str, quantiles: &[f64]) -> Result<Vec<f64>> {
+    let digest = digest(reader, entry)?;
+    quantiles
+        .iter()
+        .map(|&q| {
+            digest.quantile(q).ok_or_else(|| {
+                Error::InvalidEntry(format!("Entry '{}' has no finite values", entry))
+            })
+        })
+        .collect()
+}
+
+/// Build a [`TDigest`] of a numeric entry's finite values in one pass.
+///
+/// # Errors
+///
+/// Returns an error if the entry is not in the log or is not `double`,
+/// `float` or `int64`.
+pub fn digest(reader: &WpilogReader, entry: &str) -> Result<TDigest> {
+    let mut pass = DigestPass {
+        entry,
+        entry_type: None,
+        digest: TDigest::default(),
+    };
+    reader.low_level_reader().visit(&mut pass)?;
+    match pass.entry_type {
+        None => Err(Error::InvalidEntry(format!("No records found for entry '{}'", entry))),
+        Some(EntryType::Double | EntryType::Float | EntryType::Int64) => Ok(pass.digest),
+        Some(other) => Err(Error::TypeMismatch(format!(
+            "entry '{}' has type {}, which has no percentiles",
+            entry, other
+        ))),
+    }
+}
+
+struct DigestPass<'a> {
+    entry: &'a str,
+    entry_type: Option<EntryType>,
+    digest: TDigest,
+}
+
+impl RecordVisitor for DigestPass<'_> {
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        if entry.name != self.entry {
+            return Ok(());
+        }
+        // A reused name keeps the type it was first logged with
+        if *self.entry_type.get_or_insert_with(|| entry.entry_type.clone()) != entry.entry_type {
+            return Ok(());
+        }
+        if matches!(entry.entry_type, EntryType::Double | EntryType::Float | EntryType::Int64) {
+            self.digest.add(numeric_value(entry, timestamp, payload)?);
+        }
+        Ok(())
+    }
+}
+
+/// Mergeable quantile sketch (Dunning's merging t-digest).
+///
+/// Memory is bounded by the compression (about `compression` centroids)
+/// regardless of how many values are added. Estimates are most accurate near
+/// the tails, which is where latency questions usually are; the minimum and
+/// maximum are exact.
+#[derive(Debug, Clone)]
+pub struct TDigest {
+    compression: f64,
+    /// Merged centroids, sorted by mean
+    centroids: Vec<Centroid>,
+    /// Values added since the last merge
+    buffer: Vec<f64>,
+    count: u64,
+    min: f64,
+    max: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+impl Default for TDigest {
+    /// A digest with compression 100.
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl TDigest {
+    /// Create an empty digest. Higher compression keeps more centroids and
+    /// gives more accurate estimates.
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression: compression.max(10.0),
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Add a value. NaN and infinite values are ignored.
+    pub fn add(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(value);
+        if self.buffer.len() >= 5 * self.compression as usize {
+            self.centroids = self.merged().into_owned();
+            self.buffer.clear();
+        }
+    }
+
+    /// Number of values added.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Smallest value added.
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Largest value added.
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Estimate the value at quantile `q` (clamped to `0.0..=1.0`), or `None`
+    /// if the digest is empty.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let q = q.clamp(0.0, 1.0);
+        let centroids = self.merged();
+        let total: f64 = centroids.iter().map(|c| c.weight).sum();
+        let target = q * total;
+
+        // Each centroid is centered at the middle of its weight; values are
+        // interpolated between neighboring centers, and toward the exact
+        // min/max beyond the outermost ones.
+        let first = centroids[0];
+        if target <= first.weight / 2.0 {
+            return Some(lerp(self.min, first.mean, target / (first.weight / 2.0)));
+        }
+        let mut cumulative = 0.0;
+        for pair in centroids.windows(2) {
+            let center = cumulative + pair[0].weight / 2.0;
+            let next_center = cumulative + pair[0].weight + pair[1].weight / 2.0;
+            if target <= next_center {
+                return Some(lerp(
+                    pair[0].mean,
+                    pair[1].mean,
+                    (target - center) / (next_center - center),
+                ));
+            }
+            cumulative += pair[0].weight;
+        }
+        let last = centroids[centroids.len() - 1];
+        let center = total - last.weight / 2.0;
+        Some(lerp(last.mean, self.max, (target - center) / (last.weight / 2.0)))
+    }
+
+    /// Centroids with the buffered values merged in.
+    fn merged(&self) -> Cow<'_, [Centroid]> {
+        if self.buffer.is_empty() {
+            return Cow::Borrowed(&self.centroids);
+        }
+        let mut items: Vec<Centroid> = self
+            .centroids
+            .iter()
+            .copied()
+            .chain(self.buffer.iter().map(|&mean| Centroid { mean, weight: 1.0 }))
+            .collect();
+        items.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total: f64 = items.iter().map(|c| c.weight).sum();
+        let mut merged = Vec::with_capacity(self.compression as usize);
+        let mut current = items[0];
+        let mut weight_before = 0.0;
+        let mut limit = total * self.k_inverse(self.k(0.0) + 1.0);
+        for &item in &items[1..] {
+            if weight_before + current.weight + item.weight <= limit {
+                let weight = current.weight + item.weight;
+                current.mean += (item.mean - current.mean) * item.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_before += current.weight;
+                merged.push(current);
+                limit = total * self.k_inverse(self.k(weight_before / total) + 1.0);
+                current = item;
+            }
+        }
+        merged.push(current);
+        Cow::Owned(merged)
+    }
+
+    /// Scale function k1, which keeps centroids small near the tails.
+    fn k(&self, q: f64) -> f64 {
+        self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin()
+    }
+
+    fn k_inverse(&self, k: f64) -> f64 {
+        let k = k.min(self.compression / 4.0);
+        ((2.0 * PI * k / self.compression).sin() + 1.0) / 2.0
+    }
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t.clamp(0.0, 1.0)
+}
+
+/// Trailing-window aggregates of a numeric entry, emitted as derived columns.
+///
+/// For every sample at time `t`, the mean and maximum of the entry's samples
+/// in `(t - window, t]` are written to `<entry>/mean_<window>` and
+/// `<entry>/max_<window>` (e.g. `/Drive/Velocity/mean_1s`). The derived rows
+/// keep the timestamp, entry ID and loop count of their sample, so they can
+/// be exported alongside the raw rows.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::stats::RollingWindow;
+/// use wpilog_parser::{ParquetWriter, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("match.wpilog")?;
+/// let velocity = reader.read_entry("/Drive/Velocity")?;
+/// let smoothed = RollingWindow::new(1.0).apply(&velocity, "/Drive/Velocity");
+/// ParquetWriter::new("./output").write(&smoothed)?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingWindow {
+    window_secs: f64,
+}
+
+impl RollingWindow {
+    /// Aggregate over the trailing `window_secs` seconds.
+    pub fn new(window_secs: f64) -> Self {
+        Self { window_secs }
+    }
+
+    /// Suffix naming the window in derived columns: `1s`, `250ms`, `1.5s`.
+    pub fn label(&self) -> String {
+        if self.window_secs < 1.0 {
+            format!("{}ms", self.window_secs * 1000.0)
+        } else {
+            format!("{}s", self.window_secs)
+        }
+    }
+
+    /// Derive the mean and max columns for `column` from `rows`, one row per
+    /// numeric sample. Rows without a numeric value in `column` are skipped.
+    pub fn apply(&self, rows: &[WideRow], column: &str) -> Vec<WideRow> {
+        let label = self.label();
+        let mean_column = format!("{}/mean_{}", column, label);
+        let max_column = format!("{}/max_{}", column, label);
+
+        let mut window: VecDeque<(f64, f64)> = VecDeque::new();
+        // Candidates for the window maximum, with decreasing values
+        let mut maxima: VecDeque<(f64, f64)> = VecDeque::new();
+        let mut sum = 0.0;
+        let mut derived = Vec::new();
+        for row in rows {
+            let Some(value) = row.data.get(column).and_then(|v| v.as_f64()) else {
+                continue;
+            };
+            let t = row.timestamp;
+            while window.front().is_some_and(|&(start, _)| start <= t - self.window_secs) {
+                let (_, old) = window.pop_front().unwrap_or_default();
+                sum -= old;
+            }
+            while maxima.front().is_some_and(|&(start, _)| start <= t - self.window_secs) {
+                maxima.pop_front();
+            }
+            window.push_back((t, value));
+            sum += value;
+            while maxima.back().is_some_and(|&(_, max)| max <= value) {
+                maxima.pop_back();
+            }
+            maxima.push_back((t, value));
+
+            let mut out = WideRow::new(t, row.entry, "double".to_string(), row.loop_count);
+            out.insert(mean_column.clone(), json!(sum / window.len() as f64));
+            out.insert(max_column.clone(), json!(maxima.front().map_or(value, |&(_, max)| max)));
+            derived.push(out);
+        }
+        derived
+    }
+}
+
+/// Read a numeric entry and derive its trailing mean and max over
+/// `window_secs` seconds. See [`RollingWindow`].
+///
+/// # Errors
+///
+/// Returns an error if the entry is not in the log.
+pub fn rolling(reader: &WpilogReader, entry: &str, window_secs: f64) -> Result<Vec<WideRow>> {
+    let rows = reader.read_entry(entry)?;
+    if rows.is_empty() {
+        return Err(Error::InvalidEntry(format!("No records found for entry '{}'", entry)));
+    }
+    Ok(RollingWindow::new(window_secs).apply(&rows, entry))
+}
@@ -0,0 +1,593 @@
+//! Decoding of WPILib struct payloads.
+//!
+//! Struct entries log packed little-endian fields described by a schema
+//! published in a `.schema/struct:<Name>` entry. A [`SchemaRegistry`] holds
+//! such schemas and decodes payloads with the same code the log reader uses,
+//! so NT4 clients and other tools can decode struct values without reading a
+//! log:
+//!
+//! ```
+//! use wpilog_parser::structs::{self, SchemaRegistry};
+//!
+//! let mut registry = SchemaRegistry::new();
+//! registry.add("Translation2d", "double x; double y")?;
+//! registry.add("struct:Pose2d", "Translation2d translation; double rotation")?;
+//!
+//! let bytes: Vec<u8> = [1.0f64, 2.0, 0.5].iter().flat_map(|v| v.to_le_bytes()).collect();
+//! let pose = structs::decode(&registry, "struct:Pose2d", &bytes)?;
+//! assert_eq!(pose["translation.y"], 2.0);
+//! # Ok::<(), wpilog_parser::Error>(())
+//! ```
+
+use byteorder::{ByteOrder, LittleEndian};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::{Error, Result};
+use crate::models::{DerivedSchema, DerivedSchemaColumn, ResolvedField};
+
+/// Struct schemas by [canonical name](canonical_struct_name).
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<String, DerivedSchema>,
+}
+
+impl SchemaRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the schema `name` (with or without the `struct:` prefix)
+    /// from its `definition`, e.g. `double x; double y`.
+    ///
+    /// As in a log, the first definition of a name wins. Nested schemas may
+    /// be added in any order; they are only looked up when decoding.
+    pub fn add(&mut self, name: &str, definition: &str) -> Result<()> {
+        register_schema(&mut self.schemas, name, definition)
+    }
+
+    /// The schema registered as `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&DerivedSchema> {
+        find_struct_schema(&self.schemas, name)
+    }
+
+    /// Number of registered schemas.
+    pub fn len(&self) -> usize {
+        self.schemas.len()
+    }
+
+    /// Whether no schemas are registered.
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    /// The registered schemas, by canonical name.
+    pub fn schemas(&self) -> &BTreeMap<String, DerivedSchema> {
+        &self.schemas
+    }
+
+    /// Flattened layout of the struct `name`: its primitive fields, with
+    /// dotted names and byte offsets, after nested structs are expanded.
+    pub fn resolve(&self, name: &str) -> Result<Vec<ResolvedField>> {
+        resolve_struct(&self.schemas, name)
+    }
+
+    /// Decode a payload of the struct `type_name`. See [`decode`].
+    pub fn decode(&self, type_name: &str, bytes: &[u8]) -> Result<HashMap<String, serde_json::Value>> {
+        decode_struct(&self.schemas, type_name, bytes)
+    }
+
+    /// Size in bytes of one packed value of the struct `name`.
+    pub fn size(&self, name: &str) -> Result<usize> {
+        struct_size(&self.schemas, name)
+    }
+
+    /// Decode a `struct:<Name>[]` payload of the struct `type_name`. See
+    /// [`decode_array`].
+    pub fn decode_array(&self, type_name: &str, bytes: &[u8]) -> Result<Vec<HashMap<String, serde_json::Value>>> {
+        decode_struct_array(&self.schemas, type_name, bytes)
+    }
+}
+
+impl From<BTreeMap<String, DerivedSchema>> for SchemaRegistry {
+    /// Wrap schemas collected by a [`Formatter`](crate::formatter::Formatter).
+    fn from(schemas: BTreeMap<String, DerivedSchema>) -> Self {
+        Self { schemas }
+    }
+}
+
+/// Decode a payload of the struct `type_name` (e.g. `struct:Pose2d`) with
+/// the schemas in `registry`.
+///
+/// Fields are keyed by their dotted path, so nested structs are flattened
+/// into keys such as `translation.x`. An empty payload decodes every field
+/// as null.
+///
+/// # Errors
+///
+/// Returns [`Error::SchemaError`] if the schema or a nested one is missing or
+/// recursive, and [`Error::ParseError`] if the payload is too short.
+pub fn decode(
+    registry: &SchemaRegistry,
+    type_name: &str,
+    bytes: &[u8],
+) -> Result<HashMap<String, serde_json::Value>> {
+    registry.decode(type_name, bytes)
+}
+
+/// Decode a payload of an array of the struct `type_name`, as logged by
+/// `struct:<Name>[]` entries: the elements packed back to back, each decoded
+/// as by [`decode`]. An empty payload is an empty array.
+///
+/// # Errors
+///
+/// Returns [`Error::SchemaError`] if the schema or a nested one is missing or
+/// recursive, and [`Error::ParseError`] if the payload is not a whole number
+/// of elements.
+pub fn decode_array(
+    registry: &SchemaRegistry,
+    type_name: &str,
+    bytes: &[u8],
+) -> Result<Vec<HashMap<String, serde_json::Value>>> {
+    registry.decode_array(type_name, bytes)
+}
+
+/// Register the schema `name` from its `definition` in `schemas`, keeping
+/// any earlier definition, as schemas are only logged once.
+pub(crate) fn register_schema(
+    schemas: &mut BTreeMap<String, DerivedSchema>,
+    name: &str,
+    definition: &str,
+) -> Result<()> {
+    let columns = convert_struct_schema_to_columns(definition)?;
+    schemas
+        .entry(canonical_struct_name(name).to_string())
+        .or_insert_with(|| DerivedSchema {
+            name: name.to_string(),
+            columns,
+        });
+    Ok(())
+}
+
+/// [`SchemaRegistry::resolve`] over a schema map.
+pub(crate) fn resolve_struct(
+    schemas: &BTreeMap<String, DerivedSchema>,
+    name: &str,
+) -> Result<Vec<ResolvedField>> {
+    let schema = find_struct_schema(schemas, name)
+        .ok_or_else(|| Error::SchemaError(format!("No struct schema found for: {}", name)))?;
+
+    let mut fields = Vec::new();
+    let mut chain = vec![canonical_struct_name(&schema.name).to_string()];
+    resolve_fields(&schema.columns, "", 0, schemas, &mut chain, &mut fields)?;
+    Ok(fields.iter().map(FieldSlot::resolved).collect())
+}
+
+/// [`SchemaRegistry::size`] over a schema map.
+pub(crate) fn struct_size(schemas: &BTreeMap<String, DerivedSchema>, name: &str) -> Result<usize> {
+    let schema = find_struct_schema(schemas, name)
+        .ok_or_else(|| Error::SchemaError(format!("No struct schema found for: {}", name)))?;
+
+    let mut chain = vec![canonical_struct_name(&schema.name).to_string()];
+    resolve_fields(&schema.columns, "", 0, schemas, &mut chain, &mut Vec::new())
+}
+
+/// [`decode_array`] over a schema map.
+pub(crate) fn decode_struct_array(
+    schemas: &BTreeMap<String, DerivedSchema>,
+    type_name: &str,
+    bytes: &[u8],
+) -> Result<Vec<HashMap<String, serde_json::Value>>> {
+    let size = struct_size(schemas, type_name)?;
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+    if size == 0 || !bytes.len().is_multiple_of(size) {
+        return Err(Error::ParseError(format!(
+            "Struct array of {} needs a multiple of {} bytes, got {}",
+            canonical_struct_name(type_name),
+            size,
+            bytes.len()
+        )));
+    }
+    bytes
+        .chunks(size)
+        .map(|element| decode_struct(schemas, type_name, element))
+        .collect()
+}
+
+/// [`decode`] over a schema map.
+pub(crate) fn decode_struct(
+    schemas: &BTreeMap<String, DerivedSchema>,
+    type_name: &str,
+    bytes: &[u8],
+) -> Result<HashMap<String, serde_json::Value>> {
+    let schema = find_struct_schema(schemas, type_name)
+        .ok_or_else(|| Error::SchemaError(format!("No struct schema found for: {}", type_name)))?;
+
+    let mut chain = vec![canonical_struct_name(&schema.name).to_string()];
+    unpack_struct(&schema.columns, bytes, schemas, &mut chain)
+}
+
+/// Deepest nesting of struct schemas that is expanded. Real schemas nest a
+/// handful of levels; anything deeper is treated as malformed.
+pub const MAX_STRUCT_DEPTH: usize = 32;
+
+/// Most primitive fields a struct is expanded into, counting each element
+/// of an array of structs. Bounds the work a malformed schema can cause.
+pub const MAX_STRUCT_FIELDS: usize = 65_536;
+
+/// Parse a struct schema definition as logged in a `structschema` entry,
+/// e.g. `double x; double y`, into its fields.
+///
+/// Declarations follow the WPILib struct spec: fixed-size arrays
+/// (`double values[4]`, `char name[8]`), bit-fields (`bool enabled:1`,
+/// `uint8 mode:3`) and enums (`enum {A=0, B=1} int8 state`). The `float32`
+/// and `float64` aliases are stored as `float` and `double`.
+///
+/// # Errors
+///
+/// Returns [`Error::SchemaError`] for a malformed array length or a
+/// bit-field that is not an integer or `bool`, or wider than its type.
+pub fn convert_struct_schema_to_columns(schema_str: &str) -> Result<Vec<DerivedSchemaColumn>> {
+    let mut columns = Vec::new();
+
+    for part in schema_str.split(';') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+
+        // Enum values only name the values of the integer field that follows
+        let declaration = if part.starts_with("enum") {
+            match part.find('}') {
+                Some(pos) => part[pos + 1..].trim(),
+                None => continue,
+            }
+        } else {
+            part
+        };
+        if let Some((typ, declarator)) = declaration.split_once(char::is_whitespace) {
+            columns.push(parse_declarator(typ, declarator.trim())?);
+        }
+    }
+
+    Ok(columns)
+}
+
+/// Parse the `name`, `name[N]` or `name:bits` part of a field declaration
+/// of type `typ`.
+fn parse_declarator(typ: &str, declarator: &str) -> Result<DerivedSchemaColumn> {
+    let type_name = match typ {
+        "float32" => "float",
+        "float64" => "double",
+        other => other,
+    }
+    .to_string();
+    let invalid = |reason: &str| Error::SchemaError(format!("Invalid struct field '{} {}': {}", typ, declarator, reason));
+
+    if let Some((name, bits)) = declarator.split_once(':') {
+        let bit_width: u32 = bits.trim().parse().map_err(|_| invalid("bad bit-field width"))?;
+        let max_width = match type_name.as_str() {
+            "bool" => 1,
+            "int8" | "uint8" | "int16" | "uint16" | "int32" | "uint32" | "int64" | "uint64" => {
+                struct_field_size(&type_name).unwrap_or(0) as u32 * 8
+            }
+            _ => return Err(invalid("bit-fields must be integers or bool")),
+        };
+        if bit_width == 0 || bit_width > max_width {
+            return Err(invalid("bit-field width out of range"));
+        }
+        return Ok(DerivedSchemaColumn {
+            name: name.trim().to_string(),
+            type_name,
+            array_len: None,
+            bit_width: Some(bit_width),
+        });
+    }
+
+    if let Some((name, len)) = declarator.split_once('[') {
+        let array_len = len
+            .trim()
+            .strip_suffix(']')
+            .and_then(|len| len.trim().parse().ok())
+            .ok_or_else(|| invalid("bad array length"))?;
+        return Ok(DerivedSchemaColumn {
+            name: name.trim().to_string(),
+            type_name,
+            array_len: Some(array_len),
+            bit_width: None,
+        });
+    }
+
+    Ok(DerivedSchemaColumn {
+        name: declarator.to_string(),
+        type_name,
+        array_len: None,
+        bit_width: None,
+    })
+}
+
+/// Canonical identifier of a struct schema: its name without the `struct:`
+/// prefix, so `struct:Pose2d` (entry types) and `Pose2d` (nested field types)
+/// name the same schema.
+pub fn canonical_struct_name(name: &str) -> &str {
+    name.strip_prefix("struct:").unwrap_or(name)
+}
+
+/// Find a struct schema by exact canonical name.
+pub(crate) fn find_struct_schema<'a>(
+    schemas: &'a BTreeMap<String, DerivedSchema>,
+    name: &str,
+) -> Option<&'a DerivedSchema> {
+    schemas.get(canonical_struct_name(name))
+}
+
+/// Find the schema of a nested struct field of type `type_name` and push it
+/// onto `chain`, the schemas being expanded. Fails if the schema is missing,
+/// already in the chain (a recursive schema has no finite layout), or would
+/// nest deeper than [`MAX_STRUCT_DEPTH`].
+fn enter_nested_schema<'a>(
+    schemas: &'a BTreeMap<String, DerivedSchema>,
+    type_name: &str,
+    chain: &mut Vec<String>,
+) -> Result<&'a DerivedSchema> {
+    let canonical = canonical_struct_name(type_name);
+    if chain.iter().any(|name| name == canonical) {
+        return Err(Error::SchemaError(format!(
+            "Recursive struct schema: {} -> {}",
+            chain.join(" -> "),
+            canonical
+        )));
+    }
+    if chain.len() >= MAX_STRUCT_DEPTH {
+        return Err(Error::SchemaError(format!(
+            "Struct schemas nested deeper than {} levels: {} -> {}",
+            MAX_STRUCT_DEPTH,
+            chain.join(" -> "),
+            canonical
+        )));
+    }
+    let nested = find_struct_schema(schemas, canonical)
+        .ok_or_else(|| Error::SchemaError(format!("No nested schema found for: {}", type_name)))?;
+    chain.push(canonical.to_string());
+    Ok(nested)
+}
+
+/// Size in bytes of a primitive struct field type.
+fn struct_field_size(type_name: &str) -> Option<usize> {
+    match type_name {
+        "double" | "int64" | "uint64" => Some(8),
+        "float" | "int32" | "uint32" => Some(4),
+        "int16" | "uint16" | "float16" => Some(2),
+        "bool" | "char" | "int8" | "uint8" => Some(1),
+        _ => None,
+    }
+}
+
+/// Decode one little-endian primitive value of `type_name` from exactly
+/// [`struct_field_size`] bytes.
+fn primitive_value(type_name: &str, bytes: &[u8]) -> serde_json::Value {
+    match type_name {
+        "double" => json!(LittleEndian::read_f64(bytes)),
+        "float" => json!(LittleEndian::read_f32(bytes)),
+        "float16" => json!(half::f16::from_le_bytes([bytes[0], bytes[1]]).to_f32()),
+        "int64" => json!(LittleEndian::read_i64(bytes)),
+        "uint64" => json!(LittleEndian::read_u64(bytes)),
+        "int32" => json!(LittleEndian::read_i32(bytes)),
+        "uint32" => json!(LittleEndian::read_u32(bytes)),
+        "int16" => json!(LittleEndian::read_i16(bytes)),
+        "uint16" => json!(LittleEndian::read_u16(bytes)),
+        "int8" => json!(bytes[0] as i8),
+        "uint8" => json!(bytes[0]),
+        "bool" => json!(bytes[0] != 0),
+        "char" => json!(String::from_utf8_lossy(bytes)),
+        _ => json!(null),
+    }
+}
+
+/// A primitive field of a struct layout, after nested structs are expanded.
+pub(crate) struct FieldSlot {
+    name: String,
+    type_name: String,
+    offset: usize,
+    /// Size in bytes of the field, the whole array or a bit-field's storage unit
+    size: usize,
+    array_len: Option<usize>,
+    /// Shift and width of a bit-field within its storage unit
+    bits: Option<(u32, u32)>,
+}
+
+impl FieldSlot {
+    fn resolved(&self) -> ResolvedField {
+        ResolvedField {
+            name: self.name.clone(),
+            type_name: match self.array_len {
+                Some(len) => format!("{}[{}]", self.type_name, len),
+                None => self.type_name.clone(),
+            },
+            offset: self.offset,
+            size: self.size,
+        }
+    }
+
+    /// Decode the field from a struct payload.
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value> {
+        let bytes = self.offset.checked_add(self.size).and_then(|end| data.get(self.offset..end));
+        let Some(bytes) = bytes else {
+            return Err(Error::ParseError(format!(
+                "Not enough data for {} at offset {}, need {} bytes but only {} available",
+                self.type_name,
+                self.offset,
+                self.size,
+                data.len().saturating_sub(self.offset)
+            )));
+        };
+
+        if let Some((shift, width)) = self.bits {
+            let mut unit = [0u8; 8];
+            unit[..bytes.len()].copy_from_slice(bytes);
+            let value = (u64::from_le_bytes(unit) >> shift) & (u64::MAX >> (64 - width));
+            return Ok(match self.type_name.as_str() {
+                "bool" => json!(value != 0),
+                // Sign-extend from the top bit of the field
+                "int8" | "int16" | "int32" | "int64" => json!(((value << (64 - width)) as i64) >> (64 - width)),
+                _ => json!(value),
+            });
+        }
+
+        Ok(match (self.type_name.as_str(), self.array_len) {
+            (_, None) => primitive_value(&self.type_name, bytes),
+            // Strings are padded with trailing nulls
+            ("char", Some(_)) => json!(String::from_utf8_lossy(bytes).trim_end_matches('\0')),
+            (type_name, Some(_)) => {
+                let element_size = struct_field_size(type_name).unwrap_or(1);
+                serde_json::Value::Array(
+                    bytes
+                        .chunks(element_size)
+                        .map(|element| primitive_value(type_name, element))
+                        .collect(),
+                )
+            }
+        })
+    }
+}
+
+/// A bit-field storage unit being filled.
+struct BitUnit {
+    offset: usize,
+    size: usize,
+    used: u32,
+}
+
+/// Append the primitive fields of `columns` starting at `offset`, returning
+/// the offset just past them.
+///
+/// Consecutive bit-fields are packed from the least significant bit of an
+/// integer of their type's size; a field that does not fit, or whose type
+/// has another size, starts a new integer. `bool` bit-fields fit into the
+/// current integer whatever its size.
+pub(crate) fn resolve_fields(
+    columns: &[DerivedSchemaColumn],
+    prefix: &str,
+    mut offset: usize,
+    schemas: &BTreeMap<String, DerivedSchema>,
+    chain: &mut Vec<String>,
+    fields: &mut Vec<FieldSlot>,
+) -> Result<usize> {
+    let mut unit: Option<BitUnit> = None;
+    for col in columns {
+        let name = if prefix.is_empty() {
+            col.name.clone()
+        } else {
+            format!("{}.{}", prefix, col.name)
+        };
+        if fields.len() >= MAX_STRUCT_FIELDS {
+            return Err(too_many_fields(chain));
+        }
+
+        if let Some(width) = col.bit_width {
+            let size = struct_field_size(&col.type_name).unwrap_or(1);
+            let fits = unit.as_ref().is_some_and(|unit| {
+                (col.type_name == "bool" || unit.size == size) && unit.used + width <= unit.size as u32 * 8
+            });
+            if !fits {
+                if let Some(full) = unit.take() {
+                    offset = end_of(offset, full.size)?;
+                }
+            }
+            let unit = unit.get_or_insert(BitUnit { offset, size, used: 0 });
+            fields.push(FieldSlot {
+                name,
+                type_name: col.type_name.clone(),
+                offset: unit.offset,
+                size: unit.size,
+                array_len: None,
+                bits: Some((unit.used, width)),
+            });
+            unit.used += width;
+            continue;
+        }
+        if let Some(full) = unit.take() {
+            offset = end_of(offset, full.size)?;
+        }
+
+        match struct_field_size(&col.type_name) {
+            Some(size) => {
+                let size = size
+                    .checked_mul(col.array_len.unwrap_or(1))
+                    .ok_or_else(|| Error::SchemaError(format!("Struct array field '{}' is too large", name)))?;
+                fields.push(FieldSlot {
+                    name,
+                    type_name: col.type_name.clone(),
+                    offset,
+                    size,
+                    array_len: col.array_len,
+                    bits: None,
+                });
+                offset = end_of(offset, size)?;
+            }
+            None => {
+                let nested = enter_nested_schema(schemas, &col.type_name, chain)?;
+                match col.array_len {
+                    // Arrays of structs are flattened as `name[0].field`, ...
+                    Some(len) => {
+                        if len > MAX_STRUCT_FIELDS - fields.len() {
+                            return Err(too_many_fields(chain));
+                        }
+                        for i in 0..len {
+                            let element = format!("{}[{}]", name, i);
+                            offset = resolve_fields(&nested.columns, &element, offset, schemas, chain, fields)?;
+                        }
+                    }
+                    None => offset = resolve_fields(&nested.columns, &name, offset, schemas, chain, fields)?,
+                }
+                chain.pop();
+            }
+        }
+    }
+    if let Some(full) = unit {
+        offset = end_of(offset, full.size)?;
+    }
+    Ok(offset)
+}
+
+/// The offset `size` bytes past `offset`, failing for layouts too large to
+/// address.
+fn end_of(offset: usize, size: usize) -> Result<usize> {
+    offset
+        .checked_add(size)
+        .ok_or_else(|| Error::SchemaError("Struct layout is too large".to_string()))
+}
+
+fn too_many_fields(chain: &[String]) -> Error {
+    Error::SchemaError(format!(
+        "Struct {} expands to more than {} fields",
+        chain.first().map(String::as_str).unwrap_or_default(),
+        MAX_STRUCT_FIELDS
+    ))
+}
+
+/// Unpack a struct payload into its fields, keyed by dotted path. An empty
+/// payload decodes every field as null.
+pub(crate) fn unpack_struct(
+    columns: &[DerivedSchemaColumn],
+    data: &[u8],
+    schemas: &BTreeMap<String, DerivedSchema>,
+    chain: &mut Vec<String>,
+) -> Result<HashMap<String, serde_json::Value>> {
+    let mut fields = Vec::with_capacity(columns.len());
+    resolve_fields(columns, "", 0, schemas, chain, &mut fields)?;
+    fields
+        .into_iter()
+        .map(|field| {
+            let value = if data.is_empty() {
+                json!(null)
+            } else {
+                field.decode(data)?
+            };
+            Ok((field.name, value))
+        })
+        .collect()
+}
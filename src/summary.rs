@@ -0,0 +1,175 @@
+//! One-pass overview of a log for file browsers.
+//!
+//! [`LogSummary`] collects everything a list view needs to describe a log:
+//! header fields, time span, counts, top-level namespaces, struct schemas, FMS
+//! match information, and warnings about damaged or unusual logs. Only control
+//! records and a handful of match-info payloads are decoded.
+
+use crate::datalog::DataLogReader;
+use crate::entry_type::{DecodedValue, EntryType};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Overview of a log, built by [`WpilogReader::summarize`](crate::WpilogReader::summarize).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogSummary {
+    /// WPILog format version (e.g., 0x0100)
+    pub version: u16,
+    /// Extra header string
+    pub extra_header: String,
+    /// Timestamp of the first record (microseconds)
+    pub start_timestamp: Option<u64>,
+    /// Timestamp of the last record (microseconds)
+    pub end_timestamp: Option<u64>,
+    /// Time between the first and last record, in seconds
+    pub duration_secs: f64,
+    /// Number of data records
+    pub record_count: u64,
+    /// Number of distinct entry names
+    pub entry_count: usize,
+    /// First path segment of every non-schema entry name (e.g., `Drive` for
+    /// `/Drive/LeftVelocity`), sorted
+    pub namespaces: Vec<String>,
+    /// Names of logged schemas (e.g., `struct:Pose2d`), sorted
+    pub schemas: Vec<String>,
+    /// Match information logged by the Driver Station, if any
+    pub match_info: Option<MatchInfo>,
+    /// Problems found while scanning, as human-readable messages
+    pub warnings: Vec<String>,
+}
+
+/// FMS match information from `FMSInfo/*` or AdvantageKit `DriverStation/*` entries.
+///
+/// The last non-empty value of each field wins, since robots log blank match
+/// info until the FMS connects.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchInfo {
+    pub event_name: Option<String>,
+    /// `Practice`, `Qualification` or `Elimination`
+    pub match_type: Option<String>,
+    pub match_number: Option<i64>,
+    pub replay_number: Option<i64>,
+}
+
+impl MatchInfo {
+    fn is_empty(&self) -> bool {
+        self.event_name.is_none()
+            && self.match_type.is_none()
+            && self.match_number.is_none()
+            && self.replay_number.is_none()
+    }
+
+    fn update(&mut self, field: &str, value: DecodedValue) {
+        match (field, value) {
+            ("EventName", DecodedValue::String(name)) if !name.is_empty() => {
+                self.event_name = Some(name);
+            }
+            ("MatchType", DecodedValue::String(kind)) if !kind.is_empty() && kind != "None" => {
+                self.match_type = Some(kind);
+            }
+            ("MatchType", DecodedValue::Int64(kind)) => {
+                let kind = match kind {
+                    1 => "Practice",
+                    2 => "Qualification",
+                    3 => "Elimination",
+                    _ => return,
+                };
+                self.match_type = Some(kind.to_string());
+            }
+            ("MatchNumber", DecodedValue::Int64(n)) if n > 0 => self.match_number = Some(n),
+            ("ReplayNumber", DecodedValue::Int64(n)) if n > 0 => self.replay_number = Some(n),
+            _ => {}
+        }
+    }
+}
+
+impl LogSummary {
+    /// Summarize the log read by `reader`.
+    pub fn from_reader(reader: &DataLogReader<'_>) -> Result<Self> {
+        if !reader.is_valid() {
+            return Err(Error::InvalidFormat("Not a valid WPILOG file".to_string()));
+        }
+
+        let mut summary = LogSummary {
+            version: reader.get_version(),
+            extra_header: reader.get_extra_header(),
+            ..Default::default()
+        };
+        let mut active: HashMap<u32, (String, EntryType)> = HashMap::new();
+        let mut names: BTreeSet<String> = BTreeSet::new();
+        let mut namespaces: BTreeSet<String> = BTreeSet::new();
+        let mut schemas: BTreeSet<String> = BTreeSet::new();
+        let mut match_info = MatchInfo::default();
+        let mut orphaned = 0u64;
+
+        for record in reader.records()? {
+            let record = match record {
+                Ok(record) => record,
+                Err(Error::Truncated { offset, .. }) => {
+                    summary.warnings.push(format!(
+                        "Log is truncated; ignored a partial record at byte {}",
+                        offset
+                    ));
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            summary.start_timestamp.get_or_insert(record.timestamp);
+            summary.end_timestamp = Some(record.timestamp);
+
+            if record.is_start() {
+                let start = record.get_start_data()?;
+                if let Some(schema) = start.name.split(".schema/").nth(1) {
+                    schemas.insert(schema.to_string());
+                } else if let Some(namespace) = start.name.split('/').find(|s| !s.is_empty()) {
+                    namespaces.insert(namespace.to_string());
+                }
+                names.insert(start.name.clone());
+                active.insert(start.entry, (start.name, start.entry_type));
+            } else if record.is_finish() {
+                active.remove(&record.get_finish_entry()?);
+            } else if !record.is_control() {
+                summary.record_count += 1;
+                match active.get(&record.entry) {
+                    Some((name, entry_type)) => {
+                        if let Some(field) = match_info_field(name) {
+                            if let Ok(value) = record.decode_as(entry_type) {
+                                match_info.update(field, value);
+                            }
+                        }
+                    }
+                    None => orphaned += 1,
+                }
+            }
+        }
+
+        if orphaned > 0 {
+            summary.warnings.push(format!(
+                "{} record(s) belong to entry IDs with no Start record",
+                orphaned
+            ));
+        }
+
+        if let (Some(start), Some(end)) = (summary.start_timestamp, summary.end_timestamp) {
+            summary.duration_secs = end.saturating_sub(start) as f64 / 1_000_000.0;
+        }
+        summary.entry_count = names.len();
+        summary.namespaces = namespaces.into_iter().collect();
+        summary.schemas = schemas.into_iter().collect();
+        summary.match_info = (!match_info.is_empty()).then_some(match_info);
+        Ok(summary)
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Match-info field named by an entry such as `/FMSInfo/EventName` or
+/// `/DriverStation/MatchNumber`.
+fn match_info_field(name: &str) -> Option<&str> {
+    let (parent, field) = name.rsplit_once('/')?;
+    (parent.ends_with("FMSInfo") || parent.ends_with("DriverStation")).then_some(field)
+}
@@ -0,0 +1,242 @@
+//! Which commands ran when.
+//!
+//! [`CommandTimeline`] rebuilds a Gantt-style list of command runs from the
+//! entries WPILib and AdvantageKit publish about the command scheduler:
+//!
+//! - `.../Scheduler/Names` (`string[]`): the scheduled commands, published by
+//!   the `CommandScheduler` sendable on SmartDashboard or LiveWindow and
+//!   recorded from NetworkTables by `DataLogManager`
+//! - `.../<Subsystem>/.command` (`string`): the command requiring each
+//!   subsystem, or `none`
+//! - `.../CommandsAll/<Command>` (`boolean`): AdvantageKit's per-command
+//!   running flags
+//!
+//! Only these entries' payloads are decoded.
+
+use crate::datalog::{DataLogReader, DataLogRecord, RecordVisitor, StartRecordData};
+use crate::entry_type::{DecodedValue, EntryType};
+use crate::error::Result;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// One run of a command.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandSpan {
+    pub command: String,
+    /// Subsystem the command required, for spans from `.command` entries
+    pub subsystem: Option<String>,
+    /// Entry the span was decoded from
+    pub source: String,
+    /// Time the command was first seen running (seconds)
+    pub start_secs: f64,
+    /// Time it was first seen stopped, or `None` if it was still running
+    /// when the log (or its source entry) ended
+    pub end_secs: Option<f64>,
+}
+
+impl CommandSpan {
+    /// Length of the run, counting a run that never ended until `log_end`.
+    pub fn duration_secs(&self, log_end: f64) -> f64 {
+        self.end_secs.unwrap_or(log_end) - self.start_secs
+    }
+}
+
+/// Command runs of a log, built by
+/// [`WpilogReader::command_timeline`](crate::WpilogReader::command_timeline).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CommandTimeline {
+    /// Runs sorted by start time, then command name
+    pub spans: Vec<CommandSpan>,
+    /// Timestamp of the first record (seconds)
+    pub log_start_secs: f64,
+    /// Timestamp of the last record (seconds)
+    pub log_end_secs: f64,
+}
+
+impl CommandTimeline {
+    /// Build the timeline of the log read by `reader`.
+    pub fn from_reader(reader: &DataLogReader<'_>) -> Result<Self> {
+        let mut visitor = TimelineVisitor::default();
+        reader.visit(&mut visitor)?;
+        Ok(visitor.finish())
+    }
+
+    /// Names of all commands that ran, sorted.
+    pub fn commands(&self) -> Vec<&str> {
+        let names: BTreeSet<&str> = self.spans.iter().map(|span| span.command.as_str()).collect();
+        names.into_iter().collect()
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// What a scheduler-related entry reports.
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    /// The set of scheduled commands
+    Scheduler,
+    /// The command requiring a subsystem
+    Subsystem(String),
+    /// Whether one command is running
+    Flag(String),
+}
+
+impl Source {
+    fn of(entry: &StartRecordData) -> Option<Self> {
+        let (parent, leaf) = entry.name.rsplit_once('/')?;
+        match &entry.entry_type {
+            EntryType::StringArray if leaf == "Names" && parent.ends_with("Scheduler") => {
+                Some(Source::Scheduler)
+            }
+            EntryType::String if leaf == ".command" => {
+                let subsystem = parent.rsplit('/').next().filter(|s| !s.is_empty())?;
+                Some(Source::Subsystem(subsystem.to_string()))
+            }
+            EntryType::Boolean if parent.ends_with("/CommandsAll") || parent == "CommandsAll" => {
+                Some(Source::Flag(leaf.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct TimelineVisitor {
+    /// Entry ID -> source, for active scheduler-related entries
+    sources: HashMap<u32, (String, Source)>,
+    /// (entry ID, command) -> index of its open span
+    open: HashMap<(u32, String), usize>,
+    spans: Vec<CommandSpan>,
+    first_timestamp: Option<u64>,
+    last_timestamp: u64,
+}
+
+impl TimelineVisitor {
+    fn seen(&mut self, timestamp: u64) {
+        self.first_timestamp.get_or_insert(timestamp);
+        self.last_timestamp = self.last_timestamp.max(timestamp);
+    }
+
+    fn start(&mut self, entry: u32, source: &str, command: &str, subsystem: Option<&str>, timestamp: u64) {
+        let key = (entry, command.to_string());
+        if self.open.contains_key(&key) {
+            return;
+        }
+        self.open.insert(key, self.spans.len());
+        self.spans.push(CommandSpan {
+            command: command.to_string(),
+            subsystem: subsystem.map(str::to_string),
+            source: source.to_string(),
+            start_secs: secs(timestamp),
+            end_secs: None,
+        });
+    }
+
+    fn end(&mut self, entry: u32, command: &str, timestamp: u64) {
+        if let Some(index) = self.open.remove(&(entry, command.to_string())) {
+            self.spans[index].end_secs = Some(secs(timestamp));
+        }
+    }
+
+    /// End every open span of `entry` except those in `keep`.
+    fn end_all_except(&mut self, entry: u32, keep: &BTreeSet<&str>, timestamp: u64) {
+        let ended: Vec<String> = self
+            .open
+            .keys()
+            .filter(|(id, command)| *id == entry && !keep.contains(command.as_str()))
+            .map(|(_, command)| command.clone())
+            .collect();
+        for command in ended {
+            self.end(entry, &command, timestamp);
+        }
+    }
+
+    fn finish(mut self) -> CommandTimeline {
+        self.spans.sort_by(|a, b| {
+            a.start_secs
+                .total_cmp(&b.start_secs)
+                .then_with(|| a.command.cmp(&b.command))
+        });
+        CommandTimeline {
+            spans: self.spans,
+            log_start_secs: secs(self.first_timestamp.unwrap_or(0)),
+            log_end_secs: secs(self.last_timestamp),
+        }
+    }
+}
+
+impl RecordVisitor for TimelineVisitor {
+    fn on_start(&mut self, start: &StartRecordData, timestamp: u64) -> Result<()> {
+        self.seen(timestamp);
+        match Source::of(start) {
+            Some(source) => {
+                self.sources.insert(start.entry, (start.name.clone(), source));
+            }
+            None => {
+                self.sources.remove(&start.entry);
+            }
+        }
+        Ok(())
+    }
+
+    fn on_finish(&mut self, entry: u32, timestamp: u64) -> Result<()> {
+        self.seen(timestamp);
+        // Spans still open when their entry finishes stay open: the entry
+        // stopped reporting, not the command
+        self.sources.remove(&entry);
+        self.open.retain(|(id, _), _| *id != entry);
+        Ok(())
+    }
+
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        self.seen(timestamp);
+        let Some((name, source)) = self.sources.get(&entry.entry).cloned() else {
+            return Ok(());
+        };
+        let record = DataLogRecord {
+            entry: entry.entry,
+            timestamp,
+            data: payload.to_vec(),
+        };
+        let Ok(value) = record.decode_as(&entry.entry_type) else {
+            log::warn!("Skipping an undecodable value of '{}' in the command timeline", name);
+            return Ok(());
+        };
+
+        match (source, value) {
+            (Source::Scheduler, DecodedValue::StringArray(names)) => {
+                let running: BTreeSet<&str> = names.iter().map(String::as_str).collect();
+                self.end_all_except(entry.entry, &running, timestamp);
+                for command in running {
+                    self.start(entry.entry, &name, command, None, timestamp);
+                }
+            }
+            (Source::Subsystem(subsystem), DecodedValue::String(command)) => {
+                let running: BTreeSet<&str> = match command.as_str() {
+                    "" | "none" => BTreeSet::new(),
+                    command => BTreeSet::from([command]),
+                };
+                self.end_all_except(entry.entry, &running, timestamp);
+                for command in running {
+                    self.start(entry.entry, &name, command, Some(&subsystem), timestamp);
+                }
+            }
+            (Source::Flag(command), DecodedValue::Boolean(running)) => {
+                if running {
+                    self.start(entry.entry, &name, &command, None, timestamp);
+                } else {
+                    self.end(entry.entry, &command, timestamp);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn secs(timestamp: u64) -> f64 {
+    timestamp as f64 / 1_000_000.0
+}
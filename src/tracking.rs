@@ -0,0 +1,309 @@
+//! Setpoint tracking reports for closed-loop controllers.
+//!
+//! [`TrackingAnalysis`] pairs a setpoint entry with the measurement it
+//! controls (e.g. `/Arm/Setpoint` and `/Arm/Position`) and reports how well
+//! the measurement followed it: RMSE and peak error over the whole log, and
+//! overshoot and settling time for each setpoint step. This is the usual first
+//! look when tuning a PID loop.
+
+use crate::error::{Error, Result};
+use crate::reader::WpilogReader;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A setpoint entry and the measurement entry that should follow it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackingPair {
+    pub setpoint: String,
+    pub measurement: String,
+}
+
+impl std::str::FromStr for TrackingPair {
+    type Err = Error;
+
+    /// Parse `SETPOINT=MEASUREMENT`.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('=') {
+            Some((setpoint, measurement)) if !setpoint.is_empty() && !measurement.is_empty() => {
+                Ok(Self {
+                    setpoint: setpoint.to_string(),
+                    measurement: measurement.to_string(),
+                })
+            }
+            _ => Err(Error::Other(format!(
+                "Expected SETPOINT=MEASUREMENT, got '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Computes [`TrackingReport`]s for setpoint/measurement pairs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wpilog_parser::{TrackingAnalysis, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("match.wpilog")?;
+/// let reports = TrackingAnalysis::new()
+///     .pair("/Arm/Setpoint", "/Arm/Position")
+///     .min_step(5.0)
+///     .run(&reader)?;
+/// for report in &reports {
+///     println!("{}", report);
+/// }
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackingAnalysis {
+    pairs: Vec<TrackingPair>,
+    min_step: f64,
+    settling_band: f64,
+}
+
+impl Default for TrackingAnalysis {
+    fn default() -> Self {
+        Self {
+            pairs: Vec::new(),
+            min_step: 0.0,
+            settling_band: 0.02,
+        }
+    }
+}
+
+impl TrackingAnalysis {
+    /// Create an analysis with no pairs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyze how `measurement` follows `setpoint`.
+    pub fn pair(mut self, setpoint: impl Into<String>, measurement: impl Into<String>) -> Self {
+        self.pairs.push(TrackingPair {
+            setpoint: setpoint.into(),
+            measurement: measurement.into(),
+        });
+        self
+    }
+
+    /// Analyze all of `pairs`, e.g. a mapping loaded from a config file.
+    pub fn pairs(mut self, pairs: impl IntoIterator<Item = TrackingPair>) -> Self {
+        self.pairs.extend(pairs);
+        self
+    }
+
+    /// Only treat jumps of more than `min_step` between consecutive setpoint
+    /// samples as steps (default: every change). Profiled setpoints, which
+    /// move a little every loop, then only report their large jumps.
+    pub fn min_step(mut self, min_step: f64) -> Self {
+        self.min_step = min_step;
+        self
+    }
+
+    /// Settling band as a fraction of the step size (default: 0.02, i.e. 2%).
+    pub fn settling_band(mut self, fraction: f64) -> Self {
+        self.settling_band = fraction;
+        self
+    }
+
+    /// Compute one report per pair, in the order they were added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry of a pair has no numeric samples.
+    pub fn run(&self, reader: &WpilogReader) -> Result<Vec<TrackingReport>> {
+        self.pairs.iter().map(|pair| self.report(reader, pair)).collect()
+    }
+
+    fn report(&self, reader: &WpilogReader, pair: &TrackingPair) -> Result<TrackingReport> {
+        let rows = reader.read_entries(&[&pair.setpoint, &pair.measurement])?;
+
+        let mut report = TrackingReport {
+            pair: pair.clone(),
+            samples: 0,
+            rmse: 0.0,
+            mean_abs_error: 0.0,
+            max_abs_error: 0.0,
+            steps: Vec::new(),
+        };
+        let mut setpoint: Option<f64> = None;
+        let mut saw_measurement = false;
+        let mut step: Option<StepTracker> = None;
+        let mut squared_sum = 0.0;
+        let mut abs_sum = 0.0;
+
+        for row in &rows {
+            if let Some(value) = row.data.get(&pair.setpoint).and_then(|v| v.as_f64()) {
+                if let Some(previous) = setpoint.filter(|&previous| (value - previous).abs() > self.min_step.max(0.0)) {
+                    if let Some(done) = step.take() {
+                        report.steps.push(done.finish());
+                    }
+                    step = Some(StepTracker::new(row.timestamp, previous, value, self.settling_band));
+                }
+                setpoint = Some(value);
+            }
+            let Some(measurement) = row.data.get(&pair.measurement).and_then(|v| v.as_f64()) else {
+                continue;
+            };
+            saw_measurement = true;
+            let Some(target) = setpoint else {
+                continue;
+            };
+            let error = target - measurement;
+            report.samples += 1;
+            squared_sum += error * error;
+            abs_sum += error.abs();
+            report.max_abs_error = report.max_abs_error.max(error.abs());
+            if let Some(step) = &mut step {
+                step.observe(row.timestamp, measurement);
+            }
+        }
+        if let Some(done) = step.take() {
+            report.steps.push(done.finish());
+        }
+
+        if setpoint.is_none() {
+            return Err(Error::InvalidEntry(format!(
+                "No numeric samples for setpoint '{}'",
+                pair.setpoint
+            )));
+        }
+        if !saw_measurement {
+            return Err(Error::InvalidEntry(format!(
+                "No numeric samples for measurement '{}'",
+                pair.measurement
+            )));
+        }
+        if report.samples > 0 {
+            report.rmse = (squared_sum / report.samples as f64).sqrt();
+            report.mean_abs_error = abs_sum / report.samples as f64;
+        }
+        Ok(report)
+    }
+}
+
+/// How well one measurement followed its setpoint.
+///
+/// Errors are `setpoint - measurement`, with the setpoint held at its last
+/// value, evaluated at every measurement sample after the first setpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrackingReport {
+    pub pair: TrackingPair,
+    /// Measurement samples with a setpoint to compare against
+    pub samples: usize,
+    /// Root-mean-square error
+    pub rmse: f64,
+    pub mean_abs_error: f64,
+    pub max_abs_error: f64,
+    /// Setpoint steps in log order
+    pub steps: Vec<StepResponse>,
+}
+
+impl TrackingReport {
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl fmt::Display for TrackingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} -> {}", self.pair.setpoint, self.pair.measurement)?;
+        writeln!(
+            f,
+            "  {} samples, RMSE {:.4}, mean |error| {:.4}, max |error| {:.4}",
+            self.samples, self.rmse, self.mean_abs_error, self.max_abs_error
+        )?;
+        for step in &self.steps {
+            let settling = match step.settling_time_secs {
+                Some(secs) => format!("settled in {:.3} s", secs),
+                None => "did not settle".to_string(),
+            };
+            writeln!(
+                f,
+                "  step at {:.3} s: {} -> {}, overshoot {:.4} ({:.1}%), {}",
+                step.start_secs,
+                step.from,
+                step.to,
+                step.overshoot,
+                step.overshoot_percent,
+                settling
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Response of the measurement to one setpoint step, up to the next step or
+/// the end of the log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepResponse {
+    /// Timestamp of the step (seconds)
+    pub start_secs: f64,
+    /// Setpoint before the step
+    pub from: f64,
+    /// Setpoint after the step
+    pub to: f64,
+    /// How far the measurement went past the new setpoint, in the direction
+    /// of the step (0 if it never crossed it)
+    pub overshoot: f64,
+    /// Overshoot as a percentage of the step size
+    pub overshoot_percent: f64,
+    /// Time from the step until the measurement entered the settling band for
+    /// good, or `None` if it was outside the band at the last sample
+    pub settling_time_secs: Option<f64>,
+    /// Measurement samples during the step
+    pub samples: usize,
+}
+
+/// Accumulates a [`StepResponse`] while its step is active.
+struct StepTracker {
+    start: f64,
+    from: f64,
+    to: f64,
+    /// Half-width of the settling band around `to`
+    band: f64,
+    overshoot: f64,
+    samples: usize,
+    /// Timestamp the measurement last entered the band, if it is inside it
+    settled_at: Option<f64>,
+}
+
+impl StepTracker {
+    fn new(start: f64, from: f64, to: f64, settling_band: f64) -> Self {
+        Self {
+            start,
+            from,
+            to,
+            band: settling_band * (to - from).abs(),
+            overshoot: 0.0,
+            samples: 0,
+            settled_at: None,
+        }
+    }
+
+    fn observe(&mut self, timestamp: f64, measurement: f64) {
+        let direction = (self.to - self.from).signum();
+        self.overshoot = self.overshoot.max((measurement - self.to) * direction);
+        self.samples += 1;
+        if (measurement - self.to).abs() > self.band {
+            self.settled_at = None;
+        } else {
+            self.settled_at.get_or_insert(timestamp);
+        }
+    }
+
+    fn finish(self) -> StepResponse {
+        let size = (self.to - self.from).abs();
+        StepResponse {
+            start_secs: self.start,
+            from: self.from,
+            to: self.to,
+            overshoot: self.overshoot,
+            overshoot_percent: 100.0 * self.overshoot / size,
+            settling_time_secs: self.settled_at.map(|settled| settled - self.start),
+            samples: self.samples,
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Enabled-time accounting for mechanism wear tracking.
+//!
+//! [`UsageSummary`] adds up how long the robot was enabled in a log, split
+//! into autonomous, teleop and test, and counts enable cycles and brownouts.
+//! Robot state is read from the Driver Station entries `DataLogManager` records
+//! (`DS:enabled`, `DS:autonomous`, `DS:test`), AdvantageKit's
+//! `/DriverStation/*` entries, or the `FMSInfo/FMSControlData` control word;
+//! brownouts are rising edges of boolean `.../BrownedOut` entries such as
+//! AdvantageKit's `/SystemStats/BrownedOut`.
+//!
+//! [`UsageReport::from_directory`] sums the summaries of every log in a
+//! directory.
+
+use crate::datalog::{DataLogReader, DataLogRecord, RecordVisitor, StartRecordData};
+use crate::entry_type::DecodedValue;
+use crate::error::{Error, Result};
+use crate::reader::WpilogReader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::ops::AddAssign;
+use std::path::Path;
+
+/// Enabled time and cycle counts of one log, or of several added together.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageSummary {
+    /// Number of logs summarized
+    pub logs: usize,
+    /// Time covered by the logs, in seconds
+    pub duration_secs: f64,
+    /// Total time enabled, in seconds
+    pub enabled_secs: f64,
+    /// Time enabled in autonomous, in seconds
+    pub auto_secs: f64,
+    /// Time enabled in teleop, in seconds
+    pub teleop_secs: f64,
+    /// Time enabled in test mode, in seconds
+    pub test_secs: f64,
+    /// Number of disabled -> enabled transitions
+    pub enable_cycles: u64,
+    /// Number of brownouts
+    pub brownouts: u64,
+}
+
+impl UsageSummary {
+    /// Account the log read by `reader`.
+    pub fn from_reader(reader: &DataLogReader<'_>) -> Result<Self> {
+        let mut visitor = UsageVisitor::default();
+        reader.visit(&mut visitor)?;
+        Ok(visitor.finish())
+    }
+}
+
+/// Timestamp (microseconds) the robot was first enabled, or with
+/// `autonomous` first enabled in autonomous, in the log read by `reader`, if
+/// it ever was.
+pub(crate) fn first_enabled(reader: &DataLogReader<'_>, autonomous: bool) -> Result<Option<u64>> {
+    let mut visitor = UsageVisitor::default();
+    reader.visit(&mut visitor)?;
+    Ok(if autonomous {
+        visitor.first_auto
+    } else {
+        visitor.first_enabled
+    })
+}
+
+impl AddAssign<&UsageSummary> for UsageSummary {
+    fn add_assign(&mut self, other: &UsageSummary) {
+        self.logs += other.logs;
+        self.duration_secs += other.duration_secs;
+        self.enabled_secs += other.enabled_secs;
+        self.auto_secs += other.auto_secs;
+        self.teleop_secs += other.teleop_secs;
+        self.test_secs += other.test_secs;
+        self.enable_cycles += other.enable_cycles;
+        self.brownouts += other.brownouts;
+    }
+}
+
+/// Usage of each log in a directory and their total.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// File name and summary of each log, sorted by file name
+    pub logs: Vec<(String, UsageSummary)>,
+    pub total: UsageSummary,
+}
+
+impl UsageReport {
+    /// Summarize every `.wpilog` file in `dir`.
+    ///
+    /// Logs that cannot be read are skipped with a warning, so one corrupt
+    /// file does not hide a season's worth of usage.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::UsageReport;
+    ///
+    /// let report = UsageReport::from_directory("/mnt/robot-logs")?;
+    /// println!(
+    ///     "{:.1} h enabled over {} cycles",
+    ///     report.total.enabled_secs / 3600.0,
+    ///     report.total.enable_cycles
+    /// );
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn from_directory<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(Error::InvalidFormat(format!(
+                "'{}' is not a valid directory",
+                dir.display()
+            )));
+        }
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("wpilog"))
+            .collect();
+        paths.sort();
+
+        let mut report = UsageReport::default();
+        for path in paths {
+            let file = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match WpilogReader::from_file(&path).and_then(|reader| reader.usage()) {
+                Ok(summary) => report.add(file, summary),
+                Err(e) => log::warn!("Skipping {} in usage report: {}", file, e),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Add one log's summary to the report.
+    pub fn add(&mut self, file: impl Into<String>, summary: UsageSummary) {
+        self.total += &summary;
+        self.logs.push((file.into(), summary));
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Robot state an entry reports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Signal {
+    Enabled,
+    Autonomous,
+    Test,
+    ControlWord,
+    BrownedOut,
+}
+
+impl Signal {
+    fn of(name: &str) -> Option<Self> {
+        match name {
+            "DS:enabled" => Some(Signal::Enabled),
+            "DS:autonomous" => Some(Signal::Autonomous),
+            "DS:test" => Some(Signal::Test),
+            _ if name.ends_with("DriverStation/Enabled") => Some(Signal::Enabled),
+            _ if name.ends_with("DriverStation/Autonomous") => Some(Signal::Autonomous),
+            _ if name.ends_with("DriverStation/Test") => Some(Signal::Test),
+            _ if name.ends_with("FMSInfo/FMSControlData") => Some(Signal::ControlWord),
+            _ if name.ends_with("/BrownedOut") => Some(Signal::BrownedOut),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct UsageVisitor {
+    signals: HashMap<u32, Signal>,
+    summary: UsageSummary,
+    enabled: bool,
+    autonomous: bool,
+    test: bool,
+    browned_out: bool,
+    /// Timestamp the current state was entered
+    since: u64,
+    first_timestamp: Option<u64>,
+    last_timestamp: u64,
+    first_enabled: Option<u64>,
+    first_auto: Option<u64>,
+}
+
+impl UsageVisitor {
+    fn seen(&mut self, timestamp: u64) {
+        self.first_timestamp.get_or_insert(timestamp);
+        self.last_timestamp = self.last_timestamp.max(timestamp);
+    }
+
+    /// Credit the time since the last state change to the current state.
+    fn account(&mut self, timestamp: u64) {
+        let secs = timestamp.saturating_sub(self.since) as f64 / 1_000_000.0;
+        self.since = timestamp.max(self.since);
+        if !self.enabled {
+            return;
+        }
+        self.summary.enabled_secs += secs;
+        if self.test {
+            self.summary.test_secs += secs;
+        } else if self.autonomous {
+            self.summary.auto_secs += secs;
+        } else {
+            self.summary.teleop_secs += secs;
+        }
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            self.summary.enable_cycles += 1;
+            // `account` has just moved `since` to the record's timestamp
+            self.first_enabled.get_or_insert(self.since);
+        }
+        self.enabled = enabled;
+    }
+
+    fn finish(mut self) -> UsageSummary {
+        self.account(self.last_timestamp);
+        self.summary.logs = 1;
+        self.summary.duration_secs =
+            self.last_timestamp.saturating_sub(self.first_timestamp.unwrap_or(0)) as f64 / 1_000_000.0;
+        self.summary
+    }
+}
+
+impl RecordVisitor for UsageVisitor {
+    fn on_start(&mut self, start: &StartRecordData, timestamp: u64) -> Result<()> {
+        self.seen(timestamp);
+        match Signal::of(&start.name) {
+            Some(signal) => self.signals.insert(start.entry, signal),
+            None => self.signals.remove(&start.entry),
+        };
+        Ok(())
+    }
+
+    fn on_finish(&mut self, _entry: u32, timestamp: u64) -> Result<()> {
+        self.seen(timestamp);
+        Ok(())
+    }
+
+    fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+        self.seen(timestamp);
+        let Some(&signal) = self.signals.get(&entry.entry) else {
+            return Ok(());
+        };
+        let record = DataLogRecord {
+            entry: entry.entry,
+            timestamp,
+            data: payload.to_vec(),
+        };
+        let value = match record.decode_as(&entry.entry_type) {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("Skipping an undecodable value of '{}' in usage: {}", entry.name, e);
+                return Ok(());
+            }
+        };
+
+        self.account(timestamp);
+        match (signal, value) {
+            (Signal::Enabled, DecodedValue::Boolean(enabled)) => self.set_enabled(enabled),
+            (Signal::Autonomous, DecodedValue::Boolean(autonomous)) => self.autonomous = autonomous,
+            (Signal::Test, DecodedValue::Boolean(test)) => self.test = test,
+            (Signal::ControlWord, DecodedValue::Int64(word)) => {
+                self.set_enabled(word & 0x01 != 0);
+                self.autonomous = word & 0x02 != 0;
+                self.test = word & 0x04 != 0;
+            }
+            (Signal::BrownedOut, DecodedValue::Boolean(browned_out)) => {
+                if browned_out && !self.browned_out {
+                    self.summary.brownouts += 1;
+                }
+                self.browned_out = browned_out;
+            }
+            _ => {}
+        }
+        if self.enabled && self.autonomous && !self.test {
+            // `account` has just moved `since` to the record's timestamp
+            self.first_auto.get_or_insert(self.since);
+        }
+        Ok(())
+    }
+}
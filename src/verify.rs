@@ -0,0 +1,208 @@
+//! Fidelity checks between decoded records and their Parquet export.
+//!
+//! Intended for pipelines that delete the original `.wpilog` after conversion:
+//! re-read the written files and make sure nothing was lost.
+
+use crate::error::Result;
+use crate::formats::parquet::read_parquet_directory;
+use crate::models::WideRow;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Default number of rows whose values are compared cell by cell.
+const DEFAULT_SAMPLE_ROWS: usize = 1_000;
+
+/// A single difference between the source records and the export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mismatch {
+    /// Total number of rows differs
+    RowCount { source: usize, exported: usize },
+    /// Number of non-null values in a column differs
+    NonNullCount {
+        column: String,
+        source: usize,
+        exported: usize,
+    },
+    /// A sampled cell value differs
+    Value {
+        row: usize,
+        column: String,
+        source: Value,
+        exported: Value,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::RowCount { source, exported } => {
+                write!(f, "row count: source {} != exported {}", source, exported)
+            }
+            Mismatch::NonNullCount {
+                column,
+                source,
+                exported,
+            } => write!(
+                f,
+                "non-null count for '{}': source {} != exported {}",
+                column, source, exported
+            ),
+            Mismatch::Value {
+                row,
+                column,
+                source,
+                exported,
+            } => write!(
+                f,
+                "row {} column '{}': source {} != exported {}",
+                row, column, source, exported
+            ),
+        }
+    }
+}
+
+/// Result of comparing records against their Parquet export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Rows in the source record set
+    pub source_rows: usize,
+    /// Rows read back from the export
+    pub exported_rows: usize,
+    /// Number of rows whose values were compared
+    pub sampled_rows: usize,
+    /// All differences found
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl VerifyReport {
+    /// Whether the export matched the source.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Human-readable diff, one mismatch per line.
+    pub fn diff(&self) -> String {
+        self.mismatches
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Compare `records` against the Parquet files in `directory`.
+///
+/// Checks the total row count and per-column non-null counts over all rows, and
+/// compares cell values for an evenly spaced sample of rows.
+pub fn verify_export<P: AsRef<Path>>(records: &[WideRow], directory: P) -> Result<VerifyReport> {
+    verify_export_sampled(records, directory, DEFAULT_SAMPLE_ROWS)
+}
+
+/// Like [`verify_export`], comparing values for at most `sample_rows` rows.
+pub fn verify_export_sampled<P: AsRef<Path>>(
+    records: &[WideRow],
+    directory: P,
+    sample_rows: usize,
+) -> Result<VerifyReport> {
+    let exported = read_parquet_directory(directory.as_ref())?;
+    let mut report = VerifyReport {
+        source_rows: records.len(),
+        exported_rows: exported.len(),
+        ..Default::default()
+    };
+
+    if records.len() != exported.len() {
+        report.mismatches.push(Mismatch::RowCount {
+            source: records.len(),
+            exported: exported.len(),
+        });
+    }
+
+    let source_counts = non_null_counts(records);
+    let exported_counts = non_null_counts(&exported);
+    let mut columns: Vec<&String> = source_counts.keys().chain(exported_counts.keys()).collect();
+    columns.sort();
+    columns.dedup();
+    for column in columns {
+        let source = source_counts.get(column).copied().unwrap_or(0);
+        let exported = exported_counts.get(column).copied().unwrap_or(0);
+        if source != exported {
+            report.mismatches.push(Mismatch::NonNullCount {
+                column: column.clone(),
+                source,
+                exported,
+            });
+        }
+    }
+
+    let rows = records.len().min(exported.len());
+    let stride = if sample_rows == 0 { 0 } else { rows.div_ceil(sample_rows).max(1) };
+    if stride > 0 {
+        for row in (0..rows).step_by(stride) {
+            report.sampled_rows += 1;
+            compare_row(row, &records[row], &exported[row], &mut report.mismatches);
+        }
+    }
+
+    Ok(report)
+}
+
+fn is_null(value: &Value) -> bool {
+    // Null cells in string columns are written as the literal "null"
+    matches!(value, Value::Null) || value.as_str() == Some("null")
+}
+
+fn non_null_counts(rows: &[WideRow]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for row in rows {
+        for (column, value) in &row.data {
+            if !is_null(value) {
+                *counts.entry(column.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+fn compare_row(row: usize, source: &WideRow, exported: &WideRow, mismatches: &mut Vec<Mismatch>) {
+    for (column, source_value) in &source.data {
+        let exported_value = exported.data.get(column).unwrap_or(&Value::Null);
+        if !values_match(source_value, exported_value) {
+            mismatches.push(Mismatch::Value {
+                row,
+                column: column.clone(),
+                source: source_value.clone(),
+                exported: exported_value.clone(),
+            });
+        }
+    }
+}
+
+fn values_match(source: &Value, exported: &Value) -> bool {
+    if is_null(source) || is_null(exported) {
+        return is_null(source) && is_null(exported);
+    }
+    match (source, exported) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| values_match(x, y))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, x)| b.get(key).is_some_and(|y| values_match(x, y)))
+        }
+        // Other objects and mixed arrays are exported as JSON strings
+        (Value::Object(_) | Value::Array(_), Value::String(s)) => {
+            serde_json::to_string(source).ok().as_deref() == Some(s.as_str())
+        }
+        _ => source == exported,
+    }
+}
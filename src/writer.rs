@@ -1,9 +1,25 @@
 //! High-level API for writing parsed WPILog data to various formats.
 
 use crate::error::{Error, Result};
-use crate::formats::parquet::ParquetFormatter;
-use crate::models::WideRow;
+use crate::formats::parquet::{
+    ColumnOrder, ColumnType, ParquetCompression, ParquetFormatter, StatisticsLevel,
+};
+use crate::provenance::Provenance;
+use crate::models::{LongRow, WideRow};
+use crate::reduce::ChangeFilter;
+use crate::sink::{DirectorySink, OutputSink};
+use crate::spill::SpillStore;
+use arrow::datatypes::SchemaRef;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::path::Path;
+use std::sync::Arc;
 
 /// Writer for outputting WPILog data to Apache Parquet format.
 ///
@@ -25,8 +41,23 @@ use std::path::Path;
 /// # Ok::<(), wpilog_parser::Error>(())
 /// ```
 pub struct ParquetWriter {
-    output_directory: String,
+    sink: Arc<dyn OutputSink>,
     chunk_size: usize,
+    properties: Option<WriterProperties>,
+    compression: Option<ParquetCompression>,
+    dictionary: Option<bool>,
+    max_row_group_size: Option<usize>,
+    statistics: Option<StatisticsLevel>,
+    lookup_indexes: bool,
+    change_filter: Option<ChangeFilter>,
+    threads: usize,
+    target_file_size: Option<usize>,
+    max_bytes_in_flight: Option<usize>,
+    column_types: HashMap<String, ColumnType>,
+    column_order: ColumnOrder,
+    constant_columns: Vec<(String, String)>,
+    schema: Option<SchemaRef>,
+    first_chunk_index: usize,
 }
 
 impl ParquetWriter {
@@ -44,9 +75,40 @@ impl ParquetWriter {
     /// let writer = ParquetWriter::new("./output");
     /// ```
     pub fn new<P: AsRef<Path>>(output_directory: P) -> Self {
+        Self::to_sink(DirectorySink::new(output_directory))
+    }
+
+    /// Create a new Parquet writer that hands its files to `sink`, such as a
+    /// [`MemorySink`](crate::MemorySink) or a closure returning an
+    /// [`io::Write`](std::io::Write) for each file name.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{MemorySink, ParquetWriter};
+    ///
+    /// let sink = MemorySink::new();
+    /// let writer = ParquetWriter::to_sink(sink.clone());
+    /// ```
+    pub fn to_sink<S: OutputSink + 'static>(sink: S) -> Self {
         Self {
-            output_directory: output_directory.as_ref().to_string_lossy().to_string(),
+            sink: Arc::new(sink),
             chunk_size: 50_000, // Default chunk size
+            properties: None,
+            compression: None,
+            dictionary: None,
+            max_row_group_size: None,
+            statistics: None,
+            lookup_indexes: false,
+            change_filter: None,
+            threads: 1,
+            target_file_size: None,
+            max_bytes_in_flight: None,
+            column_types: HashMap::new(),
+            column_order: ColumnOrder::Alphabetical,
+            constant_columns: Vec::new(),
+            schema: None,
+            first_chunk_index: 0,
         }
     }
 
@@ -72,6 +134,321 @@ impl ParquetWriter {
         self
     }
 
+    /// Use custom low-level Parquet writer properties.
+    ///
+    /// This is an escape hatch for settings not otherwise exposed, such as
+    /// bloom filters, column-specific encodings, or page index settings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use parquet::file::properties::WriterProperties;
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let props = WriterProperties::builder()
+    ///     .set_bloom_filter_enabled(true)
+    ///     .build();
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .writer_properties(props);
+    /// ```
+    pub fn writer_properties(mut self, properties: WriterProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// Compress files with `compression` (default: uncompressed).
+    ///
+    /// Zstandard usually shrinks log exports several times over for a modest
+    /// cost in write speed; Snappy and LZ4 compress less but barely slow
+    /// writes down. Applied on top of any [`writer_properties`](Self::writer_properties).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ParquetCompression, ParquetWriter};
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .compression(ParquetCompression::Zstd(3));
+    /// ```
+    pub fn compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Enable or disable dictionary encoding of all columns (default:
+    /// enabled).
+    ///
+    /// Dictionaries shrink columns with few distinct values, such as modes
+    /// and states; disabling them speeds up writing columns of mostly unique
+    /// values.
+    pub fn dictionary(mut self, enabled: bool) -> Self {
+        self.dictionary = Some(enabled);
+        self
+    }
+
+    /// Start a new row group after `rows` rows (default: 1,048,576).
+    ///
+    /// Smaller row groups let readers skip more data by statistics and use
+    /// less memory, larger ones compress better.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .max_row_group_size(10_000);
+    /// ```
+    pub fn max_row_group_size(mut self, rows: usize) -> Self {
+        self.max_row_group_size = Some(rows);
+        self
+    }
+
+    /// Set how much column statistics files carry (default:
+    /// [`StatisticsLevel::Page`]).
+    pub fn statistics(mut self, level: StatisticsLevel) -> Self {
+        self.statistics = Some(level);
+        self
+    }
+
+    /// Enable bloom filters and page indexes for fast lookups.
+    ///
+    /// Adds bloom filters on the `type` and `entry` columns and page-level
+    /// statistics on `timestamp`, which greatly accelerates predicate pushdown
+    /// for "find when X happened" queries in tools like DuckDB. Default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .lookup_indexes(true);
+    /// ```
+    pub fn lookup_indexes(mut self, enabled: bool) -> Self {
+        self.lookup_indexes = enabled;
+        self
+    }
+
+    /// Keep only change points for selected entries before writing.
+    ///
+    /// See [`ChangeFilter`] for the exact semantics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ChangeFilter, ParquetWriter};
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .change_filter(ChangeFilter::new().booleans(true));
+    /// ```
+    pub fn change_filter(mut self, filter: ChangeFilter) -> Self {
+        self.change_filter = Some(filter);
+        self
+    }
+
+    /// Write chunk files in parallel on up to `threads` threads.
+    ///
+    /// Chunks are independent, so on fast storage conversion time scales
+    /// roughly with the number of threads. `0` uses all available CPUs.
+    /// Default is 1 (sequential).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .threads(4);
+    /// ```
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Split files by estimated size instead of row count.
+    ///
+    /// Rows vary from a single boolean to arrays with thousands of elements, so
+    /// a fixed row count can produce wildly different file sizes. With a target
+    /// size, each file holds rows until their estimated uncompressed size
+    /// reaches `bytes`; [`chunk_size`](Self::chunk_size) is then ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .target_file_size(128 * 1024 * 1024);
+    /// ```
+    pub fn target_file_size(mut self, bytes: usize) -> Self {
+        self.target_file_size = Some(bytes);
+        self
+    }
+
+    /// Cap the memory used to build each chunk at roughly `bytes`.
+    ///
+    /// A wide-format chunk holds a slot for every row in every column, so a
+    /// chunk covering many large array entries can need far more memory than
+    /// its row count suggests. Chunks whose estimated Arrow arrays would exceed
+    /// `bytes` are split into more files. Works with either
+    /// [`chunk_size`](Self::chunk_size) or [`target_file_size`](Self::target_file_size).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .max_bytes_in_flight(512 * 1024 * 1024);
+    /// ```
+    pub fn max_bytes_in_flight(mut self, bytes: usize) -> Self {
+        self.max_bytes_in_flight = Some(bytes);
+        self
+    }
+
+    /// Force the Parquet type of a column instead of inferring it from the
+    /// first value, e.g. `Float32` for a latency column or `Dictionary` for a
+    /// mode string. Values that do not fit the type are written as nulls.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ColumnType, ParquetWriter};
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .column_type("/Vision/Latency", ColumnType::Float32)
+    ///     .column_type("/Mode", ColumnType::Dictionary);
+    /// ```
+    pub fn column_type(mut self, column: impl Into<String>, column_type: ColumnType) -> Self {
+        self.column_types.insert(column.into(), column_type);
+        self
+    }
+
+    /// Force the Parquet types of several columns, replacing any earlier
+    /// overrides. See [`column_type`](Self::column_type).
+    pub fn column_types(mut self, column_types: HashMap<String, ColumnType>) -> Self {
+        self.column_types = column_types;
+        self
+    }
+
+    /// Order value columns by `order` instead of by name (default:
+    /// [`ColumnOrder::Alphabetical`]).
+    ///
+    /// Declaration order follows the order entries were started in the log,
+    /// so an export lines up with the robot code that logged it; an explicit
+    /// order puts important columns first. Either way the order depends only
+    /// on the columns present, so exports of similar logs diff cleanly.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ColumnOrder, ParquetWriter};
+    ///
+    /// let writer = ParquetWriter::new("./output").column_order(ColumnOrder::Explicit(vec![
+    ///     "/Robot/Pose".to_string(),
+    ///     "/Drive/LeftVelocity".to_string(),
+    /// ]));
+    /// ```
+    pub fn column_order(mut self, order: ColumnOrder) -> Self {
+        self.column_order = order;
+        self
+    }
+
+    /// Stamp a string column holding `value` into every row, so batch
+    /// pipelines can tag exports with the event, match or robot they came
+    /// from.
+    ///
+    /// Constant columns follow the fixed columns in the order they were added;
+    /// adding a name again replaces its value. Writing fails if a logged entry
+    /// has the same column name.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let writer = ParquetWriter::new("./output")
+    ///     .constant_column("event", "2025casj")
+    ///     .constant_column("match", "Q42");
+    /// ```
+    pub fn constant_column(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        match self.constant_columns.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(column) => column.1 = value,
+            None => self.constant_columns.push((name, value)),
+        }
+        self
+    }
+
+    /// Stamp the source log's `source_file`, `source_sha256` and
+    /// `converted_at` into every row as [constant columns](Self::constant_column).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ParquetWriter, Provenance, WpilogReader};
+    ///
+    /// let records = WpilogReader::from_file("data.wpilog")?.read_all()?;
+    /// ParquetWriter::new("./output")
+    ///     .provenance(&Provenance::of_file("data.wpilog")?)
+    ///     .write(&records)?;
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn provenance(mut self, provenance: &Provenance) -> Self {
+        for (name, value) in provenance.columns() {
+            self = self.constant_column(name, value);
+        }
+        self
+    }
+
+    /// Write every file with `schema` instead of inferring one from the rows.
+    ///
+    /// By default all files of one write share a schema inferred from every
+    /// row, so multi-file readers such as Spark and DuckDB see one table.
+    /// Supplying the schema, for example one read back with
+    /// [`read_parquet_schema`](crate::formats::parquet::read_parquet_schema),
+    /// extends that to files written from different logs. Fields are filled
+    /// by name, values are cast to the field's type and missing columns are
+    /// written as nulls; writing fails if a row has a value column or a
+    /// constant column the schema lacks. Long-format writes ignore it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use wpilog_parser::formats::parquet::read_parquet_schema;
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// let schema = read_parquet_schema(Path::new("./output/match1/file_part000.parquet"))?;
+    /// let writer = ParquetWriter::new("./output/match2").schema(schema);
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Number the chunk files from `index` (default: 0), so a conversion of
+    /// newly appended records adds files next to the ones already written
+    /// instead of overwriting them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::ParquetWriter;
+    ///
+    /// // Three files were written before; the next one is file_part003.parquet
+    /// let writer = ParquetWriter::new("./output")
+    ///     .first_chunk_index(3);
+    /// ```
+    pub fn first_chunk_index(mut self, index: usize) -> Self {
+        self.first_chunk_index = index;
+        self
+    }
+
     /// Write the records to Parquet format.
     ///
     /// This will create one or more Parquet files in the output directory,
@@ -102,13 +479,7 @@ impl ParquetWriter {
     /// # Ok::<(), wpilog_parser::Error>(())
     /// ```
     pub fn write(self, records: &[WideRow]) -> Result<()> {
-        let formatter = ParquetFormatter::new(self.output_directory, self.chunk_size);
-
-        formatter
-            .convert(records)
-            .map_err(|e| Error::OutputError(e.to_string()))?;
-
-        Ok(())
+        self.write_with_stats(records).map(|_| ())
     }
 
     /// Write records to Parquet and return statistics about the write operation.
@@ -117,28 +488,269 @@ impl ParquetWriter {
     ///
     /// A `WriteStats` struct containing information about the write operation.
     pub fn write_with_stats(self, records: &[WideRow]) -> Result<WriteStats> {
-        let num_records = records.len();
-        let num_chunks = (num_records + self.chunk_size - 1) / self.chunk_size;
-        let chunk_size = self.chunk_size;
+        let rows = self.prepare(records);
+
+        let formatter = self.formatter()?;
+        let chunks = formatter.plan_chunks(&rows);
+        formatter.convert_chunks(&chunks)?;
+
+        Ok(WriteStats {
+            num_records: rows.len(),
+            num_chunks: chunks.len(),
+            chunk_size: if self.fixed_chunk_size() {
+                self.chunk_size
+            } else {
+                chunks.iter().map(|chunk| chunk.len()).max().unwrap_or(0)
+            },
+        })
+    }
 
-        self.write(records)?;
+    /// Write long-format rows, such as those of
+    /// [`WpilogReader::read_all_long`](crate::WpilogReader::read_all_long),
+    /// `chunk_size` rows per file.
+    ///
+    /// Every file has the fixed [long layout](crate::formats::long): the
+    /// `timestamp`, `entry`, `entry_name`, `type` and `loop_count` columns and
+    /// one typed value column per kind of value, so files written from logs
+    /// with thousands of sparse entries stay narrow. Writer properties and
+    /// constant columns apply; column types, column order and the change filter apply to wide
+    /// rows only and are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `records` is empty or the Parquet files cannot be
+    /// written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ParquetWriter, WpilogReader};
+    ///
+    /// let rows = WpilogReader::from_file("data.wpilog")?.read_all_long()?;
+    /// let stats = ParquetWriter::new("./output").write_long(&rows)?;
+    /// println!("{}", stats.summary());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn write_long(self, records: &[LongRow]) -> Result<WriteStats> {
+        let chunk_size = self.chunk_size.max(1);
+        let mut formatter = ParquetFormatter::new(String::new(), chunk_size)
+            .with_sink(self.sink.clone())
+            .with_first_chunk_index(self.first_chunk_index);
+        if let Some(properties) = self.properties()? {
+            formatter = formatter.with_writer_properties(properties);
+        }
+        for (name, value) in &self.constant_columns {
+            formatter = formatter.with_constant_column(name.as_str(), value.as_str());
+        }
+        formatter.convert_long(records)?;
 
         Ok(WriteStats {
-            num_records,
-            num_chunks,
+            num_records: records.len(),
+            num_chunks: records.len().div_ceil(chunk_size),
             chunk_size,
         })
     }
+
+    /// Write rows staged in a [`SpillStore`], reading them back one batch at a
+    /// time.
+    ///
+    /// Chunk files are written sequentially. Unless a [`schema`](Self::schema)
+    /// is set, the store is read twice: once to infer the schema every file
+    /// shares, then to write. A change filter cannot be applied to spilled
+    /// rows since it needs the whole record set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a change filter is configured, or an error
+    /// if the store is empty or the spill or Parquet files cannot be read or written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ParquetWriter, SpillStore, WpilogReader};
+    ///
+    /// let mut store = SpillStore::new()?;
+    /// WpilogReader::from_file("data.wpilog")?.read_to_spill(&mut store)?;
+    ///
+    /// let stats = ParquetWriter::new("./output").write_spilled(&store)?;
+    /// println!("{}", stats.summary());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn write_spilled(self, store: &SpillStore) -> Result<WriteStats> {
+        if self.change_filter.is_some() {
+            return Err(Error::Config(
+                "Change filters are not supported for spilled records".to_string(),
+            ));
+        }
+
+        let mut formatter = self.formatter()?;
+        if self.schema.is_none() {
+            // The store can be read twice, so every file gets the schema of all rows
+            let schema = formatter.streamed_schema(store.batches())?;
+            formatter = formatter.with_schema(schema);
+        }
+        self.write_batches(&formatter, store.batches())
+    }
+
+    /// Write rows from an iterator, such as [`WpilogReader::rows`](crate::WpilogReader::rows),
+    /// holding at most one chunk of them in memory.
+    ///
+    /// With a [`schema`](Self::schema), chunk files are written sequentially
+    /// as rows arrive. Without one, rows are first staged in a temporary
+    /// [`SpillStore`] so every file gets the schema of all rows, as in
+    /// [`write_spilled`](Self::write_spilled); memory use stays bounded but
+    /// the rows pass through disk once more. A change filter cannot be applied
+    /// to streamed rows since it needs the whole record set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a change filter is configured, or an error
+    /// if the iterator yields an error or no rows, or the Parquet files cannot be written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wpilog_parser::{ParquetWriter, WpilogReader};
+    ///
+    /// let reader = WpilogReader::from_file("data.wpilog")?;
+    /// let stats = ParquetWriter::new("./output").write_rows(reader.rows()?)?;
+    /// println!("{}", stats.summary());
+    /// # Ok::<(), wpilog_parser::Error>(())
+    /// ```
+    pub fn write_rows<I>(self, rows: I) -> Result<WriteStats>
+    where
+        I: IntoIterator<Item = Result<WideRow>>,
+    {
+        if self.change_filter.is_some() {
+            return Err(Error::Config(
+                "Change filters are not supported for streamed records".to_string(),
+            ));
+        }
+
+        let batch_rows = self.chunk_size.max(1);
+        if self.schema.is_none() {
+            let mut store = SpillStore::new()?.batch_rows(batch_rows);
+            for row in rows {
+                store.push(row?)?;
+            }
+            return self.write_spilled(&store);
+        }
+
+        let mut rows = rows.into_iter();
+        let batches = std::iter::from_fn(|| {
+            match rows.by_ref().take(batch_rows).collect::<Result<Vec<WideRow>>>() {
+                Ok(batch) if batch.is_empty() => None,
+                batch => Some(batch),
+            }
+        });
+        self.write_batches(&self.formatter()?, batches)
+    }
+
+    /// Write rows arriving in batches, one chunk file at a time.
+    fn write_batches<I>(&self, formatter: &ParquetFormatter, batches: I) -> Result<WriteStats>
+    where
+        I: IntoIterator<Item = Result<Vec<WideRow>>>,
+    {
+        let written = formatter.convert_batches(batches)?;
+
+        Ok(WriteStats {
+            num_records: written.iter().sum(),
+            num_chunks: written.len(),
+            chunk_size: if self.fixed_chunk_size() {
+                self.chunk_size
+            } else {
+                written.iter().copied().max().unwrap_or(0)
+            },
+        })
+    }
+
+    /// Whether every chunk but the last holds exactly `chunk_size` rows.
+    fn fixed_chunk_size(&self) -> bool {
+        self.target_file_size.is_none() && self.max_bytes_in_flight.is_none()
+    }
+
+    /// Check the settings that can only fail when files are written.
+    fn validated(self) -> Result<Self> {
+        self.properties()?;
+        Ok(self)
+    }
+
+    /// Writer properties with this writer's overrides applied, if any were
+    /// set.
+    fn properties(&self) -> Result<Option<WriterProperties>> {
+        let overridden = self.compression.is_some()
+            || self.dictionary.is_some()
+            || self.max_row_group_size.is_some()
+            || self.statistics.is_some();
+        if !overridden {
+            return Ok(self.properties.clone());
+        }
+
+        let mut builder = self.properties.clone().unwrap_or_default().into_builder();
+        if let Some(compression) = self.compression {
+            builder = builder.set_compression(compression.codec()?);
+        }
+        if let Some(enabled) = self.dictionary {
+            builder = builder.set_dictionary_enabled(enabled);
+        }
+        if let Some(rows) = self.max_row_group_size {
+            if rows == 0 {
+                return Err(Error::Config("max_row_group_size must be positive".to_string()));
+            }
+            builder = builder.set_max_row_group_size(rows);
+        }
+        if let Some(level) = self.statistics {
+            builder = builder.set_statistics_enabled(level.into());
+        }
+        Ok(Some(builder.build()))
+    }
+
+    /// Build the formatter configured by this writer.
+    fn formatter(&self) -> Result<ParquetFormatter> {
+        let mut formatter =
+            ParquetFormatter::new(String::new(), self.chunk_size).with_sink(self.sink.clone());
+        if let Some(properties) = self.properties()? {
+            formatter = formatter.with_writer_properties(properties);
+        }
+        if self.lookup_indexes {
+            formatter = formatter.with_lookup_indexes();
+        }
+        if let Some(bytes) = self.target_file_size {
+            formatter = formatter.with_target_file_size(bytes);
+        }
+        if let Some(bytes) = self.max_bytes_in_flight {
+            formatter = formatter.with_max_bytes_in_flight(bytes);
+        }
+        for (name, value) in &self.constant_columns {
+            formatter = formatter.with_constant_column(name.as_str(), value.as_str());
+        }
+        if let Some(schema) = &self.schema {
+            formatter = formatter.with_schema(Arc::clone(schema));
+        }
+        Ok(formatter
+            .with_column_types(self.column_types.clone())
+            .with_column_order(self.column_order.clone())
+            .with_first_chunk_index(self.first_chunk_index)
+            .with_threads(self.threads))
+    }
+
+    /// Apply row-level transformations configured on this writer.
+    fn prepare<'a>(&self, records: &'a [WideRow]) -> Cow<'a, [WideRow]> {
+        match &self.change_filter {
+            Some(filter) => Cow::Owned(filter.apply(records)),
+            None => Cow::Borrowed(records),
+        }
+    }
 }
 
 /// Statistics about a Parquet write operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WriteStats {
     /// Total number of records written
     pub num_records: usize,
     /// Number of Parquet files created
     pub num_chunks: usize,
-    /// Rows per file (chunk size)
+    /// Rows in the largest file (the chunk size when chunking by row count)
     pub chunk_size: usize,
 }
 
@@ -150,6 +762,11 @@ impl WriteStats {
             self.num_records, self.num_chunks, self.chunk_size
         )
     }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
 }
 
 /// Builder for configuring Parquet write options.
@@ -170,22 +787,60 @@ impl WriteStats {
 /// # Ok::<(), wpilog_parser::Error>(())
 /// ```
 pub struct ParquetWriterBuilder {
-    output_directory: Option<String>,
+    sink: Option<Arc<dyn OutputSink>>,
     chunk_size: usize,
+    properties: Option<WriterProperties>,
+    compression: Option<ParquetCompression>,
+    dictionary: Option<bool>,
+    max_row_group_size: Option<usize>,
+    statistics: Option<StatisticsLevel>,
+    lookup_indexes: bool,
+    change_filter: Option<ChangeFilter>,
+    threads: usize,
+    target_file_size: Option<usize>,
+    max_bytes_in_flight: Option<usize>,
+    column_types: HashMap<String, ColumnType>,
+    column_order: ColumnOrder,
+    constant_columns: Vec<(String, String)>,
+    schema: Option<SchemaRef>,
+    first_chunk_index: usize,
 }
 
 impl ParquetWriterBuilder {
     /// Create a new Parquet writer builder with default options.
     pub fn new() -> Self {
         Self {
-            output_directory: None,
+            sink: None,
             chunk_size: 50_000,
+            properties: None,
+            compression: None,
+            dictionary: None,
+            max_row_group_size: None,
+            statistics: None,
+            lookup_indexes: false,
+            change_filter: None,
+            threads: 1,
+            target_file_size: None,
+            max_bytes_in_flight: None,
+            column_types: HashMap::new(),
+            column_order: ColumnOrder::Alphabetical,
+            constant_columns: Vec::new(),
+            schema: None,
+            first_chunk_index: 0,
         }
     }
 
     /// Set the output directory.
     pub fn output_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
-        self.output_directory = Some(path.as_ref().to_string_lossy().to_string());
+        self.sink = Some(Arc::new(DirectorySink::new(path)));
+        self
+    }
+
+    /// Hand files to `sink` instead of writing them to a directory.
+    ///
+    /// See [`ParquetWriter::to_sink`].
+    pub fn sink<S: OutputSink + 'static>(mut self, sink: S) -> Self {
+        self.sink = Some(Arc::new(sink));
         self
     }
 
@@ -195,20 +850,180 @@ impl ParquetWriterBuilder {
         self
     }
 
+    /// Set the low-level Parquet writer properties.
+    ///
+    /// See [`ParquetWriter::writer_properties`].
+    pub fn writer_properties(mut self, properties: WriterProperties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// Set the compression codec.
+    ///
+    /// See [`ParquetWriter::compression`].
+    pub fn compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Enable or disable dictionary encoding.
+    ///
+    /// See [`ParquetWriter::dictionary`].
+    pub fn dictionary(mut self, enabled: bool) -> Self {
+        self.dictionary = Some(enabled);
+        self
+    }
+
+    /// Set the maximum rows per row group.
+    ///
+    /// See [`ParquetWriter::max_row_group_size`].
+    pub fn max_row_group_size(mut self, rows: usize) -> Self {
+        self.max_row_group_size = Some(rows);
+        self
+    }
+
+    /// Set the level of column statistics.
+    ///
+    /// See [`ParquetWriter::statistics`].
+    pub fn statistics(mut self, level: StatisticsLevel) -> Self {
+        self.statistics = Some(level);
+        self
+    }
+
+    /// Enable bloom filters and page indexes for fast lookups.
+    ///
+    /// See [`ParquetWriter::lookup_indexes`].
+    pub fn lookup_indexes(mut self, enabled: bool) -> Self {
+        self.lookup_indexes = enabled;
+        self
+    }
+
+    /// Keep only change points for selected entries before writing.
+    ///
+    /// See [`ParquetWriter::change_filter`].
+    pub fn change_filter(mut self, filter: ChangeFilter) -> Self {
+        self.change_filter = Some(filter);
+        self
+    }
+
+    /// Write chunk files in parallel.
+    ///
+    /// See [`ParquetWriter::threads`].
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Split files by estimated size instead of row count.
+    ///
+    /// See [`ParquetWriter::target_file_size`].
+    pub fn target_file_size(mut self, bytes: usize) -> Self {
+        self.target_file_size = Some(bytes);
+        self
+    }
+
+    /// Cap the memory used to build each chunk.
+    ///
+    /// See [`ParquetWriter::max_bytes_in_flight`].
+    pub fn max_bytes_in_flight(mut self, bytes: usize) -> Self {
+        self.max_bytes_in_flight = Some(bytes);
+        self
+    }
+
+    /// Force the Parquet type of a column.
+    ///
+    /// See [`ParquetWriter::column_type`].
+    pub fn column_type(mut self, column: impl Into<String>, column_type: ColumnType) -> Self {
+        self.column_types.insert(column.into(), column_type);
+        self
+    }
+
+    /// Force the Parquet types of several columns.
+    ///
+    /// See [`ParquetWriter::column_types`].
+    pub fn column_types(mut self, column_types: HashMap<String, ColumnType>) -> Self {
+        self.column_types = column_types;
+        self
+    }
+
+    /// Order value columns.
+    ///
+    /// See [`ParquetWriter::column_order`].
+    pub fn column_order(mut self, order: ColumnOrder) -> Self {
+        self.column_order = order;
+        self
+    }
+
+    /// Stamp a constant column into every row.
+    ///
+    /// See [`ParquetWriter::constant_column`].
+    pub fn constant_column(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        match self.constant_columns.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(column) => column.1 = value,
+            None => self.constant_columns.push((name, value)),
+        }
+        self
+    }
+
+    /// Stamp the source log's provenance into every row.
+    ///
+    /// See [`ParquetWriter::provenance`].
+    pub fn provenance(mut self, provenance: &Provenance) -> Self {
+        for (name, value) in provenance.columns() {
+            self = self.constant_column(name, value);
+        }
+        self
+    }
+
+    /// Write every file with `schema`.
+    ///
+    /// See [`ParquetWriter::schema`].
+    pub fn schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Number chunk files from `index`.
+    ///
+    /// See [`ParquetWriter::first_chunk_index`].
+    pub fn first_chunk_index(mut self, index: usize) -> Self {
+        self.first_chunk_index = index;
+        self
+    }
+
     /// Build the Parquet writer.
     ///
     /// # Errors
     ///
-    /// Returns an error if output_directory was not set.
+    /// Returns [`Error::Config`] if neither output_directory nor sink was set,
+    /// for an invalid compression level, or for a zero row group size.
     pub fn build(self) -> Result<ParquetWriter> {
-        let output_directory = self
-            .output_directory
-            .ok_or_else(|| Error::Other("Output directory not set".to_string()))?;
+        let sink = self
+            .sink
+            .ok_or_else(|| Error::Config("Output directory not set".to_string()))?;
 
-        Ok(ParquetWriter {
-            output_directory,
+        ParquetWriter {
+            sink,
             chunk_size: self.chunk_size,
-        })
+            properties: self.properties,
+            compression: self.compression,
+            dictionary: self.dictionary,
+            max_row_group_size: self.max_row_group_size,
+            statistics: self.statistics,
+            lookup_indexes: self.lookup_indexes,
+            change_filter: self.change_filter,
+            threads: self.threads,
+            target_file_size: self.target_file_size,
+            max_bytes_in_flight: self.max_bytes_in_flight,
+            column_types: self.column_types,
+            column_order: self.column_order,
+            constant_columns: self.constant_columns,
+            schema: self.schema,
+            first_chunk_index: self.first_chunk_index,
+        }
+        .validated()
     }
 }
 
@@ -217,3 +1032,108 @@ impl Default for ParquetWriterBuilder {
         Self::new()
     }
 }
+
+/// Writer for JSON Lines (NDJSON) output: one JSON object per row, for
+/// piping into `jq`, Elasticsearch and other JSON-native tools.
+///
+/// Rows are serialized as they are written, so a log can be streamed through
+/// [`WpilogReader::stream`](crate::WpilogReader::stream) without holding it in
+/// memory. Object keys are sorted, so the same rows always produce the same
+/// bytes.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fs::File;
+/// use wpilog_parser::{JsonlWriter, WpilogReader};
+///
+/// let reader = WpilogReader::from_file("data.wpilog")?;
+/// let mut writer = JsonlWriter::gzip(File::create("data.jsonl.gz")?);
+/// reader.stream(|row| writer.write_row(&row))?;
+/// writer.finish()?;
+/// # Ok::<(), wpilog_parser::Error>(())
+/// ```
+pub struct JsonlWriter<W: Write> {
+    out: JsonlOutput<W>,
+    rows: u64,
+    line: Vec<u8>,
+}
+
+/// Destination of a [`JsonlWriter`], compressed or not.
+enum JsonlOutput<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+}
+
+impl JsonlWriter<BufWriter<File>> {
+    /// Create (or truncate) the file at `path`, gzip-compressing it if its
+    /// name ends in `.gz`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            Ok(Self::gzip(file))
+        } else {
+            Ok(Self::new(file))
+        }
+    }
+}
+
+impl<W: Write> JsonlWriter<W> {
+    /// Write uncompressed lines to `out`.
+    pub fn new(out: W) -> Self {
+        Self::with_output(JsonlOutput::Plain(out))
+    }
+
+    /// Write gzip-compressed lines to `out`.
+    pub fn gzip(out: W) -> Self {
+        Self::with_output(JsonlOutput::Gzip(GzEncoder::new(out, Compression::default())))
+    }
+
+    fn with_output(out: JsonlOutput<W>) -> Self {
+        Self {
+            out,
+            rows: 0,
+            line: Vec::new(),
+        }
+    }
+
+    /// Write one row, such as a [`WideRow`] or [`LongRow`](crate::models::LongRow), as a line.
+    pub fn write_row<T: Serialize>(&mut self, row: &T) -> Result<()> {
+        // Going through a `Value` sorts the keys of flattened `HashMap`s
+        let value = serde_json::to_value(row)?;
+        self.line.clear();
+        serde_json::to_writer(&mut self.line, &value)?;
+        self.line.push(b'\n');
+        match &mut self.out {
+            JsonlOutput::Plain(out) => out.write_all(&self.line)?,
+            JsonlOutput::Gzip(out) => out.write_all(&self.line)?,
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Write each of `rows` as a line.
+    pub fn write_rows<'a, T, I>(&mut self, rows: I) -> Result<()>
+    where
+        T: Serialize + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        rows.into_iter().try_for_each(|row| self.write_row(row))
+    }
+
+    /// Number of rows written so far.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Finish the gzip stream if any, flush, and return the underlying writer.
+    pub fn finish(self) -> Result<W> {
+        let mut out = match self.out {
+            JsonlOutput::Plain(out) => out,
+            JsonlOutput::Gzip(out) => out.finish()?,
+        };
+        out.flush()?;
+        Ok(out)
+    }
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::WpilogBuilder;
+use std::fs;
+use tempfile::tempdir;
+use wpilog_parser::{LogAligner, TimeOrigin};
+
+/// A run whose auto period starts `auto_start` microseconds after boot.
+fn run_log(auto_start: u64, speed: f64) -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(0, 1, "DS:enabled", "boolean", "")
+        .start_record(0, 2, "DS:autonomous", "boolean", "")
+        .start_record(0, 3, "/speed", "double", "")
+        .boolean_record(1, 0, false)
+        .boolean_record(2, auto_start, true)
+        .boolean_record(1, auto_start, true)
+        .double_record(3, auto_start + 500_000, speed)
+        .build()
+}
+
+#[test]
+fn test_align_on_auto_start() {
+    let dir = tempdir().unwrap();
+    let q12 = dir.path().join("Q12.wpilog");
+    let q27 = dir.path().join("Q27.wpilog");
+    fs::write(&q12, run_log(40_000_000, 1.0)).unwrap();
+    fs::write(&q27, run_log(95_000_000, 2.0)).unwrap();
+
+    let rows = LogAligner::new(TimeOrigin::AutoStart)
+        .add_file(&q12)
+        .add_file(&q27)
+        .read_all()
+        .unwrap();
+
+    let speeds: Vec<(f64, &str, f64)> = rows
+        .iter()
+        .filter_map(|r| {
+            let speed = r.data.get("/speed")?.as_f64()?;
+            Some((r.timestamp, r.data["log"].as_str().unwrap(), speed))
+        })
+        .collect();
+    assert_eq!(speeds, vec![(0.5, "Q12", 1.0), (0.5, "Q27", 2.0)]);
+
+    // Merged on the shared axis: the later boot's disabled record comes first
+    assert_eq!(rows[0].timestamp, -95.0);
+    assert_eq!(rows[0].data["log"], "Q27");
+
+    let named = LogAligner::new(TimeOrigin::AutoStart)
+        .log_column("match")
+        .add_named("qual-12", &q12)
+        .read_all()
+        .unwrap();
+    assert!(named.iter().all(|r| r.data["match"] == "qual-12"));
+}
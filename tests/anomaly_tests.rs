@@ -0,0 +1,63 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{AnomalyDetector, AnomalyKind, WpilogReader};
+
+/// A noisy signal sampled every 20 ms with a two-sample glitch at 1.0s and a
+/// stuck stretch from 2.0s to 3.0s.
+fn sensor_log() -> Vec<u8> {
+    let mut builder = WpilogBuilder::new()
+        .start_record(0, 1, "/Arm/Encoder", "double", "")
+        .start_record(0, 2, "/Robot/Mode", "int64", "");
+    for i in 0..200u64 {
+        let t = i * 20_000;
+        let value = match i {
+            50 | 51 => 100.0,
+            100..=150 => 7.0,
+            _ => (i % 5) as f64,
+        };
+        builder = builder.double_record(1, t, value).int64_record(2, t, 3);
+    }
+    builder.build()
+}
+
+#[test]
+fn test_detects_spikes_and_flatlines() {
+    let reader = WpilogReader::from_bytes(sensor_log()).unwrap();
+    let anomalies = AnomalyDetector::new().window(20).run(&reader).unwrap();
+
+    let spikes: Vec<_> = anomalies.iter().filter(|a| a.kind == AnomalyKind::Spike).collect();
+    assert_eq!(spikes.len(), 1, "{:?}", anomalies);
+    assert_eq!((spikes[0].start_secs, spikes[0].end_secs), (1.0, 1.02));
+    assert_eq!(spikes[0].samples, 2);
+    assert_eq!(spikes[0].value, 100.0);
+    assert!(spikes[0].z_score > 4.0);
+
+    let flatlines: Vec<_> = anomalies.iter().filter(|a| a.kind == AnomalyKind::Flatline).collect();
+    assert_eq!(flatlines.len(), 1);
+    assert_eq!(flatlines[0].entry, "/Arm/Encoder");
+    assert_eq!((flatlines[0].start_secs, flatlines[0].end_secs), (2.0, 3.0));
+    assert_eq!((flatlines[0].samples, flatlines[0].value), (51, 7.0));
+}
+
+#[test]
+fn test_detector_sensitivity_and_selection() {
+    let reader = WpilogReader::from_bytes(sensor_log()).unwrap();
+
+    let anomalies = AnomalyDetector::new()
+        .window(20)
+        .z_threshold(1000.0)
+        .flatline_secs(None)
+        .run(&reader)
+        .unwrap();
+    assert!(anomalies.is_empty(), "{:?}", anomalies);
+
+    let anomalies = AnomalyDetector::new()
+        .window(20)
+        .entries(&["/Robot/*"], &[])
+        .unwrap()
+        .run(&reader)
+        .unwrap();
+    // A constant entry is never a flatline
+    assert!(anomalies.is_empty(), "{:?}", anomalies);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::WpilogBuilder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use tempfile::tempdir;
+use wpilog_parser::{Error, LogSet};
+use zip::write::SimpleFileOptions;
+
+fn log_with_value(value: f64) -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .double_record(1, 1_100_000, value)
+        .build()
+}
+
+/// Files placed in each archive: two logs, a resource fork and a non-log.
+fn archive_files() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("logs/b.wpilog", log_with_value(2.0)),
+        ("logs/._b.wpilog", b"resource fork".to_vec()),
+        ("a.WPILOG", log_with_value(1.0)),
+        ("notes.txt", b"not a log".to_vec()),
+    ]
+}
+
+fn write_zip(path: &Path) {
+    let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+    zip.add_directory("logs/", SimpleFileOptions::default()).unwrap();
+    for (name, data) in archive_files() {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&data).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+fn write_tar<W: Write>(out: W) -> W {
+    let mut tar = tar::Builder::new(out);
+    for (name, data) in archive_files() {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, name, data.as_slice()).unwrap();
+    }
+    tar.into_inner().unwrap()
+}
+
+fn assert_logs(logs: &LogSet) {
+    let names: Vec<_> = logs.iter().map(|log| log.name()).collect();
+    assert_eq!(names, ["a.WPILOG", "logs/b.wpilog"]);
+
+    let b = logs.get("logs/b.wpilog").unwrap();
+    assert_eq!(b.file_name(), "b.wpilog");
+    assert_eq!(b.stem(), "b");
+    let records = b.reader().unwrap().read_all().unwrap();
+    assert_eq!(records[0].data["/value"], 2.0);
+}
+
+#[test]
+fn test_log_set_from_zip_and_tar_archives() {
+    let dir = tempdir().unwrap();
+
+    let zip_path = dir.path().join("logs.zip");
+    write_zip(&zip_path);
+    assert_logs(&LogSet::from_archive(&zip_path).unwrap());
+
+    let tar_path = dir.path().join("logs.tar");
+    write_tar(File::create(&tar_path).unwrap());
+    assert_logs(&LogSet::from_archive(&tar_path).unwrap());
+
+    // Detected from the contents, whatever the extension
+    let gz_path = dir.path().join("logs.bin");
+    write_tar(GzEncoder::new(File::create(&gz_path).unwrap(), Compression::default()))
+        .finish()
+        .unwrap();
+    assert_logs(&LogSet::from_archive(&gz_path).unwrap());
+}
+
+#[test]
+fn test_log_set_rejects_other_files() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("data.wpilog");
+    File::create(&path).unwrap().write_all(&log_with_value(1.0)).unwrap();
+
+    assert!(matches!(
+        LogSet::from_archive(&path),
+        Err(Error::InvalidFormat(_))
+    ));
+}
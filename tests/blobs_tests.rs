@@ -0,0 +1,82 @@
+mod common;
+
+use arrow::array::AsArray;
+use common::WpilogBuilder;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::fs::{self, File};
+use tempfile::tempdir;
+use wpilog_parser::{BlobStore, WpilogReader, WpilogReaderBuilder};
+
+fn log_with_blobs() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Swerve/States", "proto:SwerveModuleState", "")
+        .start_record(1_000_000, 2, "/Vendor/Status", "raw", "")
+        .start_record(1_000_000, 3, "/Speed", "double", "")
+        .raw_record(1, 1_100_000, &[0x0a, 0x02])
+        .raw_record(1, 1_100_000, &[0x0a, 0x03])
+        .raw_record(2, 1_200_000, &[1, 2, 3])
+        .double_record(3, 1_300_000, 1.5)
+        .build()
+}
+
+#[test]
+fn test_read_blobs_collects_undecoded_entries() {
+    let blobs = WpilogReader::from_bytes(log_with_blobs()).unwrap().read_blobs().unwrap();
+    assert_eq!(blobs.len(), 3);
+    assert_eq!(blobs[0].name, "/Swerve/States");
+    assert_eq!(blobs[0].type_name, "proto:SwerveModuleState");
+    assert_eq!(blobs[0].timestamp, 1_100_000);
+    assert_eq!(blobs[1].data, vec![0x0a, 0x03]);
+    assert_eq!((blobs[2].entry, blobs[2].data.as_slice()), (2, &[1u8, 2, 3][..]));
+}
+
+#[test]
+fn test_exclude_undecoded_keeps_table_clean() {
+    let rows = WpilogReaderBuilder::new()
+        .exclude_undecoded(true)
+        .from_bytes(log_with_blobs())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].data.keys().collect::<Vec<_>>(), ["/Speed"]);
+}
+
+#[test]
+fn test_blob_store_parquet() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("out").join("blobs.parquet");
+    let blobs = WpilogReader::from_bytes(log_with_blobs()).unwrap().read_blobs().unwrap();
+    assert_eq!(BlobStore::parquet(&path).write(&blobs).unwrap(), 3);
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    let batch = reader.into_iter().next().unwrap().unwrap();
+    assert_eq!(batch.num_rows(), 3);
+    let names = batch.column_by_name("name").unwrap().as_string::<i32>();
+    assert_eq!(names.value(2), "/Vendor/Status");
+    let data = batch.column_by_name("data").unwrap().as_binary::<i32>();
+    assert_eq!(data.value(0), &[0x0a, 0x02]);
+
+    // Nothing to write: no file
+    let empty = dir.path().join("empty.parquet");
+    assert_eq!(BlobStore::parquet(&empty).write(&[]).unwrap(), 0);
+    assert!(!empty.exists());
+}
+
+#[test]
+fn test_blob_store_directory() {
+    let dir = tempdir().unwrap();
+    let blobs = WpilogReader::from_bytes(log_with_blobs()).unwrap().read_blobs().unwrap();
+    BlobStore::directory(dir.path()).write(&blobs).unwrap();
+
+    let states = dir.path().join("Swerve").join("States");
+    assert_eq!(fs::read(states.join("1100000.bin")).unwrap(), [0x0a, 0x02]);
+    assert_eq!(fs::read(states.join("1100000-1.bin")).unwrap(), [0x0a, 0x03]);
+    assert_eq!(
+        fs::read(dir.path().join("Vendor").join("Status").join("1200000.bin")).unwrap(),
+        [1, 2, 3]
+    );
+}
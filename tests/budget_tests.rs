@@ -0,0 +1,91 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{EntryAction, Error, QuickScan, SizeBudget, WpilogReader, WpilogReaderBuilder};
+
+/// Ten seconds of `/fast` at 100 Hz (8 KB), `/big` arrays at 10 Hz (80 KB)
+/// and `/slow` at 1 Hz.
+fn sample_log() -> Vec<u8> {
+    let mut builder = WpilogBuilder::new()
+        .start_record(0, 1, "/fast", "double", "")
+        .start_record(0, 2, "/big", "double[]", "")
+        .start_record(0, 3, "/slow", "double", "");
+    for i in 0..1000u64 {
+        let timestamp = i * 10_000;
+        builder = builder.double_record(1, timestamp, i as f64);
+        if i % 10 == 0 {
+            builder = builder.double_array_record(2, timestamp, &[0.0; 100]);
+        }
+        if i % 100 == 0 {
+            builder = builder.double_record(3, timestamp, 1.0);
+        }
+    }
+    builder.build()
+}
+
+fn scan() -> QuickScan {
+    WpilogReader::from_bytes(sample_log()).unwrap().quick_scan().unwrap()
+}
+
+#[test]
+fn test_budget_already_met() {
+    let advice = SizeBudget::new(1_000_000).advise(&scan()).unwrap();
+    assert!(advice.fits());
+    assert_eq!(advice.estimated_bytes, advice.current_bytes);
+    assert!(advice.entries.is_empty());
+    assert_eq!(advice.profile().max_rate_hz, None);
+}
+
+#[test]
+fn test_budget_met_by_downsampling() {
+    let scan = scan();
+    assert_eq!(scan.data_bytes(), 88_080);
+
+    let advice = SizeBudget::new(85_000).advise(&scan).unwrap();
+    assert!(!advice.fits());
+    assert_eq!(advice.max_rate_hz, Some(50.0));
+    assert!(advice.estimated_bytes <= 85_000);
+    assert_eq!(advice.entries.len(), 1);
+    assert_eq!(advice.entries[0].name, "/fast");
+    assert!(matches!(
+        advice.entries[0].action,
+        EntryAction::Downsample { rate_hz } if rate_hz > 100.0
+    ));
+    assert_eq!(advice.excluded().count(), 0);
+}
+
+#[test]
+fn test_budget_excludes_largest_entries() {
+    let data = sample_log();
+    let scan = WpilogReader::from_bytes(data.clone()).unwrap().quick_scan().unwrap();
+
+    let advice = SizeBudget::new(10_000).advise(&scan).unwrap();
+    assert_eq!(advice.max_rate_hz, Some(10.0));
+    assert!(advice.estimated_bytes <= 10_000);
+    assert_eq!(advice.excluded().collect::<Vec<_>>(), ["/big"]);
+    assert_eq!(advice.entries[0].name, "/big");
+    assert_eq!(advice.entries[0].estimated_bytes, 0);
+
+    // The profile applies the advice when reading
+    let profile = advice.profile();
+    assert_eq!(profile.exclude, ["/big"]);
+    let rows = WpilogReaderBuilder::new()
+        .profile(profile)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert!(rows.iter().all(|row| !row.data.contains_key("/big")));
+    let fast = rows.iter().filter(|row| row.data.contains_key("/fast")).count();
+    assert!((100..=101).contains(&fast), "{}", fast);
+}
+
+#[test]
+fn test_budget_rejects_invalid_settings() {
+    let scan = scan();
+    assert!(matches!(SizeBudget::new(0).advise(&scan), Err(Error::Config(_))));
+    assert!(matches!(
+        SizeBudget::new(1).min_rate_hz(0.0).advise(&scan),
+        Err(Error::Config(_))
+    ));
+}
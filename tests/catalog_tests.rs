@@ -0,0 +1,117 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{WpilogReader, WpilogReaderBuilder};
+
+#[test]
+fn test_catalog_single_lifetime() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor", "double", "")
+        .double_record(1, 1_100_000, 1.0)
+        .double_record(1, 1_200_000, 2.0)
+        .finish_record(1_300_000, 1)
+        .build();
+
+    let catalog = WpilogReader::from_bytes(data).unwrap().catalog().unwrap();
+
+    assert_eq!(catalog.len(), 1);
+    let lifetime = &catalog.lifetimes()[0];
+    assert_eq!(lifetime.name, "/sensor");
+    assert_eq!(lifetime.generation, 1);
+    assert_eq!(lifetime.start_timestamp, 1_000_000);
+    assert_eq!(lifetime.finish_timestamp, Some(1_300_000));
+    assert_eq!(lifetime.record_count, 2);
+    assert!(catalog.restarted_entries().is_empty());
+    assert_eq!(catalog.unfinished().count(), 0);
+}
+
+#[test]
+fn test_catalog_restarted_and_unfinished() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor", "double", "")
+        .double_record(1, 1_100_000, 1.0)
+        .finish_record(1_200_000, 1)
+        .start_record(1_300_000, 2, "/sensor", "int64", "")
+        .int64_record(2, 1_400_000, 5)
+        .int64_record(2, 1_500_000, 6)
+        .start_record(1_300_000, 3, "/other", "boolean", "")
+        .build();
+
+    let catalog = WpilogReader::from_bytes(data).unwrap().catalog().unwrap();
+
+    assert_eq!(catalog.len(), 3);
+    assert_eq!(catalog.restarted_entries(), vec!["/sensor"]);
+
+    let generations: Vec<_> = catalog.generations("/sensor").collect();
+    assert_eq!(generations.len(), 2);
+    assert_eq!(generations[0].type_name, "double");
+    assert_eq!(generations[0].record_count, 1);
+    assert_eq!(generations[1].generation, 2);
+    assert_eq!(generations[1].type_name, "int64");
+    assert_eq!(generations[1].record_count, 2);
+
+    let unfinished: Vec<_> = catalog.unfinished().map(|l| l.name.as_str()).collect();
+    assert_eq!(unfinished, vec!["/sensor", "/other"]);
+}
+
+#[test]
+fn test_catalog_tracks_metadata_updates() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor", "double", r#"{"v":1}"#)
+        .set_metadata_record(1_100_000, 1, r#"{"v":2}"#)
+        .build();
+
+    let catalog = WpilogReader::from_bytes(data).unwrap().catalog().unwrap();
+
+    assert_eq!(catalog.lifetimes()[0].metadata, r#"{"v":2}"#);
+}
+
+#[test]
+fn test_catalog_aliases() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/drive/left/current", "double", r#"{"alias":"Left Drive Current"}"#)
+        .start_record(1_000_000, 2, "/drive/right/current", "double", "not json")
+        .build();
+
+    let catalog = WpilogReader::from_bytes(data).unwrap().catalog().unwrap();
+    let aliases = catalog.aliases();
+
+    assert_eq!(aliases.len(), 1);
+    assert_eq!(aliases["/drive/left/current"], "Left Drive Current");
+    assert_eq!(catalog.lifetimes()[1].alias(), None);
+}
+
+#[test]
+fn test_alias_columns() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/drive/left/current", "double", r#"{"alias":"Left Drive Current"}"#)
+        .start_record(1_000_000, 2, "/drive/right/current", "double", "")
+        .double_record(1, 1_100_000, 12.5)
+        .double_record(2, 1_100_000, 13.5)
+        .build();
+
+    let rows = WpilogReaderBuilder::new()
+        .alias_columns(true)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+
+    assert_eq!(rows[0].data.get("Left Drive Current").unwrap().as_f64(), Some(12.5));
+    assert_eq!(rows[1].data.get("/drive/right/current").unwrap().as_f64(), Some(13.5));
+}
+
+#[test]
+fn test_catalog_json_round_trip() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor", "double", "units=m")
+        .double_record(1, 1_100_000, 1.0)
+        .build();
+
+    let catalog = WpilogReader::from_bytes(data).unwrap().catalog().unwrap();
+    let json = catalog.to_json().unwrap();
+    assert!(json.contains("\"/sensor\""));
+
+    let parsed: wpilog_parser::EntryCatalog = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.lifetimes(), catalog.lifetimes());
+}
@@ -1,4 +1,7 @@
-/// Test utilities for building WPILOG files
+//! Test utilities for building WPILOG files
+// Each test binary uses a different subset of the helpers
+#![allow(dead_code)]
+
 use byteorder::{LittleEndian, WriteBytesExt};
 
 /// Builder for creating WPILOG test files
@@ -189,7 +192,7 @@ impl WpilogBuilder {
 
     /// Add a struct schema record
     pub fn struct_schema_record(
-        mut self,
+        self,
         timestamp: u64,
         entry_id: u32,
         schema_name: &str,
@@ -283,6 +286,21 @@ impl Default for WpilogBuilder {
     }
 }
 
+/// Log with a double `/a` and a string `/b`, where `/a` is finished and
+/// started again under a new entry ID.
+pub fn sample_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .start_record(1_000_000, 2, "/b", "string", "")
+        .double_record(1, 1_100_000, 1.5)
+        .string_record(2, 1_150_000, "hello")
+        .double_record(1, 1_200_000, 2.5)
+        .finish_record(1_300_000, 1)
+        .start_record(1_400_000, 3, "/a", "double", "")
+        .double_record(3, 1_500_000, 3.5)
+        .build()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,92 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{ContractViolation, EntryContract, SchemaContract, WpilogReader};
+
+/// Ten seconds of log: a 50 Hz velocity, a 1 Hz voltage logged as float and
+/// a vision entry that is started but never written.
+fn robot_log() -> WpilogReader {
+    let mut builder = WpilogBuilder::new()
+        .start_record(0, 1, "/Drive/Velocity", "double", "")
+        .start_record(0, 2, "/Robot/Voltage", "float", "")
+        .start_record(0, 3, "/Vision/Pose", "struct:Pose2d", "");
+    for i in 0..=500u64 {
+        builder = builder.double_record(1, i * 20_000, i as f64);
+    }
+    for i in 0..=10u64 {
+        builder = builder.float_record(2, i * 1_000_000, 12.0);
+    }
+    WpilogReader::from_bytes(builder.build()).unwrap()
+}
+
+#[test]
+fn test_contract_satisfied() {
+    let contract = SchemaContract::from_json(
+        r#"{"entries": [
+            {"name": "/Drive/Velocity", "type": "double", "min_rate_hz": 45},
+            {"name": "/Robot/Voltage"}
+        ]}"#,
+    )
+    .unwrap();
+    let report = contract.check(&robot_log()).unwrap();
+    assert!(report.is_ok(), "{}", report);
+    assert_eq!(report.checked, 2);
+    assert_eq!(report.duration_secs, 10.0);
+}
+
+#[test]
+fn test_contract_violations() {
+    let contract = SchemaContract::default()
+        .entry(EntryContract {
+            name: "/Drive/Velocity".to_string(),
+            entry_type: None,
+            min_rate_hz: Some(100.0),
+        })
+        .entry(EntryContract {
+            name: "/Robot/Voltage".to_string(),
+            entry_type: Some("double".to_string()),
+            min_rate_hz: None,
+        })
+        .entry(EntryContract {
+            name: "/Vision/Pose".to_string(),
+            entry_type: Some("struct:Pose2d".to_string()),
+            min_rate_hz: None,
+        })
+        .entry(EntryContract {
+            name: "/Intake/Current".to_string(),
+            ..Default::default()
+        });
+    let report = contract.check(&robot_log()).unwrap();
+
+    assert!(!report.is_ok());
+    assert_eq!(report.violations.len(), 4);
+    match &report.violations[0] {
+        ContractViolation::UnderSampled { entry, rate_hz, samples, .. } => {
+            assert_eq!(entry, "/Drive/Velocity");
+            assert_eq!(*samples, 501);
+            assert!((rate_hz - 50.1).abs() < 1e-9);
+        }
+        other => panic!("expected under-sampled, got {:?}", other),
+    }
+    assert_eq!(
+        report.violations[1],
+        ContractViolation::Mistyped {
+            entry: "/Robot/Voltage".to_string(),
+            expected: "double".to_string(),
+            actual: "float".to_string(),
+        }
+    );
+    assert_eq!(
+        report.violations[2],
+        ContractViolation::Missing {
+            entry: "/Vision/Pose".to_string()
+        }
+    );
+    assert_eq!(report.violations[3].entry(), "/Intake/Current");
+}
+
+#[test]
+fn test_contract_rejects_negative_rate() {
+    let result = SchemaContract::from_json(r#"{"entries": [{"name": "/A", "min_rate_hz": -1}]}"#);
+    assert!(result.is_err());
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::sample_log;
+use wpilog_parser::{DecodedValue, WpilogReader};
+
+#[test]
+fn test_cursor_seek_and_neighbours() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let mut cursor = reader.cursor().unwrap();
+    assert_eq!(cursor.entry_names(), vec!["/a", "/b"]);
+
+    assert_eq!(cursor.next_before("/a").unwrap(), None);
+    assert_eq!(cursor.next_after("/a").unwrap().unwrap().timestamp, 1_100_000);
+
+    cursor.seek_to(1_200_000);
+    let before = cursor.next_before("/a").unwrap().unwrap();
+    assert_eq!(before.timestamp, 1_200_000);
+    assert_eq!(before.value, DecodedValue::Double(2.5));
+
+    // Lifetimes of the same name are merged into one timeline
+    let after = cursor.next_after("/a").unwrap().unwrap();
+    assert_eq!(after.timestamp, 1_500_000);
+    assert_eq!(after.value, DecodedValue::Double(3.5));
+
+    assert_eq!(
+        cursor.next_before("/b").unwrap().unwrap().value,
+        DecodedValue::String("hello".to_string())
+    );
+    assert_eq!(cursor.next_after("/b").unwrap(), None);
+    assert_eq!(cursor.next_before("/missing").unwrap(), None);
+}
+
+#[test]
+fn test_cursor_seek_backwards() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let mut cursor = reader.cursor().unwrap();
+
+    cursor.seek_to(2_000_000);
+    assert_eq!(cursor.next_before("/a").unwrap().unwrap().timestamp, 1_500_000);
+
+    cursor.seek_to(1_150_000);
+    assert_eq!(cursor.position(), 1_150_000);
+    assert_eq!(cursor.next_before("/a").unwrap().unwrap().timestamp, 1_100_000);
+    assert_eq!(cursor.next_after("/a").unwrap().unwrap().timestamp, 1_200_000);
+}
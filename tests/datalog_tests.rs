@@ -1,7 +1,11 @@
+// Fixture values such as 3.14 are arbitrary, not approximations of consts::PI
+#![allow(clippy::approx_constant, clippy::bool_assert_comparison)]
+
 mod common;
 
 use common::WpilogBuilder;
 use wpilog_parser::datalog::DataLogReader;
+use wpilog_parser::Error;
 
 // ============================================================================
 // HEADER TESTS
@@ -121,6 +125,31 @@ fn test_start_record_with_metadata() {
     assert_eq!(start_data.metadata, r#"{"source":"NT","unit":"meters"}"#);
 }
 
+#[test]
+fn test_start_record_parses_entry_type() {
+    use wpilog_parser::EntryType;
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "pose", "struct:Pose2d[]", "")
+        .start_record(1_000_000, 2, "custom", "rawBytes", "")
+        .build();
+
+    let reader = DataLogReader::new(&data);
+    let types: Vec<_> = reader
+        .records()
+        .unwrap()
+        .map(|r| r.unwrap().get_start_data().unwrap().entry_type)
+        .collect();
+
+    assert_eq!(
+        types,
+        vec![
+            EntryType::StructArray("struct:Pose2d".to_string()),
+            EntryType::Raw("rawBytes".to_string()),
+        ]
+    );
+}
+
 #[test]
 fn test_start_record_utf8_names() {
     let data = WpilogBuilder::new()
@@ -492,6 +521,29 @@ fn test_string_array_with_utf8() {
     assert_eq!(values, vec!["hello", "世界", "🌍"]);
 }
 
+#[test]
+fn test_small_integer_and_half_float_getters() {
+    use wpilog_parser::datalog::DataLogRecord;
+
+    let record = |data: &[u8]| DataLogRecord {
+        entry: 1,
+        timestamp: 0,
+        data: data.to_vec(),
+    };
+
+    assert_eq!(record(&[0xFE]).get_int8().unwrap(), -2);
+    assert_eq!(record(&[0xFE]).get_uint8().unwrap(), 254);
+    assert_eq!(record(&(-300i16).to_le_bytes()).get_int16().unwrap(), -300);
+    assert_eq!(record(&60_000u16.to_le_bytes()).get_uint16().unwrap(), 60_000);
+    // 0x3E00 = 1.5, 0xC000 = -2.0 in IEEE 754 binary16
+    assert_eq!(record(&[0x00, 0x3E]).get_f16().unwrap(), 1.5);
+    assert_eq!(record(&[0x00, 0xC0]).get_f16().unwrap(), -2.0);
+
+    assert!(record(&[1, 2]).get_int8().is_err());
+    assert!(record(&[1]).get_int16().is_err());
+    assert!(record(&[1, 2, 3]).get_f16().is_err());
+}
+
 // ============================================================================
 // VARIABLE-LENGTH ENCODING TESTS
 // ============================================================================
@@ -700,3 +752,521 @@ fn test_zero_timestamp() {
     assert_eq!(records[0].as_ref().unwrap().timestamp, 0);
     assert_eq!(records[1].as_ref().unwrap().timestamp, 0);
 }
+
+#[test]
+fn test_wrong_getter_returns_parse_error() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "test", "float", "")
+        .float_record(1, 1_100_000, 1.5)
+        .build();
+
+    let reader = DataLogReader::new(&data);
+    let records: Vec<_> = reader.records().unwrap().collect();
+
+    let err = records[1].as_ref().unwrap().get_double().unwrap_err();
+    assert!(matches!(err, Error::ParseError(_)));
+}
+
+#[test]
+fn test_decode_as_checks_declared_type() {
+    use wpilog_parser::{DecodedValue, EntryType};
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "test", "double", "")
+        .float_record(1, 1_100_000, 1.5)
+        .double_record(1, 1_200_000, 2.5)
+        .build();
+
+    let reader = DataLogReader::new(&data);
+    let records: Vec<_> = reader.records().unwrap().map(|r| r.unwrap()).collect();
+
+    let err = records[1].decode_as(&EntryType::Double).unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch(_)));
+    assert!(err.to_string().contains("got 4 bytes"));
+
+    assert_eq!(
+        records[2].decode_as(&EntryType::Double).unwrap(),
+        DecodedValue::Double(2.5)
+    );
+    assert_eq!(EntryType::parse("struct:Pose2d[]").to_string(), "struct:Pose2d[]");
+}
+
+#[test]
+fn test_records_filtered_skips_other_entries() {
+    use std::collections::HashSet;
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "a", "double", "")
+        .start_record(1_000_000, 2, "b", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .double_record(2, 1_200_000, 2.5)
+        .double_record(1, 1_300_000, 3.5)
+        .build();
+
+    let reader = DataLogReader::new(&data);
+    let entries: Vec<u32> = reader
+        .records_filtered(&HashSet::from([2]))
+        .unwrap()
+        .map(|r| r.unwrap().entry)
+        .collect();
+    // Control records are always yielded
+    assert_eq!(entries, vec![0, 0, 2]);
+
+    let mut records = reader.records_filtered(&HashSet::new()).unwrap();
+    records.entry_filter_mut().unwrap().insert(1);
+    assert_eq!(records.filter(|r| r.as_ref().unwrap().entry == 1).count(), 2);
+    assert!(reader.records().unwrap().entry_filter_mut().is_none());
+}
+
+#[test]
+fn test_invalid_utf8_preserves_source() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "test", "string", "")
+        .raw_record(1, 1_100_000, &[0xff, 0xfe])
+        .build();
+
+    let reader = DataLogReader::new(&data);
+    let records: Vec<_> = reader.records().unwrap().collect();
+
+    let err = records[1].as_ref().unwrap().get_string().unwrap_err();
+    assert!(matches!(err, Error::Utf8Error(_)));
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn test_low_level_control_constants() {
+    use wpilog_parser::low_level::{
+        DataLogReader, CONTROL_FINISH, CONTROL_SET_METADATA, CONTROL_START,
+    };
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "test", "int64", "")
+        .set_metadata_record(1_100_000, 1, "meta")
+        .finish_record(1_200_000, 1)
+        .build();
+
+    let reader = DataLogReader::new(&data);
+    let control_types: Vec<u8> = reader
+        .records()
+        .unwrap()
+        .map(|r| r.unwrap().data[0])
+        .collect();
+
+    assert_eq!(
+        control_types,
+        vec![CONTROL_START, CONTROL_SET_METADATA, CONTROL_FINISH]
+    );
+}
+
+#[test]
+fn test_visitor_walks_records() {
+    use wpilog_parser::low_level::{RecordVisitor, StartRecordData};
+
+    #[derive(Default)]
+    struct Counter {
+        starts: Vec<String>,
+        finishes: Vec<u32>,
+        payload_bytes: usize,
+        doubles: Vec<(String, u64)>,
+    }
+
+    impl RecordVisitor for Counter {
+        fn on_start(&mut self, start: &StartRecordData, _timestamp: u64) -> wpilog_parser::Result<()> {
+            self.starts.push(start.name.clone());
+            Ok(())
+        }
+
+        fn on_finish(&mut self, entry: u32, _timestamp: u64) -> wpilog_parser::Result<()> {
+            self.finishes.push(entry);
+            Ok(())
+        }
+
+        fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> wpilog_parser::Result<()> {
+            self.payload_bytes += payload.len();
+            if entry.type_name == "double" {
+                self.doubles.push((entry.name.clone(), timestamp));
+            }
+            Ok(())
+        }
+    }
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .start_record(1_000_000, 2, "/b", "boolean", "")
+        .double_record(1, 1_100_000, 1.0)
+        .boolean_record(2, 1_100_000, true)
+        .finish_record(1_200_000, 1)
+        .double_record(1, 1_300_000, 2.0) // After finish: skipped
+        .build();
+
+    let mut counter = Counter::default();
+    DataLogReader::new(&data).visit(&mut counter).unwrap();
+
+    assert_eq!(counter.starts, vec!["/a", "/b"]);
+    assert_eq!(counter.finishes, vec![1]);
+    assert_eq!(counter.payload_bytes, 9);
+    assert_eq!(counter.doubles, vec![("/a".to_string(), 1_100_000)]);
+}
+
+#[test]
+fn test_debug_dump() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 3, "/speed", "double", "")
+        .double_record(3, 1_200_000, 1.5)
+        .build();
+
+    let reader = DataLogReader::new(&data);
+    let records: Vec<_> = reader.records().unwrap().map(|r| r.unwrap()).collect();
+
+    let start = records[0].debug_dump();
+    assert!(start.starts_with("entry 0 @ 1000000 us"));
+    assert!(start.contains("control: Start entry 3 \"/speed\" type \"double\""));
+
+    let value = records[1].debug_dump();
+    assert!(value.starts_with("entry 3 @ 1200000 us, 8-byte payload\n"));
+    assert!(value.contains("0000  00 00 00 00 00 00 f8 3f"));
+    assert!(value.contains("|.......?|"));
+    assert!(value.contains("double: 1.5\n"));
+    assert!(value.contains("int64: 4609434218613702656\n"));
+}
+
+// ============================================================================
+// MALFORMED INPUT TESTS
+// ============================================================================
+
+fn with_records(records: &[u8]) -> Vec<u8> {
+    let mut data = WpilogBuilder::new().build();
+    data.extend_from_slice(records);
+    data
+}
+
+#[test]
+fn test_oversized_payload_size_is_truncation() {
+    // 4-byte size field claiming u32::MAX bytes of payload
+    let data = with_records(&[0x0C, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0x10, 0xAA]);
+    let reader = DataLogReader::new(&data);
+
+    assert_eq!(reader.records().unwrap().allow_truncated().count(), 0);
+    let err = reader.record_at(12).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Truncated {
+            offset: 12,
+            needed: 4_294_967_302,
+            available: 8
+        }
+    ));
+}
+
+#[test]
+fn test_truncated_final_record_is_reported() {
+    let mut data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "test", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .double_record(1, 1_200_000, 2.5)
+        .build();
+    let last_record = data.len() - 14;
+    data.truncate(data.len() - 3);
+
+    let reader = DataLogReader::new(&data);
+    let records: Vec<_> = reader.records().unwrap().collect();
+    assert_eq!(records.len(), 3);
+    assert!(records[1].is_ok());
+    assert!(matches!(
+        records[2],
+        Err(Error::Truncated { offset, needed: 14, available: 11 }) if offset == last_record
+    ));
+
+    // Clean end of log and opting out both end without an error
+    assert_eq!(reader.records().unwrap().allow_truncated().count(), 2);
+    let data = WpilogBuilder::new().start_record(1_000_000, 1, "test", "double", "").build();
+    assert!(DataLogReader::new(&data).records().unwrap().all(|r| r.is_ok()));
+}
+
+#[test]
+fn test_oversized_extra_header_is_truncation() {
+    let mut data = WpilogBuilder::new().build();
+    data[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    let reader = DataLogReader::new(&data);
+    assert!(matches!(reader.records(), Err(Error::Truncated { .. })));
+    assert_eq!(reader.get_extra_header(), "");
+}
+
+#[test]
+fn test_oversized_start_name_is_parse_error() {
+    // Start record whose name length is u32::MAX
+    let mut payload = vec![0u8];
+    payload.extend_from_slice(&1u32.to_le_bytes());
+    payload.extend_from_slice(&u32::MAX.to_le_bytes());
+    payload.extend_from_slice(&[0; 8]);
+    let data = WpilogBuilder::new().raw_record(0, 1_000, &payload).build();
+
+    let reader = DataLogReader::new(&data);
+    let record = reader.records().unwrap().next().unwrap().unwrap();
+    assert!(matches!(record.get_start_data(), Err(Error::ParseError(_))));
+}
+
+#[test]
+fn test_random_bytes_never_panic() {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    for len in 0..512 {
+        let bytes: Vec<u8> = (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let data = with_records(&bytes);
+        let reader = DataLogReader::new(&data);
+
+        for record in reader.records().unwrap().allow_truncated().flatten() {
+            let _ = record.get_start_data();
+            let _ = record.get_set_metadata_data();
+            let _ = record.get_string_array();
+            let _ = record.get_double_array();
+        }
+        let _ = wpilog_parser::RecordIndex::build(&reader);
+    }
+}
+
+// ============================================================================
+// QUIRKS TESTS
+// ============================================================================
+
+/// A log whose data records are each followed by `padding` zero bytes.
+fn padded_log(padding: usize) -> Vec<u8> {
+    let mut data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "test", "double", "")
+        .build();
+    for (i, value) in [1.5, 2.5].iter().enumerate() {
+        let record = WpilogBuilder::new()
+            .double_record(1, 1_100_000 + i as u64, *value)
+            .build();
+        data.extend_from_slice(&record[12..]);
+        data.extend(std::iter::repeat_n(0, padding));
+    }
+    data
+}
+
+#[test]
+fn test_padded_records_quirk() {
+    use wpilog_parser::{Quirks, WpilogReaderBuilder};
+
+    let data = padded_log(3);
+    let quirks = Quirks {
+        padded_records: true,
+        ..Quirks::default()
+    };
+
+    // Strictly, the padding parses as empty control records
+    let strict: Vec<_> = DataLogReader::new(&data).records().unwrap().collect();
+    assert!(strict.len() > 3);
+
+    let reader = DataLogReader::new(&data).with_quirks(quirks);
+    let values: Vec<f64> = reader
+        .records()
+        .unwrap()
+        .skip(1)
+        .map(|record| record.unwrap().get_double().unwrap())
+        .collect();
+    assert_eq!(values, vec![1.5, 2.5]);
+
+    let reader = WpilogReaderBuilder::new().quirks(quirks).from_bytes(data).unwrap();
+    // Padding at the end of the log is not followed by a record
+    assert_eq!(reader.quick_scan().unwrap().quirks.padding_bytes, 3);
+    assert_eq!(reader.read_all().unwrap().len(), 2);
+}
+
+#[test]
+fn test_padding_without_quirk_is_reported() {
+    use wpilog_parser::WpilogReader;
+
+    // Four zero bytes are one empty control record
+    let reader = WpilogReader::from_bytes(padded_log(4)).unwrap();
+    let scan = reader.quick_scan().unwrap();
+    assert_eq!(scan.quirks.empty_control_records, 2);
+    assert_eq!(scan.quirks.padding_bytes, 0);
+    assert_eq!(reader.read_all().unwrap().len(), 2);
+}
+
+/// A double entry with an empty payload between two values, and an empty
+/// string, which is a valid value.
+fn empty_payload_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/voltage", "double", "")
+        .start_record(1_000_000, 2, "/mode", "string", "")
+        .double_record(1, 1_100_000, 12.5)
+        .raw_record(1, 1_200_000, &[])
+        .double_record(1, 1_300_000, 12.0)
+        .string_record(2, 1_300_000, "")
+        .build()
+}
+
+#[test]
+fn test_empty_payloads_quirk() {
+    use wpilog_parser::{EmptyPayloads, Quirks, WpilogReaderBuilder};
+
+    let read = |empty_payloads| {
+        WpilogReaderBuilder::new()
+            .quirks(Quirks {
+                empty_payloads,
+                ..Quirks::default()
+            })
+            .from_bytes(empty_payload_log())
+            .unwrap()
+    };
+
+    // Strictly, an empty double is a type mismatch naming the entry
+    let err = read(EmptyPayloads::Error).read_all().unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch(_)));
+    assert!(err.to_string().contains("'/voltage'"), "{}", err);
+    assert_eq!(read(EmptyPayloads::Error).quick_scan().unwrap().quirks.empty_payloads, 0);
+
+    let reader = read(EmptyPayloads::Skip);
+    assert_eq!(reader.quick_scan().unwrap().quirks.empty_payloads, 1);
+    let rows = reader.read_all().unwrap();
+    let voltages: Vec<_> = rows.iter().filter_map(|row| row.data.get("/voltage")).collect();
+    assert_eq!(voltages, vec![12.5, 12.0]);
+    // The empty string is a value, not an empty payload to handle
+    assert_eq!(rows.last().unwrap().data["/mode"], "");
+
+    let rows = read(EmptyPayloads::Raw).read_all().unwrap();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[1].timestamp, 1.2);
+    assert!(rows[1].data["/voltage"].is_null());
+}
+
+#[test]
+fn test_lossy_utf8_quirk() {
+    use wpilog_parser::{Quirks, Warning, WpilogReaderBuilder};
+
+    // A string array of "ok" and "b\xffd"
+    let mut strings = Vec::new();
+    strings.extend_from_slice(&2u32.to_le_bytes());
+    for value in [&b"ok"[..], &b"b\xffd"[..]] {
+        strings.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        strings.extend_from_slice(value);
+    }
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/DS/Message", "string", "")
+        .start_record(1_000_000, 2, "/Names", "string[]", "")
+        .raw_record(1, 1_100_000, b"bad \xc3\x28 text")
+        .raw_record(2, 1_100_000, &strings)
+        .string_record(1, 1_200_000, "fine")
+        .build();
+    let read = |lossy_utf8| {
+        WpilogReaderBuilder::new()
+            .quirks(Quirks {
+                lossy_utf8,
+                ..Quirks::default()
+            })
+            .from_bytes(data.clone())
+            .unwrap()
+    };
+
+    // Strictly, the record fails naming the entry
+    let err = read(false).read_all().unwrap_err();
+    assert!(matches!(err, Error::TypeMismatch(_)));
+    assert!(err.to_string().contains("'/DS/Message'"), "{}", err);
+
+    let (rows, formatter) = read(true).read_all_with_metadata().unwrap();
+    assert_eq!(rows[0].data["/DS/Message"], "bad \u{fffd}( text");
+    assert_eq!(rows[1].data["/Names"], serde_json::json!(["ok", "b\u{fffd}d"]));
+    assert_eq!(rows[2].data["/DS/Message"], "fine");
+    assert_eq!(
+        formatter.warnings,
+        vec![
+            Warning::InvalidUtf8 { entry: "/DS/Message".to_string(), records: 1 },
+            Warning::InvalidUtf8 { entry: "/Names".to_string(), records: 1 },
+        ]
+    );
+
+    let long = read(true).read_all_long().unwrap();
+    assert_eq!(long[0].value.as_ref().unwrap().string.as_deref(), Some("bad \u{fffd}( text"));
+}
+
+#[test]
+fn test_on_record_error_callback() {
+    use std::sync::{Arc, Mutex};
+    use wpilog_parser::{RecordAction, WpilogReaderBuilder};
+
+    // A 4-byte payload for a double, between two good records
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/voltage", "double", "")
+        .start_record(1_000_000, 2, "/mode", "string", "")
+        .double_record(1, 1_100_000, 12.5)
+        .raw_record(1, 1_200_000, &[0, 0, 0, 0])
+        .string_record(2, 1_300_000, "auto")
+        .build();
+    let read = |action: RecordAction| {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&seen);
+        let result = WpilogReaderBuilder::new()
+            .on_record_error(move |failure| {
+                log.lock().unwrap().push((
+                    failure.entry.to_string(),
+                    failure.entry_id,
+                    failure.type_name.to_string(),
+                    failure.timestamp,
+                    failure.error.to_string(),
+                ));
+                action
+            })
+            .from_bytes(data.clone())
+            .unwrap()
+            .read_all();
+        let seen = seen.lock().unwrap().clone();
+        (result, seen)
+    };
+
+    let (result, seen) = read(RecordAction::Abort);
+    assert!(matches!(result, Err(Error::TypeMismatch(_))));
+    assert_eq!(seen.len(), 1);
+    let (entry, entry_id, type_name, timestamp, error) = &seen[0];
+    assert_eq!((entry.as_str(), *entry_id, type_name.as_str(), *timestamp), ("/voltage", 1, "double", 1_200_000));
+    assert!(error.contains("'/voltage'"), "{}", error);
+
+    let (rows, _) = read(RecordAction::Skip);
+    let rows = rows.unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].data["/mode"], "auto");
+
+    let (rows, _) = read(RecordAction::Null);
+    let rows = rows.unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1].timestamp, 1.2);
+    assert!(rows[1].data["/voltage"].is_null());
+
+    assert_eq!("null".parse::<RecordAction>().unwrap(), RecordAction::Null);
+    assert!(matches!("retry".parse::<RecordAction>(), Err(Error::Config(_))));
+}
+
+#[test]
+fn test_oversized_lengths_quirk() {
+    use wpilog_parser::Quirks;
+
+    // 1-byte size field claiming 16 bytes, followed by only 8
+    let mut data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "test", "raw", "")
+        .build();
+    data.extend_from_slice(&[0x20, 0x01, 0x10, 0x40, 0x42, 0x0F]);
+    data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let strict = DataLogReader::new(&data);
+    assert!(matches!(
+        strict.records().unwrap().nth(1),
+        Some(Err(Error::Truncated { .. }))
+    ));
+
+    let reader = DataLogReader::new(&data).with_quirks(Quirks {
+        oversized_lengths: true,
+        ..Quirks::default()
+    });
+    let records: Vec<_> = reader.records().unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(records[1].timestamp, 1_000_000);
+}
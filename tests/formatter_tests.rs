@@ -1,3 +1,6 @@
+// Fixture values such as 3.14 are arbitrary, not approximations of consts::PI
+#![allow(clippy::approx_constant, clippy::bool_assert_comparison)]
+
 mod common;
 
 use byteorder::{LittleEndian, WriteBytesExt};
@@ -7,6 +10,7 @@ use std::io::Write;
 use tempfile::tempdir;
 use wpilog_parser::formatter::Formatter;
 use wpilog_parser::models::OutputFormat;
+use wpilog_parser::WpilogReaderBuilder;
 
 // ============================================================================
 // FULL PARSING PIPELINE TESTS
@@ -48,6 +52,41 @@ fn test_parse_single_double_value() {
     assert_eq!(value.as_f64().unwrap(), 25.5);
 }
 
+#[test]
+fn test_type_mismatch_names_entry() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor/temperature", "double", "")
+        .float_record(1, 1_100_000, 25.5)
+        .build();
+
+    let err = WpilogReaderBuilder::new()
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap_err();
+
+    assert!(matches!(err, wpilog_parser::Error::TypeMismatch(_)));
+    assert!(err.to_string().contains("'/sensor/temperature'"));
+}
+
+#[test]
+fn test_type_mismatch_names_entry_long() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor/temperature", "double", "")
+        .float_record(1, 1_100_000, 25.5)
+        .build();
+
+    let err = WpilogReaderBuilder::new()
+        .output_format(OutputFormat::Long)
+        .from_bytes(data)
+        .unwrap()
+        .read_all_long()
+        .unwrap_err();
+
+    assert!(matches!(err, wpilog_parser::Error::TypeMismatch(_)));
+    assert!(err.to_string().contains("'/sensor/temperature'"));
+}
+
 #[test]
 fn test_parse_multiple_entries() {
     let dir = tempdir().unwrap();
@@ -210,6 +249,257 @@ fn test_parse_with_finish_records() {
     assert!(rows[2].data.contains_key("/sensor2"));
 }
 
+#[test]
+fn test_reused_name_generation_columns() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .finish_record(1_200_000, 1)
+        .start_record(1_300_000, 1, "/sensor", "int64", "")
+        .int64_record(1, 1_400_000, 7)
+        .finish_record(1_500_000, 1)
+        .start_record(1_600_000, 2, "/sensor", "double", "")
+        .double_record(2, 1_700_000, 2.5)
+        .build();
+
+    // Default: values are merged under the original name
+    let rows = WpilogReaderBuilder::new()
+        .from_bytes(data.clone())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert!(rows.iter().all(|r| r.data.contains_key("/sensor")));
+
+    // Generation columns: redefinitions get their own column
+    let rows = WpilogReaderBuilder::new()
+        .generation_columns(true)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].data.get("/sensor").unwrap().as_f64().unwrap(), 1.5);
+    assert_eq!(rows[1].data.get("/sensor#2").unwrap().as_i64().unwrap(), 7);
+    // Same type as the first definition, so it merges back into the original column
+    assert_eq!(rows[2].data.get("/sensor").unwrap().as_f64().unwrap(), 2.5);
+}
+
+#[test]
+fn test_generation_columns_keep_one_type_per_column() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .finish_record(1_200_000, 1)
+        .start_record(1_300_000, 1, "/sensor", "int64", "")
+        .int64_record(1, 1_400_000, 7)
+        .finish_record(1_500_000, 1)
+        .start_record(1_600_000, 1, "/sensor", "string", "")
+        .string_record(1, 1_700_000, "up")
+        .finish_record(1_800_000, 1)
+        .start_record(1_900_000, 1, "/sensor", "int64", "")
+        .int64_record(1, 2_000_000, 8)
+        .build();
+
+    let rows = WpilogReaderBuilder::new()
+        .generation_columns(true)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+    let columns: Vec<_> = rows.iter().map(|r| r.data.keys().next().unwrap().as_str()).collect();
+    assert_eq!(columns, ["/sensor", "/sensor#2", "/sensor#3", "/sensor#2"]);
+    assert_eq!(rows[3].data["/sensor#2"].as_i64().unwrap(), 8);
+}
+
+#[test]
+fn test_buffer_orphaned_records() {
+    let data = WpilogBuilder::new()
+        .double_record(1, 1_000_000, 1.5)
+        .int64_record(2, 1_050_000, 9)
+        .start_record(1_100_000, 1, "/late", "double", "")
+        .double_record(1, 1_200_000, 2.5)
+        .build();
+
+    // Default: records before the Start are dropped but counted
+    let (rows, formatter) = WpilogReaderBuilder::new()
+        .from_bytes(data.clone())
+        .unwrap()
+        .read_all_with_metadata()
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(formatter.orphaned_records.get(&1), Some(&1));
+    assert_eq!(formatter.orphaned_records.get(&2), Some(&1));
+
+    // Buffered: decoded once the Start arrives; entry 2 is never started
+    let (rows, formatter) = WpilogReaderBuilder::new()
+        .buffer_orphans(true)
+        .from_bytes(data)
+        .unwrap()
+        .read_all_with_metadata()
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].data.get("/late").unwrap().as_f64().unwrap(), 1.5);
+    assert_eq!((rows[0].timestamp * 1_000_000.0).round() as u64, 1_000_000);
+    assert_eq!(rows[1].data.get("/late").unwrap().as_f64().unwrap(), 2.5);
+    assert_eq!(formatter.orphaned_records.len(), 1);
+    assert_eq!(formatter.orphaned_records.get(&2), Some(&1));
+}
+
+#[test]
+fn test_filtered_read_counts_orphans() {
+    let data = WpilogBuilder::new()
+        .double_record(1, 1_000_000, 1.5)
+        .int64_record(2, 1_050_000, 9)
+        .start_record(1_100_000, 1, "/late", "double", "")
+        .start_record(1_100_000, 3, "/other", "double", "")
+        .double_record(1, 1_200_000, 2.5)
+        .double_record(3, 1_200_000, 3.5)
+        .finish_record(1_300_000, 3)
+        .double_record(3, 1_400_000, 4.5)
+        .build();
+
+    let (rows, formatter) = WpilogReaderBuilder::new()
+        .include_prefix("/late")
+        .from_bytes(data)
+        .unwrap()
+        .read_all_with_metadata()
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(formatter.orphaned_records.get(&1), Some(&1));
+    assert_eq!(formatter.orphaned_records.get(&2), Some(&1));
+    // Only the record after the Finish of an unselected entry is an orphan
+    assert_eq!(formatter.orphaned_records.get(&3), Some(&1));
+}
+
+#[test]
+fn test_buffered_orphans_need_a_matching_restart() {
+    let read = |restart_type: &str| {
+        let data = WpilogBuilder::new()
+            .start_record(1_000_000, 1, "/value", "double", "")
+            .double_record(1, 1_100_000, 1.5)
+            .finish_record(1_200_000, 1)
+            .double_record(1, 1_300_000, 2.5)
+            .start_record(1_400_000, 1, "/value", restart_type, "")
+            .build();
+        WpilogReaderBuilder::new()
+            .buffer_orphans(true)
+            .from_bytes(data)
+            .unwrap()
+            .read_all_with_metadata()
+            .unwrap()
+    };
+
+    let (rows, formatter) = read("double");
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].data["/value"].as_f64(), Some(2.5));
+    assert!(formatter.orphaned_records.is_empty());
+
+    // A restart with another type would misread the 8 bytes
+    let (rows, formatter) = read("int64");
+    assert_eq!(rows.len(), 1);
+    assert_eq!(formatter.orphaned_records.get(&1), Some(&1));
+}
+
+#[test]
+fn test_unsupported_types_are_reported() {
+    use wpilog_parser::{Error, Warning};
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Swerve/States", "proto:SwerveModuleState", "")
+        .start_record(1_000_000, 2, "/Vendor/Status", "vendorblob", "")
+        .start_record(1_000_000, 3, "/Speed", "double", "")
+        .raw_record(1, 1_100_000, &[0x0a, 0x02])
+        .raw_record(1, 1_150_000, &[0x0a, 0x03])
+        .raw_record(2, 1_200_000, &[1, 2, 3])
+        .double_record(3, 1_300_000, 1.5)
+        .build();
+
+    // Default: the entries are null columns, reported once each
+    let (rows, formatter) = WpilogReaderBuilder::new()
+        .from_bytes(data.clone())
+        .unwrap()
+        .read_all_with_metadata()
+        .unwrap();
+    assert_eq!(rows.len(), 4);
+    assert!(rows[0].data["/Swerve/States"].is_null());
+    assert_eq!(formatter.warnings.len(), 2);
+    match &formatter.warnings[0] {
+        Warning::UnsupportedType { entry, type_name, hint } => {
+            assert_eq!(entry, "/Swerve/States");
+            assert_eq!(type_name, "proto:SwerveModuleState");
+            assert!(hint.contains("protobuf"));
+        }
+        other => panic!("unexpected warning: {}", other),
+    }
+    assert!(formatter.warnings[1].to_string().contains("/Vendor/Status"));
+
+    // Strict: the read fails naming the entry, unless it is not read at all
+    let reader = WpilogReaderBuilder::new()
+        .deny_unsupported_types(true)
+        .from_bytes(data)
+        .unwrap();
+    assert_eq!(reader.read_entries(&["/Speed"]).unwrap().len(), 1);
+    let err = reader.read_all().unwrap_err();
+    assert!(matches!(err, Error::UnsupportedType { ref entry, .. } if entry == "/Swerve/States"));
+}
+
+#[test]
+fn test_truncated_log_keeps_complete_records() {
+    let mut data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/sensor", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .double_record(1, 1_200_000, 2.5)
+        .build();
+    data.truncate(data.len() - 3);
+    let cut = data.len() - 11;
+
+    let (rows, formatter) = WpilogReaderBuilder::new()
+        .from_bytes(data)
+        .unwrap()
+        .read_all_with_metadata()
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(formatter.truncated_at, Some(cut));
+}
+
+#[test]
+fn test_stream_matches_read_all() {
+    use wpilog_parser::WpilogReader;
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .start_record(1_000_000, 2, "/b", "string", "")
+        .double_record(1, 1_100_000, 1.5)
+        .string_record(2, 1_200_000, "hello")
+        .double_record(1, 1_300_000, 2.5)
+        .build();
+
+    let reader = WpilogReader::from_bytes(data.clone()).unwrap();
+    let mut streamed = Vec::new();
+    reader
+        .stream(|row| {
+            streamed.push(row);
+            Ok(())
+        })
+        .unwrap();
+
+    let collected = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+    assert_eq!(streamed.len(), collected.len());
+    for (a, b) in streamed.iter().zip(&collected) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.data, b.data);
+    }
+
+    // Errors from the sink stop decoding
+    let mut seen = 0;
+    let result = reader.stream(|_| {
+        seen += 1;
+        Err(wpilog_parser::Error::Other("stop".to_string()))
+    });
+    assert!(result.is_err());
+    assert_eq!(seen, 1);
+}
+
 #[test]
 fn test_loop_count_increments() {
     let dir = tempdir().unwrap();
@@ -235,9 +525,6 @@ fn test_loop_count_increments() {
         OutputFormat::Wide,
     );
 
-    // Reset loop count before test
-    Formatter::reset_loop_count();
-
     formatter.read_wpilog(true).unwrap();
     let rows = formatter.read_wpilog(false).unwrap();
 
@@ -490,7 +777,6 @@ fn test_struct_parsing_simple() {
     );
 
     // First pass: infer schema
-    Formatter::reset_loop_count();
     formatter.read_wpilog(true).unwrap();
 
     // Second pass: read data
@@ -537,7 +823,6 @@ fn test_struct_parsing_mixed_types() {
         OutputFormat::Wide,
     );
 
-    Formatter::reset_loop_count();
     formatter.read_wpilog(true).unwrap();
     let rows = formatter.read_wpilog(false).unwrap();
 
@@ -577,7 +862,6 @@ fn test_struct_with_int64() {
         OutputFormat::Wide,
     );
 
-    Formatter::reset_loop_count();
     formatter.read_wpilog(true).unwrap();
     let rows = formatter.read_wpilog(false).unwrap();
 
@@ -588,3 +872,522 @@ fn test_struct_with_int64() {
     assert_eq!(obj.get("id").unwrap().as_i64().unwrap(), 42);
     assert_eq!(obj.get("timestamp").unwrap().as_i64().unwrap(), 9000000000);
 }
+
+#[test]
+fn test_resolved_schema_flattens_nested_structs() {
+    use wpilog_parser::models::ResolvedField;
+
+    let data = WpilogBuilder::new()
+        .struct_schema_record(1_000_000, 1, "struct:Translation2d", "double x; double y")
+        .struct_schema_record(1_000_000, 2, "struct:Pose2d", "Translation2d translation; float heading; int32 id")
+        .build();
+
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    formatter.read_wpilog_from_bytes(&data, true).unwrap();
+
+    let field = |name: &str, type_name: &str, offset, size| ResolvedField {
+        name: name.to_string(),
+        type_name: type_name.to_string(),
+        offset,
+        size,
+    };
+    let expected = vec![
+        field("translation.x", "double", 0, 8),
+        field("translation.y", "double", 8, 8),
+        field("heading", "float", 16, 4),
+        field("id", "int32", 20, 4),
+    ];
+    assert_eq!(formatter.resolved_schema("struct:Pose2d").unwrap(), expected);
+    assert_eq!(formatter.resolved_schema("Pose2d").unwrap(), expected);
+    assert!(formatter.resolved_schema("Missing").is_err());
+}
+
+#[test]
+fn test_parse_json_flattens_top_level_keys() {
+    use serde_json::json;
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Vision/Result", "json", "")
+        .string_record(1, 1_100_000, r#"{"tv": true, "tid": 7, "pose": {"x": 1.5}}"#)
+        .string_record(1, 1_200_000, "not json")
+        .build();
+
+    let raw = WpilogReaderBuilder::new()
+        .from_bytes(data.clone())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert!(raw[0].data["/Vision/Result"].is_string());
+
+    let parsed = WpilogReaderBuilder::new()
+        .parse_json(0)
+        .from_bytes(data.clone())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(parsed[0].data["/Vision/Result"]["pose"]["x"], json!(1.5));
+
+    let flat = WpilogReaderBuilder::new()
+        .parse_json(1)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(flat[0].data["/Vision/Result/tv"], json!(true));
+    assert_eq!(flat[0].data["/Vision/Result/tid"], json!(7));
+    assert_eq!(flat[0].data["/Vision/Result/pose"], json!({"x": 1.5}));
+    assert!(!flat[0].data.contains_key("/Vision/Result"));
+    assert_eq!(flat[1].data["/Vision/Result"], json!("not json"));
+}
+
+fn msgpack(value: rmpv::Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    rmpv::encode::write_value(&mut bytes, &value).unwrap();
+    bytes
+}
+
+#[test]
+fn test_msgpack_columns_from_sampled_schema() {
+    use rmpv::Value;
+    use serde_json::json;
+
+    let reading = |current: Value, ok: bool| {
+        msgpack(Value::Map(vec![
+            (Value::from("current"), current),
+            (Value::from("ok"), Value::from(ok)),
+        ]))
+    };
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/PDH", "msgpack", "")
+        .start_record(1_000_000, 2, "/Misc", "msgpack", "")
+        .raw_record(1, 1_100_000, &reading(Value::from(3), true))
+        .raw_record(1, 1_200_000, &reading(Value::from(4.5), false))
+        .raw_record(1, 1_300_000, &msgpack(Value::from("glitch")))
+        .raw_record(2, 1_400_000, &msgpack(Value::Array(vec![Value::from(1)])))
+        .raw_record(2, 1_500_000, &msgpack(Value::from("two")))
+        .build();
+
+    let records = WpilogReaderBuilder::new()
+        .msgpack_columns(2)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+
+    // Integer and float samples widen to a float column
+    assert_eq!(records[0].data["/PDH/current"], json!(3.0));
+    assert_eq!(records[0].data["/PDH/ok"], json!(true));
+    assert_eq!(records[1].data["/PDH/current"], json!(4.5));
+    assert!(!records[0].data.contains_key("/PDH"));
+    // Records that don't fit the sampled schema fall back to JSON strings
+    assert_eq!(records[2].data["/PDH"], json!("\"glitch\""));
+    // Irregular entries are written as JSON strings
+    assert_eq!(records[3].data["/Misc"], json!("[1]"));
+    assert_eq!(records[4].data["/Misc"], json!("\"two\""));
+}
+
+#[test]
+fn test_concurrent_reads_keep_separate_loop_counts() {
+    use wpilog_parser::WpilogReader;
+
+    let log = |loops: u64| {
+        let mut builder = WpilogBuilder::new()
+            .start_record(1_000_000, 1, "/Timestamp", "int64", "")
+            .start_record(1_000_000, 2, "/value", "double", "");
+        for i in 0..loops {
+            let ts = 1_000_000 + i * 20_000;
+            builder = builder.int64_record(1, ts, ts as i64).double_record(2, ts + 1, i as f64);
+        }
+        builder.build()
+    };
+
+    let handles: Vec<_> = (0..4)
+        .map(|n| {
+            let data = log(200 + n * 50);
+            std::thread::spawn(move || WpilogReader::from_bytes(data).unwrap().read_all().unwrap())
+        })
+        .collect();
+
+    for (n, handle) in handles.into_iter().enumerate() {
+        let rows = handle.join().unwrap();
+        let loops = 200 + n as u64 * 50;
+        assert_eq!(rows.len() as u64, loops * 2);
+        // Each value row follows its loop's /Timestamp row
+        for (i, row) in rows.iter().enumerate().filter(|(_, row)| row.entry == 2) {
+            assert_eq!(row.loop_count, i as u64 / 2 + 1);
+        }
+    }
+}
+
+#[test]
+fn test_struct_schema_names_match_exactly() {
+    let mut pose = Vec::new();
+    pose.write_f64::<LittleEndian>(1.0).unwrap();
+    let mut pose2d = Vec::new();
+    pose2d.write_i32::<LittleEndian>(7).unwrap();
+    pose2d.write_f64::<LittleEndian>(2.0).unwrap();
+
+    // `Pose` is a prefix of `Pose2d` and is logged second
+    let data = WpilogBuilder::new()
+        .struct_schema_record(1_000_000, 1, "struct:Pose2d", "int32 id; Pose inner")
+        .struct_schema_record(1_000_000, 2, "struct:Pose", "double x")
+        .start_record(1_100_000, 3, "/pose", "struct:Pose", "")
+        .start_record(1_100_000, 4, "/pose2d", "struct:Pose2d", "")
+        .struct_record(3, 1_200_000, &pose)
+        .struct_record(4, 1_300_000, &pose2d)
+        .build();
+
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    formatter.read_wpilog_from_bytes(&data, true).unwrap();
+    assert_eq!(
+        formatter.struct_schemas.keys().collect::<Vec<_>>(),
+        vec!["Pose", "Pose2d"]
+    );
+
+    let rows = formatter.read_wpilog_from_bytes(&data, false).unwrap();
+    assert_eq!(rows[0].data["/pose"], serde_json::json!({"x": 1.0}));
+    assert_eq!(rows[1].data["/pose2d"], serde_json::json!({"id": 7, "inner.x": 2.0}));
+}
+
+#[test]
+fn test_single_pass_defers_structs_until_schema() {
+    let mut pose = Vec::new();
+    pose.write_i32::<LittleEndian>(7).unwrap();
+    pose.write_f64::<LittleEndian>(2.0).unwrap();
+
+    // Records of `Pose2d` arrive before its nested `Pose` schema is logged
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Timestamp", "int64", "")
+        .struct_schema_record(1_000_000, 2, "struct:Pose2d", "int32 id; Pose inner")
+        .start_record(1_000_000, 3, "/pose2d", "struct:Pose2d", "")
+        .struct_record(3, 1_100_000, &pose)
+        .int64_record(1, 1_150_000, 1)
+        .struct_record(3, 1_200_000, &pose)
+        .struct_schema_record(1_300_000, 4, "struct:Pose", "double x")
+        .int64_record(1, 1_400_000, 2)
+        .build();
+
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    let rows = formatter.read_wpilog_from_bytes(&data, false).unwrap();
+    let poses: Vec<(f64, u64)> = rows
+        .iter()
+        .filter(|row| row.data.contains_key("/pose2d"))
+        .map(|row| (row.timestamp, row.loop_count))
+        .collect();
+    // Decoded once the schema appears, with the loop counts they were read at
+    assert_eq!(poses, vec![(1.1, 0), (1.2, 1)]);
+    assert_eq!(rows[1].data["/pose2d"], serde_json::json!({"id": 7, "inner.x": 2.0}));
+    assert_eq!(rows.last().unwrap().loop_count, 1);
+
+    // A schema that never appears still fails the read
+    let missing = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/pose", "struct:Pose", "")
+        .struct_record(1, 1_100_000, &pose)
+        .build();
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    assert!(formatter.read_wpilog_from_bytes(&missing, false).is_err());
+}
+
+#[test]
+fn test_recursive_struct_schema_is_rejected() {
+    let data = WpilogBuilder::new()
+        .struct_schema_record(1_000_000, 1, "struct:Node", "double value; Link next")
+        .struct_schema_record(1_000_000, 2, "struct:Link", "Node node")
+        .start_record(1_100_000, 3, "/node", "struct:Node", "")
+        .struct_record(3, 1_200_000, &[0; 16])
+        .build();
+
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    formatter.read_wpilog_from_bytes(&data, true).unwrap();
+
+    let err = formatter.resolved_schema("Node").unwrap_err();
+    assert!(err.to_string().contains("Node -> Link -> Node"), "{}", err);
+
+    let err = formatter.read_wpilog_from_bytes(&data, false).unwrap_err();
+    assert!(matches!(err, wpilog_parser::Error::SchemaError(_)), "{}", err);
+}
+
+#[test]
+fn test_struct_nesting_depth_is_limited() {
+    use wpilog_parser::formatter::MAX_STRUCT_DEPTH;
+
+    // S0 -> S1 -> ... -> S{MAX}, each wrapping the next
+    let mut builder = WpilogBuilder::new();
+    for level in 0..=MAX_STRUCT_DEPTH {
+        let fields = if level == MAX_STRUCT_DEPTH {
+            "double x".to_string()
+        } else {
+            format!("S{} inner", level + 1)
+        };
+        builder = builder.struct_schema_record(1_000_000, level as u32 + 1, &format!("struct:S{}", level), &fields);
+    }
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    formatter.read_wpilog_from_bytes(&builder.build(), true).unwrap();
+
+    let err = formatter.resolved_schema("S0").unwrap_err();
+    assert!(matches!(err, wpilog_parser::Error::SchemaError(_)));
+    assert!(err.to_string().contains("S0 -> S1 -> S2"), "{}", err);
+
+    // One level less is fine
+    let fields = formatter.resolved_schema("S1").unwrap();
+    assert_eq!(fields.len(), 1);
+    assert_eq!(fields[0].name.matches("inner").count(), MAX_STRUCT_DEPTH - 1);
+
+    // A schema that contains itself directly
+    let data = WpilogBuilder::new()
+        .struct_schema_record(1_000_000, 1, "struct:Loop", "Loop again")
+        .start_record(1_100_000, 2, "/loop", "struct:Loop", "")
+        .struct_record(2, 1_200_000, &[1])
+        .build();
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    formatter.read_wpilog_from_bytes(&data, true).unwrap();
+    let err = formatter.read_wpilog_from_bytes(&data, false).unwrap_err();
+    assert!(err.to_string().contains("Loop -> Loop"), "{}", err);
+}
+
+#[test]
+fn test_struct_small_integer_and_half_float_fields() {
+    let mut payload = Vec::new();
+    payload.write_i8(-5).unwrap();
+    payload.write_u8(200).unwrap();
+    payload.write_i16::<LittleEndian>(-1_000).unwrap();
+    payload.write_u16::<LittleEndian>(50_000).unwrap();
+    payload.extend_from_slice(&[0x00, 0x3E]); // float16 1.5
+    payload.write_f64::<LittleEndian>(9.0).unwrap();
+
+    let data = WpilogBuilder::new()
+        .struct_schema_record(
+            1_000_000,
+            1,
+            "struct:Vendor",
+            "int8 a; uint8 b; int16 c; uint16 d; float16 e; double f",
+        )
+        .start_record(1_100_000, 2, "/vendor", "struct:Vendor", "")
+        .struct_record(2, 1_200_000, &payload)
+        .build();
+
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Wide);
+    formatter.read_wpilog_from_bytes(&data, true).unwrap();
+    let offsets: Vec<usize> = formatter
+        .resolved_schema("Vendor")
+        .unwrap()
+        .iter()
+        .map(|field| field.offset)
+        .collect();
+    assert_eq!(offsets, vec![0, 1, 2, 4, 6, 8]);
+
+    let rows = formatter.read_wpilog_from_bytes(&data, false).unwrap();
+    assert_eq!(
+        rows[0].data["/vendor"],
+        serde_json::json!({"a": -5, "b": 200, "c": -1000, "d": 50000, "e": 1.5, "f": 9.0})
+    );
+}
+
+#[test]
+fn test_read_appended_resumes_after_offset() {
+    let full = WpilogBuilder::new()
+        .start_record(0, 1, "/Timestamp", "int64", "")
+        .start_record(0, 2, "/a", "double", "")
+        .int64_record(1, 1_000, 1)
+        .double_record(2, 1_000, 1.0)
+        .int64_record(1, 2_000, 2)
+        .double_record(2, 2_000, 2.0)
+        .build();
+
+    // The log as seen while the last record was half written
+    let partial = full[..full.len() - 5].to_vec();
+    let reader = wpilog_parser::WpilogReader::from_bytes(partial).unwrap();
+    let (first, offset) = reader.read_appended(0).unwrap();
+    assert_eq!(first.len(), 3);
+    assert!(offset < full.len() - 5);
+
+    let reader = wpilog_parser::WpilogReader::from_bytes(full.clone()).unwrap();
+    let (rest, end) = reader.read_appended(offset).unwrap();
+    assert_eq!(end, full.len());
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].data["/a"], 2.0);
+    // Loop counts continue from the records already converted
+    assert_eq!(rest[0].loop_count, 2);
+}
+
+#[test]
+fn test_unmapped_reader_survives_truncation() {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "/a", "double", "")
+        .double_record(1, 1_000, 1.0)
+        .double_record(1, 2_000, 2.0)
+        .build();
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("live.wpilog");
+    std::fs::write(&path, &data).unwrap();
+
+    let reader = wpilog_parser::WpilogReader::from_file_unmapped(&path).unwrap();
+    // The writer restarts the log while it is being read
+    File::create(&path).unwrap();
+    let (rows, end) = reader.read_appended(0).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(end, data.len());
+}
+
+#[test]
+fn test_long_rows_keep_structs_and_nested_json() {
+    use wpilog_parser::datalog::DataLogReader;
+    use wpilog_parser::formatter::ParseContext;
+
+    let mut pose = Vec::new();
+    pose.write_f64::<LittleEndian>(1.5).unwrap();
+    pose.write_f32::<LittleEndian>(0.25).unwrap();
+    pose.write_i32::<LittleEndian>(7).unwrap();
+    let data = WpilogBuilder::new()
+        .struct_schema_record(1_000_000, 1, "struct:Pose", "double x; float heading; int32 id")
+        .start_record(1_000_000, 2, "/pose", "struct:Pose", "")
+        .start_record(1_000_000, 3, "/targets", "json", "")
+        .struct_record(2, 1_100_000, &pose)
+        .string_record(3, 1_200_000, "[[1, 2], [3]]")
+        .build();
+
+    let mut formatter = Formatter::new(String::new(), String::new(), OutputFormat::Long);
+    formatter.read_wpilog_from_bytes(&data, true).unwrap();
+
+    let long_rows = |formatter: &Formatter| {
+        let mut context = ParseContext::default();
+        let mut entries = std::collections::HashMap::new();
+        let mut rows = Vec::new();
+        for record in DataLogReader::new(&data).records().unwrap() {
+            let record = record.unwrap();
+            if record.is_start() {
+                let start = record.get_start_data().unwrap();
+                entries.insert(start.entry, start);
+            } else if entries[&record.entry].type_name != "structschema" {
+                rows.extend(formatter.parse_records_long(&record, &entries[&record.entry], &mut context).unwrap());
+            }
+        }
+        rows
+    };
+
+    let rows = long_rows(&formatter);
+    assert_eq!(rows.len(), 2);
+    let object = |i: usize| rows[i].value.as_ref().unwrap().object.clone().unwrap();
+    assert_eq!(object(0), serde_json::json!({"x": 1.5, "heading": 0.25, "id": 7}));
+    assert_eq!(object(1), serde_json::json!([[1, 2], [3]]));
+
+    formatter.long_struct_fields = true;
+    let rows = long_rows(&formatter);
+    let fields: Vec<(&str, &str)> = rows[..3]
+        .iter()
+        .map(|r| (r.entry_name.as_str(), r.type_name.as_str()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![("/pose/x", "double"), ("/pose/heading", "float"), ("/pose/id", "int32")]
+    );
+    assert_eq!(rows[0].value.as_ref().unwrap().double, Some(1.5));
+    assert_eq!(rows[1].value.as_ref().unwrap().float, Some(0.25));
+    assert_eq!(rows[2].value.as_ref().unwrap().int64, Some(7));
+    assert_eq!(rows[2].loop_count, rows[0].loop_count);
+}
+
+#[test]
+fn test_from_file_maps_log() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("test.wpilog");
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .double_record(1, 1_100_000, 2.5)
+        .build();
+    File::create(&file_path).unwrap().write_all(&data).unwrap();
+
+    let records = wpilog_parser::WpilogReader::from_file(&file_path).unwrap().read_all().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].data["/value"], 2.5);
+
+    // Empty files cannot be mapped and fall back to reading
+    let empty = dir.path().join("empty.wpilog");
+    File::create(&empty).unwrap();
+    assert!(matches!(
+        wpilog_parser::WpilogReader::from_file(&empty),
+        Err(wpilog_parser::Error::InvalidFormat(_))
+    ));
+}
+
+#[test]
+fn test_from_shared_and_cow_bytes() {
+    use std::borrow::Cow;
+    use std::sync::Arc;
+    use wpilog_parser::WpilogReader;
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .double_record(1, 1_100_000, 2.5)
+        .build();
+    let values = |records: Vec<wpilog_parser::WideRow>| {
+        records.into_iter().map(|row| (row.timestamp, row.data)).collect::<Vec<_>>()
+    };
+    let expected = values(WpilogReader::from_bytes(data.clone()).unwrap().read_all().unwrap());
+
+    let shared: Arc<[u8]> = data.clone().into();
+    let records = WpilogReader::from_shared_bytes(Arc::clone(&shared)).unwrap().read_all().unwrap();
+    assert_eq!(values(records), expected);
+    assert_eq!(Arc::strong_count(&shared), 1);
+
+    let leaked: &'static [u8] = Box::leak(data.clone().into_boxed_slice());
+    let records = WpilogReader::from_cow(Cow::Borrowed(leaked)).unwrap().read_all().unwrap();
+    assert_eq!(values(records), expected);
+    let records = WpilogReaderBuilder::new()
+        .record_ids(true)
+        .from_cow(Cow::Owned(data))
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert!(records[0].data.contains_key("record_id"));
+
+    assert!(matches!(
+        WpilogReader::from_shared_bytes(Arc::from(&b"not a log"[..])),
+        Err(wpilog_parser::Error::InvalidFormat(_))
+    ));
+}
+
+#[test]
+fn test_builder_rejects_invalid_configuration() {
+    use std::time::Duration;
+    use wpilog_parser::{Error, Pivot};
+
+    let data = || {
+        WpilogBuilder::new()
+            .start_record(1_000_000, 1, "/value", "double", "")
+            .double_record(1, 1_000_000, 1.0)
+            .build()
+    };
+    let config_error = |result: wpilog_parser::Result<_>| match result {
+        Err(Error::Config(msg)) => msg,
+        Err(e) => panic!("expected a configuration error, got {}", e),
+        Ok(_) => panic!("expected a configuration error"),
+    };
+
+    let msg = config_error(
+        WpilogReaderBuilder::new()
+            .output_format(OutputFormat::Long)
+            .pivot(Pivot::new())
+            .from_bytes(data()),
+    );
+    assert!(msg.contains("pivot"), "{}", msg);
+    config_error(WpilogReaderBuilder::new().record_ids(true).pivot(Pivot::new()).from_bytes(data()));
+    config_error(WpilogReaderBuilder::new().head_duration(Duration::ZERO).from_bytes(data()));
+    config_error(WpilogReaderBuilder::new().msgpack_columns(0).from_bytes(data()));
+    assert!(WpilogReaderBuilder::new().pivot(Pivot::new()).validate().is_ok());
+
+    // Long output is not silently read as wide rows
+    let reader = WpilogReaderBuilder::new()
+        .output_format(OutputFormat::Long)
+        .from_bytes(data())
+        .unwrap();
+    assert_eq!(reader.output_format(), OutputFormat::Long);
+    assert_eq!(reader.read_all_long().unwrap().len(), 1);
+    assert!(matches!(reader.read_all(), Err(Error::Config(_))));
+    config_error(
+        WpilogReaderBuilder::new()
+            .output_format(OutputFormat::Long)
+            .record_ids(true)
+            .from_bytes(data()),
+    );
+}
@@ -0,0 +1,49 @@
+//! Parses every log in `tests/golden/logs` and compares it with its snapshot
+//! in `tests/golden/snapshots`. Run with `UPDATE_GOLDEN=1` to (re)write the
+//! snapshots after an intended change in decoding, then review the diff.
+
+use std::path::Path;
+use tempfile::tempdir;
+use wpilog_parser::snapshot::{check_golden_dir, LogSnapshot};
+
+#[test]
+fn test_golden_logs_match_snapshots() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+
+    let report = check_golden_dir(root.join("logs"), root.join("snapshots"), update).unwrap();
+    for (log, differences) in &report.mismatched {
+        eprintln!("{}:\n  {}", log, differences.join("\n  "));
+    }
+    assert!(report.missing.is_empty(), "logs without snapshots: {:?}", report.missing);
+    assert!(report.is_ok(), "snapshots differ; rerun with UPDATE_GOLDEN=1 if intended");
+    assert!(!report.matched.is_empty() || update);
+}
+
+#[test]
+fn test_golden_check_reports_changes() {
+    let dir = tempdir().unwrap();
+    let logs = dir.path().join("logs");
+    let snapshots = dir.path().join("snapshots");
+    std::fs::create_dir(&logs).unwrap();
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/logs/synthetic_all_types.wpilog");
+    std::fs::copy(&source, logs.join("run.wpilog")).unwrap();
+
+    let report = check_golden_dir(&logs, &snapshots, false).unwrap();
+    assert_eq!(report.missing, vec!["run"]);
+
+    let report = check_golden_dir(&logs, &snapshots, true).unwrap();
+    assert_eq!(report.updated, vec!["run"]);
+    assert!(check_golden_dir(&logs, &snapshots, false).unwrap().is_ok());
+
+    // Tamper with one value in the snapshot
+    let path = snapshots.join("run.json");
+    let mut snapshot = LogSnapshot::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    snapshot.rows[2].loop_count += 1;
+    std::fs::write(&path, snapshot.to_json().unwrap()).unwrap();
+
+    let report = check_golden_dir(&logs, &snapshots, false).unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.mismatched["run"].len(), 1);
+    assert!(report.mismatched["run"][0].starts_with("row 2:"));
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::{sample_log, WpilogBuilder};
+use wpilog_parser::WpilogReader;
+
+#[test]
+fn test_index_lists_records_per_lifetime() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let index = reader.index().unwrap();
+
+    assert_eq!(index.entries().len(), 3);
+    assert_eq!(index.record_count(), 4);
+
+    let lifetimes: Vec<_> = index.find("/a").collect();
+    assert_eq!(lifetimes.len(), 2);
+    assert_eq!(lifetimes[0].start.entry, 1);
+    assert_eq!(lifetimes[0].start_timestamp, 1_000_000);
+    let timestamps: Vec<u64> = lifetimes[0].records.iter().map(|r| r.timestamp).collect();
+    assert_eq!(timestamps, vec![1_100_000, 1_200_000]);
+    assert_eq!(lifetimes[1].records.len(), 1);
+}
+
+#[test]
+fn test_record_at_decodes_indexed_offset() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let index = reader.index().unwrap();
+    let low_level = reader.low_level_reader();
+
+    let location = index.find("/b").next().unwrap().records[0];
+    let record = low_level.record_at(location.offset).unwrap();
+    assert_eq!(record.entry, 2);
+    assert_eq!(record.timestamp, 1_150_000);
+    assert_eq!(record.get_string().unwrap(), "hello");
+
+    assert!(low_level.record_at(usize::MAX / 2).is_err());
+}
+
+#[test]
+fn test_read_entry() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+
+    let rows = reader.read_entry("/a").unwrap();
+    let values: Vec<f64> = rows.iter().map(|r| r.data["/a"].as_f64().unwrap()).collect();
+    assert_eq!(values, vec![1.5, 2.5, 3.5]);
+    assert_eq!(rows[2].entry, 3);
+
+    assert!(reader.read_entry("/missing").unwrap().is_empty());
+}
+
+#[test]
+fn test_read_entries_follows_reused_ids() {
+    let data = WpilogBuilder::new()
+        .struct_schema_record(1_000_000, 9, "struct:Point", "double x; double y")
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .start_record(1_000_000, 2, "/p", "struct:Point", "")
+        .double_record(1, 1_100_000, 1.5)
+        .struct_record(2, 1_150_000, &[0; 16])
+        .finish_record(1_200_000, 1)
+        .start_record(1_300_000, 1, "/other", "double", "")
+        .double_record(1, 1_400_000, 9.0)
+        .build();
+    let reader = WpilogReader::from_bytes(data).unwrap();
+
+    let rows = reader.read_entries(&["/a", "/p"]).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].data["/a"].as_f64().unwrap(), 1.5);
+    assert_eq!(rows[1].data["/p"]["x"].as_f64().unwrap(), 0.0);
+}
+
+#[test]
+fn test_quick_scan_counts_without_decoding() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .start_record(1_000_000, 2, "/b", "int64", "")
+        .double_record(1, 1_100_000, 1.0)
+        .int64_record(2, 1_200_000, 2)
+        .double_record(1, 1_300_000, 3.0)
+        .finish_record(1_400_000, 1)
+        .start_record(1_500_000, 3, "/a", "string", "")
+        .string_record(3, 1_600_000, "x")
+        .double_record(9, 2_500_000, 4.0)
+        .build();
+
+    let scan = WpilogReader::from_bytes(data).unwrap().quick_scan().unwrap();
+    assert_eq!(scan.record_count, 9);
+    assert_eq!(scan.data_record_count, 5);
+    assert_eq!(scan.entry_counts["/a"], 3);
+    assert_eq!(scan.entry_counts["/b"], 1);
+    assert_eq!(scan.start_timestamp, Some(1_000_000));
+    assert_eq!(scan.end_timestamp, Some(2_500_000));
+    assert_eq!(scan.duration_secs(), 1.5);
+    assert_eq!(scan.count_matching(|name| name == "/b"), 1);
+    assert_eq!(scan.truncated_at, None);
+}
+
+#[test]
+fn test_quick_scan_entry_sizes() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .start_record(1_000_000, 2, "/b", "string", "")
+        .start_record(1_000_000, 3, "/c", "boolean", "")
+        .double_record(1, 1_100_000, 1.0)
+        .string_record(2, 1_200_000, "a longer message")
+        .double_record(1, 1_300_000, 3.0)
+        .boolean_record(3, 1_400_000, true)
+        .double_record(9, 2_500_000, 4.0)
+        .build();
+
+    let scan = WpilogReader::from_bytes(data).unwrap().quick_scan().unwrap();
+    assert_eq!(scan.entry_bytes["/a"], 16);
+    assert_eq!(scan.entry_bytes["/b"], 16);
+    // Records with no Start record are not attributed to any entry
+    assert_eq!(scan.data_bytes(), 33);
+
+    let sizes = scan.entry_sizes();
+    let names: Vec<_> = sizes.iter().map(|size| size.name.as_str()).collect();
+    assert_eq!(names, ["/a", "/b", "/c"]);
+    assert_eq!(sizes[0].records, 2);
+    assert_eq!(sizes[2].bytes, 1);
+    assert!((sizes.iter().map(|size| size.share).sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_read_entry_keeps_loop_counts() {
+    let mut builder = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Timestamp", "int64", "")
+        .start_record(1_000_000, 2, "/a", "double", "");
+    for i in 0..3u64 {
+        let ts = 1_100_000 + i * 20_000;
+        builder = builder.int64_record(1, ts, ts as i64).double_record(2, ts + 1, i as f64);
+    }
+    let data = builder.build();
+
+    let loop_counts = |rows: Vec<wpilog_parser::WideRow>| -> Vec<u64> {
+        rows.iter().filter(|row| row.entry == 2).map(|row| row.loop_count).collect()
+    };
+    let all = loop_counts(WpilogReader::from_bytes(data.clone()).unwrap().read_all().unwrap());
+    assert_eq!(all, vec![1, 2, 3]);
+    let reader = WpilogReader::from_bytes(data).unwrap();
+    assert_eq!(loop_counts(reader.read_entry("/a").unwrap()), all);
+    assert_eq!(loop_counts(reader.read_entries(&["/a"]).unwrap()), all);
+}
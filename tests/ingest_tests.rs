@@ -0,0 +1,164 @@
+mod common;
+
+use common::WpilogBuilder;
+use std::fs;
+use std::time::Duration;
+use tempfile::tempdir;
+use wpilog_parser::ingest::read_manifest;
+use wpilog_parser::IngestDaemon;
+
+fn sample_log(value: f64) -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .double_record(1, 1_100_000, value)
+        .double_record(1, 1_200_000, value + 1.0)
+        .build()
+}
+
+#[test]
+fn test_ingest_converts_new_logs_once() {
+    let input = tempdir().unwrap();
+    let output = tempdir().unwrap();
+    fs::write(input.path().join("match1.wpilog"), sample_log(1.0)).unwrap();
+    fs::write(input.path().join("notes.txt"), "not a log").unwrap();
+
+    let mut daemon = IngestDaemon::new(input.path(), output.path()).min_age(Duration::ZERO);
+    let written = daemon.run_once().unwrap();
+    assert_eq!(written.len(), 1);
+    assert_eq!(written[0].file, "match1.wpilog");
+    assert_eq!(written[0].stats.num_records, 2);
+    assert!(output.path().join("filename=match1").is_dir());
+
+    // Already in the manifest, so a second scan only picks up the new log
+    fs::write(input.path().join("match2.wpilog"), sample_log(5.0)).unwrap();
+    let written = daemon.run_once().unwrap();
+    assert_eq!(written.len(), 1);
+    assert_eq!(written[0].file, "match2.wpilog");
+
+    // A fresh daemon resumes from the manifest
+    let mut restarted = IngestDaemon::new(input.path(), output.path()).min_age(Duration::ZERO);
+    assert!(restarted.run_once().unwrap().is_empty());
+
+    let manifest = read_manifest(daemon.manifest_path()).unwrap();
+    assert_eq!(manifest.len(), 2);
+    assert_eq!(manifest[1].summary.record_count, 2);
+    assert!(input.path().join("match1.wpilog").exists());
+}
+
+#[test]
+fn test_ingest_prunes_verified_logs_and_skips_fresh_ones() {
+    let input = tempdir().unwrap();
+    let output = tempdir().unwrap();
+    let log = input.path().join("match.wpilog");
+    fs::write(&log, sample_log(1.0)).unwrap();
+
+    let mut daemon = IngestDaemon::new(input.path(), output.path())
+        .min_age(Duration::from_secs(3600))
+        .prune_raw(true);
+    assert!(daemon.run_once().unwrap().is_empty());
+    assert!(log.exists());
+
+    let mut daemon = IngestDaemon::new(input.path(), output.path())
+        .min_age(Duration::ZERO)
+        .prune_raw(true);
+    let written = daemon.run_once().unwrap();
+    assert!(written[0].pruned);
+    assert!(!log.exists());
+}
+
+#[test]
+fn test_ingest_skips_invalid_logs() {
+    let input = tempdir().unwrap();
+    let output = tempdir().unwrap();
+    fs::write(input.path().join("broken.wpilog"), b"garbage").unwrap();
+
+    let mut daemon = IngestDaemon::new(input.path(), output.path())
+        .min_age(Duration::ZERO)
+        .prune_raw(true);
+    assert!(daemon.run_once().unwrap().is_empty());
+    assert!(input.path().join("broken.wpilog").exists());
+    assert!(read_manifest(daemon.manifest_path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_ingest_incremental_appends_new_records() {
+    let input = tempdir().unwrap();
+    let output = tempdir().unwrap();
+    let log = input.path().join("live.wpilog");
+    let mut full = WpilogBuilder::new().start_record(0, 1, "/a", "double", "");
+    for i in 0..10 {
+        full = full.double_record(1, (i + 1) * 1000, i as f64);
+    }
+    let full = full.build();
+    // Six complete 13-byte records and part of the seventh
+    let cut = full.len() - 4 * 13 + 3;
+    fs::write(&log, &full[..cut]).unwrap();
+
+    let mut daemon = IngestDaemon::new(input.path(), output.path())
+        .min_age(Duration::from_secs(3600))
+        .chunk_size(4)
+        .prune_raw(true)
+        .incremental(true);
+    let written = daemon.run_once().unwrap();
+    assert_eq!(written.len(), 1);
+    assert_eq!(written[0].stats.num_records, 6);
+    assert_eq!((written[0].first_chunk, written[0].stats.num_chunks), (0, 2));
+    assert!(!written[0].pruned);
+
+    // Unchanged: nothing to do
+    assert!(daemon.run_once().unwrap().is_empty());
+
+    fs::write(&log, &full).unwrap();
+    let mut restarted = IngestDaemon::new(input.path(), output.path())
+        .chunk_size(4)
+        .incremental(true);
+    let written = restarted.run_once().unwrap();
+    assert_eq!(written.len(), 1);
+    assert_eq!(written[0].stats.num_records, 4);
+    assert_eq!(written[0].first_chunk, 2);
+    assert_eq!(written[0].converted_bytes, Some(full.len() as u64));
+
+    let output_dir = output.path().join("filename=live");
+    let rows = wpilog_parser::formats::parquet::read_parquet_directory(&output_dir).unwrap();
+    assert_eq!(rows.len(), 10);
+    assert!(output_dir.join("file_part002.parquet").exists());
+    assert_eq!(read_manifest(restarted.manifest_path()).unwrap().len(), 2);
+    assert!(log.exists());
+}
+
+#[test]
+fn test_ingest_incremental_keeps_schema_across_deltas() {
+    use arrow::datatypes::DataType;
+    use wpilog_parser::formats::parquet::{chunk_file_name, read_parquet_schema};
+
+    let input = tempdir().unwrap();
+    let output = tempdir().unwrap();
+    let log = input.path().join("live.wpilog");
+    let first = WpilogBuilder::new()
+        .start_record(0, 1, "/a", "double", "")
+        .double_record(1, 1000, 1.0)
+        .build();
+    fs::write(&log, &first).unwrap();
+
+    let mut daemon = IngestDaemon::new(input.path(), output.path()).incremental(true);
+    assert_eq!(daemon.run_once().unwrap().len(), 1);
+
+    // The appended records only hold a new entry
+    let appended = WpilogBuilder::new()
+        .start_record(2000, 2, "/b", "string", "")
+        .string_record(2, 2000, "on")
+        .build();
+    let header_len = WpilogBuilder::new().build().len();
+    let mut full = first.clone();
+    full.extend_from_slice(&appended[header_len..]);
+    fs::write(&log, &full).unwrap();
+    let written = daemon.run_once().unwrap();
+    assert_eq!(written[0].first_chunk, 1);
+
+    let output_dir = output.path().join("filename=live");
+    let before = read_parquet_schema(&output_dir.join(chunk_file_name(0))).unwrap();
+    let after = read_parquet_schema(&output_dir.join(chunk_file_name(1))).unwrap();
+    assert_eq!(&after.fields()[..before.fields().len()], &before.fields()[..]);
+    assert_eq!(after.field_with_name("/a").unwrap().data_type(), &DataType::Float64);
+    assert_eq!(after.field_with_name("/b").unwrap().data_type(), &DataType::Utf8);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::WpilogBuilder;
+use flate2::read::GzDecoder;
+use serde_json::{json, Value};
+use std::io::Read;
+use tempfile::tempdir;
+use wpilog_parser::models::LongRow;
+use wpilog_parser::{JsonlWriter, WpilogReader};
+
+fn sample_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/drive/speed", "double", "")
+        .start_record(1_000_000, 2, "/drive/mode", "string", "")
+        .double_record(1, 1_100_000, 1.5)
+        .string_record(2, 1_200_000, "auto")
+        .build()
+}
+
+fn lines(text: &str) -> Vec<Value> {
+    text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+#[test]
+fn test_jsonl_writes_one_sorted_object_per_row() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let mut writer = JsonlWriter::new(Vec::new());
+    reader.stream(|row| writer.write_row(&row)).unwrap();
+    assert_eq!(writer.rows(), 2);
+    let text = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    assert_eq!(
+        text.lines().next().unwrap(),
+        r#"{"/drive/speed":1.5,"entry":1,"loop_count":0,"timestamp":1.1,"type":"double"}"#
+    );
+    let rows = lines(&text);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1]["/drive/mode"], json!("auto"));
+
+    // Same rows, same bytes
+    let mut again = JsonlWriter::new(Vec::new());
+    again.write_rows(&reader.read_all().unwrap()).unwrap();
+    assert_eq!(String::from_utf8(again.finish().unwrap()).unwrap(), text);
+}
+
+#[test]
+fn test_jsonl_gzip_and_long_rows() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("rows.jsonl.gz");
+
+    let mut row = LongRow::new(1.1, 1, "double".to_string(), 3);
+    row.entry_name = "/drive/speed".to_string();
+    row.value.as_mut().unwrap().double = Some(1.5);
+
+    let mut writer = JsonlWriter::create(&path).unwrap();
+    writer.write_rows([&row, &row]).unwrap();
+    writer.finish().unwrap();
+
+    let mut text = String::new();
+    GzDecoder::new(std::fs::File::open(&path).unwrap())
+        .read_to_string(&mut text)
+        .unwrap();
+    let rows = lines(&text);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["entry_name"], json!("/drive/speed"));
+    assert_eq!(rows[0]["value"]["double"], json!(1.5));
+    assert_eq!(rows[0]["loop_count"], json!(3));
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::WpilogBuilder;
+use serde_json::json;
+use std::collections::HashMap;
+use wpilog_parser::datalog::DataLogReader;
+use wpilog_parser::{DataLogWriter, Error, WpilogReader};
+
+#[test]
+fn test_writer_matches_builder_bytes() {
+    let mut writer = DataLogWriter::with_extra_header(Vec::new(), "robot").unwrap();
+    let a = writer.start("/a", "double", "", 1_000_000).unwrap();
+    let b = writer
+        .start("/b", "string[]", "{\"source\":\"nt\"}", 1_000_000)
+        .unwrap();
+    writer.set_metadata(a, "units=volts", 1_050_000).unwrap();
+    writer.append_double(a, 1_100_000, 1.5).unwrap();
+    writer
+        .append_string_array(b, 70_000_000_000, &["x", "yz"])
+        .unwrap();
+    writer.finish(a, 70_000_000_001).unwrap();
+    let written = writer.into_inner().unwrap();
+
+    let expected = WpilogBuilder::with_header(0x0100, "robot")
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .start_record(1_000_000, 2, "/b", "string[]", "{\"source\":\"nt\"}")
+        .set_metadata_record(1_050_000, 1, "units=volts")
+        .double_record(1, 1_100_000, 1.5)
+        .string_array_record(2, 70_000_000_000, &["x", "yz"])
+        .finish_record(70_000_000_001, 1)
+        .build();
+
+    assert_eq!(written, expected);
+}
+
+#[test]
+fn test_writer_round_trips_typed_values() {
+    let mut writer = DataLogWriter::new(Vec::new()).unwrap();
+    let flag = writer.start("/flag", "boolean", "", 0).unwrap();
+    let count = writer.start("/count", "int64", "", 0).unwrap();
+    let ratio = writer.start("/ratio", "float", "", 0).unwrap();
+    let name = writer.start("/name", "string", "", 0).unwrap();
+    let flags = writer.start("/flags", "boolean[]", "", 0).unwrap();
+    let counts = writer.start("/counts", "int64[]", "", 0).unwrap();
+    let ratios = writer.start("/ratios", "float[]", "", 0).unwrap();
+    let values = writer.start("/values", "double[]", "", 0).unwrap();
+
+    writer.append_boolean(flag, 1_000_000, true).unwrap();
+    writer.append_int64(count, 1_000_000, -42).unwrap();
+    writer.append_float(ratio, 1_000_000, 0.5).unwrap();
+    writer.append_string(name, 1_000_000, "hello").unwrap();
+    writer
+        .append_boolean_array(flags, 1_000_000, &[true, false])
+        .unwrap();
+    writer
+        .append_int64_array(counts, 1_000_000, &[1, 2, 3])
+        .unwrap();
+    writer
+        .append_float_array(ratios, 1_000_000, &[0.25])
+        .unwrap();
+    writer
+        .append_double_array(values, 1_000_000, &[1.5, 2.5])
+        .unwrap();
+    let data = writer.into_inner().unwrap();
+
+    let reader = DataLogReader::new(&data);
+    assert!(reader.is_valid());
+    assert_eq!(reader.get_version(), 0x0100);
+
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+    let row: HashMap<_, _> = rows.into_iter().flat_map(|row| row.data).collect();
+    assert_eq!(row["/flag"], json!(true));
+    assert_eq!(row["/count"], json!(-42));
+    assert_eq!(row["/ratio"], json!(0.5));
+    assert_eq!(row["/name"], json!("hello"));
+    assert_eq!(row["/flags"], json!([true, false]));
+    assert_eq!(row["/counts"], json!([1, 2, 3]));
+    assert_eq!(row["/ratios"], json!([0.25]));
+    assert_eq!(row["/values"], json!([1.5, 2.5]));
+}
+
+#[test]
+fn test_writer_rejects_mismatched_and_unstarted_entries() {
+    let mut writer = DataLogWriter::new(Vec::new()).unwrap();
+    let value = writer.start("/value", "double", "", 0).unwrap();
+
+    assert!(matches!(
+        writer.append_int64(value, 1, 3),
+        Err(Error::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        writer.append_double(value + 1, 1, 3.0),
+        Err(Error::InvalidEntry(_))
+    ));
+
+    writer.finish(value, 2).unwrap();
+    assert!(matches!(
+        writer.append_double(value, 3, 3.0),
+        Err(Error::InvalidEntry(_))
+    ));
+    assert!(matches!(
+        writer.finish(value, 3),
+        Err(Error::InvalidEntry(_))
+    ));
+
+    // Finished IDs are not reused
+    assert_eq!(writer.start("/value", "double", "", 4).unwrap(), value + 1);
+}
+
+#[test]
+fn test_writer_raw_and_file_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.wpilog");
+
+    let mut writer = DataLogWriter::create(&path).unwrap();
+    let raw = writer.start("/raw", "raw", "", 0).unwrap();
+    let json_entry = writer.start("/json", "json", "", 0).unwrap();
+    writer.append_raw(raw, 1_000_000, &[1, 2, 3]).unwrap();
+    writer
+        .append_string(json_entry, 1_000_000, "{\"x\":1}")
+        .unwrap();
+    writer.flush().unwrap();
+    drop(writer);
+
+    let data = std::fs::read(&path).unwrap();
+    let records: Vec<_> = DataLogReader::new(&data)
+        .records()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[2].entry, raw);
+    assert_eq!(records[2].data, vec![1, 2, 3]);
+    assert_eq!(records[3].get_string().unwrap(), "{\"x\":1}");
+}
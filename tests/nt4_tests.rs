@@ -0,0 +1,77 @@
+#![cfg(feature = "nt4")]
+// tungstenite's handshake callback signature returns a large error type
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use common::WpilogBuilder;
+use std::net::TcpListener;
+use std::thread;
+use tungstenite::handshake::server::{Request, Response};
+use tungstenite::Message;
+use wpilog_parser::nt4::Nt4Publisher;
+use wpilog_parser::{Replayer, WpilogReader};
+
+#[test]
+fn test_nt4_publisher_announces_and_sends_values() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept_hdr(stream, |_: &Request, mut response: Response| {
+            response
+                .headers_mut()
+                .insert("Sec-WebSocket-Protocol", "v4.1.networktables.first.wpi.edu".parse().unwrap());
+            Ok(response)
+        })
+        .unwrap();
+
+        let mut messages = Vec::new();
+        loop {
+            match socket.read() {
+                Ok(Message::Binary(frame)) => {
+                    let value = rmpv::decode::read_value(&mut &frame[..]).unwrap();
+                    if value[0].as_i64() == Some(-1) {
+                        // Time sync reply: [-1, server time, type, client time]
+                        let reply = rmpv::Value::Array(vec![
+                            (-1).into(),
+                            1_000_000_000.into(),
+                            2.into(),
+                            value[3].clone(),
+                        ]);
+                        let mut out = Vec::new();
+                        rmpv::encode::write_value(&mut out, &reply).unwrap();
+                        socket.send(Message::Binary(out.into())).unwrap();
+                    } else {
+                        messages.push(format!("{}", value));
+                    }
+                }
+                Ok(Message::Text(text)) => messages.push(text.to_string()),
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        messages
+    });
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "NT:/SmartDashboard/speed", "double", "")
+        .double_record(1, 1_000_000, 2.5)
+        .finish_record(1_100_000, 1)
+        .build();
+    let reader = WpilogReader::from_bytes(data).unwrap();
+    {
+        let mut publisher = Nt4Publisher::connect_to_port("127.0.0.1", port, "test").unwrap();
+        Replayer::new(&reader)
+            .speed(f64::INFINITY)
+            .visit(&mut publisher)
+            .unwrap();
+    }
+
+    let messages = server.join().unwrap();
+    assert_eq!(messages.len(), 3, "{:?}", messages);
+    assert!(messages[0].contains("\"publish\""));
+    assert!(messages[0].contains("\"/SmartDashboard/speed\""));
+    assert!(messages[1].starts_with("[1, ") && messages[1].ends_with(", 1, 2.5]"), "{}", messages[1]);
+    assert!(messages[2].contains("\"unpublish\""));
+}
@@ -1,3 +1,6 @@
+// Assertions compare with `false` explicitly
+#![allow(clippy::bool_comparison)]
+
 mod common;
 
 use common::WpilogBuilder;
@@ -261,3 +264,901 @@ fn test_mixed_scalar_and_array_columns() {
         .expect("Should have /enabled column");
     assert!(enabled_field.is_primitive(), "Enabled should be primitive");
 }
+
+#[test]
+fn test_custom_writer_properties() {
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use wpilog_parser::{ParquetWriterBuilder, WpilogReader};
+
+    let dir = tempdir().unwrap();
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/temperature", "double", "")
+        .double_record(1, 1_100_000, 25.5)
+        .double_record(1, 1_200_000, 26.5)
+        .build();
+
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    let output_dir = dir.path().join("output");
+    let props = WriterProperties::builder()
+        .set_created_by("wpilog-parser-test".to_string())
+        .build();
+    ParquetWriterBuilder::new()
+        .output_directory(&output_dir)
+        .writer_properties(props)
+        .build()
+        .unwrap()
+        .write(&rows)
+        .unwrap();
+
+    let file = File::open(output_dir.join("file_part000.parquet")).unwrap();
+    let reader = SerializedFileReader::new(file).unwrap();
+    assert_eq!(
+        reader.metadata().file_metadata().created_by(),
+        Some("wpilog-parser-test")
+    );
+}
+
+#[test]
+fn test_compression_and_encoding_options() {
+    use parquet::basic::{Compression, Encoding};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use wpilog_parser::{Error, ParquetCompression, ParquetWriter, ParquetWriterBuilder, StatisticsLevel, WpilogReader};
+
+    let mut builder = WpilogBuilder::new().start_record(1_000_000, 1, "/temperature", "double", "");
+    for i in 0..10u64 {
+        builder = builder.double_record(1, 1_100_000 + i * 1_000, i as f64);
+    }
+    let rows = WpilogReader::from_bytes(builder.build()).unwrap().read_all().unwrap();
+
+    let dir = tempdir().unwrap();
+    ParquetWriter::new(dir.path())
+        .compression(ParquetCompression::Zstd(5))
+        .dictionary(false)
+        .max_row_group_size(4)
+        .statistics(StatisticsLevel::None)
+        .write(&rows)
+        .unwrap();
+
+    let file = File::open(dir.path().join("file_part000.parquet")).unwrap();
+    let reader = SerializedFileReader::new(file).unwrap();
+    let metadata = reader.metadata();
+    assert_eq!(metadata.num_row_groups(), 3);
+    let column = metadata.row_group(0).column(0);
+    assert!(matches!(column.compression(), Compression::ZSTD(_)));
+    assert!(column.statistics().is_none());
+    assert!(!column.encodings().any(|encoding| encoding == Encoding::RLE_DICTIONARY));
+
+    // Parsed from the CLI's names
+    assert_eq!("zstd".parse::<ParquetCompression>().unwrap(), ParquetCompression::Zstd(3));
+    assert_eq!("LZ4".parse::<ParquetCompression>().unwrap(), ParquetCompression::Lz4);
+    assert!("zstd:30".parse::<ParquetCompression>().is_err());
+    assert_eq!("chunk".parse::<StatisticsLevel>().unwrap(), StatisticsLevel::Chunk);
+
+    assert!(matches!(
+        ParquetWriterBuilder::new()
+            .output_directory(dir.path())
+            .compression(ParquetCompression::Zstd(0))
+            .build(),
+        Err(Error::Config(_))
+    ));
+    assert!(matches!(
+        ParquetWriter::new(dir.path()).max_row_group_size(0).write(&rows),
+        Err(Error::Config(_))
+    ));
+}
+
+#[test]
+fn test_lookup_indexes_enable_bloom_filters() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use wpilog_parser::{ParquetWriter, WpilogReader};
+
+    let dir = tempdir().unwrap();
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/temperature", "double", "")
+        .double_record(1, 1_100_000, 25.5)
+        .double_record(1, 1_200_000, 26.5)
+        .build();
+
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    let output_dir = dir.path().join("output");
+    ParquetWriter::new(&output_dir)
+        .lookup_indexes(true)
+        .write(&rows)
+        .unwrap();
+
+    let file = File::open(output_dir.join("file_part000.parquet")).unwrap();
+    let reader = SerializedFileReader::new(file).unwrap();
+    let row_group = reader.metadata().row_group(0);
+
+    let column = |name: &str| {
+        row_group
+            .columns()
+            .iter()
+            .find(|c| c.column_path().string() == name)
+            .unwrap()
+    };
+    assert!(column("type").bloom_filter_offset().is_some());
+    assert!(column("entry").bloom_filter_offset().is_some());
+    assert!(column("timestamp").column_index_offset().is_some());
+}
+
+#[test]
+fn test_parquet_round_trip() {
+    use wpilog_parser::{FromParquet, ParquetWriter, Records, WpilogReader};
+
+    let dir = tempdir().unwrap();
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/temperature", "double", "")
+        .start_record(1_000_000, 2, "/counts", "int64[]", "")
+        .start_record(1_000_000, 3, "/enabled", "boolean", "")
+        .start_record(1_000_000, 4, "/mode", "string", "")
+        .double_record(1, 1_100_000, 25.5)
+        .int64_array_record(2, 1_100_000, &[1, 2, 3])
+        .boolean_record(3, 1_200_000, true)
+        .string_record(4, 1_300_000, "auto")
+        .build();
+
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    let output_dir = dir.path().join("output");
+    ParquetWriter::new(&output_dir)
+        .chunk_size(2)
+        .write(&rows)
+        .unwrap();
+
+    let imported = Records::from_parquet(&output_dir).unwrap();
+
+    assert_eq!(imported.len(), rows.len());
+    for (original, read_back) in rows.iter().zip(&imported) {
+        assert_eq!(original.timestamp, read_back.timestamp);
+        assert_eq!(original.entry, read_back.entry);
+        assert_eq!(original.type_name, read_back.type_name);
+        assert_eq!(original.loop_count, read_back.loop_count);
+        assert_eq!(original.data, read_back.data);
+    }
+}
+
+#[test]
+fn test_parquet_import_empty_directory() {
+    use wpilog_parser::{FromParquet, Records};
+
+    let dir = tempdir().unwrap();
+    assert!(Records::from_parquet(dir.path()).is_err());
+}
+
+#[test]
+fn test_parquet_import_rejects_foreign_columns() {
+    use arrow::array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray, UInt32Array};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+    use wpilog_parser::formats::parquet::read_parquet_directory;
+    use wpilog_parser::Error;
+
+    let write = |timestamp: ArrayRef, loop_count: i64| {
+        let dir = tempdir().unwrap();
+        let batch = RecordBatch::try_from_iter([
+            ("timestamp", timestamp),
+            ("entry", Arc::new(UInt32Array::from(vec![1])) as ArrayRef),
+            ("type", Arc::new(StringArray::from(vec!["double"])) as ArrayRef),
+            ("loop_count", Arc::new(Int64Array::from(vec![loop_count])) as ArrayRef),
+        ])
+        .unwrap();
+        let file = File::create(dir.path().join("file_part000.parquet")).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        dir
+    };
+
+    let seconds: ArrayRef = Arc::new(Float64Array::from(vec![1.0]));
+    assert_eq!(read_parquet_directory(write(seconds.clone(), 3).path()).unwrap()[0].loop_count, 3);
+
+    // Right names, wrong types or values
+    let text: ArrayRef = Arc::new(StringArray::from(vec!["1.0"]));
+    assert!(matches!(read_parquet_directory(write(text, 3).path()), Err(Error::SchemaError(_))));
+    assert!(matches!(read_parquet_directory(write(seconds, -1).path()), Err(Error::SchemaError(_))));
+}
+
+#[test]
+fn test_parallel_chunk_writing() {
+    use wpilog_parser::{FromParquet, ParquetWriter, Records, WpilogReader};
+
+    let dir = tempdir().unwrap();
+
+    let mut builder = WpilogBuilder::new().start_record(1_000_000, 1, "/value", "double", "");
+    for i in 0..50 {
+        builder = builder.double_record(1, 1_000_000 + i * 20_000, i as f64);
+    }
+    let rows = WpilogReader::from_bytes(builder.build()).unwrap().read_all().unwrap();
+
+    let output_dir = dir.path().join("output");
+    let stats = ParquetWriter::new(&output_dir)
+        .chunk_size(7)
+        .threads(4)
+        .write_with_stats(&rows)
+        .unwrap();
+
+    assert_eq!(stats.num_chunks, 8);
+    assert!(output_dir.join("file_part007.parquet").exists());
+
+    let imported = Records::from_parquet(&output_dir).unwrap();
+    let timestamps: Vec<f64> = imported.iter().map(|r| r.timestamp).collect();
+    let expected: Vec<f64> = rows.iter().map(|r| r.timestamp).collect();
+    assert_eq!(timestamps, expected);
+}
+
+#[test]
+fn test_memory_and_closure_sinks() {
+    use std::sync::{Arc, Mutex};
+    use wpilog_parser::{MemorySink, ParquetWriter, WpilogReader};
+
+    let dir = tempdir().unwrap();
+
+    let mut builder = WpilogBuilder::new().start_record(1_000_000, 1, "/value", "double", "");
+    for i in 0..20 {
+        builder = builder.double_record(1, 1_000_000 + i * 20_000, i as f64);
+    }
+    let rows = WpilogReader::from_bytes(builder.build()).unwrap().read_all().unwrap();
+
+    let output_dir = dir.path().join("output");
+    ParquetWriter::new(&output_dir).chunk_size(8).write(&rows).unwrap();
+
+    let sink = MemorySink::new();
+    ParquetWriter::to_sink(sink.clone())
+        .chunk_size(8)
+        .threads(2)
+        .write(&rows)
+        .unwrap();
+    assert_eq!(
+        sink.names(),
+        vec!["file_part000.parquet", "file_part001.parquet", "file_part002.parquet"]
+    );
+    for (name, bytes) in sink.files() {
+        assert_eq!(bytes, std::fs::read(output_dir.join(&name)).unwrap(), "{}", name);
+    }
+
+    let opened = Arc::new(Mutex::new(Vec::new()));
+    let names = opened.clone();
+    ParquetWriter::to_sink(move |name: &str| {
+        names.lock().unwrap().push(name.to_string());
+        Ok(std::io::sink())
+    })
+    .chunk_size(8)
+    .write(&rows)
+    .unwrap();
+    assert_eq!(opened.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn test_size_targeted_chunking() {
+    use wpilog_parser::{ParquetWriter, WpilogReader};
+
+    let dir = tempdir().unwrap();
+
+    // 10 rows with 100-element arrays followed by 10 boolean rows
+    let mut builder = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/big", "double[]", "")
+        .start_record(1_000_000, 2, "/flag", "boolean", "");
+    for i in 0..10 {
+        builder = builder.double_array_record(1, 1_000_000 + i * 1_000, &[1.0; 100]);
+    }
+    for i in 0..10 {
+        builder = builder.boolean_record(2, 2_000_000 + i * 1_000, i % 2 == 0);
+    }
+    let rows = WpilogReader::from_bytes(builder.build()).unwrap().read_all().unwrap();
+
+    let stats = ParquetWriter::new(dir.path())
+        .target_file_size(2_000)
+        .write_with_stats(&rows)
+        .unwrap();
+
+    // Each array row is ~800 bytes, so array rows are split every 3 rows while
+    // all boolean rows fit in the last file
+    assert_eq!(stats.num_records, 20);
+    assert_eq!(stats.num_chunks, 4);
+    assert_eq!(stats.chunk_size, 11);
+}
+
+#[test]
+fn test_max_bytes_in_flight_splits_wide_chunks() {
+    use wpilog_parser::formats::parquet::read_parquet_directory;
+    use wpilog_parser::{ParquetWriter, WpilogReader};
+
+    let dir = tempdir().unwrap();
+
+    // 10 array entries with one row each: every row adds a column, so the
+    // null slots grow with the square of the chunk's row count
+    let mut builder = WpilogBuilder::new();
+    for i in 0..10 {
+        builder = builder.start_record(1_000_000, i + 1, &format!("/arm{}", i), "double[]", "");
+    }
+    for i in 0..10 {
+        builder = builder.double_array_record(i + 1, 1_100_000 + i as u64 * 1_000, &[0.5; 10]);
+    }
+    let rows = WpilogReader::from_bytes(builder.build()).unwrap().read_all().unwrap();
+
+    let formatter = ParquetFormatter::new(String::new(), 50_000).with_max_bytes_in_flight(1_000);
+    let sizes: Vec<usize> = formatter.plan_chunks(&rows).iter().map(|chunk| chunk.len()).collect();
+    // ~112 bytes of values per row plus 8 bytes per row per column:
+    // 6 rows need ~960 bytes, 7 would need ~1,176
+    assert_eq!(sizes, vec![6, 4]);
+
+    let stats = ParquetWriter::new(dir.path())
+        .max_bytes_in_flight(1_000)
+        .write_with_stats(&rows)
+        .unwrap();
+    assert_eq!(stats.num_chunks, 2);
+    assert_eq!(stats.chunk_size, 6);
+    assert_eq!(read_parquet_directory(dir.path()).unwrap().len(), 10);
+}
+
+#[test]
+fn test_long_format_value_columns() {
+    use arrow::array::{Array, AsArray, Float64Array};
+    use arrow::datatypes::{DataType, Int32Type};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::collections::HashMap;
+    use wpilog_parser::datalog::DataLogReader;
+    use wpilog_parser::formats::long::long_schema;
+    use wpilog_parser::formatter::ParseContext;
+
+    let dir = tempdir().unwrap();
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/temperature", "double", "")
+        .start_record(1_000_000, 2, "/counts", "int64[]", "")
+        .start_record(1_000_000, 3, "/enabled", "boolean", "")
+        .double_record(1, 1_100_000, 25.5)
+        .int64_array_record(2, 1_100_000, &[1, 2, 3])
+        .boolean_record(3, 1_200_000, true)
+        .double_record(1, 1_300_000, 26.0)
+        .build();
+
+    let formatter = Formatter::new(String::new(), String::new(), OutputFormat::Long);
+    let mut context = ParseContext::default();
+    let mut entries = HashMap::new();
+    let mut rows = Vec::new();
+    for record in DataLogReader::new(&data).records().unwrap() {
+        let record = record.unwrap();
+        if record.is_start() {
+            let start = record.get_start_data().unwrap();
+            entries.insert(start.entry, start);
+        } else {
+            rows.push(formatter.parse_record_long(&record, &entries[&record.entry], &mut context).unwrap());
+        }
+    }
+
+    let output_dir = dir.path().join("output");
+    ParquetFormatter::new(output_dir.to_str().unwrap().to_string(), 3)
+        .convert_long(&rows)
+        .unwrap();
+
+    let file = File::open(output_dir.join("file_part000.parquet")).unwrap();
+    let batch = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert!(output_dir.join("file_part001.parquet").exists());
+
+    let schema = batch.schema();
+    for field in long_schema().fields() {
+        assert_eq!(schema.field_with_name(field.name()).unwrap().data_type(), field.data_type());
+    }
+    assert!(matches!(
+        schema.field_with_name("entry_name").unwrap().data_type(),
+        DataType::Dictionary(_, _)
+    ));
+
+    let names = batch.column_by_name("entry_name").unwrap().as_dictionary::<Int32Type>();
+    let name_values = names.values().as_string::<i32>();
+    assert_eq!(name_values.value(names.keys().value(1) as usize), "/counts");
+
+    let doubles = batch
+        .column_by_name("value_double")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert_eq!(doubles.value(0), 25.5);
+    assert!(doubles.is_null(1));
+
+    let arrays = batch.column_by_name("value_int64_array").unwrap().as_list::<i32>();
+    assert!(arrays.is_null(0));
+    assert_eq!(arrays.value(1).len(), 3);
+    assert!(batch.column_by_name("value_bool").unwrap().is_valid(2));
+}
+
+#[test]
+fn test_read_all_long_and_write_long() {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::Float64Type;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use wpilog_parser::{FilterProfile, ParquetWriter, WpilogReader, WpilogReaderBuilder};
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/temperature", "double", "")
+        .start_record(1_000_000, 2, "/mode", "string", "")
+        .double_record(1, 1_100_000, 25.5)
+        .string_record(2, 1_100_000, "auto")
+        .double_record(1, 1_150_000, 26.0)
+        .double_record(1, 1_300_000, 26.5)
+        .build();
+
+    let rows = WpilogReader::from_bytes(data.clone()).unwrap().read_all_long().unwrap();
+    let names: Vec<_> = rows.iter().map(|row| row.entry_name.as_str()).collect();
+    assert_eq!(names, ["/temperature", "/mode", "/temperature", "/temperature"]);
+    assert_eq!(rows[1].type_name, "string");
+    assert_eq!(rows[1].value.as_ref().unwrap().string.as_deref(), Some("auto"));
+
+    // Reading long rows works whatever the configured output format
+    let long = WpilogReaderBuilder::new()
+        .output_format(OutputFormat::Long)
+        .from_bytes(data.clone())
+        .unwrap();
+    assert_eq!(long.read_all_long().unwrap().len(), 4);
+
+    // Profile downsampling limits each entry's rate
+    let profile = FilterProfile {
+        max_rate_hz: Some(10.0),
+        ..Default::default()
+    };
+    let downsampled = WpilogReaderBuilder::new()
+        .profile(profile)
+        .from_bytes(data)
+        .unwrap()
+        .read_all_long()
+        .unwrap();
+    let timestamps: Vec<_> = downsampled.iter().map(|row| row.timestamp).collect();
+    assert_eq!(timestamps, [1.1, 1.1, 1.3]);
+
+    let dir = tempdir().unwrap();
+    let stats = ParquetWriter::new(dir.path())
+        .chunk_size(3)
+        .write_long(&rows)
+        .unwrap();
+    assert_eq!((stats.num_records, stats.num_chunks, stats.chunk_size), (4, 2, 3));
+
+    let file = File::open(dir.path().join("file_part000.parquet")).unwrap();
+    let batch = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(batch.num_rows(), 3);
+    let doubles = batch.column_by_name("value_double").unwrap().as_primitive::<Float64Type>();
+    assert_eq!(doubles.value(0), 25.5);
+    assert!(doubles.is_null(1));
+    assert_eq!(batch.column_by_name("value_string").unwrap().as_string::<i32>().value(1), "auto");
+}
+
+#[test]
+fn test_path_segments_split() {
+    use wpilog_parser::formats::long::PathSegments;
+
+    let segments = PathSegments::subsystem_device_signal();
+    let split = |name: &str| segments.split(name);
+    let some = |s: &str| Some(s.to_string());
+
+    assert_eq!(split("/Drive/Left/Velocity"), vec![some("Drive"), some("Left"), some("Velocity")]);
+    assert_eq!(split("/Drive/Gyro"), vec![some("Drive"), None, some("Gyro")]);
+    assert_eq!(
+        split("/Drive/Module0/Steer/Angle"),
+        vec![some("Drive"), some("Module0/Steer"), some("Angle")]
+    );
+    assert_eq!(split("voltage"), vec![None, None, some("voltage")]);
+    assert_eq!(split("/"), vec![None, None, None]);
+
+    let leaf_only = PathSegments::new(["signal"]).unwrap();
+    assert_eq!(leaf_only.split("/Drive/Gyro"), vec![some("Gyro")]);
+    assert!(PathSegments::new(Vec::<String>::new()).is_err());
+}
+
+#[test]
+fn test_long_format_path_segment_columns() {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::Int32Type;
+    use wpilog_parser::formats::long::{long_record_batch_with, PathSegments};
+    use wpilog_parser::models::LongRow;
+
+    let rows: Vec<LongRow> = ["/Drive/Left/Velocity", "/Drive/Gyro", "/Drive/Left/Velocity"]
+        .into_iter()
+        .map(|name| {
+            let mut row = LongRow::new(1.0, 1, "double".to_string(), 0);
+            row.entry_name = name.to_string();
+            row
+        })
+        .collect();
+
+    let batch = long_record_batch_with(&rows, Some(&PathSegments::subsystem_device_signal())).unwrap();
+    let schema = batch.schema();
+    let names: Vec<&str> = schema.fields().iter().take(6).map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["timestamp", "entry", "entry_name", "subsystem", "device", "signal"]);
+
+    let column = |name: &str| {
+        let dict = batch.column_by_name(name).unwrap().as_dictionary::<Int32Type>();
+        let values = dict.values().as_string::<i32>();
+        (0..dict.len())
+            .map(|i| dict.is_valid(i).then(|| values.value(dict.keys().value(i) as usize).to_string()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(column("subsystem"), vec![Some("Drive".to_string()); 3]);
+    assert_eq!(column("device"), vec![Some("Left".to_string()), None, Some("Left".to_string())]);
+    assert_eq!(column("signal")[1].as_deref(), Some("Gyro"));
+}
+
+#[test]
+fn test_column_type_overrides() {
+    use arrow::datatypes::DataType;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use wpilog_parser::formats::parquet::read_parquet_directory;
+    use wpilog_parser::{ColumnType, ParquetWriter, WpilogReader};
+
+    let dir = tempdir().unwrap();
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Vision/Latency", "double", "")
+        .start_record(1_000_000, 2, "/Mode", "string", "")
+        .start_record(1_000_000, 3, "/Count", "int64", "")
+        .double_record(1, 1_100_000, 12.5)
+        .string_record(2, 1_200_000, "auto")
+        .string_record(2, 1_300_000, "teleop")
+        .string_record(2, 1_400_000, "auto")
+        .int64_record(3, 1_500_000, 7)
+        .build();
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    ParquetWriter::new(dir.path())
+        .column_type("/Vision/Latency", ColumnType::Float32)
+        .column_type("/Mode", ColumnType::Dictionary)
+        .column_type("/Count", "string".parse().unwrap())
+        .write(&rows)
+        .unwrap();
+
+    let file = File::open(dir.path().join("file_part000.parquet")).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+    let schema = reader.schema();
+    let data_type = |name: &str| schema.field_with_name(name).unwrap().data_type().clone();
+    assert_eq!(data_type("/Vision/Latency"), DataType::Float32);
+    assert!(matches!(data_type("/Mode"), DataType::Dictionary(_, _)));
+    assert_eq!(data_type("/Count"), DataType::Utf8);
+
+    let imported = read_parquet_directory(dir.path()).unwrap();
+    assert_eq!(imported[0].data["/Vision/Latency"], json!(12.5));
+    assert_eq!(imported[2].data["/Mode"], json!("teleop"));
+    assert_eq!(imported[3].data["/Mode"], json!("auto"));
+    assert_eq!(imported[4].data["/Count"], json!("7"));
+
+    assert!("decimal".parse::<ColumnType>().is_err());
+}
+
+#[test]
+fn test_oversized_string_columns_use_large_utf8() {
+    use arrow::datatypes::DataType;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use wpilog_parser::formats::parquet::read_parquet_directory;
+    use wpilog_parser::WpilogReader;
+
+    let dir = tempdir().unwrap();
+    let blob = format!("{{\"log\":\"{}\"}}", "x".repeat(64));
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Dump", "json", "")
+        .start_record(1_000_000, 2, "/Mode", "string", "")
+        .start_record(1_000_000, 3, "/Faults", "string[]", "")
+        .string_record(1, 1_100_000, &blob)
+        .string_record(2, 1_200_000, "auto")
+        .string_array_record(3, 1_300_000, &[&"y".repeat(40), &"z".repeat(40)])
+        .build();
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    ParquetFormatter::new(dir.path().to_str().unwrap().to_string(), 50_000)
+        .with_large_string_threshold(32)
+        .convert(&rows)
+        .unwrap();
+
+    let file = File::open(dir.path().join("file_part000.parquet")).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+    let schema = reader.schema();
+    let data_type = |name: &str| schema.field_with_name(name).unwrap().data_type().clone();
+    assert_eq!(data_type("/Dump"), DataType::LargeUtf8);
+    assert_eq!(data_type("/Mode"), DataType::Utf8);
+    match data_type("/Faults") {
+        DataType::List(item) => assert_eq!(item.data_type(), &DataType::LargeUtf8),
+        other => panic!("unexpected type {:?}", other),
+    }
+
+    let imported = read_parquet_directory(dir.path()).unwrap();
+    assert_eq!(imported[0].data["/Dump"], json!(blob));
+    assert_eq!(imported[1].data["/Mode"], json!("auto"));
+}
+
+#[test]
+fn test_column_order_options() {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use wpilog_parser::{ColumnOrder, ParquetWriter, WpilogReader};
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Zeta", "double", "")
+        .start_record(1_000_000, 2, "/Alpha", "int64", "")
+        .start_record(1_000_000, 3, "/Mid", "string", "")
+        .string_record(3, 1_100_000, "auto")
+        .int64_record(2, 1_200_000, 4)
+        .double_record(1, 1_300_000, 0.5)
+        .build();
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    let value_columns = |order: ColumnOrder| {
+        let dir = tempdir().unwrap();
+        ParquetWriter::new(dir.path()).column_order(order).write(&rows).unwrap();
+        let file = File::open(dir.path().join("file_part000.parquet")).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        reader
+            .schema()
+            .fields()
+            .iter()
+            .skip(4)
+            .map(|field| field.name().clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(value_columns(ColumnOrder::Alphabetical), ["/Alpha", "/Mid", "/Zeta"]);
+    assert_eq!(value_columns(ColumnOrder::Declaration), ["/Zeta", "/Alpha", "/Mid"]);
+    assert_eq!(
+        value_columns(ColumnOrder::Explicit(vec!["/Mid".to_string(), "/Missing".to_string()])),
+        ["/Mid", "/Alpha", "/Zeta"]
+    );
+
+    assert_eq!("declaration".parse::<ColumnOrder>().unwrap(), ColumnOrder::Declaration);
+    assert!("random".parse::<ColumnOrder>().is_err());
+}
+
+#[test]
+fn test_constant_columns() {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use wpilog_parser::formats::parquet::read_parquet_directory;
+    use wpilog_parser::{ParquetWriter, WpilogReader};
+
+    let dir = tempdir().unwrap();
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Speed", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .double_record(1, 1_200_000, 2.5)
+        .build();
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    ParquetWriter::new(dir.path())
+        .constant_column("event", "2025casj")
+        .constant_column("match", "Q41")
+        .constant_column("match", "Q42")
+        .write(&rows)
+        .unwrap();
+
+    let file = File::open(dir.path().join("file_part000.parquet")).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+    let names: Vec<_> = reader.schema().fields().iter().map(|f| f.name().clone()).collect();
+    assert_eq!(names, ["timestamp", "entry", "type", "loop_count", "event", "match", "/Speed"]);
+
+    let imported = read_parquet_directory(dir.path()).unwrap();
+    assert!(imported.iter().all(|row| row.data["match"] == json!("Q42")));
+    assert!(imported.iter().all(|row| row.data["event"] == json!("2025casj")));
+
+    // A constant column may not shadow a logged one
+    let err = ParquetWriter::new(dir.path().join("clash"))
+        .constant_column("/Speed", "0")
+        .write(&rows)
+        .unwrap_err();
+    assert!(err.to_string().contains("/Speed"));
+}
+
+#[test]
+fn test_arrow_converter_matches_parquet_schema() {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use wpilog_parser::{ArrowConverter, Error, ParquetWriter, WpilogReader};
+
+    let dir = tempdir().unwrap();
+    let mut builder = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .start_record(1_000_000, 2, "/mode", "string", "");
+    for i in 0..10 {
+        builder = builder
+            .double_record(1, 1_000_000 + i * 20_000, i as f64)
+            .string_record(2, 1_010_000 + i * 20_000, "auto");
+    }
+    let rows = WpilogReader::from_bytes(builder.build()).unwrap().read_all().unwrap();
+
+    let converter = ArrowConverter::new().constant_column("match", "Q1");
+    let batches = converter.to_record_batches(&rows, 8).unwrap();
+    assert_eq!(
+        batches.iter().map(|batch| batch.num_rows()).collect::<Vec<_>>(),
+        vec![8, 8, 4]
+    );
+
+    let output_dir = dir.path().join("output");
+    ParquetWriter::new(&output_dir)
+        .chunk_size(8)
+        .constant_column("match", "Q1")
+        .write(&rows)
+        .unwrap();
+    let file = File::open(output_dir.join("file_part000.parquet")).unwrap();
+    let written: Vec<_> = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(batches[0].schema().fields(), written[0].schema().fields());
+    assert_eq!(batches[0].column(1), written[0].column(1));
+
+    let single = converter.to_record_batch(&rows).unwrap();
+    assert_eq!(single.num_rows(), rows.len());
+    assert_eq!(converter.record_batches(&rows, 8).unwrap().count(), 3);
+    assert!(matches!(
+        converter.to_record_batches(&rows, 0),
+        Err(Error::Config(_))
+    ));
+}
+
+#[test]
+fn test_chunks_share_one_schema() {
+    use arrow::datatypes::DataType;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::path::Path;
+    use wpilog_parser::formats::parquet::read_parquet_schema;
+    use wpilog_parser::{Error, ParquetWriter, SpillStore, WpilogReader};
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Speed", "double", "")
+        .start_record(1_000_000, 2, "/Mode", "string", "")
+        .double_record(1, 1_100_000, 1.5)
+        .double_record(1, 1_200_000, 2.5)
+        .string_record(2, 1_300_000, "auto")
+        .string_record(2, 1_400_000, "teleop")
+        .build();
+    let rows = WpilogReader::from_bytes(data.clone()).unwrap().read_all().unwrap();
+
+    let schemas = |dir: &Path| {
+        (0..2)
+            .map(|i| read_parquet_schema(&dir.join(format!("file_part{:03}.parquet", i))).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    // Neither chunk has values for both columns
+    let dir = tempdir().unwrap();
+    ParquetWriter::new(dir.path()).chunk_size(2).write(&rows).unwrap();
+    let written = schemas(dir.path());
+    assert_eq!(written[0], written[1]);
+    assert_eq!(written[0].field_with_name("/Speed").unwrap().data_type(), &DataType::Float64);
+    assert_eq!(written[0].field_with_name("/Mode").unwrap().data_type(), &DataType::Utf8);
+    let file = File::open(dir.path().join("file_part000.parquet")).unwrap();
+    let batch = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(batch.column_by_name("/Mode").unwrap().null_count(), 2);
+
+    // Spilled rows are read twice to infer the same schema
+    let spill_dir = tempdir().unwrap();
+    let mut store = SpillStore::new().unwrap().batch_rows(1);
+    WpilogReader::from_bytes(data).unwrap().read_to_spill(&mut store).unwrap();
+    ParquetWriter::new(spill_dir.path()).chunk_size(2).write_spilled(&store).unwrap();
+    assert_eq!(schemas(spill_dir.path()), written);
+
+    // A supplied schema covers logs with fewer columns...
+    let speed_only: Vec<_> = rows.iter().filter(|row| row.data.contains_key("/Speed")).cloned().collect();
+    let other_dir = tempdir().unwrap();
+    ParquetWriter::new(other_dir.path())
+        .chunk_size(1)
+        .schema(written[0].clone())
+        .write(&speed_only)
+        .unwrap();
+    assert_eq!(schemas(other_dir.path()), written);
+
+    // ...but not ones with columns it lacks
+    let mut extra = rows.clone();
+    extra[0].data.insert("/Other".to_string(), serde_json::json!(1));
+    let result = ParquetWriter::new(tempdir().unwrap().path())
+        .schema(written[0].clone())
+        .write(&extra);
+    assert!(matches!(result, Err(Error::SchemaError(_))));
+}
+
+#[test]
+fn test_streamed_chunks_share_one_schema() {
+    use arrow::datatypes::DataType;
+    use wpilog_parser::formats::parquet::{chunk_file_name, read_parquet_schema};
+    use wpilog_parser::{ParquetWriter, WpilogReader};
+
+    // `/Mode` only starts in the second chunk
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Speed", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .double_record(1, 1_200_000, 2.5)
+        .start_record(1_300_000, 2, "/Mode", "string", "")
+        .string_record(2, 1_300_000, "auto")
+        .double_record(1, 1_400_000, 3.5)
+        .build();
+    let reader = WpilogReader::from_bytes(data).unwrap();
+    let schema = |dir: &tempfile::TempDir, index| read_parquet_schema(&dir.path().join(chunk_file_name(index))).unwrap();
+
+    let dir = tempdir().unwrap();
+    let stats = ParquetWriter::new(dir.path()).chunk_size(2).write_rows(reader.rows().unwrap()).unwrap();
+    assert_eq!(stats.num_chunks, 2);
+    assert_eq!(schema(&dir, 0), schema(&dir, 1));
+    assert_eq!(schema(&dir, 0).field_with_name("/Mode").unwrap().data_type(), &DataType::Utf8);
+
+    // Batches read once keep each column's type, adding new columns as they appear
+    let rows = reader.read_entries(&["/Speed", "/Mode"]).unwrap();
+    let batches_dir = tempdir().unwrap();
+    let formatter = ParquetFormatter::new(batches_dir.path().to_str().unwrap().to_string(), 2);
+    formatter.convert_batches(rows.chunks(2).map(|chunk| Ok(chunk.to_vec()))).unwrap();
+    let (first, second) = (schema(&batches_dir, 0), schema(&batches_dir, 1));
+    assert!(first.field_with_name("/Mode").is_err());
+    assert_eq!(&second.fields()[..first.fields().len()], &first.fields()[..]);
+}
+
+#[test]
+fn test_struct_array_list_of_struct_column() {
+    use arrow::datatypes::DataType;
+    use byteorder::{LittleEndian, WriteBytesExt};
+    use serde_json::json;
+    use wpilog_parser::formats::parquet::{read_parquet_directory, read_parquet_schema};
+    use wpilog_parser::{Error, ParquetWriter, WpilogReader};
+
+    let mut corners = Vec::new();
+    for value in [1.0, 2.0, 3.0, 4.0] {
+        corners.write_f64::<LittleEndian>(value).unwrap();
+    }
+    let builder = || {
+        WpilogBuilder::new()
+            .struct_schema_record(1_000_000, 1, "struct:Translation2d", "double x; double y")
+            .start_record(1_000_000, 2, "/Vision/Corners", "struct:Translation2d[]", "")
+    };
+    let data = builder()
+        .struct_array_record(2, 1_100_000, &[])
+        .struct_array_record(2, 1_200_000, &corners)
+        .build();
+
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+    let values: Vec<_> = rows
+        .iter()
+        .filter_map(|row| row.data.get("/Vision/Corners"))
+        .collect();
+    assert_eq!(values[0], &json!([]));
+    assert_eq!(values[1], &json!([{"x": 1.0, "y": 2.0}, {"x": 3.0, "y": 4.0}]));
+
+    // An empty array first does not decide the column type
+    let dir = tempdir().unwrap();
+    ParquetWriter::new(dir.path()).write(&rows).unwrap();
+    let schema = read_parquet_schema(&dir.path().join("file_part000.parquet")).unwrap();
+    let DataType::List(item) = schema.field_with_name("/Vision/Corners").unwrap().data_type() else {
+        panic!("not a list column");
+    };
+    assert!(matches!(item.data_type(), DataType::Struct(fields) if fields.len() == 2));
+    let read: Vec<_> = read_parquet_directory(dir.path())
+        .unwrap()
+        .into_iter()
+        .filter_map(|row| row.data.get("/Vision/Corners").cloned())
+        .collect();
+    assert_eq!(read, values.into_iter().cloned().collect::<Vec<_>>());
+
+    // Payloads must hold whole elements
+    let data = builder().struct_array_record(2, 1_100_000, &corners[..24]).build();
+    assert!(matches!(
+        WpilogReader::from_bytes(data).unwrap().read_all(),
+        Err(Error::ParseError(_))
+    ));
+}
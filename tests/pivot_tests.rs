@@ -0,0 +1,68 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{FillPolicy, Pivot, WpilogReader, WpilogReaderBuilder};
+
+fn sample_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(0, 1, "/speed", "double", "")
+        .start_record(0, 2, "/enabled", "boolean", "")
+        .double_record(1, 1_000_000, 1.5)
+        .boolean_record(2, 1_000_000, true)
+        .double_record(1, 2_000_000, 2.5)
+        .double_record(1, 4_000_000, 3.5)
+        .build()
+}
+
+#[test]
+fn test_pivot_merges_rows_by_timestamp() {
+    let records = WpilogReader::from_bytes(sample_log()).unwrap().read_all().unwrap();
+    let table = Pivot::new().apply(&records);
+
+    assert_eq!(table.len(), 3);
+    assert_eq!(table[0].timestamp, 1.0);
+    assert_eq!(table[0].data.len(), 2);
+    // Null fill leaves cells empty
+    assert!(!table[1].data.contains_key("/enabled"));
+}
+
+#[test]
+fn test_pivot_forward_fill_with_horizon() {
+    let records = WpilogReader::from_bytes(sample_log()).unwrap().read_all().unwrap();
+    let table = Pivot::new()
+        .fill(FillPolicy::ForwardFill)
+        .max_forward_fill(1.5)
+        .apply(&records);
+
+    assert_eq!(table[1].data.get("/enabled").unwrap().as_bool(), Some(true));
+    // Last /enabled sample is 3 s old at t=4, beyond the horizon
+    assert!(!table[2].data.contains_key("/enabled"));
+}
+
+#[test]
+fn test_pivot_per_entry_zero_fill() {
+    let table = WpilogReaderBuilder::new()
+        .pivot(
+            Pivot::new()
+                .fill(FillPolicy::ForwardFill)
+                .entry_fill("/enabled", FillPolicy::Zero),
+        )
+        .from_bytes(sample_log())
+        .unwrap()
+        .read_all()
+        .unwrap();
+
+    assert_eq!(table.len(), 3);
+    assert_eq!(table[1].data.get("/enabled").unwrap().as_bool(), Some(false));
+    assert_eq!(table[2].data.get("/speed").unwrap().as_f64(), Some(3.5));
+}
+
+#[test]
+fn test_pivot_rejected_when_streaming() {
+    let reader = WpilogReaderBuilder::new()
+        .pivot(Pivot::new())
+        .from_bytes(sample_log())
+        .unwrap();
+    assert!(matches!(reader.stream(|_| Ok(())), Err(wpilog_parser::Error::Config(_))));
+    assert!(matches!(reader.rows(), Err(wpilog_parser::Error::Config(_))));
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{PresenceChange, PresenceMatrix};
+
+fn log_with(entries: &[(&str, usize)]) -> Vec<u8> {
+    let mut builder = WpilogBuilder::new();
+    for (id, (name, _)) in entries.iter().enumerate() {
+        builder = builder.start_record(0, id as u32 + 1, name, "double", "");
+    }
+    for (id, (_, samples)) in entries.iter().enumerate() {
+        for i in 0..*samples {
+            builder = builder.double_record(id as u32 + 1, i as u64 * 1000, i as f64);
+        }
+    }
+    builder.build()
+}
+
+fn season() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let logs = [
+        ("FRC_20250301_120000.wpilog", log_with(&[("/Arm/Angle", 3), ("/Drive/Speed", 2)])),
+        ("FRC_20250302_120000.wpilog", log_with(&[("/Arm/Angle", 1), ("/Drive/Speed", 4), ("/Vision/Pose", 2)])),
+        // Started but never written: not present
+        ("FRC_20250303_120000.wpilog", log_with(&[("/Arm/Angle", 0), ("/Drive/Speed", 1), ("/Vision/Pose", 5)])),
+    ];
+    for (name, data) in logs {
+        std::fs::write(dir.path().join(name), data).unwrap();
+    }
+    std::fs::write(dir.path().join("notes.txt"), "not a log").unwrap();
+    dir
+}
+
+#[test]
+fn test_presence_matrix_counts() {
+    let dir = season();
+    let matrix = PresenceMatrix::from_directory(dir.path()).unwrap();
+
+    assert_eq!(
+        matrix.logs,
+        vec!["FRC_20250301_120000.wpilog", "FRC_20250302_120000.wpilog", "FRC_20250303_120000.wpilog"]
+    );
+    assert_eq!(matrix.entries, vec!["/Arm/Angle", "/Drive/Speed", "/Vision/Pose"]);
+    assert_eq!(matrix.row("/Arm/Angle"), Some(&[3, 1, 0][..]));
+    assert_eq!(matrix.row("/Vision/Pose"), Some(&[0, 2, 5][..]));
+    assert_eq!(matrix.row("/Missing"), None);
+    assert_eq!(matrix.common_entries(), vec!["/Drive/Speed"]);
+}
+
+#[test]
+fn test_presence_changes() {
+    let dir = season();
+    let matrix = PresenceMatrix::from_directory(dir.path()).unwrap();
+
+    assert_eq!(
+        matrix.changes(),
+        vec![
+            PresenceChange {
+                entry: "/Vision/Pose".to_string(),
+                log: "FRC_20250302_120000.wpilog".to_string(),
+                appeared: true,
+            },
+            PresenceChange {
+                entry: "/Arm/Angle".to_string(),
+                log: "FRC_20250303_120000.wpilog".to_string(),
+                appeared: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_presence_csv_and_unreadable_logs() {
+    let dir = season();
+    std::fs::write(dir.path().join("FRC_20250304_120000.wpilog"), b"garbage").unwrap();
+    let matrix = PresenceMatrix::from_directory(dir.path()).unwrap();
+    assert_eq!(matrix.logs.len(), 3);
+
+    let mut csv = Vec::new();
+    matrix.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "entry,FRC_20250301_120000.wpilog,FRC_20250302_120000.wpilog,FRC_20250303_120000.wpilog"
+    );
+    assert_eq!(lines[1], "/Arm/Angle,3,1,0");
+    assert_eq!(lines.len(), 4);
+}
+
+#[test]
+fn test_presence_rejects_missing_directory() {
+    assert!(PresenceMatrix::from_directory("/nonexistent/logs").is_err());
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::WpilogBuilder;
+use tempfile::tempdir;
+use wpilog_parser::{EntryPattern, EntrySelector, Error, OutputFormat, ProfileSet, WpilogReaderBuilder};
+
+fn sample_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(0, 1, "/Drive/Left/Velocity", "double", "")
+        .start_record(0, 2, "/Drive/Left/DebugCurrent", "double", "")
+        .start_record(0, 3, "/Vision/Tags", "int64", "")
+        .double_record(1, 1_000_000, 1.0)
+        .double_record(2, 1_000_000, 5.0)
+        .int64_record(3, 1_000_000, 2)
+        .double_record(1, 1_010_000, 1.1)
+        .double_record(1, 1_100_000, 1.2)
+        .double_record(1, 1_150_000, 1.3)
+        .double_record(1, 1_200_000, 1.4)
+        .build()
+}
+
+const PROFILES: &str = r#"{
+    "drive-analysis": {
+        "include": ["/Drive/*"],
+        "exclude": ["/Drive/*/Debug*"],
+        "max_rate_hz": 10
+    },
+    "vision-debug": { "include": ["/Vision/*"], "output_format": "long" }
+}"#;
+
+#[test]
+fn test_entry_selector_globs() {
+    let selector = EntrySelector::new(&["/Drive/*", "/Robot/Voltage"], &["*Debug*"]).unwrap();
+    assert!(selector.matches("/Drive/Left/Velocity"));
+    assert!(selector.matches("/Robot/Voltage"));
+    assert!(!selector.matches("/Robot/Voltage2"));
+    assert!(!selector.matches("/Drive/Left/DebugCurrent"));
+    assert!(!selector.matches("/Vision/Tags"));
+
+    let single = EntrySelector::new(&["/Arm/Joint?"], &[]).unwrap();
+    assert!(single.matches("/Arm/Joint1"));
+    assert!(!single.matches("/Arm/Joint12"));
+}
+
+#[test]
+fn test_profile_set_round_trip() {
+    let profiles = ProfileSet::from_json(PROFILES).unwrap();
+    assert_eq!(profiles.names().collect::<Vec<_>>(), vec!["drive-analysis", "vision-debug"]);
+    assert_eq!(
+        profiles.get("vision-debug").unwrap().output_format,
+        Some(OutputFormat::Long)
+    );
+    assert!(profiles.get("missing").is_err());
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("profiles.json");
+    profiles.save(&path).unwrap();
+    assert_eq!(ProfileSet::from_file(&path).unwrap(), profiles);
+
+    assert!(ProfileSet::from_json(r#"{"bad": {"max_rate_hz": 0}}"#).is_err());
+}
+
+#[test]
+fn test_read_with_profile_selects_and_downsamples() {
+    let profiles = ProfileSet::from_json(PROFILES).unwrap();
+    let reader = WpilogReaderBuilder::new()
+        .profile(profiles.get("drive-analysis").unwrap().clone())
+        .from_bytes(sample_log())
+        .unwrap();
+
+    let mut streamed = Vec::new();
+    reader
+        .stream(|row| {
+            streamed.push(row);
+            Ok(())
+        })
+        .unwrap();
+    let records = reader.read_all().unwrap();
+
+    // At 10 Hz, samples 10ms and 50ms after a kept one are dropped
+    let velocities: Vec<(f64, f64)> = records
+        .iter()
+        .map(|r| (r.timestamp, r.data["/Drive/Left/Velocity"].as_f64().unwrap()))
+        .collect();
+    assert_eq!(velocities, vec![(1.0, 1.0), (1.1, 1.2), (1.2, 1.4)]);
+    assert_eq!(streamed.len(), records.len());
+}
+
+#[test]
+fn test_entry_patterns() {
+    let selector = EntrySelector::from_patterns(
+        &[EntryPattern::Prefix("/Drive/".into()), EntryPattern::Regex("Tags$".into())],
+        &[EntryPattern::Regex("(?i)debug".into())],
+    )
+    .unwrap();
+    assert!(selector.matches("/Drive/Left/Velocity"));
+    assert!(selector.matches("/Vision/Tags"));
+    assert!(!selector.matches("/Drive/Left/DebugCurrent"));
+    assert!(!selector.matches("/Robot/Drive/Voltage"));
+
+    assert!(EntrySelector::from_patterns(&[EntryPattern::Regex("(".into())], &[]).is_err());
+}
+
+#[test]
+fn test_builder_include_exclude_patterns() {
+    let records = WpilogReaderBuilder::new()
+        .include_prefix("/Drive/")
+        .exclude_regex("Debug")
+        .from_bytes(sample_log())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(records.len(), 5);
+    assert!(records.iter().all(|r| r.data.keys().all(|k| k == "/Drive/Left/Velocity")));
+
+    // Builder patterns narrow a profile's selection further
+    let profiles = ProfileSet::from_json(PROFILES).unwrap();
+    let records = WpilogReaderBuilder::new()
+        .profile(profiles.get("vision-debug").unwrap().clone())
+        .output_format(OutputFormat::Wide)
+        .include_glob("/Drive/*")
+        .from_bytes(sample_log())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert!(records.is_empty());
+
+    let invalid = WpilogReaderBuilder::new().include_regex("[").from_bytes(sample_log());
+    assert!(matches!(invalid, Err(Error::Config(_))));
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use common::WpilogBuilder;
+use std::sync::mpsc;
+use wpilog_parser::{ProgressPhase, ProgressTracker, ProgressUpdate, WpilogReaderBuilder};
+
+#[test]
+fn test_read_reports_progress() {
+    let mut builder = WpilogBuilder::new().start_record(1_000_000, 1, "/value", "double", "");
+    for i in 0..500u64 {
+        builder = builder.double_record(1, 1_000_000 + i * 20_000, i as f64);
+    }
+    let data = builder.build();
+    let total_bytes = data.len() as u64;
+
+    let (sender, receiver) = mpsc::channel();
+    let records = WpilogReaderBuilder::new()
+        .progress(sender)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+
+    // The reader was consumed, so the channel is closed
+    let updates: Vec<ProgressUpdate> = receiver.iter().collect();
+
+    assert_eq!(updates.first(), Some(&ProgressUpdate::Started { total_bytes }));
+    match updates.last() {
+        Some(&ProgressUpdate::Complete {
+            records: completed,
+            entries,
+            bytes_processed,
+            elapsed_secs,
+            rows_per_sec,
+        }) => {
+            assert_eq!(completed, records.len() as u64);
+            assert_eq!(entries, 1);
+            assert_eq!(bytes_processed, total_bytes);
+            assert!(elapsed_secs > 0.0);
+            assert!((rows_per_sec * elapsed_secs - 500.0).abs() < 1e-6);
+        }
+        other => panic!("unexpected last update {:?}", other),
+    }
+
+    // Struct schemas are registered as they are read, so one pass suffices
+    let phases: Vec<usize> = updates
+        .iter()
+        .enumerate()
+        .filter(|(_, u)| matches!(u, ProgressUpdate::PhaseChanged { .. }))
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(phases.len(), 1);
+    assert_eq!(
+        updates[phases[0]],
+        ProgressUpdate::PhaseChanged { phase: ProgressPhase::DecodingRecords }
+    );
+
+    // The decoding pass knows how many rows it will emit
+    for update in &updates[phases[0]..] {
+        if let ProgressUpdate::Progress { total_records, .. } = update {
+            assert_eq!(*total_records, Some(500));
+        }
+    }
+
+    let decoding: Vec<f64> = updates[phases[0]..].iter().filter_map(|u| u.fraction()).collect();
+    assert!(decoding.len() > 10 && decoding.len() <= 102);
+    assert!(decoding.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn test_msgpack_sampling_reports_schema_pass() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .double_record(1, 1_000_000, 1.0)
+        .build();
+
+    let (sender, receiver) = mpsc::channel();
+    WpilogReaderBuilder::new()
+        .progress(sender)
+        .msgpack_columns(8)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+
+    let phases: Vec<ProgressUpdate> = receiver
+        .iter()
+        .filter(|u| matches!(u, ProgressUpdate::PhaseChanged { .. }))
+        .collect();
+    assert_eq!(
+        phases,
+        vec![
+            ProgressUpdate::PhaseChanged { phase: ProgressPhase::InferringSchema },
+            ProgressUpdate::PhaseChanged { phase: ProgressPhase::DecodingRecords },
+        ]
+    );
+}
+
+#[test]
+fn test_tracker_throttles_updates() {
+    let (sender, receiver) = mpsc::channel();
+    let mut tracker = ProgressTracker::new(sender, 1_000);
+
+    tracker.start();
+    for bytes in 1..=1_000 {
+        tracker.record(bytes);
+    }
+    tracker.complete();
+    drop(tracker);
+
+    let updates: Vec<ProgressUpdate> = receiver.iter().collect();
+    // Started + one per percent + Complete
+    assert_eq!(updates.len(), 102);
+    assert!(matches!(
+        updates[101],
+        ProgressUpdate::Complete {
+            records: 1_000,
+            entries: 0,
+            bytes_processed: 1_000,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_complete_telemetry_defaults_when_deserializing() {
+    let update: ProgressUpdate = serde_json::from_str(r#"{"event":"complete","records":5}"#).unwrap();
+    assert_eq!(
+        update,
+        ProgressUpdate::Complete {
+            records: 5,
+            entries: 0,
+            bytes_processed: 0,
+            elapsed_secs: 0.0,
+            rows_per_sec: 0.0,
+        }
+    );
+}
+
+#[test]
+fn test_progress_ignores_dropped_receiver() {
+    let (sender, receiver) = mpsc::channel();
+    drop(receiver);
+
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .double_record(1, 1_100_000, 1.0)
+        .build();
+
+    let records = WpilogReaderBuilder::new()
+        .progress(sender)
+        .from_bytes(data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(records.len(), 1);
+}
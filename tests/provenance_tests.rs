@@ -0,0 +1,55 @@
+mod common;
+
+use common::WpilogBuilder;
+use serde_json::json;
+use tempfile::tempdir;
+use wpilog_parser::formats::parquet::read_parquet_directory;
+use wpilog_parser::{ParquetWriter, Provenance, WpilogReader};
+
+#[test]
+fn test_provenance_hashes_source() {
+    let provenance = Provenance::of_bytes("abc.wpilog", b"abc");
+    assert_eq!(provenance.source_file, "abc.wpilog");
+    assert_eq!(
+        provenance.source_sha256,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+
+    // e.g. 2025-03-15T18:32:01Z
+    let at = provenance.converted_at.as_bytes();
+    assert_eq!(at.len(), 20);
+    assert_eq!((at[4], at[7], at[10], at[19]), (b'-', b'-', b'T', b'Z'));
+    assert!(provenance.converted_at.as_str() > "2025");
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("match.wpilog");
+    std::fs::write(&path, b"abc").unwrap();
+    let from_file = Provenance::of_file(&path).unwrap();
+    assert_eq!(from_file.source_file, "match.wpilog");
+    assert_eq!(from_file.source_sha256, provenance.source_sha256);
+}
+
+#[test]
+fn test_writer_stamps_provenance_columns() {
+    let dir = tempdir().unwrap();
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Speed", "double", "")
+        .double_record(1, 1_100_000, 1.5)
+        .double_record(1, 1_200_000, 2.5)
+        .build();
+    let provenance = Provenance::of_bytes("match.wpilog", &data);
+    let rows = WpilogReader::from_bytes(data).unwrap().read_all().unwrap();
+
+    ParquetWriter::new(dir.path())
+        .provenance(&provenance)
+        .write(&rows)
+        .unwrap();
+
+    let imported = read_parquet_directory(dir.path()).unwrap();
+    assert_eq!(imported.len(), 2);
+    for row in &imported {
+        assert_eq!(row.data["source_file"], json!("match.wpilog"));
+        assert_eq!(row.data["source_sha256"], json!(provenance.source_sha256));
+        assert_eq!(row.data["converted_at"], json!(provenance.converted_at));
+    }
+}
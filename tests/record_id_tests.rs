@@ -0,0 +1,62 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{RecordId, WpilogReader, WpilogReaderBuilder};
+
+fn sample_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .start_record(1_000_000, 2, "/mode", "string", "")
+        .double_record(1, 1_100_000, 1.5)
+        .string_record(2, 1_200_000, "auto")
+        .double_record(1, 1_300_000, 2.5)
+        .build()
+}
+
+#[test]
+fn test_record_ids_trace_rows_to_records() {
+    let rows = WpilogReaderBuilder::new()
+        .record_ids(true)
+        .from_bytes(sample_log())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+
+    let ids: Vec<RecordId> = rows
+        .iter()
+        .map(|row| row.data["record_id"].as_str().unwrap().parse().unwrap())
+        .collect();
+    // Deterministic and ordered by position in the log
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    let again = WpilogReaderBuilder::new()
+        .record_ids(true)
+        .from_bytes(sample_log())
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(again[2].data["record_id"], rows[2].data["record_id"]);
+
+    let record = WpilogReader::from_bytes(sample_log()).unwrap().record_at(&ids[1]).unwrap();
+    assert_eq!(record.entry, 2);
+    assert_eq!(record.timestamp, 1_200_000);
+    assert_eq!(record.get_string().unwrap(), "auto");
+}
+
+#[test]
+fn test_record_id_round_trips_and_rejects_other_logs() {
+    let id = RecordId::new([0x3f, 0x1a, 0x9c, 0x0d, 0xe4, 0xb2, 0x77, 0x56], 10_482);
+    assert_eq!(id.to_string(), "3f1a9c0de4b27756:10482");
+    assert_eq!("3f1a9c0de4b27756:10482".parse::<RecordId>().unwrap(), id);
+    assert!("3f1a9c:10".parse::<RecordId>().is_err());
+    assert!("3f1a9c0de4b27756".parse::<RecordId>().is_err());
+
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    assert!(reader.record_at(&id).is_err());
+    let past_end = RecordId::new(reader.log_hash(), 1 << 20);
+    assert!(reader.record_at(&past_end).is_err());
+
+    // Rows have no ID column unless asked for
+    let rows = reader.read_all().unwrap();
+    assert!(!rows[0].data.contains_key("record_id"));
+}
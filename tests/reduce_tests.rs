@@ -0,0 +1,162 @@
+mod common;
+
+use common::WpilogBuilder;
+use tempfile::tempdir;
+use wpilog_parser::{
+    ChangeFilter, FromParquet, ParquetWriter, Records, Subsample, WpilogReader, WpilogReaderBuilder,
+};
+
+fn flag_log() -> Records {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "/enabled", "boolean", "")
+        .start_record(0, 2, "/speed", "double", "")
+        .boolean_record(1, 1_000_000, false)
+        .boolean_record(1, 2_000_000, false)
+        .double_record(2, 2_500_000, 1.0)
+        .boolean_record(1, 3_000_000, true)
+        .boolean_record(1, 4_000_000, true)
+        .boolean_record(1, 5_000_000, true)
+        .double_record(2, 5_500_000, 1.0)
+        .build();
+
+    WpilogReader::from_bytes(data).unwrap().read_all().unwrap()
+}
+
+#[test]
+fn test_change_filter_keeps_transitions_and_endpoints() {
+    let records = flag_log();
+    let filtered = ChangeFilter::new().booleans(true).apply(&records);
+
+    let flags: Vec<(f64, bool)> = filtered
+        .iter()
+        .filter_map(|r| r.data.get("/enabled").map(|v| (r.timestamp, v.as_bool().unwrap())))
+        .collect();
+    assert_eq!(flags, vec![(1.0, false), (3.0, true), (5.0, true)]);
+
+    // Non-boolean entries are untouched
+    let speeds = filtered.iter().filter(|r| r.data.contains_key("/speed")).count();
+    assert_eq!(speeds, 2);
+}
+
+#[test]
+fn test_change_filter_named_entry() {
+    let records = flag_log();
+    let filtered = ChangeFilter::new().entry("/speed").apply(&records);
+
+    // Booleans untouched; both /speed samples are endpoints so both are kept
+    assert_eq!(filtered.len(), records.len());
+
+    let filtered = ChangeFilter::new().apply(&records);
+    assert_eq!(filtered.len(), records.len());
+}
+
+#[test]
+fn test_change_filter_applied_on_export() {
+    let dir = tempdir().unwrap();
+    let records = flag_log();
+
+    let stats = ParquetWriter::new(dir.path())
+        .change_filter(ChangeFilter::new().booleans(true))
+        .write_with_stats(&records)
+        .unwrap();
+
+    assert_eq!(stats.num_records, 5);
+    assert_eq!(Records::from_parquet(dir.path()).unwrap().len(), 5);
+}
+
+fn speed_log() -> Records {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "/speed", "double", "")
+        .start_record(0, 2, "/count", "int64", "")
+        .double_record(1, 1_000_000, 1.00)
+        .double_record(1, 2_000_000, 1.02)
+        .double_record(1, 3_000_000, 1.04)
+        .double_record(1, 4_000_000, 1.20)
+        .double_record(1, 5_000_000, 1.21)
+        .int64_record(2, 5_000_000, 3)
+        .int64_record(2, 6_000_000, 3)
+        .int64_record(2, 7_000_000, 4)
+        .int64_record(2, 8_000_000, 4)
+        .build();
+
+    WpilogReader::from_bytes(data).unwrap().read_all().unwrap()
+}
+
+fn samples(records: &[wpilog_parser::WideRow], column: &str) -> Vec<f64> {
+    records
+        .iter()
+        .filter_map(|r| r.data.get(column).and_then(|v| v.as_f64()))
+        .collect()
+}
+
+#[test]
+fn test_numeric_epsilon_drops_small_changes() {
+    let records = speed_log();
+    let filtered = ChangeFilter::new().numeric_epsilon(0.1).apply(&records);
+
+    // 1.02 and 1.04 are within 0.1 of 1.00; 1.21 is the endpoint
+    assert_eq!(samples(&filtered, "/speed"), vec![1.00, 1.20, 1.21]);
+    // Integers: repeated values dropped, endpoint kept
+    assert_eq!(samples(&filtered, "/count"), vec![3.0, 4.0, 4.0]);
+}
+
+#[test]
+fn test_entry_epsilon_overrides_default() {
+    let records = speed_log();
+    let filtered = ChangeFilter::new()
+        .numeric_epsilon(0.5)
+        .entry_epsilon("/count", 0.0)
+        .apply(&records);
+
+    assert_eq!(samples(&filtered, "/speed"), vec![1.00, 1.21]);
+    // Epsilon 0 still drops identical values
+    assert_eq!(samples(&filtered, "/count"), vec![3.0, 4.0, 4.0]);
+}
+
+fn counter_log() -> WpilogBuilder {
+    let mut builder = WpilogBuilder::new()
+        .start_record(0, 1, "/fast", "int64", "")
+        .start_record(0, 2, "/slow", "int64", "");
+    for i in 0..10 {
+        builder = builder.int64_record(1, 1_000_000 * (i + 1), i as i64);
+    }
+    builder.int64_record(2, 20_000_000, 7)
+}
+
+#[test]
+fn test_subsample_keeps_every_nth_record_per_entry() {
+    let records = WpilogReaderBuilder::new()
+        .subsample(Subsample::every_nth(4))
+        .from_bytes(counter_log().build())
+        .unwrap()
+        .read_all()
+        .unwrap();
+
+    assert_eq!(samples(&records, "/fast"), vec![0.0, 4.0, 8.0]);
+    // The first record of every entry is kept
+    assert_eq!(samples(&records, "/slow"), vec![7.0]);
+}
+
+#[test]
+fn test_subsample_parses_percentages_and_intervals() {
+    assert_eq!("1%".parse::<Subsample>().unwrap().every(), 100);
+    assert_eq!("25 %".parse::<Subsample>().unwrap().every(), 4);
+    assert_eq!("50".parse::<Subsample>().unwrap().every(), 50);
+    assert!("0".parse::<Subsample>().is_err());
+    assert!("0%".parse::<Subsample>().is_err());
+    assert!("150%".parse::<Subsample>().is_err());
+    assert!("often".parse::<Subsample>().is_err());
+}
+
+#[test]
+fn test_change_filter_rejected_for_streamed_rows() {
+    let dir = tempdir().unwrap();
+    let result = ParquetWriter::new(dir.path())
+        .change_filter(ChangeFilter::new().booleans(true))
+        .write_rows(flag_log().into_iter().map(Ok));
+    assert!(matches!(result, Err(wpilog_parser::Error::Config(_))));
+    assert!(matches!(
+        wpilog_parser::ParquetWriterBuilder::new().build(),
+        Err(wpilog_parser::Error::Config(_))
+    ));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::WpilogBuilder;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use wpilog_parser::{Replayer, WpilogReader};
+
+fn sample_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .double_record(1, 1_000_000, 1.0)
+        .double_record(1, 1_500_000, 2.0)
+        .double_record(1, 2_000_000, 3.0)
+        .build()
+}
+
+#[test]
+fn test_replay_paces_rows_by_timestamp() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let started = Instant::now();
+    let mut arrivals = Vec::new();
+
+    Replayer::new(&reader)
+        .speed(20.0)
+        .run(|row| {
+            arrivals.push((row.timestamp, started.elapsed()));
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(arrivals.len(), 3);
+    // One second of log at 20x takes 50ms
+    assert!(arrivals[1].1 >= Duration::from_millis(25));
+    assert!(arrivals[2].1 >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_replay_to_channel_stops_when_receiver_dropped() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let (tx, rx) = mpsc::channel();
+    drop(rx);
+
+    Replayer::new(&reader)
+        .speed(f64::INFINITY)
+        .run_to_channel(tx)
+        .unwrap();
+}
+
+#[test]
+fn test_replay_rejects_non_positive_speed() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    assert!(Replayer::new(&reader).speed(0.0).run(|_| Ok(())).is_err());
+}
+
+#[test]
+fn test_replay_visit_passes_raw_records() {
+    use wpilog_parser::datalog::{RecordVisitor, StartRecordData};
+    use wpilog_parser::Result;
+
+    #[derive(Default)]
+    struct Collect(Vec<(String, u64, Vec<u8>)>);
+
+    impl RecordVisitor for Collect {
+        fn on_data(&mut self, entry: &StartRecordData, timestamp: u64, payload: &[u8]) -> Result<()> {
+            self.0.push((entry.name.clone(), timestamp, payload.to_vec()));
+            Ok(())
+        }
+    }
+
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let mut collect = Collect::default();
+    Replayer::new(&reader)
+        .speed(f64::INFINITY)
+        .visit(&mut collect)
+        .unwrap();
+
+    assert_eq!(collect.0.len(), 3);
+    assert_eq!(collect.0[2], ("/a".to_string(), 2_000_000, 3.0f64.to_le_bytes().to_vec()));
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::datalog::DataLogReader;
+use wpilog_parser::{LogRewriter, WpilogReader};
+
+fn sample_log() -> Vec<u8> {
+    WpilogBuilder::with_header(0x0100, "robot")
+        .start_record(1_000_000, 5, "/a", "double", "")
+        .start_record(1_000_000, 40, "/b", "string", "")
+        .start_record(1_000_000, 300, "/c", "int64", "")
+        .set_metadata_record(1_050_000, 300, "units=ticks")
+        .double_record(5, 1_100_000, 1.5)
+        .string_record(40, 1_150_000, "hello")
+        .int64_record(300, 2_000_000, 7)
+        .double_record(5, 2_100_000, 2.5)
+        .finish_record(2_200_000, 300)
+        .build()
+}
+
+#[test]
+fn test_rewrite_keeps_everything_with_dense_ids() {
+    let data = sample_log();
+    let result = LogRewriter::new()
+        .rewrite(&DataLogReader::new(&data))
+        .unwrap();
+
+    assert_eq!(result.records, 4);
+    assert_eq!(
+        result.entry_remap.into_iter().collect::<Vec<_>>(),
+        vec![(5, 1), (40, 2), (300, 3)]
+    );
+    assert!(result.dropped_entries.is_empty());
+
+    let original = WpilogReader::from_bytes(data).unwrap();
+    let rewritten = WpilogReader::from_bytes(result.data).unwrap();
+    assert_eq!(rewritten.extra_header(), "robot");
+
+    let catalog = rewritten.catalog().unwrap();
+    let c = catalog.generations("/c").next().unwrap();
+    assert_eq!(c.entry, 3);
+    assert_eq!(c.metadata, "units=ticks");
+    assert_eq!(c.finish_timestamp, Some(2_200_000));
+
+    let before: Vec<_> = original.read_all().unwrap().into_iter().map(|r| r.data).collect();
+    let after: Vec<_> = rewritten.read_all().unwrap().into_iter().map(|r| r.data).collect();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn test_rewrite_time_range_drops_empty_entries() {
+    let data = sample_log();
+    let result = LogRewriter::new()
+        .time_range(2_000_000, 3_000_000)
+        .rewrite(&DataLogReader::new(&data))
+        .unwrap();
+
+    assert_eq!(result.records, 2);
+    assert_eq!(
+        result.entry_remap.into_iter().collect::<Vec<_>>(),
+        vec![(5, 1), (300, 2)]
+    );
+    assert_eq!(result.dropped_entries, vec![40]);
+
+    let rows = WpilogReader::from_bytes(result.data)
+        .unwrap()
+        .read_all()
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].data.get("/c").unwrap().as_i64().unwrap(), 7);
+    assert_eq!(rows[1].data.get("/a").unwrap().as_f64().unwrap(), 2.5);
+}
+
+#[test]
+fn test_rewrite_entry_filter() {
+    let data = sample_log();
+    let result = LogRewriter::new()
+        .entry("/b")
+        .rewrite(&DataLogReader::new(&data))
+        .unwrap();
+
+    assert_eq!(result.records, 1);
+    assert_eq!(result.entry_remap.get(&40), Some(&1));
+    assert_eq!(result.dropped_entries, vec![5, 300]);
+
+    let catalog = WpilogReader::from_bytes(result.data)
+        .unwrap()
+        .catalog()
+        .unwrap();
+    assert_eq!(catalog.lifetimes().len(), 1);
+}
+
+#[test]
+fn test_rewrite_checksums_detect_tampering() {
+    use wpilog_parser::{verify_checksums, Records};
+
+    let data = sample_log();
+    let result = LogRewriter::new()
+        .checksums(2)
+        .rewrite(&DataLogReader::new(&data))
+        .unwrap();
+
+    let report = verify_checksums(&DataLogReader::new(&result.data)).unwrap();
+    assert!(report.is_ok(), "{:?}", report);
+    // Checksum Start + 9 records in blocks of 2
+    assert_eq!(report.verified_blocks, 5);
+
+    let rewritten = WpilogReader::from_bytes(result.data.clone()).unwrap();
+    assert_eq!(rewritten.extra_header(), "robot\nwpilog-checksums=crc32");
+    let rows: Records = rewritten.read_all().unwrap();
+    assert_eq!(rows.iter().filter(|row| row.data.contains_key("/.checksum")).count(), 5);
+
+    // Flip the last byte of the final double (2.5)
+    let mut tampered = result.data.clone();
+    let value = tampered
+        .windows(8)
+        .rposition(|bytes| bytes == 2.5f64.to_le_bytes())
+        .unwrap();
+    tampered[value + 7] ^= 0x01;
+    let report = verify_checksums(&DataLogReader::new(&tampered)).unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.verified_blocks, 4);
+    assert_eq!(report.mismatches.len(), 1);
+
+    let plain = verify_checksums(&DataLogReader::new(&data)).unwrap();
+    assert!(!plain.declared);
+    assert!(!plain.is_ok());
+}
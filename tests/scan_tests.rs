@@ -0,0 +1,119 @@
+mod common;
+
+use arrow::array::{Array, Float64Array};
+use common::WpilogBuilder;
+use std::time::Duration;
+use wpilog_parser::{WpilogReader, WpilogReaderBuilder};
+
+fn sample_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Drive/Left", "double", "")
+        .start_record(1_000_000, 2, "/Drive/Right", "double", "")
+        .start_record(1_000_000, 3, "/Arm/Angle", "double", "")
+        .double_record(1, 1_100_000, 1.0)
+        .double_record(2, 1_100_000, 2.0)
+        .double_record(3, 1_100_000, 3.0)
+        .double_record(1, 2_100_000, 4.0)
+        .double_record(2, 2_100_000, 5.0)
+        .double_record(1, 3_100_000, 6.0)
+        .build()
+}
+
+#[test]
+fn test_scan_select_and_filter_time() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let batch = reader
+        .scan()
+        .select(["/Drive/*"])
+        .filter_time(2_000_000..3_100_000)
+        .to_arrow()
+        .unwrap();
+
+    assert_eq!(batch.num_rows(), 2);
+    let schema = batch.schema();
+    let columns: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(
+        columns,
+        vec!["timestamp", "entry", "type", "loop_count", "/Drive/Left", "/Drive/Right"]
+    );
+
+    let left = batch
+        .column_by_name("/Drive/Left")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert_eq!(left.value(0), 4.0);
+    assert!(left.is_null(1));
+
+    // Inclusive end bound
+    let rows = reader
+        .scan()
+        .select(["/Drive/Left"])
+        .filter_time(2_100_000..=3_100_000)
+        .to_rows()
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+}
+
+#[test]
+fn test_scan_without_matches_is_empty() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+
+    let batch = reader.scan().select(["/Vision/*"]).to_arrow().unwrap();
+    assert_eq!(batch.num_rows(), 0);
+    assert_eq!(batch.num_columns(), 4);
+
+    let all = reader.scan().exclude(["/Arm/*"]).to_rows().unwrap();
+    assert_eq!(all.len(), 5);
+    assert_eq!(reader.scan().to_rows().unwrap().len(), 6);
+}
+
+#[test]
+fn test_head_and_tail_duration() {
+    let timestamps = |builder: WpilogReaderBuilder| -> Vec<f64> {
+        let rows = builder.from_bytes(sample_log()).unwrap().read_all().unwrap();
+        rows.iter().map(|r| r.timestamp).collect()
+    };
+
+    // The log starts with the Start records at 1.0s
+    let head = timestamps(WpilogReaderBuilder::new().head_duration(Duration::from_millis(1100)));
+    assert_eq!(head, vec![1.1, 1.1, 1.1]);
+
+    // The last record at 3.1s is included
+    let tail = timestamps(WpilogReaderBuilder::new().tail_duration(Duration::from_secs(1)));
+    assert_eq!(tail, vec![2.1, 2.1, 3.1]);
+
+    // Scans only narrow the window further
+    let reader = WpilogReaderBuilder::new()
+        .tail_duration(Duration::from_secs(1))
+        .from_bytes(sample_log())
+        .unwrap();
+    let rows = reader.scan().filter_time(0..3_000_000).to_rows().unwrap();
+    assert_eq!(rows.len(), 2);
+}
+
+#[test]
+fn test_filter_time_keeps_loop_counts() {
+    let mut builder = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Timestamp", "int64", "")
+        .start_record(1_000_000, 2, "/value", "double", "");
+    for i in 0..4u64 {
+        let ts = 1_000_000 + i * 1_000_000;
+        builder = builder.int64_record(1, ts, ts as i64).double_record(2, ts + 1, i as f64);
+    }
+    let data = builder.build();
+
+    let all = WpilogReader::from_bytes(data.clone()).unwrap().read_all().unwrap();
+    let reader = WpilogReader::from_bytes(data).unwrap();
+    let rows = reader.scan().filter_time(3_000_000..4_000_000).to_rows().unwrap();
+    assert_eq!(rows.len(), 2);
+    for row in &rows {
+        let full = all
+            .iter()
+            .find(|full| full.entry == row.entry && full.timestamp == row.timestamp)
+            .unwrap();
+        assert_eq!(row.loop_count, full.loop_count);
+    }
+    assert_eq!(rows[1].loop_count, 3);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use common::WpilogBuilder;
+use serde::Deserialize;
+use std::collections::HashMap;
+use wpilog_parser::{Error, WpilogReader};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+fn sample_log() -> Vec<u8> {
+    let mut point = Vec::new();
+    point.write_f64::<LittleEndian>(1.5).unwrap();
+    point.write_f64::<LittleEndian>(-2.0).unwrap();
+
+    WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Robot/Voltage", "double", "")
+        .start_record(1_000_000, 2, "/Robot/Enabled", "boolean", "")
+        .struct_schema_record(1_000_000, 3, "struct:Point", "double x; double y")
+        .start_record(1_000_000, 4, "/Drive/Pose", "struct:Point", "")
+        .double_record(1, 1_000_000, 12.5)
+        .boolean_record(2, 1_100_000, true)
+        .struct_record(4, 1_200_000, &point)
+        .double_record(1, 2_000_000, 11.0)
+        .build()
+}
+
+#[test]
+fn test_entry_series_scalars() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+
+    let voltage = reader.entry_series::<f64>("/Robot/Voltage").unwrap();
+    assert_eq!(voltage.timestamps, vec![1.0, 2.0]);
+    assert_eq!(voltage.values, vec![12.5, 11.0]);
+    assert_eq!(voltage.value_at(1.5), Some(&12.5));
+    assert_eq!(voltage.value_at(0.5), None);
+
+    let enabled = reader.entry_series::<bool>("/Robot/Enabled").unwrap();
+    assert_eq!(enabled.iter().collect::<Vec<_>>(), vec![(1.1, &true)]);
+
+    assert!(reader.entry_series::<f64>("/Missing").unwrap().is_empty());
+    assert!(matches!(
+        reader.entry_series::<bool>("/Robot/Voltage"),
+        Err(Error::TypeMismatch(_))
+    ));
+}
+
+#[test]
+fn test_entry_series_structs() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+
+    let pose = reader.entry_series::<Point>("/Drive/Pose").unwrap();
+    assert_eq!(pose.values, vec![Point { x: 1.5, y: -2.0 }]);
+
+    let fields = reader.entry_series::<HashMap<String, f64>>("/Drive/Pose").unwrap();
+    assert_eq!(fields.values[0]["y"], -2.0);
+}
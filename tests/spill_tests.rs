@@ -0,0 +1,126 @@
+mod common;
+
+use common::WpilogBuilder;
+use tempfile::tempdir;
+use wpilog_parser::{FromParquet, ParquetWriter, Pivot, Records, SpillStore, WpilogReader, WpilogReaderBuilder};
+
+fn sample_log() -> Vec<u8> {
+    let mut builder = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/value", "double", "")
+        .start_record(1_000_000, 2, "/counts", "int64[]", "")
+        .start_record(1_000_000, 3, "/mode", "string", "");
+    for i in 0..25u64 {
+        builder = builder
+            .double_record(1, 1_000_000 + i * 20_000, i as f64 * 0.5)
+            .int64_array_record(2, 1_000_000 + i * 20_000, &[i as i64, -1])
+            .string_record(3, 1_010_000 + i * 20_000, if i % 2 == 0 { "auto" } else { "teleop" });
+    }
+    builder.build()
+}
+
+#[test]
+fn test_spill_store_preserves_rows() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let mut store = SpillStore::new().unwrap().batch_rows(10);
+
+    let staged = reader.read_to_spill(&mut store).unwrap();
+    assert_eq!(staged, 75);
+    assert_eq!(store.len(), 75);
+    assert_eq!(store.spilled_batches(), 8);
+
+    let expected = WpilogReader::from_bytes(sample_log()).unwrap().read_all().unwrap();
+    let restored: Vec<_> = store
+        .batches()
+        .collect::<wpilog_parser::Result<Vec<_>>>()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+
+    assert_eq!(restored.len(), expected.len());
+    for (original, read_back) in expected.iter().zip(&restored) {
+        assert_eq!(original.timestamp, read_back.timestamp);
+        assert_eq!(original.entry, read_back.entry);
+        assert_eq!(original.type_name, read_back.type_name);
+        assert_eq!(original.data, read_back.data);
+    }
+}
+
+#[test]
+fn test_write_spilled_matches_in_memory_write() {
+    let dir = tempdir().unwrap();
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let mut store = SpillStore::new_in(dir.path()).unwrap().batch_rows(7);
+    reader.read_to_spill(&mut store).unwrap();
+
+    let spilled_dir = dir.path().join("spilled");
+    let stats = ParquetWriter::new(&spilled_dir)
+        .chunk_size(20)
+        .write_spilled(&store)
+        .unwrap();
+    assert_eq!(stats.num_records, 75);
+    assert_eq!(stats.num_chunks, 4);
+
+    let records = WpilogReader::from_bytes(sample_log()).unwrap().read_all().unwrap();
+    let memory_dir = dir.path().join("memory");
+    ParquetWriter::new(&memory_dir).chunk_size(20).write(&records).unwrap();
+
+    let spilled = Records::from_parquet(&spilled_dir).unwrap();
+    let in_memory = Records::from_parquet(&memory_dir).unwrap();
+    assert_eq!(spilled.len(), in_memory.len());
+    for (a, b) in spilled.iter().zip(&in_memory) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.data, b.data);
+    }
+}
+
+#[test]
+fn test_spill_rejects_pivot() {
+    let reader = WpilogReaderBuilder::new()
+        .pivot(Pivot::new())
+        .from_bytes(sample_log())
+        .unwrap();
+    let mut store = SpillStore::new().unwrap();
+
+    assert!(reader.read_to_spill(&mut store).is_err());
+}
+
+#[test]
+fn test_rows_iterator_matches_read_all() {
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let expected = reader.read_all().unwrap();
+
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+    let rows: Vec<_> = reader.rows().unwrap().collect::<wpilog_parser::Result<_>>().unwrap();
+    assert_eq!(rows.len(), expected.len());
+    for (a, b) in expected.iter().zip(&rows) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.loop_count, b.loop_count);
+        assert_eq!(a.data, b.data);
+    }
+
+    // Lazy: taking a few rows does not decode the rest
+    let mut lazy = reader.rows().unwrap();
+    assert_eq!(lazy.by_ref().take(2).count(), 2);
+    assert_eq!(lazy.count(), 73);
+}
+
+#[test]
+fn test_write_rows_streams_chunks() {
+    let dir = tempdir().unwrap();
+    let reader = WpilogReader::from_bytes(sample_log()).unwrap();
+
+    let stats = ParquetWriter::new(dir.path())
+        .chunk_size(20)
+        .write_rows(reader.rows().unwrap())
+        .unwrap();
+    assert_eq!(stats.num_records, 75);
+    assert_eq!(stats.num_chunks, 4);
+    assert_eq!(Records::from_parquet(dir.path()).unwrap().len(), 75);
+
+    let pivoted = WpilogReaderBuilder::new()
+        .pivot(Pivot::new())
+        .from_bytes(sample_log())
+        .unwrap();
+    assert!(pivoted.rows().is_err());
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "datafusion")]
+
+mod common;
+
+use arrow::array::{Float64Array, Int64Array, StringArray};
+use common::WpilogBuilder;
+use datafusion::prelude::SessionContext;
+use std::sync::Arc;
+use wpilog_parser::sql::WpilogTable;
+use wpilog_parser::WpilogReader;
+
+fn sample_table() -> WpilogTable {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/Drive/Left", "double", "")
+        .start_record(1_000_000, 2, "/Drive/Right", "double", "")
+        .start_record(1_000_000, 3, "/Arm/Angle", "double", "")
+        .start_record(1_000_000, 4, "/Status", "string", "")
+        .double_record(1, 1_100_000, 1.0)
+        .double_record(2, 1_100_000, 3.0)
+        .double_record(3, 1_100_000, 100.0)
+        .string_record(4, 1_200_000, "enabled")
+        .double_record(1, 2_100_000, 5.0)
+        .double_record(2, 3_100_000, 7.0)
+        .build();
+    WpilogTable::new(WpilogReader::from_bytes(data).unwrap())
+}
+
+async fn query(sql: &str) -> Vec<arrow::record_batch::RecordBatch> {
+    let ctx = SessionContext::new();
+    ctx.register_table("log", Arc::new(sample_table())).unwrap();
+    ctx.sql(sql).await.unwrap().collect().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_sql_aggregate_with_name_filter() {
+    let batches = query("SELECT avg(value) AS avg FROM log WHERE name LIKE '/Drive%'").await;
+    let avg = batches[0].column(0).as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(avg.value(0), 4.0);
+
+    let batches = query(
+        "SELECT name, count(*) AS n FROM log WHERE name IN ('/Drive/Left', '/Status') \
+         GROUP BY name ORDER BY name",
+    )
+    .await;
+    let names = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+    let counts = batches[0].column(1).as_any().downcast_ref::<Int64Array>().unwrap();
+    assert_eq!((names.value(0), counts.value(0)), ("/Drive/Left", 2));
+    assert_eq!((names.value(1), counts.value(1)), ("/Status", 1));
+}
+
+#[tokio::test]
+async fn test_sql_time_filter_and_text() {
+    let batches = query(
+        "SELECT name, value FROM log WHERE timestamp > 1.1 AND timestamp <= 2.1 ORDER BY timestamp",
+    )
+    .await;
+    let names = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(batches[0].num_rows(), 2);
+    assert_eq!(names.value(0), "/Status");
+    assert_eq!(names.value(1), "/Drive/Left");
+
+    let batches = query("SELECT text FROM log WHERE name = '/Status'").await;
+    let text = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(text.value(0), "enabled");
+
+    let batches = query("SELECT count(*) FROM log WHERE name = '/Vision/Target'").await;
+    let count = batches[0].column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+    assert_eq!(count.value(0), 0);
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use common::WpilogBuilder;
+use serde_json::json;
+use wpilog_parser::stats::{histogram, percentiles, rolling, Histogram, TDigest};
+use wpilog_parser::{Error, WpilogReader};
+
+#[test]
+fn test_numeric_histogram() {
+    let mut builder = WpilogBuilder::new().start_record(0, 1, "/PDH/Current", "double", "");
+    for (i, value) in [0.0, 1.0, 2.0, 9.0, 10.0, 10.0, f64::NAN].into_iter().enumerate() {
+        builder = builder.double_record(1, 1000 * i as u64, value);
+    }
+    let reader = WpilogReader::from_bytes(builder.build()).unwrap();
+
+    let Histogram::Numeric(numeric) = histogram(&reader, "/PDH/Current", 5).unwrap() else {
+        panic!("expected a numeric histogram");
+    };
+    assert_eq!((numeric.min, numeric.max), (0.0, 10.0));
+    let counts: Vec<u64> = numeric.bins.iter().map(|bin| bin.count).collect();
+    // The maximum falls in the last bin
+    assert_eq!(counts, vec![2, 1, 0, 0, 3]);
+    assert_eq!(numeric.bins[1].lower, 2.0);
+    assert_eq!(numeric.bins[4].upper, 10.0);
+    assert_eq!(numeric.samples, 6);
+    assert_eq!(numeric.non_finite, 1);
+}
+
+#[test]
+fn test_state_histogram() {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "/Robot/Mode", "string", "")
+        .start_record(0, 2, "/Robot/Enabled", "boolean", "")
+        .string_record(1, 1_000_000, "Disabled")
+        .boolean_record(2, 1_000_000, false)
+        .string_record(1, 2_000_000, "Auto")
+        .boolean_record(2, 2_000_000, true)
+        .string_record(1, 4_000_000, "Disabled")
+        .boolean_record(2, 5_000_000, false)
+        .finish_record(6_000_000, 2)
+        .string_record(1, 7_000_000, "Disabled")
+        .build();
+    let reader = WpilogReader::from_bytes(data).unwrap();
+
+    let Histogram::States(mode) = histogram(&reader, "/Robot/Mode", 10).unwrap() else {
+        panic!("expected a state histogram");
+    };
+    // The last sample lasts until the end of the log
+    let states: Vec<(&str, u64, f64)> = mode
+        .states
+        .iter()
+        .map(|state| (state.state.as_str(), state.samples, state.duration_secs))
+        .collect();
+    assert_eq!(states, vec![("Disabled", 3, 4.0), ("Auto", 1, 2.0)]);
+    assert_eq!(mode.total_secs, 6.0);
+
+    // A finished entry's state ends at its Finish record
+    let Histogram::States(enabled) = histogram(&reader, "/Robot/Enabled", 10).unwrap() else {
+        panic!("expected a state histogram");
+    };
+    let states: Vec<(&str, f64)> = enabled
+        .states
+        .iter()
+        .map(|state| (state.state.as_str(), state.duration_secs))
+        .collect();
+    assert_eq!(states, vec![("true", 3.0), ("false", 2.0)]);
+}
+
+#[test]
+fn test_histogram_errors() {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "/Vision/Corners", "double[]", "")
+        .double_array_record(1, 1000, &[1.0, 2.0])
+        .build();
+    let reader = WpilogReader::from_bytes(data).unwrap();
+
+    assert!(matches!(histogram(&reader, "/Missing", 10), Err(Error::InvalidEntry(_))));
+    assert!(matches!(histogram(&reader, "/Vision/Corners", 10), Err(Error::TypeMismatch(_))));
+    assert!(histogram(&reader, "/Vision/Corners", 0).is_err());
+}
+
+#[test]
+fn test_tdigest_quantiles() {
+    let mut digest = TDigest::default();
+    assert_eq!(digest.quantile(0.5), None);
+    // Enough values to force several merges
+    for i in 0..100_000 {
+        digest.add(((i * 7919) % 100_000) as f64);
+    }
+    digest.add(f64::NAN);
+
+    assert_eq!(digest.count(), 100_000);
+    assert_eq!(digest.quantile(0.0), Some(0.0));
+    assert_eq!(digest.quantile(1.0), Some(99_999.0));
+    for (q, expected) in [(0.5, 50_000.0), (0.9, 90_000.0), (0.99, 99_000.0), (0.999, 99_900.0)] {
+        let estimate = digest.quantile(q).unwrap();
+        assert!(
+            (estimate - expected).abs() < 0.005 * 100_000.0,
+            "q{} estimated {} expected {}",
+            q,
+            estimate,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_percentiles_of_entry() {
+    let mut builder = WpilogBuilder::new()
+        .start_record(0, 1, "/Vision/LatencyMs", "int64", "")
+        .start_record(0, 2, "/Vision/Target", "string", "");
+    for i in 1..=101 {
+        builder = builder.int64_record(1, 1000 * i as u64, i);
+    }
+    let reader = WpilogReader::from_bytes(builder.string_record(2, 0, "none").build()).unwrap();
+
+    let values = percentiles(&reader, "/Vision/LatencyMs", &[0.0, 0.5, 1.0]).unwrap();
+    assert_eq!(values[0], 1.0);
+    assert!((values[1] - 51.0).abs() <= 0.5);
+    assert_eq!(values[2], 101.0);
+
+    assert!(matches!(percentiles(&reader, "/Vision/Target", &[0.5]), Err(Error::TypeMismatch(_))));
+}
+
+#[test]
+fn test_rolling_window() {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "/Drive/Velocity", "double", "")
+        .double_record(1, 1_000_000, 1.0)
+        .double_record(1, 1_500_000, 5.0)
+        .double_record(1, 2_000_000, 3.0)
+        .double_record(1, 2_600_000, 2.0)
+        .build();
+    let reader = WpilogReader::from_bytes(data).unwrap();
+
+    let rows = rolling(&reader, "/Drive/Velocity", 1.0).unwrap();
+    let derived: Vec<(f64, serde_json::Value, serde_json::Value)> = rows
+        .iter()
+        .map(|row| {
+            (
+                row.timestamp,
+                row.data["/Drive/Velocity/mean_1s"].clone(),
+                row.data["/Drive/Velocity/max_1s"].clone(),
+            )
+        })
+        .collect();
+    // Windows are (t - 1s, t], so the sample at 1.0s has left by 2.0s
+    assert_eq!(
+        derived,
+        vec![
+            (1.0, json!(1.0), json!(1.0)),
+            (1.5, json!(3.0), json!(5.0)),
+            (2.0, json!(4.0), json!(5.0)),
+            (2.6, json!(2.5), json!(3.0)),
+        ]
+    );
+    assert!(rows.iter().all(|row| row.entry == 1));
+
+    assert!(rolling(&reader, "/Missing", 1.0).is_err());
+}
@@ -0,0 +1,136 @@
+use byteorder::{LittleEndian, WriteBytesExt};
+use serde_json::json;
+use wpilog_parser::structs::{self, SchemaRegistry};
+use wpilog_parser::Error;
+
+fn pose_registry() -> SchemaRegistry {
+    let mut registry = SchemaRegistry::new();
+    // Nested schemas may be registered after the structs using them
+    registry.add("struct:Pose2d", "Translation2d translation; Rotation2d rotation").unwrap();
+    registry.add("struct:Translation2d", "double x; double y").unwrap();
+    registry.add("Rotation2d", "double value").unwrap();
+    registry
+}
+
+#[test]
+fn test_decode_nested_struct() {
+    let registry = pose_registry();
+    let mut bytes = Vec::new();
+    for value in [1.5, -2.0, 0.25] {
+        bytes.write_f64::<LittleEndian>(value).unwrap();
+    }
+
+    let pose = structs::decode(&registry, "struct:Pose2d", &bytes).unwrap();
+    assert_eq!(pose["translation.x"], json!(1.5));
+    assert_eq!(pose["translation.y"], json!(-2.0));
+    assert_eq!(pose["rotation.value"], json!(0.25));
+    assert_eq!(registry.decode("Pose2d", &bytes).unwrap(), pose);
+
+    let fields = registry.resolve("Pose2d").unwrap();
+    assert_eq!(
+        fields.iter().map(|f| (f.name.as_str(), f.offset)).collect::<Vec<_>>(),
+        vec![("translation.x", 0), ("translation.y", 8), ("rotation.value", 16)]
+    );
+}
+
+#[test]
+fn test_decode_errors() {
+    let registry = pose_registry();
+    assert!(matches!(
+        structs::decode(&registry, "struct:Missing", &[0; 8]),
+        Err(Error::SchemaError(_))
+    ));
+    assert!(matches!(
+        structs::decode(&registry, "struct:Pose2d", &[0; 12]),
+        Err(Error::ParseError(_))
+    ));
+
+    // The first definition of a name wins
+    let mut registry = SchemaRegistry::new();
+    registry.add("Gain", "float kP").unwrap();
+    registry.add("Gain", "double kP").unwrap();
+    assert_eq!(registry.len(), 1);
+    assert_eq!(registry.get("struct:Gain").unwrap().columns[0].type_name, "float");
+}
+
+#[test]
+fn test_decode_full_struct_spec() {
+    let mut registry = pose_registry();
+    registry
+        .add(
+            "Status",
+            "bool enabled; char name[8]; uint32 count; int8 raw[2]; \
+             bool a:1; uint8 mode:3; enum {UP=1, DOWN=-1} int8 delta:4; \
+             float32 gain; Translation2d corners[2]",
+        )
+        .unwrap();
+
+    let mut bytes = vec![1];
+    bytes.extend_from_slice(b"Arm\0\0\0\0\0");
+    bytes.write_u32::<LittleEndian>(7).unwrap();
+    bytes.extend_from_slice(&[0xff, 5]);
+    // a = 1, mode = 0b101, delta = -3 (0b1101), packed from the low bit
+    bytes.push(1 | 0b101 << 1 | 0b1101 << 4);
+    bytes.write_f32::<LittleEndian>(0.5).unwrap();
+    for value in [1.0, 2.0, 3.0, 4.0] {
+        bytes.write_f64::<LittleEndian>(value).unwrap();
+    }
+
+    let status = registry.decode("Status", &bytes).unwrap();
+    assert_eq!(status["enabled"], json!(true));
+    assert_eq!(status["name"], json!("Arm"));
+    assert_eq!(status["count"], json!(7));
+    assert_eq!(status["raw"], json!([-1, 5]));
+    assert_eq!(status["a"], json!(true));
+    assert_eq!(status["mode"], json!(5));
+    assert_eq!(status["delta"], json!(-3));
+    assert_eq!(status["gain"], json!(0.5));
+    assert_eq!(status["corners[1].y"], json!(4.0));
+
+    let fields = registry.resolve("Status").unwrap();
+    assert_eq!(
+        fields.iter().map(|f| (f.name.as_str(), f.type_name.as_str(), f.offset, f.size)).take(8).collect::<Vec<_>>(),
+        vec![
+            ("enabled", "bool", 0, 1),
+            ("name", "char[8]", 1, 8),
+            ("count", "uint32", 9, 4),
+            ("raw", "int8[2]", 13, 2),
+            ("a", "bool", 15, 1),
+            ("mode", "uint8", 15, 1),
+            ("delta", "int8", 15, 1),
+            ("gain", "float", 16, 4),
+        ]
+    );
+    assert_eq!(registry.size("Status").unwrap(), bytes.len());
+
+    // A bit-field of another size starts a new integer
+    registry.add("Flags", "uint8 low:4; uint16 high:4; bool done:1").unwrap();
+    let fields = registry.resolve("Flags").unwrap();
+    assert_eq!(fields.iter().map(|f| f.offset).collect::<Vec<_>>(), vec![0, 1, 1]);
+    assert_eq!(registry.size("Flags").unwrap(), 3);
+
+    for definition in ["uint8 flags:9", "double d:1", "bool b:2", "double v[x]"] {
+        assert!(matches!(
+            SchemaRegistry::new().add("Bad", definition),
+            Err(Error::SchemaError(_))
+        ));
+    }
+}
+
+#[test]
+fn test_hostile_struct_layouts_are_rejected() {
+    let mut registry = SchemaRegistry::new();
+    registry.add("Huge", "uint8 x[4294967295]").unwrap();
+    registry.add("Wrapped", "Huge items[4294967295]").unwrap();
+    registry.add("Wide", "uint64 x[18446744073709551615]").unwrap();
+    registry.add("Past", "uint8 a[18446744073709551615]; uint8 b").unwrap();
+    registry.add("Point", "double x").unwrap();
+    registry.add("Cloud", "Point points[100000]").unwrap();
+
+    for name in ["Wrapped", "Wide", "Past", "Cloud"] {
+        assert!(matches!(registry.resolve(name), Err(Error::SchemaError(_))), "{}", name);
+        assert!(matches!(registry.decode(name, &[0; 8]), Err(Error::SchemaError(_))), "{}", name);
+    }
+    // A layout that fits in memory but not in the payload is a parse error
+    assert!(matches!(registry.decode("Huge", &[0; 8]), Err(Error::ParseError(_))));
+}
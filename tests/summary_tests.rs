@@ -0,0 +1,80 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{MatchInfo, WpilogReader};
+
+#[test]
+fn test_summarize_counts_and_namespaces() {
+    let data = WpilogBuilder::with_header(0x0100, "robot")
+        .struct_schema_record(1_000_000, 1, "struct:Translation2d", "double x;double y")
+        .start_record(1_000_000, 2, "/Drive/LeftVelocity", "double", "")
+        .start_record(1_000_000, 3, "/Drive/RightVelocity", "double", "")
+        .start_record(1_000_000, 4, "NT:/SmartDashboard/Auto", "string", "")
+        .double_record(2, 1_500_000, 1.0)
+        .double_record(3, 1_500_000, 2.0)
+        .string_record(4, 2_000_000, "left")
+        .double_record(9, 3_500_000, 0.0)
+        .build();
+
+    let summary = WpilogReader::from_bytes(data).unwrap().summarize().unwrap();
+
+    assert_eq!(summary.version, 0x0100);
+    assert_eq!(summary.extra_header, "robot");
+    assert_eq!(summary.start_timestamp, Some(1_000_000));
+    assert_eq!(summary.end_timestamp, Some(3_500_000));
+    assert_eq!(summary.duration_secs, 2.5);
+    // The schema payload counts as a data record
+    assert_eq!(summary.record_count, 5);
+    assert_eq!(summary.entry_count, 4);
+    assert_eq!(summary.namespaces, vec!["Drive", "NT:"]);
+    assert_eq!(summary.schemas, vec!["struct:Translation2d"]);
+    assert_eq!(summary.match_info, None);
+    assert_eq!(summary.warnings.len(), 1);
+    assert!(summary.warnings[0].contains("no Start record"));
+}
+
+#[test]
+fn test_summarize_match_info_keeps_last_non_empty_values() {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/FMSInfo/EventName", "string", "")
+        .start_record(1_000_000, 2, "/FMSInfo/MatchType", "int64", "")
+        .start_record(1_000_000, 3, "/FMSInfo/MatchNumber", "int64", "")
+        .string_record(1, 1_100_000, "")
+        .int64_record(2, 1_100_000, 0)
+        .int64_record(3, 1_100_000, 0)
+        .string_record(1, 5_000_000, "CASJ")
+        .int64_record(2, 5_000_000, 2)
+        .int64_record(3, 5_000_000, 42)
+        .string_record(1, 9_000_000, "")
+        .build();
+
+    let summary = WpilogReader::from_bytes(data).unwrap().summarize().unwrap();
+
+    assert_eq!(
+        summary.match_info,
+        Some(MatchInfo {
+            event_name: Some("CASJ".to_string()),
+            match_type: Some("Qualification".to_string()),
+            match_number: Some(42),
+            replay_number: None,
+        })
+    );
+    assert!(summary.warnings.is_empty());
+}
+
+#[test]
+fn test_summarize_reports_truncation() {
+    let mut data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/a", "double", "")
+        .double_record(1, 1_100_000, 1.0)
+        .double_record(1, 1_200_000, 2.0)
+        .build();
+    data.truncate(data.len() - 3);
+
+    let summary = WpilogReader::from_bytes(data).unwrap().summarize().unwrap();
+
+    assert_eq!(summary.record_count, 1);
+    assert!(summary.warnings[0].contains("truncated"));
+    let json = summary.to_json().unwrap();
+    assert!(json.contains("\"record_count\": 1"));
+}
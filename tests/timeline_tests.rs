@@ -0,0 +1,57 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::WpilogReader;
+
+#[test]
+fn test_command_timeline_from_scheduler_and_subsystems() {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "NT:/SmartDashboard/Scheduler/Names", "string[]", "")
+        .start_record(0, 2, "NT:/SmartDashboard/Arm/.command", "string", "")
+        .start_record(0, 3, "/Drive/Velocity", "double", "")
+        .string_array_record(1, 1_000_000, &["DriveWithJoysticks"])
+        .string_record(2, 1_000_000, "none")
+        .string_array_record(1, 2_000_000, &["DriveWithJoysticks", "RaiseArm"])
+        .string_record(2, 2_000_000, "RaiseArm")
+        .string_array_record(1, 3_500_000, &["DriveWithJoysticks"])
+        .string_record(2, 3_500_000, "none")
+        .double_record(3, 5_000_000, 1.0)
+        .build();
+    let timeline = WpilogReader::from_bytes(data).unwrap().command_timeline().unwrap();
+
+    assert_eq!(timeline.log_end_secs, 5.0);
+    assert_eq!(timeline.commands(), vec!["DriveWithJoysticks", "RaiseArm"]);
+    let spans: Vec<(&str, Option<&str>, f64, Option<f64>)> = timeline
+        .spans
+        .iter()
+        .map(|s| (s.command.as_str(), s.subsystem.as_deref(), s.start_secs, s.end_secs))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            ("DriveWithJoysticks", None, 1.0, None),
+            ("RaiseArm", None, 2.0, Some(3.5)),
+            ("RaiseArm", Some("Arm"), 2.0, Some(3.5)),
+        ]
+    );
+    assert_eq!(timeline.spans[0].duration_secs(timeline.log_end_secs), 4.0);
+    assert_eq!(timeline.spans[1].source, "NT:/SmartDashboard/Scheduler/Names");
+}
+
+#[test]
+fn test_command_timeline_from_advantagekit_flags() {
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "/RealOutputs/CommandsAll/AutoAlign", "boolean", "")
+        .boolean_record(1, 1_000_000, false)
+        .boolean_record(1, 2_000_000, true)
+        .boolean_record(1, 2_500_000, true)
+        .boolean_record(1, 3_000_000, false)
+        .boolean_record(1, 4_000_000, true)
+        .build();
+    let timeline = WpilogReader::from_bytes(data).unwrap().command_timeline().unwrap();
+
+    let spans: Vec<(f64, Option<f64>)> =
+        timeline.spans.iter().map(|s| (s.start_secs, s.end_secs)).collect();
+    assert_eq!(spans, vec![(2.0, Some(3.0)), (4.0, None)]);
+    assert!(timeline.spans.iter().all(|s| s.command == "AutoAlign"));
+}
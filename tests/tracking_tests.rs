@@ -0,0 +1,83 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{TrackingAnalysis, TrackingPair, WpilogReader};
+
+fn arm_log() -> Vec<u8> {
+    let mut builder = WpilogBuilder::new()
+        .start_record(0, 1, "/Arm/Setpoint", "double", "")
+        .start_record(0, 2, "/Arm/Position", "double", "")
+        .double_record(1, 0, 0.0)
+        .double_record(2, 0, 0.0)
+        .double_record(1, 1_000_000, 10.0);
+    // Overshoots to 12, rings, and stays within 2% of 10 from 1.5s
+    let response = [
+        (1_100_000, 5.0),
+        (1_200_000, 12.0),
+        (1_300_000, 9.0),
+        (1_400_000, 10.5),
+        (1_500_000, 10.1),
+        (1_600_000, 9.9),
+    ];
+    for (timestamp, position) in response {
+        builder = builder.double_record(2, timestamp, position);
+    }
+    // A step down that never settles
+    builder
+        .double_record(1, 2_000_000, 0.0)
+        .double_record(2, 2_100_000, 4.0)
+        .build()
+}
+
+#[test]
+fn test_tracking_report() {
+    let reader = WpilogReader::from_bytes(arm_log()).unwrap();
+    let reports = TrackingAnalysis::new()
+        .pair("/Arm/Setpoint", "/Arm/Position")
+        .run(&reader)
+        .unwrap();
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+
+    let errors = [0.0, 5.0, -2.0, 1.0, -0.5, -0.1, 0.1, -4.0];
+    let rmse = (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt();
+    assert_eq!(report.samples, errors.len());
+    assert!((report.rmse - rmse).abs() < 1e-9);
+    assert_eq!(report.max_abs_error, 5.0);
+
+    assert_eq!(report.steps.len(), 2);
+    let up = &report.steps[0];
+    assert_eq!((up.start_secs, up.from, up.to), (1.0, 0.0, 10.0));
+    assert_eq!(up.overshoot, 2.0);
+    assert_eq!(up.overshoot_percent, 20.0);
+    assert!((up.settling_time_secs.unwrap() - 0.5).abs() < 1e-9);
+    assert_eq!(up.samples, 6);
+
+    let down = &report.steps[1];
+    assert_eq!((down.from, down.to), (10.0, 0.0));
+    assert_eq!(down.overshoot, 0.0);
+    assert_eq!(down.settling_time_secs, None);
+
+    let text = report.to_string();
+    assert!(text.contains("/Arm/Setpoint -> /Arm/Position"));
+    assert!(text.contains("did not settle"));
+}
+
+#[test]
+fn test_tracking_min_step_and_pairs() {
+    let reader = WpilogReader::from_bytes(arm_log()).unwrap();
+    let pair: TrackingPair = "/Arm/Setpoint=/Arm/Position".parse().unwrap();
+    let reports = TrackingAnalysis::new()
+        .pairs([pair])
+        .min_step(10.0)
+        .run(&reader)
+        .unwrap();
+    // Steps of exactly 10 are not larger than the minimum
+    assert!(reports[0].steps.is_empty());
+
+    assert!("/Arm/Setpoint".parse::<TrackingPair>().is_err());
+    assert!(TrackingAnalysis::new()
+        .pair("/Arm/Setpoint", "/Missing")
+        .run(&reader)
+        .is_err());
+}
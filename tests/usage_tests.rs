@@ -0,0 +1,95 @@
+mod common;
+
+use common::WpilogBuilder;
+use wpilog_parser::{UsageReport, WpilogReader};
+
+/// 15s of auto, a 5s disabled gap, then 20s of teleop with one brownout.
+fn match_log() -> Vec<u8> {
+    WpilogBuilder::new()
+        .start_record(0, 1, "DS:enabled", "boolean", "")
+        .start_record(0, 2, "DS:autonomous", "boolean", "")
+        .start_record(0, 3, "/SystemStats/BrownedOut", "boolean", "")
+        .boolean_record(1, 0, false)
+        .boolean_record(2, 10_000_000, true)
+        .boolean_record(1, 10_000_000, true)
+        .boolean_record(1, 25_000_000, false)
+        .boolean_record(2, 25_000_000, false)
+        .boolean_record(1, 30_000_000, true)
+        .boolean_record(3, 40_000_000, true)
+        .boolean_record(3, 40_100_000, true)
+        .boolean_record(3, 41_000_000, false)
+        .boolean_record(1, 50_000_000, false)
+        .boolean_record(3, 60_000_000, false)
+        .build()
+}
+
+#[test]
+fn test_usage_summary() {
+    let usage = WpilogReader::from_bytes(match_log()).unwrap().usage().unwrap();
+    assert_eq!(usage.logs, 1);
+    assert_eq!(usage.duration_secs, 60.0);
+    assert_eq!(usage.enabled_secs, 35.0);
+    assert_eq!(usage.auto_secs, 15.0);
+    assert_eq!(usage.teleop_secs, 20.0);
+    assert_eq!(usage.test_secs, 0.0);
+    assert_eq!(usage.enable_cycles, 2);
+    assert_eq!(usage.brownouts, 1);
+}
+
+#[test]
+fn test_usage_from_control_word() {
+    // FMSControlData bits: enabled = 1, auto = 2, test = 4
+    let data = WpilogBuilder::new()
+        .start_record(0, 1, "NT:/FMSInfo/FMSControlData", "int64", "")
+        .int64_record(1, 0, 0)
+        .int64_record(1, 1_000_000, 3)
+        .int64_record(1, 3_000_000, 1)
+        .int64_record(1, 6_000_000, 5)
+        .int64_record(1, 7_000_000, 0)
+        .build();
+    let usage = WpilogReader::from_bytes(data).unwrap().usage().unwrap();
+    assert_eq!((usage.auto_secs, usage.teleop_secs, usage.test_secs), (2.0, 3.0, 1.0));
+    // Mode changes while enabled are not new cycles
+    assert_eq!(usage.enable_cycles, 1);
+}
+
+#[test]
+fn test_usage_report_for_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.wpilog"), match_log()).unwrap();
+    std::fs::write(dir.path().join("b.wpilog"), match_log()).unwrap();
+    std::fs::write(dir.path().join("broken.wpilog"), b"not a log").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), b"ignored").unwrap();
+
+    let report = UsageReport::from_directory(dir.path()).unwrap();
+    let files: Vec<&str> = report.logs.iter().map(|(file, _)| file.as_str()).collect();
+    assert_eq!(files, vec!["a.wpilog", "b.wpilog"]);
+    assert_eq!(report.total.logs, 2);
+    assert_eq!(report.total.enabled_secs, 70.0);
+    assert_eq!(report.total.enable_cycles, 4);
+    assert_eq!(report.total.brownouts, 2);
+}
+
+#[test]
+fn test_time_origin_rezeroes_timestamps() {
+    use wpilog_parser::{TimeOrigin, WpilogReaderBuilder};
+
+    let first_timestamps = |origin: TimeOrigin| -> Vec<f64> {
+        let rows = WpilogReaderBuilder::new()
+            .time_origin(origin)
+            .from_bytes(match_log())
+            .unwrap()
+            .read_all()
+            .unwrap();
+        rows.iter().take(3).map(|r| r.timestamp).collect()
+    };
+
+    assert_eq!(first_timestamps(TimeOrigin::FirstRecord), vec![0.0, 10.0, 10.0]);
+    // Enabled at 10s; earlier records get negative times
+    assert_eq!(first_timestamps(TimeOrigin::FirstEnabled), vec![-10.0, 0.0, 0.0]);
+    assert_eq!(first_timestamps("2.5".parse().unwrap()), vec![-2.5, 7.5, 7.5]);
+
+    assert_eq!("first-enabled".parse::<TimeOrigin>().unwrap(), TimeOrigin::FirstEnabled);
+    assert!("-1".parse::<TimeOrigin>().is_err());
+    assert!("kickoff".parse::<TimeOrigin>().is_err());
+}
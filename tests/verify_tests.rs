@@ -0,0 +1,84 @@
+mod common;
+
+use common::WpilogBuilder;
+use tempfile::tempdir;
+use wpilog_parser::verify::Mismatch;
+use wpilog_parser::{verify_export, ParquetWriter, WpilogReader};
+
+fn sample_records() -> wpilog_parser::Records {
+    let data = WpilogBuilder::new()
+        .start_record(1_000_000, 1, "/temperature", "double", "")
+        .start_record(1_000_000, 2, "/velocities", "double[]", "")
+        .start_record(1_000_000, 3, "/mode", "string", "")
+        .double_record(1, 1_100_000, 25.5)
+        .double_array_record(2, 1_100_000, &[1.0, 2.0])
+        .string_record(3, 1_200_000, "teleop")
+        .double_record(1, 1_300_000, 26.5)
+        .build();
+
+    WpilogReader::from_bytes(data).unwrap().read_all().unwrap()
+}
+
+#[test]
+fn test_verify_matching_export() {
+    let dir = tempdir().unwrap();
+    let records = sample_records();
+
+    ParquetWriter::new(dir.path()).write(&records).unwrap();
+
+    let report = verify_export(&records, dir.path()).unwrap();
+    assert!(report.is_ok(), "{}", report.diff());
+    assert_eq!(report.source_rows, 4);
+    assert_eq!(report.exported_rows, 4);
+    assert_eq!(report.sampled_rows, 4);
+}
+
+#[test]
+fn test_verify_detects_missing_rows() {
+    let dir = tempdir().unwrap();
+    let records = sample_records();
+
+    ParquetWriter::new(dir.path()).write(&records[..3]).unwrap();
+
+    let report = verify_export(&records, dir.path()).unwrap();
+    assert!(!report.is_ok());
+    assert!(report.mismatches.contains(&Mismatch::RowCount {
+        source: 4,
+        exported: 3
+    }));
+    assert!(report.diff().contains("non-null count for '/temperature'"));
+}
+
+#[test]
+fn test_verify_detects_changed_values() {
+    let dir = tempdir().unwrap();
+    let records = sample_records();
+
+    ParquetWriter::new(dir.path()).write(&records).unwrap();
+
+    let mut altered = records.clone();
+    altered[0].insert("/temperature".to_string(), serde_json::json!(99.0));
+
+    let report = verify_export(&altered, dir.path()).unwrap();
+    assert_eq!(report.mismatches.len(), 1);
+    assert!(matches!(
+        &report.mismatches[0],
+        Mismatch::Value { row: 0, column, .. } if column == "/temperature"
+    ));
+}
+
+#[test]
+fn test_verify_report_json_round_trip() {
+    let dir = tempdir().unwrap();
+    let records = sample_records();
+
+    ParquetWriter::new(dir.path()).write(&records[..3]).unwrap();
+
+    let report = verify_export(&records, dir.path()).unwrap();
+    let json = report.to_json().unwrap();
+    assert!(json.contains("\"kind\": \"row_count\""));
+
+    let parsed: wpilog_parser::verify::VerifyReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.mismatches, report.mismatches);
+    assert_eq!(parsed.source_rows, report.source_rows);
+}